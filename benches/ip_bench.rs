@@ -0,0 +1,58 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use matchy::{glob::MatchMode, mmdb_builder::MmdbBuilder, Database};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Compare string lookups with lookups of already-parsed addresses
+///
+/// The cache is disabled so every query walks the tree and the difference
+/// is the cost of classifying and parsing the query string.
+fn bench_ip_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ip_lookup");
+
+    let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+    for i in 0..10_000u32 {
+        let net = Ipv4Addr::from(0x0a00_0000 | (i << 8));
+        builder
+            .add_ip(&format!("{}/24", net), HashMap::new())
+            .unwrap();
+    }
+    let db = Database::from_bytes_builder(builder.build().unwrap())
+        .no_cache()
+        .open()
+        .unwrap();
+
+    let addrs: Vec<Ipv4Addr> = (0..10_000u32)
+        .map(|i| Ipv4Addr::from(0x0a00_0000 | (i.wrapping_mul(2_654_435_761) & 0x00ff_ffff)))
+        .collect();
+    let strings: Vec<String> = addrs.iter().map(|a| a.to_string()).collect();
+
+    group.throughput(Throughput::Elements(addrs.len() as u64));
+    group.bench_function("lookup_str", |b| {
+        b.iter(|| {
+            for s in &strings {
+                black_box(db.lookup(black_box(s)).unwrap());
+            }
+        });
+    });
+    group.bench_function("lookup_ip", |b| {
+        b.iter(|| {
+            for &addr in &addrs {
+                black_box(db.lookup_ip(black_box(IpAddr::V4(addr))).unwrap());
+            }
+        });
+    });
+    group.bench_function("lookup_ipv4", |b| {
+        b.iter(|| {
+            for &addr in &addrs {
+                black_box(db.lookup_ipv4(black_box(addr)).unwrap());
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ip_lookup);
+criterion_main!(benches);
@@ -157,7 +157,7 @@ fn bench_mmdb_build_complex_data(c: &mut Criterion) {
         metadata.insert("first_seen".to_string(), DataValue::Uint64(1234567890));
         metadata.insert("last_seen".to_string(), DataValue::Uint64(1234567999));
         metadata.insert("confidence".to_string(), DataValue::Float(0.95));
-        data.insert("metadata".to_string(), DataValue::Map(metadata));
+        data.insert("metadata".to_string(), DataValue::from_hash_map(metadata));
 
         // Array of tags
         let tags = vec![
@@ -124,6 +124,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             matchy::extractor::ExtractedItem::Bitcoin(_) => "Bitcoin",
             matchy::extractor::ExtractedItem::Ethereum(_) => "Ethereum",
             matchy::extractor::ExtractedItem::Monero(_) => "Monero",
+            matchy::extractor::ExtractedItem::Url(_) => "URL",
+            _ => "Other",
         };
         println!("     - {} ({})", match_item.as_str(log), type_name);
     }
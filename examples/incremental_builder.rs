@@ -45,7 +45,10 @@ fn main() {
     );
 
     let phishing_id = builder
-        .add_pattern_with_data("*.phishing.com", Some(DataValue::Map(phishing_data)))
+        .add_pattern_with_data(
+            "*.phishing.com",
+            Some(DataValue::from_hash_map(phishing_data)),
+        )
         .unwrap();
     println!(
         "Added pattern '*.phishing.com' with threat data, ID: {}",
@@ -73,7 +76,7 @@ fn main() {
     );
 
     let malware_id = builder
-        .add_pattern_with_data("malware-*", Some(DataValue::Map(malware_data)))
+        .add_pattern_with_data("malware-*", Some(DataValue::from_hash_map(malware_data)))
         .unwrap();
     println!(
         "Added pattern 'malware-*' with threat data, ID: {}",
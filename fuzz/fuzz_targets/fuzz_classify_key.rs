@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use matchy::{classify_key, KeyKind};
+use std::net::IpAddr;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(query) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Classification must never panic, and must agree with the IP parser
+    match classify_key(query) {
+        KeyKind::Ip(addr) => assert_eq!(query.parse::<IpAddr>().ok(), Some(addr)),
+        KeyKind::MalformedIp | KeyKind::String => assert!(query.parse::<IpAddr>().is_err()),
+    }
+});
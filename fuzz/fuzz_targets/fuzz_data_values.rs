@@ -44,7 +44,7 @@ fuzz_target!(|data: &[u8]| {
         // Try as nested map
         let mut nested = HashMap::new();
         nested.insert("nested_str".to_string(), DataValue::String(s.to_string()));
-        data_map.insert("map_field".to_string(), DataValue::Map(nested));
+        data_map.insert("map_field".to_string(), DataValue::from_hash_map(nested));
         
         // Try as array
         let arr = vec![
@@ -222,6 +222,11 @@ namespace matchy {
  */
 #define MATCHY_VALIDATION_AUDIT 2
 
+/*
+ Exhaustive validation level - strict, but UTF-8 and pointer checks cover every data record
+ */
+#define MATCHY_VALIDATION_EXHAUSTIVE 3
+
 /*
  Opaque database builder handle
  */
@@ -306,6 +311,43 @@ typedef struct matchy_result_t {
   const struct matchy_t *_db_ref;
 } matchy_result_t;
 
+/*
+ Database metadata (the standard MMDB metadata fields)
+
+ Filled by `matchy_get_metadata`. String pointers are owned by the
+ database handle and stay valid until `matchy_close`; do not free them.
+ */
+typedef struct matchy_metadata_t {
+  /*
+   Number of nodes in the IP search tree
+   */
+  uint32_t node_count;
+  /*
+   Bits per tree record (24, 28, or 32)
+   */
+  uint16_t record_size;
+  /*
+   IP version of the search tree (4 or 6)
+   */
+  uint16_t ip_version;
+  /*
+   Database type name (empty string if not set)
+   */
+  const char *database_type;
+  /*
+   Build time in seconds since the Unix epoch
+   */
+  uint64_t build_epoch;
+  /*
+   Description languages (e.g. "en"), NULL-terminated
+   */
+  const char *const *languages;
+  /*
+   Number of entries in `languages` (excluding the NULL terminator)
+   */
+  uintptr_t language_count;
+} matchy_metadata_t;
+
 /*
  Entry handle (like MMDB_entry_s)
  */
@@ -360,6 +402,16 @@ typedef struct matchy_entry_data_list_t {
   struct matchy_entry_data_list_t *next;
 } matchy_entry_data_list_t;
 
+/*
+ Callback for `matchy_iterate`, called once per network that holds data
+
+ Receives the network address as a string (e.g. `"10.0.0.0"`), its prefix
+ length, an entry handle for `matchy_aget_value` and friends, and the
+ caller's `user_data`. The string and entry are only valid during the call.
+ Return 0 to continue, or nonzero to stop the iteration.
+ */
+typedef int (*matchy_node_callback)(const char *network, uint8_t prefix_len, const struct matchy_entry_s *entry, void *user_data);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -577,6 +629,50 @@ struct matchy_t *matchy_open_with_options(const char *filename, const struct mat
  */
 struct matchy_t *matchy_open(const char *filename);
 
+/*
+ Open several databases behind one handle
+
+ Returns a composite handle that `matchy_query` answers from the first
+ database, in `paths` order, that has a match. IPv4 queries go to every
+ database with IP data, IPv6 queries only to IPv6 databases, and string
+ queries to databases with literal or pattern data. This lets separately
+ maintained feeds (e.g. an IPv4 and an IPv6 file, or GeoIP plus threat
+ intel) be queried as one without rebuilding them into a single file.
+
+ Functions describing a single file (`matchy_format`, `matchy_metadata`,
+ `matchy_get_pattern_string`, `matchy_pattern_count`) report on the first
+ database. `matchy_has_*_data` are true if any database has that data,
+ `matchy_get_stats` sums the databases' counters (a query consulting
+ several databases counts once in each), and `matchy_clear_cache` clears
+ every cache. One `matchy_close` closes all of them. Like a single-database
+ handle, a composite handle must not be used from several threads at once.
+
+ # Parameters
+ * `paths` - Array of database paths (null-terminated C strings, must not be NULL)
+ * `count` - Number of paths (must be > 0)
+
+ # Returns
+ * Non-null pointer on success
+ * NULL on failure, including if any database fails to open
+
+ # Safety
+ * `paths` must point to `count` valid null-terminated C strings
+
+ # Example
+ ```c
+ const char *paths[] = {"geo-v4.mxy", "geo-v6.mxy", "threats.mxy"};
+ matchy_t *db = matchy_open_multi(paths, 3);
+ if (db == NULL) {
+     fprintf(stderr, "Failed to open databases\n");
+     return 1;
+ }
+ matchy_result_t result = matchy_query(db, "2001:db8::1");
+ matchy_free_result(&result);
+ matchy_close(db);
+ ```
+ */
+struct matchy_t *matchy_open_multi(const char *const *paths, uintptr_t count);
+
 /*
  Open database from memory buffer (zero-copy)
 
@@ -713,6 +809,45 @@ void matchy_close(struct matchy_t *db);
  */
 struct matchy_result_t matchy_query(const struct matchy_t *db, const char *query);
 
+/*
+ Query the database with many keys in one call
+
+ Equivalent to calling matchy_query for each key, without crossing the
+ FFI boundary per key. Use it when looking up many IPs or strings in a
+ tight loop.
+
+ # Parameters
+ * `db` - Database handle (must not be NULL)
+ * `keys` - Array of `n` IP addresses or patterns (null-terminated C strings).
+   A NULL or non-UTF-8 key is reported as not found
+ * `n` - Number of keys
+ * `results` - Caller-allocated array of `n` results, filled in key order
+
+ # Returns
+ * Number of keys that matched, or 0 if `db`, `keys`, or `results` is NULL
+ * Every one of the `n` results must be freed with matchy_free_result,
+   including results with found=false
+
+ # Safety
+ * `db` must be a valid pointer from matchy_open
+ * `keys` must point to `n` pointers, each NULL or a valid null-terminated C string
+ * `results` must point to writable space for `n` results
+
+ # Example
+ ```c
+ const char *keys[] = {"1.2.3.4", "example.com"};
+ matchy_result_t results[2];
+ size_t found = matchy_query_batch(db, keys, 2, results);
+ for (size_t i = 0; i < 2; i++) {
+     if (results[i].found) {
+         // ... use results[i]
+     }
+     matchy_free_result(&results[i]);
+ }
+ ```
+ */
+uintptr_t matchy_query_batch(const struct matchy_t *db, const char *const *keys, uintptr_t n, struct matchy_result_t *results);
+
 /*
  Free query result
 
@@ -748,6 +883,29 @@ void matchy_free_string(char *string);
  */
 const char *matchy_version(void);
 
+/*
+ Get a human-readable description of an error code
+
+ Mirrors `strerror`: every `MATCHY_SUCCESS` / `MATCHY_ERROR_*` code returned
+ by this library has a description, and unknown codes get a generic one.
+
+ # Parameters
+ * `code` - Status code returned by a matchy function
+
+ # Returns
+ * Description string (never NULL)
+ * Pointer is valid for program lifetime, do not free
+
+ # Example
+ ```c
+ int status = matchy_builder_save(builder, "threats.mxy");
+ if (status != MATCHY_SUCCESS) {
+     fprintf(stderr, "Failed to save: %s\n", matchy_strerror(status));
+ }
+ ```
+ */
+const char *matchy_strerror(int32_t code);
+
 /*
  Get database format description
 
@@ -859,6 +1017,40 @@ bool matchy_has_pattern_data(const struct matchy_t *db);
  */
 char *matchy_metadata(const struct matchy_t *db);
 
+/*
+ Get database metadata
+
+ Populates `out` with the standard MMDB metadata fields, like
+ libmaxminddb's `MMDB_s.metadata`. For a handle from `matchy_open_multi`
+ this describes the first database.
+
+ # Parameters
+ * `db` - Database handle (must not be NULL)
+ * `out` - Metadata struct to fill (must not be NULL)
+
+ # Returns
+ * MATCHY_SUCCESS (0) on success
+ * MATCHY_ERROR_NO_DATA if the database has no MMDB metadata (pattern-only databases)
+ * MATCHY_ERROR_INVALID_PARAM if a parameter is NULL
+
+ # Safety
+ * `db` must be a valid pointer from matchy_open
+ * `out` must be a valid pointer to matchy_metadata_t
+ * Strings in `out` must not be used after `matchy_close(db)`
+
+ # Example
+ ```c
+ matchy_metadata_t meta;
+ if (matchy_get_metadata(db, &meta) == MATCHY_SUCCESS) {
+     printf("%s, IPv%u, %u nodes\n", meta.database_type, meta.ip_version, meta.node_count);
+     for (size_t i = 0; i < meta.language_count; i++) {
+         printf("language: %s\n", meta.languages[i]);
+     }
+ }
+ ```
+ */
+int32_t matchy_get_metadata(const struct matchy_t *db, struct matchy_metadata_t *out);
+
 /*
  Get pattern string by ID
 
@@ -1036,7 +1228,7 @@ char *matchy_result_to_json(const struct matchy_result_t *result);
 
  # Parameters
  * `filename` - Path to database file (null-terminated C string, must not be NULL)
- * `level` - Validation level (MATCHY_VALIDATION_STANDARD, _STRICT, or _AUDIT)
+ * `level` - Validation level (MATCHY_VALIDATION_STANDARD, _STRICT, _AUDIT, or _EXHAUSTIVE)
  * `error_message` - Pointer to receive error message (may be NULL if not needed)
    If non-NULL and validation fails, receives a string that must be freed with matchy_free_string
 
@@ -1062,6 +1254,76 @@ char *matchy_result_to_json(const struct matchy_result_t *result);
  */
 int32_t matchy_validate(const char *filename, int32_t level, char **error_message);
 
+/*
+ Look up the address in a socket address with a native matchy handle
+
+ The address bytes are read straight out of the `sockaddr_in` or
+ `sockaddr_in6` and used to walk the IP tree, with no `inet_ntop` and
+ reparse round-trip. Use this in packet-processing hot paths that already
+ hold a socket address.
+
+ # Parameters
+ * `db` - Database handle (must not be NULL)
+ * `sockaddr` - `AF_INET` or `AF_INET6` socket address (must not be NULL)
+ * `error` - Receives MATCHY_SUCCESS, or MATCHY_ERROR_INVALID_PARAM for NULL
+   arguments and other address families (may be NULL)
+
+ # Returns
+ * matchy_result_t as from `matchy_query`; free it with matchy_free_result
+
+ # Safety
+ - `db` must be a valid pointer from matchy_open
+ - `sockaddr` must point to a socket address of the size its family implies
+ */
+struct matchy_result_t matchy_lookup_sockaddr(const struct matchy_t *db, const struct sockaddr *sockaddr, int *error);
+
+/*
+ Look up the address in a socket address with a native matchy handle (Windows)
+
+ # Safety
+ - `db` must be a valid pointer from matchy_open
+ - `sockaddr` must point to a socket address of the size its family implies
+ */
+struct matchy_result_t matchy_lookup_sockaddr(const struct matchy_t *db, const SOCKADDR *sockaddr, int *error);
+
+/*
+ Call `callback` for every IP network in a native matchy handle
+
+ Walks the IP tree in address order, like libmaxminddb's `MMDB_next_node`
+ iteration, so C tools can dump or diff a database. IPv4 networks in IPv6
+ databases are reported once, as IPv4. Handles from `matchy_open_multi`
+ visit each database in turn. Patterns and literal strings are not visited.
+
+ The callback may call any read-only function on `db` (`matchy_query`,
+ `matchy_aget_value` on its entry, and so on), but must not close it.
+
+ # Parameters
+ * `db` - Database handle (must not be NULL)
+ * `callback` - Called for each network (must not be NULL)
+ * `user_data` - Passed through to `callback` (may be NULL)
+
+ # Returns
+ * MATCHY_SUCCESS after every network was visited or the callback stopped early
+ * MATCHY_ERROR_INVALID_PARAM if `db` or `callback` is NULL
+ * MATCHY_ERROR_CORRUPT_DATA if the tree or a data record is malformed
+
+ # Safety
+ - `db` must be a valid pointer from matchy_open
+ - `callback` must not close `db` or keep the network string or entry past its return
+
+ # Example
+ ```c
+ int print_network(const char *network, uint8_t prefix_len,
+                   const matchy_entry_s *entry, void *user_data) {
+     printf("%s/%u\n", network, prefix_len);
+     return 0;
+ }
+
+ matchy_iterate(db, print_network, NULL);
+ ```
+ */
+int matchy_iterate(const struct matchy_t *db, matchy_node_callback callback, void *user_data);
+
 #ifdef __cplusplus
 }  // extern "C"
 #endif  // __cplusplus
@@ -30,7 +30,7 @@
 
 use crate::error::ParaglobError;
 use rustc_hash::FxHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 use std::mem;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref};
@@ -100,15 +100,16 @@ fn compute_hash(literal_id: u32) -> u64 {
 
 /// Builder for AC literal hash table
 pub struct ACLiteralHashBuilder {
-    // Map from AC literal ID to list of pattern IDs
-    mappings: HashMap<u32, Vec<u32>>,
+    // Map from AC literal ID to list of pattern IDs, ordered by ID so the
+    // serialized table is the same for the same input
+    mappings: BTreeMap<u32, Vec<u32>>,
 }
 
 impl ACLiteralHashBuilder {
     /// Create a new builder
     pub fn new() -> Self {
         Self {
-            mappings: HashMap::new(),
+            mappings: BTreeMap::new(),
         }
     }
 
@@ -243,7 +244,7 @@ impl<'a> ACLiteralHash<'a> {
         }
 
         if header.version != AC_LITERAL_HASH_VERSION {
-            return Err(ParaglobError::InvalidPattern(format!(
+            return Err(ParaglobError::UnsupportedVersion(format!(
                 "Unsupported AC literal hash version: {}",
                 header.version
             )));
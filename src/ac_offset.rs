@@ -340,13 +340,16 @@ impl ACBuilder {
                 }
 
                 StateKind::Dense => {
-                    // Write dense lookup table
+                    // Write dense lookup table. Target 0 marks a missing edge:
+                    // offset 0 is the root, and trie edges only ever point to
+                    // the child state created for them, never back to the root.
                     let lookup_offset = dense_offset;
                     let mut lookup = DenseLookup {
                         targets: [0u32; 256],
                     };
 
                     for (ch, target) in &edges {
+                        debug_assert_ne!(*target, 0, "transition into the root node");
                         lookup.targets[*ch as usize] = *target;
                     }
 
@@ -747,6 +750,7 @@ impl ACAutomaton {
                     self.buffer[target_offset_offset + 3],
                 ]);
 
+                // 0 is the root, which is never a transition target
                 if target != 0 {
                     Some(target as usize)
                 } else {
@@ -825,6 +829,30 @@ mod tests {
         assert!(ids.contains(&1));
     }
 
+    #[test]
+    fn test_dense_state_targets() {
+        // Ten first bytes make the root a dense state; "aj" makes "a" dense too
+        let patterns = vec![
+            "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "aa", "ab", "ac", "ad", "ae", "af",
+            "ag", "ah", "ai", "aj",
+        ];
+        let ac = ACAutomaton::build(&patterns, MatchMode::CaseSensitive).unwrap();
+        let root = ac.find_transition(0, b'a').unwrap();
+        assert_ne!(root, 0);
+
+        // No byte leads back to offset 0, so a 0 entry can only mean "no edge"
+        for ch in 0..=255u8 {
+            assert_ne!(ac.find_transition(0, ch), Some(0));
+            assert_ne!(ac.find_transition(root, ch), Some(0));
+        }
+        assert!(ac.find_transition(0, b'z').is_none());
+        assert!(ac.find_transition(root, b'z').is_none());
+
+        let mut ids = ac.find_pattern_ids("aj");
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 9, 19]);
+    }
+
     #[test]
     fn test_no_match() {
         let patterns = vec!["hello", "world"];
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use matchy::DataValue;
+use matchy::{DataMap, DataValue};
 use serde_json::json;
 use std::collections::HashMap;
 use std::io;
@@ -237,7 +237,7 @@ pub fn json_to_data_value(json: &serde_json::Value) -> Result<DataValue> {
             let entries = obj
                 .iter()
                 .map(|(k, v)| Ok((k.clone(), json_to_data_value(v)?)))
-                .collect::<Result<HashMap<_, _>>>()?;
+                .collect::<Result<DataMap>>()?;
             Ok(DataValue::Map(entries))
         }
     }
@@ -60,22 +60,7 @@ pub fn bench_ip_database(
     drop(db_bytes);
     println!();
 
-    println!("--- Phase 3: Load Database (mmap) ---");
-    let mut load_times = Vec::new();
-    for i in 1..=load_iterations {
-        let load_start = Instant::now();
-        let _db = Database::from(temp_file.to_str().unwrap()).open()?;
-        let load_time = load_start.elapsed();
-        load_times.push(load_time);
-        println!(
-            "  Load #{}: {:.3}ms",
-            i,
-            load_time.as_micros() as f64 / 1000.0
-        );
-    }
-    let avg_load = load_times.iter().sum::<std::time::Duration>() / load_iterations as u32;
-    println!("  Average:  {:.3}ms", avg_load.as_micros() as f64 / 1000.0);
-    println!();
+    super::bench_load(temp_file, load_iterations, "0.0.0.1")?;
 
     println!("--- Phase 4: Query Performance ---");
     let mut opener = Database::from(temp_file.to_str().unwrap());
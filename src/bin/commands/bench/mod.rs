@@ -4,7 +4,8 @@ mod literal;
 mod pattern;
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 pub use combined::bench_combined_database;
 pub use ip::bench_ip_database;
@@ -124,3 +125,66 @@ pub fn cmd_bench(
         }
     }
 }
+
+fn millis(d: Duration) -> f64 {
+    d.as_micros() as f64 / 1000.0
+}
+
+/// Phase 3: time repeated cold opens and the first query after each
+///
+/// Each open is broken down into mmap, prefault and parse time, and is run
+/// once without and once with prefaulting so the page-fault cost moved out
+/// of the first query is visible.
+pub fn bench_load(temp_file: &Path, load_iterations: usize, first_query: &str) -> Result<()> {
+    use matchy::Database;
+
+    let path = temp_file
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+
+    println!("--- Phase 3: Load Database (mmap) ---");
+    for prefault in [false, true] {
+        let mut load_times = Vec::new();
+        let mut first_query_times = Vec::new();
+        for i in 1..=load_iterations {
+            let mut opener = Database::from(path);
+            if prefault {
+                opener = opener.prefault();
+            }
+            let (db, timing) = opener.open_timed()?;
+
+            if i == 1 && !prefault {
+                println!("  Format:   {}", db.format());
+                println!("  Without prefault:");
+            } else if i == 1 {
+                println!("  With prefault:");
+            }
+
+            let query_start = Instant::now();
+            db.lookup(first_query)?;
+            let first_query_time = query_start.elapsed();
+
+            println!(
+                "    Load #{}: {:.3}ms (mmap {:.3}ms, prefault {:.3}ms, parse {:.3}ms), first query {:.3}ms",
+                i,
+                millis(timing.total()),
+                millis(timing.mmap),
+                millis(timing.prefault),
+                millis(timing.parse),
+                millis(first_query_time)
+            );
+            load_times.push(timing.total());
+            first_query_times.push(first_query_time);
+        }
+        let avg_load = load_times.iter().sum::<Duration>() / load_iterations as u32;
+        let avg_first = first_query_times.iter().sum::<Duration>() / load_iterations as u32;
+        println!(
+            "    Average:  {:.3}ms load, {:.3}ms first query",
+            millis(avg_load),
+            millis(avg_first)
+        );
+    }
+    println!();
+
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use anyhow::{Context, Result};
-use matchy::{glob::MatchMode, mmdb_builder::MmdbBuilder, DataValue};
+use matchy::misp_importer::MispImporter;
+use matchy::rules_importer::import_rules_file;
+use matchy::{glob::MatchMode, mmdb_builder::MmdbBuilder, DataValue, Database, DuplicatePolicy};
 use std::collections::HashMap;
 use std::fs;
 use std::io::{self, BufRead};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -33,6 +36,135 @@ fn set_readonly(path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Input file formats understood by `matchy build`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputFormat {
+    Text,
+    Csv,
+    Json,
+    Jsonl,
+    Misp,
+    Rules,
+}
+
+impl InputFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "jsonl" => Some(Self::Jsonl),
+            "misp" => Some(Self::Misp),
+            "rules" => Some(Self::Rules),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Jsonl => "jsonl",
+            Self::Misp => "misp",
+            Self::Rules => "rules",
+        }
+    }
+
+    /// Guess a file's format from its extension (and content, for .json)
+    fn detect(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        Ok(match extension.as_deref() {
+            Some("csv") => Self::Csv,
+            Some("jsonl") | Some("ndjson") => Self::Jsonl,
+            Some("toml") => Self::Rules,
+            Some("json") => {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read input file: {}", path.display()))?;
+                if content.contains("\"Event\"") {
+                    Self::Misp
+                } else {
+                    Self::Json
+                }
+            }
+            _ => Self::Text,
+        })
+    }
+}
+
+/// Split an input argument into its path and an optional `FORMAT:` prefix
+///
+/// Only known format names are treated as a prefix, so Windows drive
+/// letters such as `C:\feeds` are left alone.
+fn parse_input_spec(spec: &Path) -> (PathBuf, Option<InputFormat>) {
+    if let Some((prefix, rest)) = spec.to_str().and_then(|s| s.split_once(':')) {
+        if let Some(format) = InputFormat::from_name(prefix) {
+            if !rest.is_empty() {
+                return (PathBuf::from(rest), Some(format));
+            }
+        }
+    }
+    (spec.to_path_buf(), None)
+}
+
+fn parse_duplicate_policy(name: &str) -> Result<DuplicatePolicy> {
+    match name {
+        "keep-all" => Ok(DuplicatePolicy::KeepAll),
+        "keep-first" => Ok(DuplicatePolicy::KeepFirst),
+        "keep-last" => Ok(DuplicatePolicy::KeepLast),
+        "error" => Ok(DuplicatePolicy::Error),
+        _ => anyhow::bail!(
+            "Unknown duplicate policy: {}. Use 'keep-all', 'keep-first', 'keep-last', or 'error'",
+            name
+        ),
+    }
+}
+
+/// Builder layout options chosen by `--auto-tune`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    intern_strings: bool,
+}
+
+impl Layout {
+    /// Every combination `--auto-tune` tries
+    const CANDIDATES: [Layout; 2] = [
+        Layout {
+            intern_strings: false,
+        },
+        Layout {
+            intern_strings: true,
+        },
+    ];
+
+    fn describe(self) -> &'static str {
+        if self.intern_strings {
+            "interned strings"
+        } else {
+            "inline strings"
+        }
+    }
+
+    fn apply(self, builder: MmdbBuilder) -> MmdbBuilder {
+        if self.intern_strings {
+            builder.with_string_interning()
+        } else {
+            builder
+        }
+    }
+}
+
+/// One `--auto-tune` candidate
+struct TuneResult {
+    layout: Layout,
+    bytes: Vec<u8>,
+    /// Mean lookup time over the sample queries, in nanoseconds
+    latency_ns: Option<f64>,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn cmd_build(
     inputs: Vec<PathBuf>,
@@ -44,6 +176,10 @@ pub fn cmd_build(
     verbose: bool,
     debug: bool,
     case_insensitive: bool,
+    on_duplicate: String,
+    intern_strings: bool,
+    auto_tune: bool,
+    tune_queries: Option<PathBuf>,
 ) -> Result<()> {
     let match_mode = if case_insensitive {
         MatchMode::CaseInsensitive
@@ -51,11 +187,37 @@ pub fn cmd_build(
         MatchMode::CaseSensitive
     };
 
+    let default_format = match format.as_str() {
+        "auto" => None,
+        name => Some(InputFormat::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown format: {}. Use 'auto', 'text', 'csv', 'json', 'jsonl', 'misp', or 'rules'",
+                name
+            )
+        })?),
+    };
+    let duplicate_policy = parse_duplicate_policy(&on_duplicate)?;
+
+    // Resolve every input's format up front so a bad file fails before any work
+    let mut sources = Vec::with_capacity(inputs.len());
+    for spec in &inputs {
+        let (path, explicit) = parse_input_spec(spec);
+        let input_format = match explicit.or(default_format) {
+            Some(f) => f,
+            None => InputFormat::detect(&path)?,
+        };
+        sources.push((
+            path,
+            input_format,
+            explicit.is_some() || default_format.is_some(),
+        ));
+    }
+
     if debug {
         println!("Building unified MMDB database (IP + patterns)...");
-        println!("  Input files: {}", inputs.len());
-        for input in &inputs {
-            println!("    - {}", input.display());
+        println!("  Input files: {}", sources.len());
+        for (path, input_format, _) in &sources {
+            println!("    - {} ({})", path.display(), input_format.name());
         }
         println!("  Output: {}", output.display());
         println!("  Format: {}", format);
@@ -67,248 +229,67 @@ pub fn cmd_build(
                 "case-sensitive"
             }
         );
+        println!("  On duplicate: {}", on_duplicate);
         println!();
     }
 
-    let mut builder = MmdbBuilder::new(match_mode);
-
-    // Apply metadata if provided
-    if let Some(db_type) = database_type {
-        builder = builder.with_database_type(db_type);
-    }
-
-    if let Some(desc) = description {
-        builder = builder.with_description(desc_lang, desc);
-    }
-
-    match format.as_str() {
-        "text" => {
-            // Read entries from text file(s) (one per line)
-            // Auto-detects IP addresses/CIDRs vs patterns
-            let mut total_count = 0;
-
-            for input in &inputs {
-                if debug && inputs.len() > 1 {
-                    println!("  Reading: {}...", input.display());
-                }
-
-                // Validate that the file doesn't look like JSON or CSV
-                // (common user error: using wrong format flag)
-                if let Ok(content) = fs::read_to_string(input) {
-                    let trimmed = content.trim_start();
-                    if trimmed.starts_with('{') || trimmed.starts_with('[') {
-                        if trimmed.contains("\"Event\"") {
-                            anyhow::bail!(
-                                "File {} appears to be MISP JSON format.\n\n\
-                                You specified --format text, but this looks like MISP JSON.\n\
-                                Try: --format misp (or -f misp)",
-                                input.display()
-                            );
-                        } else {
-                            eprintln!(
-                                "Warning: {} looks like JSON but you specified --format text.\n\
-                                If this is a JSON file, use --format json instead.",
-                                input.display()
-                            );
-                        }
-                    }
-                    // Check for CSV-like content
-                    let first_line = content.lines().next().unwrap_or("");
-                    if first_line.contains(',') && first_line.split(',').count() > 3 {
-                        eprintln!(
-                            "Warning: {} looks like CSV but you specified --format text.\n\
-                            If this is a CSV file, use --format csv instead.",
-                            input.display()
-                        );
-                    }
-                }
-
-                let file = fs::File::open(input)
-                    .with_context(|| format!("Failed to open input file: {}", input.display()))?;
-                let reader = io::BufReader::new(file);
-
-                let mut count = 0;
-                for line in reader.lines() {
-                    let line = line?;
-                    let entry = line.trim();
-                    if !entry.is_empty() && !entry.starts_with('#') {
-                        // Auto-detection: builder will determine if it's IP or pattern
-                        builder.add_entry(entry, HashMap::new())?;
-                        count += 1;
-                        total_count += 1;
-                        if debug && total_count % 1000 == 0 {
-                            println!("    Added {} entries...", total_count);
-                        }
-                    }
-                }
-
-                if debug && inputs.len() > 1 {
-                    println!("    {} entries from this file", count);
-                }
-            }
-
-            if debug {
-                println!("  Total: {} entries", total_count);
-            }
+    // MISP-only builds keep the MISP importer's metadata unless overridden
+    let all_misp = sources.iter().all(|(_, f, _)| *f == InputFormat::Misp);
+    let database_type = database_type.or_else(|| all_misp.then(|| "MISP-ThreatIntel".to_string()));
+    let description = description.or_else(|| {
+        all_misp.then(|| "Threat intelligence database from MISP JSON feeds".to_string())
+    });
+
+    // Every --auto-tune candidate reads the inputs again into a fresh builder
+    let load = |layout: Layout, debug: bool| -> Result<(MmdbBuilder, Vec<_>)> {
+        let mut builder =
+            layout.apply(MmdbBuilder::new(match_mode).with_duplicate_policy(duplicate_policy));
+
+        // Apply metadata if provided
+        if let Some(db_type) = &database_type {
+            builder = builder.with_database_type(db_type.clone());
         }
-        "csv" => {
-            // Read entries with data from CSV file(s)
-            // First column must be named "entry" (or "key") containing IP/CIDR/pattern
-            // Remaining columns become metadata fields
-            let mut total_entries = 0;
-
-            for input in &inputs {
-                if debug && inputs.len() > 1 {
-                    println!("  Reading: {}...", input.display());
-                }
-
-                let file = fs::File::open(input)
-                    .with_context(|| format!("Failed to open CSV file: {}", input.display()))?;
-                let mut reader = csv::Reader::from_reader(file);
-
-                // Get headers
-                let headers = reader.headers().context("Failed to read CSV headers")?;
-
-                // Find the entry column (try "entry" or "key")
-                let entry_col = headers
-                    .iter()
-                    .position(|h| h == "entry" || h == "key")
-                    .ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "CSV must have an 'entry' or 'key' column. Found headers: {}",
-                            headers.iter().collect::<Vec<_>>().join(", ")
-                        )
-                    })?;
-
-                // Get other column names for metadata
-                let data_cols: Vec<(usize, String)> = headers
-                    .iter()
-                    .enumerate()
-                    .filter(|(i, _)| *i != entry_col)
-                    .map(|(i, name)| (i, name.to_string()))
-                    .collect();
-
-                // Process each row
-                for (row_num, result) in reader.records().enumerate() {
-                    let record = result.context("Failed to read CSV record")?;
-
-                    // Get the entry value
-                    let entry = record.get(entry_col).ok_or_else(|| {
-                        anyhow::anyhow!("Missing entry column at row {}", row_num + 2)
-                    })?;
-
-                    // Build data map from other columns
-                    let mut data = HashMap::new();
-                    for (col_idx, col_name) in &data_cols {
-                        if let Some(value) = record.get(*col_idx) {
-                            if !value.is_empty() {
-                                // Try to parse as number, otherwise treat as string
-                                let data_value = if let Ok(i) = value.parse::<i64>() {
-                                    DataValue::Int32(i as i32)
-                                } else if let Ok(u) = value.parse::<u64>() {
-                                    DataValue::Uint64(u)
-                                } else if let Ok(f) = value.parse::<f64>() {
-                                    DataValue::Double(f)
-                                } else if value == "true" || value == "false" {
-                                    DataValue::Bool(value == "true")
-                                } else {
-                                    DataValue::String(value.to_string())
-                                };
-                                data.insert(col_name.clone(), data_value);
-                            }
-                        }
-                    }
-
-                    builder.add_entry(entry, data)?;
-                    total_entries += 1;
-
-                    if debug && total_entries % 1000 == 0 {
-                        println!("    Added {} entries...", total_entries);
-                    }
-                }
 
-                if debug && inputs.len() > 1 {
-                    println!("    {} entries from this file", reader.position().line());
-                }
-            }
-
-            if debug {
-                println!("  Total: {} entries", total_entries);
-            }
+        if let Some(desc) = &description {
+            builder = builder.with_description(desc_lang.clone(), desc.clone());
         }
-        "json" => {
-            // Read entries with data from JSON file(s)
-            // Format: [{"key": "192.168.0.0/16" or "*.example.com", "data": {...}}]
-            let mut total_entries = 0;
-
-            for input in &inputs {
-                if debug && inputs.len() > 1 {
-                    println!("  Reading: {}...", input.display());
-                }
-
-                let content = fs::read_to_string(input)
-                    .with_context(|| format!("Failed to read JSON file: {}", input.display()))?;
-                let entries: Vec<serde_json::Value> =
-                    serde_json::from_str(&content).context("Failed to parse JSON")?;
-
-                for (i, item) in entries.iter().enumerate() {
-                    let key = item
-                        .get("key")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| anyhow::anyhow!("Missing 'key' field at index {}", i))?;
-
-                    let data = if let Some(data_json) = item.get("data") {
-                        json_to_data_map(data_json)?
-                    } else {
-                        HashMap::new()
-                    };
 
-                    builder.add_entry(key, data)?;
-                    total_entries += 1;
-
-                    if debug && total_entries % 1000 == 0 {
-                        println!("    Added {} entries...", total_entries);
-                    }
-                }
-
-                if debug && inputs.len() > 1 {
-                    println!("    {} entries from this file", entries.len());
-                }
+        let mut report = Vec::with_capacity(sources.len());
+        for (path, input_format, explicit) in &sources {
+            if debug && sources.len() > 1 {
+                println!("  Reading: {} ({})...", path.display(), input_format.name());
             }
 
-            if debug {
-                println!("  Total: {} entries", total_entries);
+            let before = builder.stats().total_entries;
+            match input_format {
+                InputFormat::Text => add_text_file(&mut builder, path, *explicit, debug)?,
+                InputFormat::Csv => add_csv_file(&mut builder, path, debug)?,
+                InputFormat::Json => add_json_file(&mut builder, path, debug)?,
+                InputFormat::Jsonl => add_jsonl_file(&mut builder, path)?,
+                InputFormat::Misp => add_misp_file(&mut builder, path)?,
+                InputFormat::Rules => add_rules_file(&mut builder, path)?,
             }
-        }
-        "misp" => {
-            // Read MISP JSON threat intelligence file(s) with streaming (low memory)
-            use matchy::misp_importer::MispImporter;
+            let added = builder.stats().total_entries - before;
 
-            if debug {
-                println!("  Processing MISP JSON files (streaming mode)...");
-            }
-
-            // Convert Vec<PathBuf> to Vec<&Path> for build_from_files
-            let input_refs: Vec<&PathBuf> = inputs.iter().collect();
-
-            // Use streaming import to process one file at a time
-            // This keeps memory usage low even for very large datasets
-            builder = MispImporter::build_from_files(
-                &input_refs,
-                MatchMode::CaseSensitive,
-                false, // Use full metadata
-            )
-            .context("Failed to process MISP JSON files")?;
-
-            if debug {
-                let stats = builder.stats();
-                println!("  Total indicators: {}", stats.total_entries);
+            if debug && sources.len() > 1 {
+                println!("    {} entries from this file", added);
             }
+            report.push((path, *input_format, added));
         }
-        _ => {
-            anyhow::bail!(
-                "Unknown format: {}. Use 'text', 'csv', 'json', or 'misp'",
-                format
+        Ok((builder, report))
+    };
+
+    let layout = Layout { intern_strings };
+    let (builder, report) = load(layout, debug)?;
+
+    if (verbose || debug) && report.len() > 1 {
+        println!("\nInputs:");
+        for (path, input_format, added) in &report {
+            println!(
+                "  {:<6} {:>10} entries  {}",
+                input_format.name(),
+                added,
+                path.display()
             );
         }
     }
@@ -321,13 +302,58 @@ pub fn cmd_build(
         println!("  IP entries:      {}", stats.ip_entries);
         println!("  Literal entries: {}", stats.literal_entries);
         println!("  Glob entries:    {}", stats.glob_entries);
+        if stats.duplicate_entries > 0 {
+            println!(
+                "  Duplicate keys:  {} ({})",
+                stats.duplicate_entries, on_duplicate
+            );
+        }
+
+        let estimate = builder.estimate_size();
+        println!(
+            "  Estimated size:  {:.2} MB ({}-bit records)",
+            estimate.total_size as f64 / (1024.0 * 1024.0),
+            estimate.record_size as u8
+        );
+        if intern_strings {
+            println!(
+                "  String dictionary: {} strings ({} bytes)",
+                estimate.dictionary_strings, estimate.dictionary_size
+            );
+        }
     }
 
     if debug {
         println!("\nSerializing...");
     }
 
-    let database_bytes = builder.build().context("Failed to build database")?;
+    let database_bytes = if auto_tune {
+        let queries = tune_queries.as_deref().map(read_tune_queries).transpose()?;
+        let mut results = Vec::with_capacity(Layout::CANDIDATES.len());
+        let mut loaded = Some((layout, builder));
+        for layout in Layout::CANDIDATES {
+            // The default layout is already loaded; the others read the inputs again
+            let builder = match loaded.take() {
+                Some((loaded_layout, builder)) if loaded_layout == layout => builder,
+                _ => load(layout, false)?.0,
+            };
+            let bytes = builder.build().context("Failed to build database")?;
+            let latency_ns = match &queries {
+                Some(queries) => Some(measure_latency(&bytes, queries)?),
+                None => None,
+            };
+            results.push(TuneResult {
+                layout,
+                bytes,
+                latency_ns,
+            });
+        }
+        let best = pick_tuned(&results);
+        print_tune_report(&results, best);
+        results.swap_remove(best).bytes
+    } else {
+        builder.build().context("Failed to build database")?
+    };
 
     if debug {
         println!("Writing to disk...");
@@ -363,3 +389,271 @@ pub fn cmd_build(
 
     Ok(())
 }
+
+/// Read `--tune-queries`: one query per line, skipping blanks and `#` comments
+fn read_tune_queries(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read query file: {}", path.display()))?;
+    let queries: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if queries.is_empty() {
+        anyhow::bail!("No queries in {}", path.display());
+    }
+    Ok(queries)
+}
+
+/// Mean lookup time of `queries` against a candidate, with the cache off
+///
+/// One untimed pass warms the page cache and the glob matcher's buffers.
+fn measure_latency(bytes: &[u8], queries: &[String]) -> Result<f64> {
+    let db = Database::from_bytes_builder(bytes.to_vec())
+        .no_cache()
+        .open()
+        .context("Failed to open candidate database")?;
+    for query in queries {
+        let _ = db.lookup(query);
+    }
+    let start = Instant::now();
+    for query in queries {
+        std::hint::black_box(db.lookup(query).ok());
+    }
+    Ok(start.elapsed().as_nanos() as f64 / queries.len() as f64)
+}
+
+/// Index of the winning `--auto-tune` candidate
+///
+/// Without timings the smallest database wins. With them, the fastest
+/// candidate no more than 10% larger than the smallest wins, so a layout
+/// never trades a large size increase for noise-level speedups.
+fn pick_tuned(results: &[TuneResult]) -> usize {
+    let smallest = results.iter().map(|r| r.bytes.len()).min().unwrap_or(0);
+    (0..results.len())
+        .filter(|&i| results[i].bytes.len() * 10 <= smallest * 11)
+        .min_by(|&a, &b| {
+            let (a, b) = (&results[a], &results[b]);
+            a.latency_ns
+                .unwrap_or(0.0)
+                .total_cmp(&b.latency_ns.unwrap_or(0.0))
+                .then(a.bytes.len().cmp(&b.bytes.len()))
+        })
+        .unwrap_or(0)
+}
+
+fn print_tune_report(results: &[TuneResult], best: usize) {
+    println!("\nAuto-tune:");
+    for (i, result) in results.iter().enumerate() {
+        let latency = match result.latency_ns {
+            Some(ns) => format!("  {:>10.0} ns/query", ns),
+            None => String::new(),
+        };
+        println!(
+            "  {} {:<42} {:>12} bytes{}",
+            if i == best { "*" } else { " " },
+            result.layout.describe(),
+            result.bytes.len(),
+            latency
+        );
+    }
+    println!("  Chose: {}", results[best].layout.describe());
+}
+
+/// Add one entry per line, auto-detecting IPs/CIDRs vs patterns
+fn add_text_file(
+    builder: &mut MmdbBuilder,
+    input: &Path,
+    explicit: bool,
+    debug: bool,
+) -> Result<()> {
+    // Validate that the file doesn't look like JSON or CSV
+    // (common user error: using wrong format flag)
+    if explicit {
+        if let Ok(content) = fs::read_to_string(input) {
+            let trimmed = content.trim_start();
+            if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                if trimmed.contains("\"Event\"") {
+                    anyhow::bail!(
+                        "File {} appears to be MISP JSON format.\n\n\
+                        You specified --format text, but this looks like MISP JSON.\n\
+                        Try: --format misp (or -f misp)",
+                        input.display()
+                    );
+                } else {
+                    eprintln!(
+                        "Warning: {} looks like JSON but you specified --format text.\n\
+                        If this is a JSON file, use --format json instead.",
+                        input.display()
+                    );
+                }
+            }
+            // Check for CSV-like content
+            let first_line = content.lines().next().unwrap_or("");
+            if first_line.contains(',') && first_line.split(',').count() > 3 {
+                eprintln!(
+                    "Warning: {} looks like CSV but you specified --format text.\n\
+                    If this is a CSV file, use --format csv instead.",
+                    input.display()
+                );
+            }
+        }
+    }
+
+    let file = fs::File::open(input)
+        .with_context(|| format!("Failed to open input file: {}", input.display()))?;
+    let reader = io::BufReader::new(file);
+
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line?;
+        let entry = line.trim();
+        if !entry.is_empty() && !entry.starts_with('#') {
+            // Auto-detection: builder will determine if it's IP or pattern
+            builder.add_entry(entry, HashMap::new())?;
+            count += 1;
+            if debug && count % 1000 == 0 {
+                println!("    Added {} entries...", count);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Add CSV rows: an 'entry' (or 'key') column plus metadata columns
+fn add_csv_file(builder: &mut MmdbBuilder, input: &Path, debug: bool) -> Result<()> {
+    let file = fs::File::open(input)
+        .with_context(|| format!("Failed to open CSV file: {}", input.display()))?;
+    let mut reader = csv::Reader::from_reader(file);
+
+    // Get headers
+    let headers = reader.headers().context("Failed to read CSV headers")?;
+
+    // Find the entry column (try "entry" or "key")
+    let entry_col = headers
+        .iter()
+        .position(|h| h == "entry" || h == "key")
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "CSV must have an 'entry' or 'key' column. Found headers: {}",
+                headers.iter().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+
+    // Get other column names for metadata
+    let data_cols: Vec<(usize, String)> = headers
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != entry_col)
+        .map(|(i, name)| (i, name.to_string()))
+        .collect();
+
+    // Process each row
+    for (row_num, result) in reader.records().enumerate() {
+        let record = result.context("Failed to read CSV record")?;
+
+        // Get the entry value
+        let entry = record
+            .get(entry_col)
+            .ok_or_else(|| anyhow::anyhow!("Missing entry column at row {}", row_num + 2))?;
+
+        // Build data map from other columns
+        let mut data = HashMap::new();
+        for (col_idx, col_name) in &data_cols {
+            if let Some(value) = record.get(*col_idx) {
+                if !value.is_empty() {
+                    // Try to parse as number, otherwise treat as string
+                    let data_value = if let Ok(i) = value.parse::<i64>() {
+                        DataValue::Int32(i as i32)
+                    } else if let Ok(u) = value.parse::<u64>() {
+                        DataValue::Uint64(u)
+                    } else if let Ok(f) = value.parse::<f64>() {
+                        DataValue::Double(f)
+                    } else if value == "true" || value == "false" {
+                        DataValue::Bool(value == "true")
+                    } else {
+                        DataValue::String(value.to_string())
+                    };
+                    data.insert(col_name.clone(), data_value);
+                }
+            }
+        }
+
+        builder.add_entry(entry, data)?;
+
+        if debug && (row_num + 1) % 1000 == 0 {
+            println!("    Added {} entries...", row_num + 1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add entries from a JSON array of `{"key": ..., "data": {...}}` objects
+fn add_json_file(builder: &mut MmdbBuilder, input: &Path, debug: bool) -> Result<()> {
+    let content = fs::read_to_string(input)
+        .with_context(|| format!("Failed to read JSON file: {}", input.display()))?;
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(&content).context("Failed to parse JSON")?;
+
+    for (i, item) in entries.iter().enumerate() {
+        let key = item
+            .get("key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'key' field at index {}", i))?;
+
+        let data = if let Some(data_json) = item.get("data") {
+            json_to_data_map(data_json)?
+        } else {
+            HashMap::new()
+        };
+
+        builder.add_entry(key, data)?;
+
+        if debug && (i + 1) % 1000 == 0 {
+            println!("    Added {} entries...", i + 1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add newline-delimited JSON objects keyed by their "key" field
+///
+/// Malformed lines are skipped with a warning, like the library importer.
+fn add_jsonl_file(builder: &mut MmdbBuilder, input: &Path) -> Result<()> {
+    let file = fs::File::open(input)
+        .with_context(|| format!("Failed to open JSONL file: {}", input.display()))?;
+    let failures = builder
+        .import_jsonl(io::BufReader::new(file), "key")
+        .with_context(|| format!("Failed to read JSONL file: {}", input.display()))?;
+
+    if !failures.is_empty() {
+        eprintln!(
+            "Warning: Skipped {} malformed line(s) in {}:",
+            failures.len(),
+            input.display()
+        );
+        for (line, error) in failures.iter().take(10) {
+            eprintln!("  - line {}: {}", line, error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add indicators from a MISP JSON event file
+fn add_misp_file(builder: &mut MmdbBuilder, input: &Path) -> Result<()> {
+    MispImporter::from_files(&[input])
+        .and_then(|importer| importer.add_to_builder(builder, false))
+        .with_context(|| format!("Failed to process MISP JSON file: {}", input.display()))
+}
+
+/// Add entries from a TOML rule file
+fn add_rules_file(builder: &mut MmdbBuilder, input: &Path) -> Result<()> {
+    import_rules_file(builder, input)
+        .map(|_| ())
+        .with_context(|| format!("Failed to process rule file: {}", input.display()))
+}
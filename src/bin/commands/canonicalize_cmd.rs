@@ -0,0 +1,22 @@
+use anyhow::{Context, Result};
+use matchy::Database;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub fn cmd_canonicalize(database: PathBuf, output: Option<PathBuf>) -> Result<()> {
+    let db = Database::from(database.to_str().unwrap())
+        .open()
+        .with_context(|| format!("Failed to load database: {}", database.display()))?;
+
+    let text = db
+        .canonical_text()
+        .with_context(|| format!("Failed to read entries from: {}", database.display()))?;
+
+    match output {
+        Some(path) => std::fs::write(&path, text)
+            .with_context(|| format!("Failed to write: {}", path.display()))?,
+        None => std::io::stdout().lock().write_all(text.as_bytes())?,
+    }
+
+    Ok(())
+}
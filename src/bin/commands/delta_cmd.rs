@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use matchy::delta::Delta;
+use matchy::Database;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn open(path: &Path) -> Result<Database> {
+    Database::from(path.to_str().unwrap())
+        .open()
+        .with_context(|| format!("Failed to load database: {}", path.display()))
+}
+
+pub fn cmd_diff_build(old: PathBuf, new: PathBuf, output: PathBuf) -> Result<()> {
+    let old_db = open(&old)?;
+    let new_db = open(&new)?;
+
+    let delta = Delta::between(&old_db, &new_db).context("Failed to compare databases")?;
+    let bytes = delta.to_bytes();
+    fs::write(&output, &bytes)
+        .with_context(|| format!("Failed to write patch: {}", output.display()))?;
+
+    println!(
+        "Patch: {} added, {} changed, {} removed ({} bytes)",
+        delta.added.len(),
+        delta.changed.len(),
+        delta.removed.len(),
+        bytes.len()
+    );
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+pub fn cmd_apply_patch(base: PathBuf, patch: PathBuf, output: PathBuf) -> Result<()> {
+    let base_db = open(&base)?;
+    let bytes =
+        fs::read(&patch).with_context(|| format!("Failed to read patch: {}", patch.display()))?;
+    let delta = Delta::from_bytes(&bytes)
+        .with_context(|| format!("Failed to parse patch: {}", patch.display()))?;
+
+    let database = delta
+        .apply(&base_db)
+        .with_context(|| format!("Failed to apply {} to {}", patch.display(), base.display()))?;
+    fs::write(&output, &database)
+        .with_context(|| format!("Failed to write database: {}", output.display()))?;
+
+    println!(
+        "Applied {} added, {} changed, {} removed",
+        delta.added.len(),
+        delta.changed.len(),
+        delta.removed.len()
+    );
+    println!("Wrote {}", output.display());
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anyhow::{Context, Result};
+use matchy::Database;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+pub fn cmd_export(database: PathBuf, output: Option<PathBuf>) -> Result<()> {
+    let db = Database::from(database.to_str().unwrap())
+        .open()
+        .with_context(|| format!("Failed to load database: {}", database.display()))?;
+
+    let mut writer: Box<dyn Write> = match &output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("Failed to create: {}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+
+    db.export_ndjson(&mut writer)
+        .with_context(|| format!("Failed to export entries from: {}", database.display()))?;
+
+    Ok(())
+}
@@ -52,8 +52,8 @@ pub fn cmd_extract(
     let output_format = OutputFormat::from_str(&format)?;
 
     // Parse extraction types
-    let (extract_ipv4, extract_ipv6, extract_domains, extract_emails) =
-        if let Some(type_str) = types {
+    let (extract_ipv4, extract_ipv6, extract_domains, extract_emails, extract_urls) = match types {
+        Some(type_str) => {
             let types_lower = type_str.to_lowercase();
             let parts: Vec<&str> = types_lower.split(',').map(|s| s.trim()).collect();
 
@@ -61,6 +61,7 @@ pub fn cmd_extract(
             let mut ipv6 = false;
             let mut domains = false;
             let mut emails = false;
+            let mut urls = false;
 
             for part in parts {
                 match part {
@@ -68,6 +69,7 @@ pub fn cmd_extract(
                     "ipv6" | "ip6" => ipv6 = true,
                     "domain" | "domains" => domains = true,
                     "email" | "emails" => emails = true,
+                    "url" | "urls" => urls = true,
                     "ip" => {
                         ipv4 = true;
                         ipv6 = true;
@@ -77,23 +79,24 @@ pub fn cmd_extract(
                         ipv6 = true;
                         domains = true;
                         emails = true;
+                        urls = true;
                     }
                     _ => anyhow::bail!(
-                    "Unknown extraction type '{}', expected: ipv4, ipv6, ip, domain, email, all",
-                    part
-                ),
+                        "Unknown extraction type '{}', expected: ipv4, ipv6, ip, domain, email, url, all",
+                        part
+                    ),
                 }
             }
 
-            if !ipv4 && !ipv6 && !domains && !emails {
+            if !ipv4 && !ipv6 && !domains && !emails && !urls {
                 anyhow::bail!("At least one extraction type must be enabled");
             }
 
-            (ipv4, ipv6, domains, emails)
-        } else {
-            // Default: extract everything
-            (true, true, true, true)
-        };
+            (ipv4, ipv6, domains, emails, urls)
+        }
+        // Default: extract everything except URLs
+        None => (true, true, true, true, false),
+    };
 
     // Build extractor
     let extractor = Extractor::builder()
@@ -101,6 +104,7 @@ pub fn cmd_extract(
         .extract_ipv6(extract_ipv6)
         .extract_domains(extract_domains)
         .extract_emails(extract_emails)
+        .extract_urls(extract_urls)
         .min_domain_labels(min_labels)
         .require_word_boundaries(!no_boundaries)
         .build()
@@ -116,6 +120,7 @@ pub fn cmd_extract(
                 None
             },
             if extract_emails { Some("emails") } else { None },
+            if extract_urls { Some("URLs") } else { None },
         ]
         .iter()
         .filter_map(|&x| x)
@@ -288,6 +293,8 @@ fn process_file<W: Write>(
                 ExtractedItem::Bitcoin(_) => {}
                 ExtractedItem::Ethereum(_) => {}
                 ExtractedItem::Monero(_) => {}
+                ExtractedItem::Url(_) => {}
+                _ => {}
             }
         }
     }
@@ -91,6 +91,14 @@ pub fn cmd_inspect(database: PathBuf, json_output: bool, verbose: bool) -> Resul
                     }
                 }
 
+                // Show provenance fields if present
+                if let Some(DataValue::String(tag)) = map.get("version_tag") {
+                    println!("  Version tag:     {}", tag);
+                }
+                if let Some(DataValue::String(comment)) = map.get("comment") {
+                    println!("  Comment:         {}", comment);
+                }
+
                 // Show build epoch if present
                 if let Some(build_epoch) = map.get("build_epoch") {
                     if let Some(epoch) = extract_uint_from_datavalue(build_epoch) {
@@ -114,7 +114,9 @@ pub fn cmd_match(
         .extract_hashes(extractor_config.should_enable("hash", default_hashes))
         .extract_bitcoin(extractor_config.should_enable("bitcoin", default_bitcoin))
         .extract_ethereum(extractor_config.should_enable("ethereum", default_ethereum))
-        .extract_monero(extractor_config.should_enable("monero", default_monero));
+        .extract_monero(extractor_config.should_enable("monero", default_monero))
+        // URLs repeat their host's domain/IP match, so they are opt-in
+        .extract_urls(extractor_config.should_enable("url", false));
 
     let extractor = builder
         .build()
@@ -147,6 +149,9 @@ pub fn cmd_match(
         if extractor.extract_monero() {
             enabled.push("Monero");
         }
+        if extractor.extract_urls() {
+            enabled.push("URLs");
+        }
 
         eprintln!(
             "[INFO] Extractors: {}",
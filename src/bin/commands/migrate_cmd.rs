@@ -0,0 +1,19 @@
+use anyhow::{Context, Result};
+use matchy::Database;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn cmd_migrate(input: PathBuf, output: PathBuf, to_version: u32) -> Result<()> {
+    let database = Database::reencode(&input, to_version)
+        .with_context(|| format!("Failed to re-encode database: {}", input.display()))?;
+
+    // Make sure the result loads before replacing anything
+    Database::from_bytes(database.clone())
+        .with_context(|| format!("Re-encoded database failed to load: {}", input.display()))?;
+
+    fs::write(&output, &database)
+        .with_context(|| format!("Failed to write database: {}", output.display()))?;
+
+    println!("Wrote {} (format v{})", output.display(), to_version);
+    Ok(())
+}
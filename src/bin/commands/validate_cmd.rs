@@ -7,17 +7,24 @@ use std::time::Instant;
 pub fn cmd_validate(
     database: PathBuf,
     level_str: String,
-    json_output: bool,
+    format: String,
     verbose: bool,
 ) -> Result<()> {
+    let json_output = match format.to_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        _ => anyhow::bail!("Invalid format: '{}'. Must be: text or json", format),
+    };
+
     // Parse validation level
     let level = match level_str.to_lowercase().as_str() {
         "standard" => ValidationLevel::Standard,
         "strict" => ValidationLevel::Strict,
         "audit" => ValidationLevel::Audit,
+        "exhaustive" => ValidationLevel::Exhaustive,
         _ => {
             anyhow::bail!(
-                "Invalid validation level: '{}'. Must be: standard, strict, or audit",
+                "Invalid validation level: '{}'. Must be: standard, strict, audit, or exhaustive",
                 level_str
             );
         }
@@ -31,26 +38,10 @@ pub fn cmd_validate(
 
     // Output results
     if json_output {
-        let output = json!({
-            "database": database.display().to_string(),
-            "validation_level": level_str,
-            "is_valid": report.is_valid(),
-            "duration_ms": duration.as_millis(),
-            "errors": report.errors,
-            "warnings": report.warnings,
-            "info": report.info,
-            "stats": {
-                "file_size": report.stats.file_size,
-                "version": report.stats.version,
-                "ac_node_count": report.stats.ac_node_count,
-                "pattern_count": report.stats.pattern_count,
-                "ip_entry_count": report.stats.ip_entry_count,
-                "literal_count": report.stats.literal_count,
-                "glob_count": report.stats.glob_count,
-                "has_data_section": report.stats.has_data_section,
-                "has_ac_literal_mapping": report.stats.has_ac_literal_mapping,
-            }
-        });
+        let mut output = report.to_json();
+        output["database"] = json!(database.display().to_string());
+        output["validation_level"] = json!(level_str);
+        output["duration_ms"] = json!(duration.as_millis());
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
         // Human-readable output
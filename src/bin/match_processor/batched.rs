@@ -281,7 +281,8 @@ pub fn process_file_batched(
                     | matchy::extractor::ExtractedItem::Hash(_, s)
                     | matchy::extractor::ExtractedItem::Bitcoin(s)
                     | matchy::extractor::ExtractedItem::Ethereum(s)
-                    | matchy::extractor::ExtractedItem::Monero(s) => db.lookup(s)?,
+                    | matchy::extractor::ExtractedItem::Monero(s)
+                    | matchy::extractor::ExtractedItem::Url(s) => db.lookup(s)?,
                     _ => continue,
                 };
 
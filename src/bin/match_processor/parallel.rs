@@ -61,6 +61,7 @@ impl ExtractorConfig {
             "domains" => vec!["domain"],
             "emails" => vec!["email"],
             "hashes" => vec!["hash"],
+            "urls" => vec!["url"],
             "ips" => vec!["ipv4", "ipv6"],
             // Pass through as-is
             _ => vec![name],
@@ -388,6 +389,8 @@ pub fn create_extractor_for_db(
         .extract_bitcoin(config.should_enable("bitcoin", default_bitcoin))
         .extract_ethereum(config.should_enable("ethereum", default_ethereum))
         .extract_monero(config.should_enable("monero", default_monero))
+        // URLs repeat their host's domain/IP match, so they are opt-in
+        .extract_urls(config.should_enable("url", false))
         .build()
         .context("Failed to create extractor")
 }
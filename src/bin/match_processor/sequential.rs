@@ -111,6 +111,8 @@ pub fn process_file(
                     matchy::extractor::ExtractedItem::Bitcoin(_) => {}
                     matchy::extractor::ExtractedItem::Ethereum(_) => {}
                     matchy::extractor::ExtractedItem::Monero(_) => {}
+                    matchy::extractor::ExtractedItem::Url(_) => {}
+                    _ => {}
                 }
             }
 
@@ -130,7 +132,9 @@ pub fn process_file(
                 | matchy::extractor::ExtractedItem::Hash(_, s)
                 | matchy::extractor::ExtractedItem::Bitcoin(s)
                 | matchy::extractor::ExtractedItem::Ethereum(s)
-                | matchy::extractor::ExtractedItem::Monero(s) => db.lookup(s)?,
+                | matchy::extractor::ExtractedItem::Monero(s)
+                | matchy::extractor::ExtractedItem::Url(s) => db.lookup(s)?,
+                _ => db.lookup(&candidate_str)?,
             };
             if let Some(start) = lookup_start {
                 stats.lookup_time += start.elapsed();
@@ -296,6 +300,8 @@ pub fn process_file_with_aggregate(
                 matchy::extractor::ExtractedItem::Bitcoin(_) => {}
                 matchy::extractor::ExtractedItem::Ethereum(_) => {}
                 matchy::extractor::ExtractedItem::Monero(_) => {}
+                matchy::extractor::ExtractedItem::Url(_) => {}
+                _ => {}
             }
 
             // Lookup candidate
@@ -317,7 +323,9 @@ pub fn process_file_with_aggregate(
                 | matchy::extractor::ExtractedItem::Hash(_, s)
                 | matchy::extractor::ExtractedItem::Bitcoin(s)
                 | matchy::extractor::ExtractedItem::Ethereum(s)
-                | matchy::extractor::ExtractedItem::Monero(s) => db.lookup(s)?,
+                | matchy::extractor::ExtractedItem::Monero(s)
+                | matchy::extractor::ExtractedItem::Url(s) => db.lookup(s)?,
+                _ => db.lookup(&candidate_str)?,
             };
             if let Some(start) = lookup_start {
                 aggregate_stats.lookup_time += start.elapsed();
@@ -423,7 +431,9 @@ pub fn process_line_matches(
             | matchy::extractor::ExtractedItem::Hash(_, s)
             | matchy::extractor::ExtractedItem::Bitcoin(s)
             | matchy::extractor::ExtractedItem::Ethereum(s)
-            | matchy::extractor::ExtractedItem::Monero(s) => db.lookup(s)?,
+            | matchy::extractor::ExtractedItem::Monero(s)
+            | matchy::extractor::ExtractedItem::Url(s) => db.lookup(s)?,
+            _ => db.lookup(&candidate_str)?,
         };
 
         let is_match = match &result {
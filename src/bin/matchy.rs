@@ -7,7 +7,8 @@ use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 use commands::{
-    cmd_bench, cmd_build, cmd_extract, cmd_inspect, cmd_match, cmd_query, cmd_validate,
+    cmd_apply_patch, cmd_bench, cmd_build, cmd_canonicalize, cmd_diff_build, cmd_export,
+    cmd_extract, cmd_inspect, cmd_match, cmd_migrate, cmd_query, cmd_validate,
 };
 
 #[derive(Parser)]
@@ -29,7 +30,8 @@ use commands::{
       matchy query threats.mxy '192.168.1.1'\n\
       matchy query threats.mxy 'evil.example.com'\n\
       matchy inspect threats.mxy --verbose\n\
-      matchy validate threats.mxy --level strict"
+      matchy validate threats.mxy --level strict\n\
+      matchy canonicalize threats.mxy > threats.txt"
 )]
 #[command(version)]
 struct Cli {
@@ -49,7 +51,7 @@ enum Commands {
         #[arg(long, default_value = "json")]
         format: String,
 
-        /// Extraction types (comma-separated): ipv4, ipv6, ip, domain, email, all (default: all)
+        /// Extraction types (comma-separated): ipv4, ipv6, ip, domain, email, url, all (default: all but url)
         #[arg(long)]
         types: Option<String>,
 
@@ -123,11 +125,11 @@ enum Commands {
         #[arg(long, default_value = "10000")]
         cache_size: usize,
 
-        /// Enable/disable extractors (comma-separated): ipv4,ipv6,domain,email,hash,bitcoin,ethereum,monero
+        /// Enable/disable extractors (comma-separated): ipv4,ipv6,domain,email,hash,bitcoin,ethereum,monero,url
         /// Prefix with '-' to disable (e.g., -domain,-email). Supports plurals (domains, hashes, emails)
         /// Group aliases: 'crypto' (bitcoin+ethereum+monero), 'ip' (ipv4+ipv6)
         /// Examples: --extractors=ip,domain  --extractors=-crypto,-hash  --extractors=-domains
-        /// Default: auto-detect from database capabilities
+        /// Default: auto-detect from database capabilities (url is never on by default)
         #[arg(long)]
         extractors: Option<String>,
     },
@@ -165,19 +167,28 @@ enum Commands {
     /// Build a unified database from patterns and/or IP addresses
     Build {
         /// Input files containing patterns, IP addresses, or MISP JSON (can specify multiple)
-        #[arg(value_name = "INPUT", required = true)]
+        ///
+        /// Prefix an input with FORMAT: (e.g. csv:feed.txt) to override --format for that file.
+        #[arg(value_name = "INPUT", required_unless_present = "input")]
         inputs: Vec<PathBuf>,
 
+        /// Additional input file (can be repeated; same as a positional INPUT)
+        #[arg(long, value_name = "INPUT")]
+        input: Vec<PathBuf>,
+
         /// Output database file (.mxy extension)
         #[arg(short, long, value_name = "FILE")]
         output: PathBuf,
 
         /// Input file format (how to parse input files)
-        /// - text: One pattern per line (default)
+        /// - auto: Detect per file from the extension (default)
+        /// - text: One pattern per line
         /// - csv: Comma-separated values with 'entry' or 'key' column
         /// - json: JSON array of {"key": "pattern", "data": {...}}
+        /// - jsonl: One JSON object per line with a "key" field
         /// - misp: MISP threat intelligence JSON format
-        #[arg(short = 'f', long, default_value = "text", value_name = "FORMAT")]
+        /// - rules: TOML rule file (see matchy::rules_importer)
+        #[arg(short = 'f', long, default_value = "auto", value_name = "FORMAT")]
         format: String,
 
         /// Custom database type name for metadata (e.g., "MyCompany-ThreatIntel")
@@ -204,6 +215,25 @@ enum Commands {
         /// Use case-insensitive matching for patterns (default: case-sensitive)
         #[arg(short = 'i', long)]
         case_insensitive: bool,
+
+        /// What to do when a key appears more than once across the inputs:
+        /// keep-all (default), keep-first, keep-last, or error
+        #[arg(long, default_value = "keep-all", value_name = "POLICY")]
+        on_duplicate: String,
+
+        /// Store strings repeated across records (e.g. categories) once, in a
+        /// shared dictionary at the start of the data section
+        #[arg(long)]
+        intern_strings: bool,
+
+        /// Build with string interning on and off and keep the smaller, or
+        /// with --tune-queries the faster unless it is over 10% larger
+        #[arg(long, conflicts_with = "intern_strings")]
+        auto_tune: bool,
+
+        /// Sample queries, one per line, to time each --auto-tune candidate
+        #[arg(long, value_name = "FILE", requires = "auto_tune")]
+        tune_queries: Option<PathBuf>,
     },
 
     /// Validate a database file for safety and correctness
@@ -212,11 +242,15 @@ enum Commands {
         #[arg(value_name = "DATABASE")]
         database: PathBuf,
 
-        /// Validation level: standard, strict (default), or audit
+        /// Validation level: standard, strict (default), audit, or exhaustive
         #[arg(short, long, default_value = "strict")]
         level: String,
 
-        /// Output results as JSON
+        /// Output format: text (default) or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Output results as JSON (same as --format json)
         #[arg(short, long)]
         json: bool,
 
@@ -225,6 +259,88 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Print a database's entries in a deterministic, sorted text form
+    ///
+    /// The output reflects only what the database answers (networks, literals,
+    /// globs, suffixes, and their data), not its on-disk layout, so two databases
+    /// are logically identical exactly when their canonical text is identical.
+    Canonicalize {
+        /// Path to the matchy database (.mxy file)
+        #[arg(value_name = "DATABASE")]
+        database: PathBuf,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Export every entry as newline-delimited JSON
+    ///
+    /// Each line is an object with the entry type, its key (a CIDR network or
+    /// the pattern string), and its data.
+    Export {
+        /// Path to the matchy database (.mxy file)
+        #[arg(value_name = "DATABASE")]
+        database: PathBuf,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a patch of the entries added, changed, and removed between two databases
+    ///
+    /// Apply it to the old database with `matchy apply-patch`.
+    DiffBuild {
+        /// The database the patch will be applied to
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// The database the patch produces
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Output patch file (.mxd extension)
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Rebuild a database by applying a patch from `matchy diff-build`
+    ///
+    /// The result is a canonical rebuild of the new database: the same entries,
+    /// metadata, and build time, byte for byte, wherever the patch is applied.
+    ApplyPatch {
+        /// The database the patch was made against
+        #[arg(value_name = "BASE")]
+        base: PathBuf,
+
+        /// Patch file (.mxd)
+        #[arg(value_name = "PATCH")]
+        patch: PathBuf,
+
+        /// Output database file (.mxy extension)
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Re-encode a database written by an older release in the current format
+    ///
+    /// Keeps every entry and its data; only the on-disk layout changes, so no
+    /// source feeds are needed.
+    Migrate {
+        /// Database to read
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Output database file (.mxy extension)
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+
+        /// Pattern format version to write (only the current version is supported)
+        #[arg(long, value_name = "VERSION", default_value_t = matchy::offset_format::VERSION)]
+        to_version: u32,
+    },
+
     /// Benchmark database performance (build, load, query)
     Bench {
         /// Type of database to benchmark: ip, literal, pattern, or combined
@@ -333,11 +449,29 @@ fn main() -> Result<()> {
         Commands::Validate {
             database,
             level,
+            format,
             json,
             verbose,
-        } => cmd_validate(database, level, json, verbose),
+        } => {
+            let format = if json { "json".to_string() } else { format };
+            cmd_validate(database, level, format, verbose)
+        }
+        Commands::Canonicalize { database, output } => cmd_canonicalize(database, output),
+        Commands::Export { database, output } => cmd_export(database, output),
+        Commands::DiffBuild { old, new, output } => cmd_diff_build(old, new, output),
+        Commands::ApplyPatch {
+            base,
+            patch,
+            output,
+        } => cmd_apply_patch(base, patch, output),
+        Commands::Migrate {
+            input,
+            output,
+            to_version,
+        } => cmd_migrate(input, output, to_version),
         Commands::Build {
             inputs,
+            input,
             output,
             format,
             database_type,
@@ -346,8 +480,12 @@ fn main() -> Result<()> {
             verbose,
             debug,
             case_insensitive,
+            on_duplicate,
+            intern_strings,
+            auto_tune,
+            tune_queries,
         } => cmd_build(
-            inputs,
+            inputs.into_iter().chain(input).collect(),
             output,
             format,
             database_type,
@@ -356,6 +494,10 @@ fn main() -> Result<()> {
             verbose,
             debug,
             case_insensitive,
+            on_duplicate,
+            intern_strings,
+            auto_tune,
+            tune_queries,
         ),
         Commands::Bench {
             db_type,
@@ -4,11 +4,14 @@
 //! containing IP addresses and patterns. This is the primary public API.
 
 use crate::data_section::DataValue;
-use crate::database::{Database as RustDatabase, QueryResult};
+use crate::database::{Database as RustDatabase, DatabaseError, QueryResult};
 use crate::glob::MatchMode;
 use crate::mmdb_builder::MmdbBuilder;
+use crate::multi_database::MultiDatabase;
+use std::cell::OnceCell;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
+use std::net::IpAddr;
 use std::os::raw::c_char;
 use std::ptr;
 use std::slice;
@@ -70,7 +73,41 @@ struct MatchyBuilderInternal {
 }
 
 struct MatchyInternal {
-    database: RustDatabase,
+    /// One database, or several from `matchy_open_multi` queried in order
+    databases: MultiDatabase,
+    /// Metadata strings handed out by `matchy_get_metadata`, built on first use
+    metadata: OnceCell<Option<MetadataStrings>>,
+}
+
+/// C strings backing a `matchy_metadata_t`, owned by the handle
+struct MetadataStrings {
+    node_count: u32,
+    record_size: u16,
+    ip_version: u16,
+    build_epoch: u64,
+    database_type: CString,
+    languages: Vec<CString>,
+    /// Pointers into `languages`, NULL-terminated
+    language_ptrs: Vec<*const c_char>,
+}
+
+impl MatchyInternal {
+    fn new(databases: Vec<RustDatabase>) -> Box<Self> {
+        Box::new(Self {
+            databases: MultiDatabase::from_databases(databases),
+            metadata: OnceCell::new(),
+        })
+    }
+
+    /// The first (or only) database, for per-file information
+    fn database(&self) -> &RustDatabase {
+        &self.databases.databases()[0]
+    }
+
+    /// Whether any of the databases satisfies `check`
+    fn any(&self, check: impl Fn(&RustDatabase) -> bool) -> bool {
+        self.databases.databases().iter().any(check)
+    }
 }
 
 // Conversion helpers for opaque types
@@ -178,8 +215,8 @@ pub unsafe extern "C" fn matchy_builder_add(
     };
 
     // Wrap in a map if it's not already a map
-    let data_map = match data {
-        DataValue::Map(m) => m,
+    let data_map: HashMap<String, DataValue> = match data {
+        DataValue::Map(m) => m.into_iter().collect(),
         _ => {
             // Single value - wrap it in a map with "value" key
             let mut map = HashMap::new();
@@ -454,8 +491,17 @@ pub unsafe extern "C" fn matchy_open_with_options(
         Err(_) => return ptr::null_mut(),
     };
 
-    let opts = &*options;
+    match open_one(path, &*options) {
+        Ok(db) => matchy_t::from_internal(MatchyInternal::new(vec![db])),
+        Err(_) => ptr::null_mut(),
+    }
+}
 
+/// Open one database file with C-side options
+fn open_one(
+    path: &str,
+    opts: &matchy_open_options_t,
+) -> Result<RustDatabase, crate::database::DatabaseError> {
     // Build database using fluent API
     let mut opener = RustDatabase::from(path);
 
@@ -465,13 +511,7 @@ pub unsafe extern "C" fn matchy_open_with_options(
         opener = opener.cache_capacity(opts.cache_capacity as usize);
     }
 
-    match opener.open() {
-        Ok(db) => {
-            let internal = Box::new(MatchyInternal { database: db });
-            matchy_t::from_internal(internal)
-        }
-        Err(_) => ptr::null_mut(),
-    }
+    opener.open()
 }
 
 /// Open database from file (memory-mapped) - SAFE mode
@@ -506,6 +546,74 @@ pub unsafe extern "C" fn matchy_open(filename: *const c_char) -> *mut matchy_t {
     matchy_open_with_options(filename, &opts)
 }
 
+/// Open several databases behind one handle
+///
+/// Returns a composite handle that `matchy_query` answers from the first
+/// database, in `paths` order, that has a match. IPv4 queries go to every
+/// database with IP data, IPv6 queries only to IPv6 databases, and string
+/// queries to databases with literal or pattern data. This lets separately
+/// maintained feeds (e.g. an IPv4 and an IPv6 file, or GeoIP plus threat
+/// intel) be queried as one without rebuilding them into a single file.
+///
+/// Functions describing a single file (`matchy_format`, `matchy_metadata`,
+/// `matchy_get_pattern_string`, `matchy_pattern_count`) report on the first
+/// database. `matchy_has_*_data` are true if any database has that data,
+/// `matchy_get_stats` sums the databases' counters (a query consulting
+/// several databases counts once in each), and `matchy_clear_cache` clears
+/// every cache. One `matchy_close` closes all of them. Like a single-database
+/// handle, a composite handle must not be used from several threads at once.
+///
+/// # Parameters
+/// * `paths` - Array of database paths (null-terminated C strings, must not be NULL)
+/// * `count` - Number of paths (must be > 0)
+///
+/// # Returns
+/// * Non-null pointer on success
+/// * NULL on failure, including if any database fails to open
+///
+/// # Safety
+/// * `paths` must point to `count` valid null-terminated C strings
+///
+/// # Example
+/// ```c
+/// const char *paths[] = {"geo-v4.mxy", "geo-v6.mxy", "threats.mxy"};
+/// matchy_t *db = matchy_open_multi(paths, 3);
+/// if (db == NULL) {
+///     fprintf(stderr, "Failed to open databases\n");
+///     return 1;
+/// }
+/// matchy_result_t result = matchy_query(db, "2001:db8::1");
+/// matchy_free_result(&result);
+/// matchy_close(db);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn matchy_open_multi(
+    paths: *const *const c_char,
+    count: usize,
+) -> *mut matchy_t {
+    if paths.is_null() || count == 0 {
+        return ptr::null_mut();
+    }
+
+    let opts = matchy_open_options_t::default();
+    let mut databases = Vec::with_capacity(count);
+    for &path in slice::from_raw_parts(paths, count) {
+        if path.is_null() {
+            return ptr::null_mut();
+        }
+        let path = match CStr::from_ptr(path).to_str() {
+            Ok(s) => s,
+            Err(_) => return ptr::null_mut(),
+        };
+        match open_one(path, &opts) {
+            Ok(db) => databases.push(db),
+            Err(_) => return ptr::null_mut(),
+        }
+    }
+
+    matchy_t::from_internal(MatchyInternal::new(databases))
+}
+
 /// Open database from memory buffer (zero-copy)
 ///
 /// Creates a database handle from a memory buffer. No data is copied.
@@ -529,10 +637,7 @@ pub unsafe extern "C" fn matchy_open_buffer(buffer: *const u8, size: usize) -> *
 
     let slice = slice::from_raw_parts(buffer, size);
     match RustDatabase::from_bytes(slice.to_vec()) {
-        Ok(db) => {
-            let internal = Box::new(MatchyInternal { database: db });
-            matchy_t::from_internal(internal)
-        }
+        Ok(db) => matchy_t::from_internal(MatchyInternal::new(vec![db])),
         Err(_) => ptr::null_mut(),
     }
 }
@@ -591,17 +696,27 @@ pub unsafe extern "C" fn matchy_get_stats(db: *const matchy_t, stats: *mut match
     }
 
     let internal = matchy_t::as_internal(db);
-    let rust_stats = internal.database.stats();
-
-    *stats = matchy_stats_t {
-        total_queries: rust_stats.total_queries,
-        queries_with_match: rust_stats.queries_with_match,
-        queries_without_match: rust_stats.queries_without_match,
-        cache_hits: rust_stats.cache_hits,
-        cache_misses: rust_stats.cache_misses,
-        ip_queries: rust_stats.ip_queries,
-        string_queries: rust_stats.string_queries,
+    let mut totals = matchy_stats_t {
+        total_queries: 0,
+        queries_with_match: 0,
+        queries_without_match: 0,
+        cache_hits: 0,
+        cache_misses: 0,
+        ip_queries: 0,
+        string_queries: 0,
     };
+    for database in internal.databases.databases() {
+        let rust_stats = database.stats();
+        totals.total_queries += rust_stats.total_queries;
+        totals.queries_with_match += rust_stats.queries_with_match;
+        totals.queries_without_match += rust_stats.queries_without_match;
+        totals.cache_hits += rust_stats.cache_hits;
+        totals.cache_misses += rust_stats.cache_misses;
+        totals.ip_queries += rust_stats.ip_queries;
+        totals.string_queries += rust_stats.string_queries;
+    }
+
+    *stats = totals;
 }
 
 /// Clear the query cache
@@ -630,7 +745,9 @@ pub unsafe extern "C" fn matchy_clear_cache(db: *const matchy_t) {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.clear_cache();
+    for database in internal.databases.databases() {
+        database.clear_cache();
+    }
 }
 
 /// Close database
@@ -722,7 +839,113 @@ pub unsafe extern "C" fn matchy_query(
     };
 
     let internal = matchy_t::as_internal(db);
-    match internal.database.lookup(query_str) {
+    let result = match internal.databases.databases() {
+        [database] => database.lookup(query_str),
+        _ => internal.databases.lookup(query_str),
+    };
+    result_from_lookup(db, result)
+}
+
+/// Query the database with many keys in one call
+///
+/// Equivalent to calling matchy_query for each key, without crossing the
+/// FFI boundary per key. Use it when looking up many IPs or strings in a
+/// tight loop.
+///
+/// # Parameters
+/// * `db` - Database handle (must not be NULL)
+/// * `keys` - Array of `n` IP addresses or patterns (null-terminated C strings).
+///   A NULL or non-UTF-8 key is reported as not found
+/// * `n` - Number of keys
+/// * `results` - Caller-allocated array of `n` results, filled in key order
+///
+/// # Returns
+/// * Number of keys that matched, or 0 if `db`, `keys`, or `results` is NULL
+/// * Every one of the `n` results must be freed with matchy_free_result,
+///   including results with found=false
+///
+/// # Safety
+/// * `db` must be a valid pointer from matchy_open
+/// * `keys` must point to `n` pointers, each NULL or a valid null-terminated C string
+/// * `results` must point to writable space for `n` results
+///
+/// # Example
+/// ```c
+/// const char *keys[] = {"1.2.3.4", "example.com"};
+/// matchy_result_t results[2];
+/// size_t found = matchy_query_batch(db, keys, 2, results);
+/// for (size_t i = 0; i < 2; i++) {
+///     if (results[i].found) {
+///         // ... use results[i]
+///     }
+///     matchy_free_result(&results[i]);
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn matchy_query_batch(
+    db: *const matchy_t,
+    keys: *const *const c_char,
+    n: usize,
+    results: *mut matchy_result_t,
+) -> usize {
+    if db.is_null() || keys.is_null() || results.is_null() {
+        return 0;
+    }
+
+    let mut found = 0;
+    for (i, &key) in slice::from_raw_parts(keys, n).iter().enumerate() {
+        // matchy_query handles NULL and non-UTF-8 keys
+        let result = matchy_query(db, key);
+        if result.found {
+            found += 1;
+        }
+        // The caller's array may be uninitialized, so write without dropping
+        ptr::write(results.add(i), result);
+    }
+    found
+}
+
+/// Query the database with an already-parsed IP address
+///
+/// Walks the IP tree directly, without formatting and reparsing the address.
+///
+/// # Safety
+/// * `db` must be a valid non-NULL pointer from matchy_open
+pub(crate) unsafe fn query_ip(db: *const matchy_t, addr: std::net::IpAddr) -> matchy_result_t {
+    let internal = matchy_t::as_internal(db);
+    let result = match internal.databases.databases() {
+        [database] => database.lookup_ip(addr),
+        _ => internal.databases.lookup_ip(addr),
+    };
+    result_from_lookup(db, result)
+}
+
+/// Every IP network behind `db` as a C result, database by database
+///
+/// # Safety
+/// * `db` must be a valid non-NULL pointer from matchy_open and outlive the iterator
+pub(crate) unsafe fn networks(
+    db: *const matchy_t,
+) -> impl Iterator<Item = Result<(IpAddr, u8, matchy_result_t), DatabaseError>> {
+    let internal = matchy_t::as_internal(db);
+    internal
+        .databases
+        .databases()
+        .iter()
+        .flat_map(move |database| {
+            database.networks().map(move |entry| {
+                let (addr, prefix_len, result) = entry?;
+                Ok((addr, prefix_len, result_from_lookup(db, Ok(Some(result)))))
+            })
+        })
+}
+
+/// Convert a lookup outcome into a C result, caching the first match's data
+fn result_from_lookup(
+    db: *const matchy_t,
+    result: Result<Option<QueryResult>, crate::database::DatabaseError>,
+) -> matchy_result_t {
+    match result {
         Ok(Some(QueryResult::Ip { data, prefix_len })) => {
             // Cache the DataValue for structured access
             let data_cache = Box::new(data);
@@ -812,6 +1035,43 @@ pub extern "C" fn matchy_version() -> *const c_char {
     concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
 }
 
+/// Get a human-readable description of an error code
+///
+/// Mirrors `strerror`: every `MATCHY_SUCCESS` / `MATCHY_ERROR_*` code returned
+/// by this library has a description, and unknown codes get a generic one.
+///
+/// # Parameters
+/// * `code` - Status code returned by a matchy function
+///
+/// # Returns
+/// * Description string (never NULL)
+/// * Pointer is valid for program lifetime, do not free
+///
+/// # Example
+/// ```c
+/// int status = matchy_builder_save(builder, "threats.mxy");
+/// if (status != MATCHY_SUCCESS) {
+///     fprintf(stderr, "Failed to save: %s\n", matchy_strerror(status));
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn matchy_strerror(code: i32) -> *const c_char {
+    let message: &'static [u8] = match code {
+        MATCHY_SUCCESS => b"Success\0",
+        MATCHY_ERROR_FILE_NOT_FOUND => b"File not found\0",
+        MATCHY_ERROR_INVALID_FORMAT => b"Invalid format\0",
+        MATCHY_ERROR_CORRUPT_DATA => b"Corrupt data\0",
+        MATCHY_ERROR_OUT_OF_MEMORY => b"Out of memory\0",
+        MATCHY_ERROR_INVALID_PARAM => b"Invalid parameter\0",
+        MATCHY_ERROR_IO => b"I/O error\0",
+        MATCHY_ERROR_LOOKUP_PATH_INVALID => b"Lookup path does not match the data\0",
+        MATCHY_ERROR_NO_DATA => b"No data\0",
+        MATCHY_ERROR_DATA_PARSE => b"Failed to parse data\0",
+        _ => b"Unknown error\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
 /// Get database format description
 ///
 /// # Parameters
@@ -831,7 +1091,7 @@ pub unsafe extern "C" fn matchy_format(db: *const matchy_t) -> *const c_char {
     }
 
     let internal = matchy_t::as_internal(db);
-    let format_str = internal.database.format();
+    let format_str = internal.database().format();
     format_str.as_ptr() as *const c_char
 }
 
@@ -853,7 +1113,7 @@ pub unsafe extern "C" fn matchy_has_ip_data(db: *const matchy_t) -> bool {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.has_ip_data()
+    internal.any(RustDatabase::has_ip_data)
 }
 
 /// Check if database supports string lookups (literals or globs)
@@ -874,7 +1134,7 @@ pub unsafe extern "C" fn matchy_has_string_data(db: *const matchy_t) -> bool {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.has_string_data()
+    internal.any(RustDatabase::has_string_data)
 }
 
 /// Check if database supports literal (exact string) lookups
@@ -895,7 +1155,7 @@ pub unsafe extern "C" fn matchy_has_literal_data(db: *const matchy_t) -> bool {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.has_literal_data()
+    internal.any(RustDatabase::has_literal_data)
 }
 
 /// Check if database supports glob pattern lookups
@@ -916,7 +1176,7 @@ pub unsafe extern "C" fn matchy_has_glob_data(db: *const matchy_t) -> bool {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.has_glob_data()
+    internal.any(RustDatabase::has_glob_data)
 }
 
 /// Check if database supports pattern matching (deprecated)
@@ -944,7 +1204,7 @@ pub unsafe extern "C" fn matchy_has_pattern_data(db: *const matchy_t) -> bool {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.has_string_data()
+    internal.any(RustDatabase::has_string_data)
 }
 
 /// Get database metadata as JSON string
@@ -967,7 +1227,7 @@ pub unsafe extern "C" fn matchy_metadata(db: *const matchy_t) -> *mut c_char {
     }
 
     let internal = matchy_t::as_internal(db);
-    match internal.database.metadata() {
+    match internal.database().metadata() {
         Some(metadata) => {
             // Convert metadata to JSON string
             match serde_json::to_string(&metadata) {
@@ -982,6 +1242,133 @@ pub unsafe extern "C" fn matchy_metadata(db: *const matchy_t) -> *mut c_char {
     }
 }
 
+/// Database metadata (the standard MMDB metadata fields)
+///
+/// Filled by `matchy_get_metadata`. String pointers are owned by the
+/// database handle and stay valid until `matchy_close`; do not free them.
+#[repr(C)]
+pub struct matchy_metadata_t {
+    /// Number of nodes in the IP search tree
+    pub node_count: u32,
+    /// Bits per tree record (24, 28, or 32)
+    pub record_size: u16,
+    /// IP version of the search tree (4 or 6)
+    pub ip_version: u16,
+    /// Database type name (empty string if not set)
+    pub database_type: *const c_char,
+    /// Build time in seconds since the Unix epoch
+    pub build_epoch: u64,
+    /// Description languages (e.g. "en"), NULL-terminated
+    pub languages: *const *const c_char,
+    /// Number of entries in `languages` (excluding the NULL terminator)
+    pub language_count: usize,
+}
+
+/// Get database metadata
+///
+/// Populates `out` with the standard MMDB metadata fields, like
+/// libmaxminddb's `MMDB_s.metadata`. For a handle from `matchy_open_multi`
+/// this describes the first database.
+///
+/// # Parameters
+/// * `db` - Database handle (must not be NULL)
+/// * `out` - Metadata struct to fill (must not be NULL)
+///
+/// # Returns
+/// * MATCHY_SUCCESS (0) on success
+/// * MATCHY_ERROR_NO_DATA if the database has no MMDB metadata (pattern-only databases)
+/// * MATCHY_ERROR_INVALID_PARAM if a parameter is NULL
+///
+/// # Safety
+/// * `db` must be a valid pointer from matchy_open
+/// * `out` must be a valid pointer to matchy_metadata_t
+/// * Strings in `out` must not be used after `matchy_close(db)`
+///
+/// # Example
+/// ```c
+/// matchy_metadata_t meta;
+/// if (matchy_get_metadata(db, &meta) == MATCHY_SUCCESS) {
+///     printf("%s, IPv%u, %u nodes\n", meta.database_type, meta.ip_version, meta.node_count);
+///     for (size_t i = 0; i < meta.language_count; i++) {
+///         printf("language: %s\n", meta.languages[i]);
+///     }
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn matchy_get_metadata(
+    db: *const matchy_t,
+    out: *mut matchy_metadata_t,
+) -> i32 {
+    if db.is_null() || out.is_null() {
+        return MATCHY_ERROR_INVALID_PARAM;
+    }
+
+    let internal = matchy_t::as_internal(db);
+    let metadata = internal
+        .metadata
+        .get_or_init(|| MetadataStrings::from_database(internal.database()));
+    let Some(metadata) = metadata else {
+        return MATCHY_ERROR_NO_DATA;
+    };
+
+    *out = matchy_metadata_t {
+        node_count: metadata.node_count,
+        record_size: metadata.record_size,
+        ip_version: metadata.ip_version,
+        database_type: metadata.database_type.as_ptr(),
+        build_epoch: metadata.build_epoch,
+        languages: metadata.language_ptrs.as_ptr(),
+        language_count: metadata.languages.len(),
+    };
+    MATCHY_SUCCESS
+}
+
+impl MetadataStrings {
+    fn from_database(database: &RustDatabase) -> Option<Self> {
+        let DataValue::Map(map) = database.metadata()? else {
+            return None;
+        };
+        let uint = |key: &str| match map.get(key) {
+            Some(DataValue::Uint16(v)) => u64::from(*v),
+            Some(DataValue::Uint32(v)) => u64::from(*v),
+            Some(DataValue::Uint64(v)) => *v,
+            _ => 0,
+        };
+        // Interior NULs can't cross the C boundary; such strings become empty
+        let c_string = |s: &str| CString::new(s).unwrap_or_default();
+
+        let database_type = match map.get("database_type") {
+            Some(DataValue::String(s)) => c_string(s),
+            _ => CString::default(),
+        };
+        let languages: Vec<CString> = match map.get("languages") {
+            Some(DataValue::Array(items)) => items
+                .iter()
+                .filter_map(|item| match item {
+                    DataValue::String(s) => Some(c_string(s)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let language_ptrs = languages
+            .iter()
+            .map(|s| s.as_ptr())
+            .chain(std::iter::once(ptr::null()))
+            .collect();
+
+        Some(Self {
+            node_count: uint("node_count") as u32,
+            record_size: uint("record_size") as u16,
+            ip_version: uint("ip_version") as u16,
+            build_epoch: uint("build_epoch"),
+            database_type,
+            languages,
+            language_ptrs,
+        })
+    }
+}
+
 /// Get pattern string by ID
 ///
 /// Returns the pattern string for a given pattern ID.
@@ -1009,7 +1396,7 @@ pub unsafe extern "C" fn matchy_get_pattern_string(
     let internal = matchy_t::as_internal(db);
 
     // Get pattern string from database
-    if let Some(pattern_str) = internal.database.get_pattern_string(pattern_id) {
+    if let Some(pattern_str) = internal.database().get_pattern_string(pattern_id) {
         match CString::new(pattern_str) {
             Ok(c_str) => return c_str.into_raw(),
             Err(_) => return ptr::null_mut(),
@@ -1039,7 +1426,7 @@ pub unsafe extern "C" fn matchy_pattern_count(db: *const matchy_t) -> usize {
     }
 
     let internal = matchy_t::as_internal(db);
-    internal.database.pattern_count()
+    internal.database().pattern_count()
 }
 
 // ============================================================================
@@ -1602,6 +1989,8 @@ pub const MATCHY_VALIDATION_STANDARD: i32 = 0;
 pub const MATCHY_VALIDATION_STRICT: i32 = 1;
 /// Audit validation level - strict plus unsafe code tracking for security reviews
 pub const MATCHY_VALIDATION_AUDIT: i32 = 2;
+/// Exhaustive validation level - strict, but UTF-8 and pointer checks cover every data record
+pub const MATCHY_VALIDATION_EXHAUSTIVE: i32 = 3;
 
 /// Validate a database file
 ///
@@ -1610,7 +1999,7 @@ pub const MATCHY_VALIDATION_AUDIT: i32 = 2;
 ///
 /// # Parameters
 /// * `filename` - Path to database file (null-terminated C string, must not be NULL)
-/// * `level` - Validation level (MATCHY_VALIDATION_STANDARD, _STRICT, or _AUDIT)
+/// * `level` - Validation level (MATCHY_VALIDATION_STANDARD, _STRICT, _AUDIT, or _EXHAUSTIVE)
 /// * `error_message` - Pointer to receive error message (may be NULL if not needed)
 ///   If non-NULL and validation fails, receives a string that must be freed with matchy_free_string
 ///
@@ -1655,6 +2044,7 @@ pub unsafe extern "C" fn matchy_validate(
         MATCHY_VALIDATION_STANDARD => ValidationLevel::Standard,
         MATCHY_VALIDATION_STRICT => ValidationLevel::Strict,
         MATCHY_VALIDATION_AUDIT => ValidationLevel::Audit,
+        MATCHY_VALIDATION_EXHAUSTIVE => ValidationLevel::Exhaustive,
         _ => return MATCHY_ERROR_INVALID_PARAM,
     };
 
@@ -6,12 +6,15 @@
 
 use super::matchy::{
     matchy_aget_value, matchy_close, matchy_entry_data_list_t, matchy_entry_data_t, matchy_entry_s,
-    matchy_get_entry_data_list, matchy_open, matchy_query, matchy_t, MATCHY_SUCCESS,
+    matchy_free_result, matchy_get_entry_data_list, matchy_open, matchy_query,
+    matchy_result_get_entry, matchy_result_t, matchy_t, networks, query_ip,
+    MATCHY_ERROR_CORRUPT_DATA, MATCHY_ERROR_INVALID_PARAM, MATCHY_SUCCESS,
 };
 use std::ffi::{CStr, CString};
 use std::mem;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
 
 // ============================================================================
@@ -55,6 +58,22 @@ pub struct MMDB_lookup_result_s {
 #[allow(non_camel_case_types)]
 pub type MMDB_entry_data_s = matchy_entry_data_t;
 
+/// Callback for `matchy_iterate`, called once per network that holds data
+///
+/// Receives the network address as a string (e.g. `"10.0.0.0"`), its prefix
+/// length, an entry handle for `matchy_aget_value` and friends, and the
+/// caller's `user_data`. The string and entry are only valid during the call.
+/// Return 0 to continue, or nonzero to stop the iteration.
+#[allow(non_camel_case_types)]
+pub type matchy_node_callback = Option<
+    unsafe extern "C" fn(
+        network: *const c_char,
+        prefix_len: u8,
+        entry: *const matchy_entry_s,
+        user_data: *mut c_void,
+    ) -> c_int,
+>;
+
 /// Entry data list node
 #[repr(C)]
 pub struct MMDB_entry_data_list_s {
@@ -184,10 +203,32 @@ pub unsafe extern "C" fn MMDB_lookup_string(
     }
 
     // Query using matchy
-    let result = matchy_query(db, ipstr);
+    if !gai_error.is_null() {
+        *gai_error = 0;
+    }
+    mmdb_result(mmdb, matchy_query(db, ipstr), mmdb_error)
+}
+
+/// Wrap a matchy result as an MMDB lookup result and report success
+unsafe fn mmdb_result(
+    mmdb: *const MMDB_s,
+    result: matchy_result_t,
+    mmdb_error: *mut c_int,
+) -> MMDB_lookup_result_s {
+    let db = (*mmdb)._matchy_db;
+    if !mmdb_error.is_null() {
+        *mmdb_error = MMDB_SUCCESS;
+    }
 
     if !result.found {
-        return set_error(0, MMDB_SUCCESS);
+        return MMDB_lookup_result_s {
+            found_entry: false,
+            entry: MMDB_entry_s {
+                mmdb: ptr::null(),
+                _matchy_entry: mem::zeroed(),
+            },
+            netmask: 0,
+        };
     }
 
     // Box the result to keep it alive - matchy_aget_value expects
@@ -205,21 +246,11 @@ pub unsafe extern "C" fn MMDB_lookup_string(
         },
     };
 
-    let lookup_result = MMDB_lookup_result_s {
+    MMDB_lookup_result_s {
         found_entry: true,
         entry: mmdb_entry,
         netmask: (*result_ptr).prefix_len as u16,
-    };
-
-    // Set success
-    if !gai_error.is_null() {
-        *gai_error = 0;
-    }
-    if !mmdb_error.is_null() {
-        *mmdb_error = MMDB_SUCCESS;
     }
-
-    lookup_result
 }
 
 /// Lookup an IP address from sockaddr
@@ -253,34 +284,90 @@ pub unsafe extern "C" fn MMDB_lookup_sockaddr(
         }
     };
 
-    if mmdb.is_null() || sockaddr.is_null() {
+    if mmdb.is_null() || sockaddr.is_null() || (*mmdb)._matchy_db.is_null() {
         return set_error(MMDB_INVALID_DATA_ERROR);
     }
 
-    // Convert sockaddr to IP string
-    let ip_addr = match (*sockaddr).sa_family as i32 {
+    match sockaddr_to_ip(sockaddr) {
+        Some(addr) => mmdb_result(mmdb, query_ip((*mmdb)._matchy_db, addr), mmdb_error),
+        None => set_error(MMDB_INVALID_DATA_ERROR),
+    }
+}
+
+/// Read the address out of an `AF_INET` or `AF_INET6` socket address
+#[cfg(unix)]
+unsafe fn sockaddr_to_ip(sockaddr: *const libc::sockaddr) -> Option<IpAddr> {
+    match (*sockaddr).sa_family as i32 {
         libc::AF_INET => {
             let sa = sockaddr as *const libc::sockaddr_in;
             let addr = u32::from_be((*sa).sin_addr.s_addr);
-            IpAddr::V4(Ipv4Addr::from(addr))
+            Some(IpAddr::V4(Ipv4Addr::from(addr)))
         }
         libc::AF_INET6 => {
             let sa = sockaddr as *const libc::sockaddr_in6;
             let addr = (*sa).sin6_addr.s6_addr;
-            IpAddr::V6(Ipv6Addr::from(addr))
+            Some(IpAddr::V6(Ipv6Addr::from(addr)))
         }
-        _ => return set_error(MMDB_INVALID_DATA_ERROR),
-    };
+        _ => None,
+    }
+}
 
-    let ip_str = ip_addr.to_string();
-    let ip_cstr = match CString::new(ip_str) {
-        Ok(s) => s,
-        Err(_) => return set_error(MMDB_OUT_OF_MEMORY_ERROR),
+/// Look up the address in a socket address with a native matchy handle
+///
+/// The address bytes are read straight out of the `sockaddr_in` or
+/// `sockaddr_in6` and used to walk the IP tree, with no `inet_ntop` and
+/// reparse round-trip. Use this in packet-processing hot paths that already
+/// hold a socket address.
+///
+/// # Parameters
+/// * `db` - Database handle (must not be NULL)
+/// * `sockaddr` - `AF_INET` or `AF_INET6` socket address (must not be NULL)
+/// * `error` - Receives MATCHY_SUCCESS, or MATCHY_ERROR_INVALID_PARAM for NULL
+///   arguments and other address families (may be NULL)
+///
+/// # Returns
+/// * matchy_result_t as from `matchy_query`; free it with matchy_free_result
+///
+/// # Safety
+/// - `db` must be a valid pointer from matchy_open
+/// - `sockaddr` must point to a socket address of the size its family implies
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn matchy_lookup_sockaddr(
+    db: *const matchy_t,
+    sockaddr: *const libc::sockaddr,
+    error: *mut c_int,
+) -> matchy_result_t {
+    let addr = if db.is_null() || sockaddr.is_null() {
+        None
+    } else {
+        sockaddr_to_ip(sockaddr)
     };
+    sockaddr_result(db, addr, error)
+}
 
-    // Use MMDB_lookup_string
-    let mut gai_error = 0;
-    MMDB_lookup_string(mmdb, ip_cstr.as_ptr(), &mut gai_error, mmdb_error)
+/// Run an IP lookup for `matchy_lookup_sockaddr`, reporting bad input in `error`
+unsafe fn sockaddr_result(
+    db: *const matchy_t,
+    addr: Option<IpAddr>,
+    error: *mut c_int,
+) -> matchy_result_t {
+    let (result, status) = match addr {
+        Some(addr) => (query_ip(db, addr), MATCHY_SUCCESS),
+        None => (
+            matchy_result_t {
+                found: false,
+                prefix_len: 0,
+                _data_cache: ptr::null_mut(),
+                _db_ref: ptr::null(),
+            },
+            MATCHY_ERROR_INVALID_PARAM,
+        ),
+    };
+    if !error.is_null() {
+        *error = status;
+    }
+    result
 }
 
 /// Lookup an IP address from sockaddr (Windows implementation)
@@ -309,37 +396,125 @@ pub unsafe extern "C" fn MMDB_lookup_sockaddr(
         }
     };
 
-    if mmdb.is_null() || sockaddr.is_null() {
+    if mmdb.is_null() || sockaddr.is_null() || (*mmdb)._matchy_db.is_null() {
         return set_error(MMDB_INVALID_DATA_ERROR);
     }
 
+    match sockaddr_to_ip(sockaddr) {
+        Some(addr) => mmdb_result(mmdb, query_ip((*mmdb)._matchy_db, addr), mmdb_error),
+        None => set_error(MMDB_INVALID_DATA_ERROR),
+    }
+}
+
+/// Read the address out of an `AF_INET` or `AF_INET6` socket address
+#[cfg(windows)]
+unsafe fn sockaddr_to_ip(sockaddr: *const winapi::shared::ws2def::SOCKADDR) -> Option<IpAddr> {
     use winapi::shared::ws2def::{AF_INET, AF_INET6, SOCKADDR_IN};
     use winapi::shared::ws2ipdef::SOCKADDR_IN6_LH;
 
-    // Convert sockaddr to IP string
-    let ip_addr = match (*sockaddr).sa_family as i32 {
+    match (*sockaddr).sa_family as i32 {
         AF_INET => {
             let sa = sockaddr as *const SOCKADDR_IN;
             let addr = u32::from_be(*(*sa).sin_addr.S_un.S_addr());
-            IpAddr::V4(Ipv4Addr::from(addr))
+            Some(IpAddr::V4(Ipv4Addr::from(addr)))
         }
         AF_INET6 => {
             let sa = sockaddr as *const SOCKADDR_IN6_LH;
             let addr = *(*sa).sin6_addr.u.Byte();
-            IpAddr::V6(Ipv6Addr::from(addr))
+            Some(IpAddr::V6(Ipv6Addr::from(addr)))
         }
-        _ => return set_error(MMDB_INVALID_DATA_ERROR),
+        _ => None,
+    }
+}
+
+/// Look up the address in a socket address with a native matchy handle (Windows)
+///
+/// # Safety
+/// - `db` must be a valid pointer from matchy_open
+/// - `sockaddr` must point to a socket address of the size its family implies
+#[cfg(windows)]
+#[no_mangle]
+pub unsafe extern "C" fn matchy_lookup_sockaddr(
+    db: *const matchy_t,
+    sockaddr: *const winapi::shared::ws2def::SOCKADDR,
+    error: *mut c_int,
+) -> matchy_result_t {
+    let addr = if db.is_null() || sockaddr.is_null() {
+        None
+    } else {
+        sockaddr_to_ip(sockaddr)
     };
+    sockaddr_result(db, addr, error)
+}
 
-    let ip_str = ip_addr.to_string();
-    let ip_cstr = match CString::new(ip_str) {
-        Ok(s) => s,
-        Err(_) => return set_error(MMDB_OUT_OF_MEMORY_ERROR),
+/// Call `callback` for every IP network in a native matchy handle
+///
+/// Walks the IP tree in address order, like libmaxminddb's `MMDB_next_node`
+/// iteration, so C tools can dump or diff a database. IPv4 networks in IPv6
+/// databases are reported once, as IPv4. Handles from `matchy_open_multi`
+/// visit each database in turn. Patterns and literal strings are not visited.
+///
+/// The callback may call any read-only function on `db` (`matchy_query`,
+/// `matchy_aget_value` on its entry, and so on), but must not close it.
+///
+/// # Parameters
+/// * `db` - Database handle (must not be NULL)
+/// * `callback` - Called for each network (must not be NULL)
+/// * `user_data` - Passed through to `callback` (may be NULL)
+///
+/// # Returns
+/// * MATCHY_SUCCESS after every network was visited or the callback stopped early
+/// * MATCHY_ERROR_INVALID_PARAM if `db` or `callback` is NULL
+/// * MATCHY_ERROR_CORRUPT_DATA if the tree or a data record is malformed
+///
+/// # Safety
+/// - `db` must be a valid pointer from matchy_open
+/// - `callback` must not close `db` or keep the network string or entry past its return
+///
+/// # Example
+/// ```c
+/// int print_network(const char *network, uint8_t prefix_len,
+///                   const matchy_entry_s *entry, void *user_data) {
+///     printf("%s/%u\n", network, prefix_len);
+///     return 0;
+/// }
+///
+/// matchy_iterate(db, print_network, NULL);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn matchy_iterate(
+    db: *const matchy_t,
+    callback: matchy_node_callback,
+    user_data: *mut c_void,
+) -> c_int {
+    let callback = match callback {
+        Some(callback) if !db.is_null() => callback,
+        _ => return MATCHY_ERROR_INVALID_PARAM,
     };
 
-    // Use MMDB_lookup_string
-    let mut gai_error = 0;
-    MMDB_lookup_string(mmdb, ip_cstr.as_ptr(), &mut gai_error, mmdb_error)
+    // A malformed database must not unwind into C
+    let walk = panic::catch_unwind(AssertUnwindSafe(|| {
+        for entry in networks(db) {
+            let Ok((addr, prefix_len, mut result)) = entry else {
+                return MATCHY_ERROR_CORRUPT_DATA;
+            };
+            // Formatted addresses never contain NUL
+            let network = CString::new(addr.to_string()).unwrap_or_default();
+            let mut entry = matchy_entry_s {
+                db: ptr::null(),
+                data_ptr: ptr::null(),
+            };
+            matchy_result_get_entry(&result, &mut entry);
+
+            let status = callback(network.as_ptr(), prefix_len, &entry, user_data);
+            matchy_free_result(&mut result);
+            if status != 0 {
+                break;
+            }
+        }
+        MATCHY_SUCCESS
+    }));
+    walk.unwrap_or(MATCHY_ERROR_CORRUPT_DATA)
 }
 
 /// Get value from entry using array path
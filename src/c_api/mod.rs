@@ -107,6 +107,12 @@
 //!
 //! This ensures readers always see consistent state.
 //!
+//! On Linux and macOS, renaming over a database that is open is safe: open
+//! handles keep reading the old file until they are closed. On Windows the
+//! rename fails while any handle has the file mapped; there, copy the file
+//! and open the copy, or close all handles before replacing it. Rust
+//! callers can use `DatabaseOpener::private_copy()` for the same effect.
+//!
 //! # Error Handling
 //!
 //! All functions return error codes. Common values:
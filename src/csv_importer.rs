@@ -0,0 +1,252 @@
+//! CSV Feed Importer
+//!
+//! Reads CSV threat feeds into an [`MmdbBuilder`] with
+//! [`MmdbBuilder::add_from_csv`]. The first row names the columns. The first
+//! column of every row is the key (IP address, CIDR, range, literal, or glob,
+//! detected like [`MmdbBuilder::add_entry`]) and the other columns become
+//! entry data, typed by a [`CsvSchema`].
+//!
+//! # Example
+//!
+//! ```
+//! use matchy::csv_importer::{CsvSchema, CsvType};
+//! use matchy::{DatabaseBuilder, MatchMode};
+//!
+//! let feed = "indicator,score,category
+//! 10.0.0.0/8,80,botnet
+//! *.evil.com,90,phishing
+//! bad.example.com,high,spam
+//! ";
+//!
+//! let schema = CsvSchema::new().column("score", CsvType::Uint32);
+//! let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+//! let import = builder.add_from_csv(feed.as_bytes(), &schema)?;
+//! assert_eq!(import.rows, 2);
+//! assert_eq!(import.failures[0].0, 4);
+//! # Ok::<(), matchy::DatabaseError>(())
+//! ```
+
+use crate::data_section::DataValue;
+use crate::database::DatabaseError;
+use crate::mmdb_builder::MmdbBuilder;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Data type a CSV column is converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvType {
+    /// Stored as-is as [`DataValue::String`]
+    String,
+    /// [`DataValue::Uint16`]
+    Uint16,
+    /// [`DataValue::Uint32`]
+    Uint32,
+    /// [`DataValue::Uint64`]
+    Uint64,
+    /// [`DataValue::Int32`]
+    Int32,
+    /// [`DataValue::Float`]
+    Float,
+    /// [`DataValue::Double`]
+    Double,
+    /// [`DataValue::Bool`] from `true` or `false` (any case)
+    Bool,
+}
+
+impl CsvType {
+    /// Convert one cell, naming the column in the error
+    fn parse(self, column: &str, cell: &str) -> Result<DataValue, DatabaseError> {
+        let invalid = || {
+            DatabaseError::Unsupported(format!(
+                "Column '{}': invalid {:?} value '{}'",
+                column, self, cell
+            ))
+        };
+        Ok(match self {
+            CsvType::String => DataValue::String(cell.to_string()),
+            CsvType::Uint16 => DataValue::Uint16(cell.parse().map_err(|_| invalid())?),
+            CsvType::Uint32 => DataValue::Uint32(cell.parse().map_err(|_| invalid())?),
+            CsvType::Uint64 => DataValue::Uint64(cell.parse().map_err(|_| invalid())?),
+            CsvType::Int32 => DataValue::Int32(cell.parse().map_err(|_| invalid())?),
+            CsvType::Float => DataValue::Float(cell.parse().map_err(|_| invalid())?),
+            CsvType::Double => DataValue::Double(cell.parse().map_err(|_| invalid())?),
+            CsvType::Bool => match cell.to_ascii_lowercase().as_str() {
+                "true" => DataValue::Bool(true),
+                "false" => DataValue::Bool(false),
+                _ => return Err(invalid()),
+            },
+        })
+    }
+}
+
+/// Column types for [`MmdbBuilder::add_from_csv`]
+///
+/// Columns not named in the schema are stored as strings.
+#[derive(Debug, Clone, Default)]
+pub struct CsvSchema {
+    columns: HashMap<String, CsvType>,
+}
+
+impl CsvSchema {
+    /// Create a schema where every column is a string
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the type of the column with header `name`
+    pub fn column(mut self, name: impl Into<String>, column_type: CsvType) -> Self {
+        self.columns.insert(name.into(), column_type);
+        self
+    }
+
+    /// Type of the column with header `name`
+    pub fn column_type(&self, name: &str) -> CsvType {
+        self.columns.get(name).copied().unwrap_or(CsvType::String)
+    }
+}
+
+/// Outcome of [`MmdbBuilder::add_from_csv`]
+#[derive(Debug)]
+pub struct CsvImport {
+    /// Number of rows added to the builder
+    pub rows: usize,
+    /// Rows that were skipped, as `(line_number, error)` with 1-based line numbers
+    pub failures: Vec<(usize, DatabaseError)>,
+}
+
+/// Add every row of a CSV feed to `builder`
+pub(crate) fn import_csv<R: Read>(
+    builder: &mut MmdbBuilder,
+    reader: R,
+    schema: &CsvSchema,
+) -> Result<CsvImport, DatabaseError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let columns: Vec<(String, CsvType)> = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(|name| (name.to_string(), schema.column_type(name)))
+        .collect();
+    if columns.is_empty() {
+        return Err(DatabaseError::Unsupported(
+            "CSV feed has no header row".to_string(),
+        ));
+    }
+
+    let mut import = CsvImport {
+        rows: 0,
+        failures: Vec::new(),
+    };
+    let mut record = csv::StringRecord::new();
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) if e.is_io_error() => return Err(csv_error(e)),
+            Err(e) => {
+                let line = e.position().map_or(0, |pos| pos.line() as usize);
+                import.failures.push((line, csv_error(e)));
+                continue;
+            }
+        }
+        let line = record.position().map_or(0, |pos| pos.line() as usize);
+        match add_row(builder, &columns, &record) {
+            Ok(()) => import.rows += 1,
+            Err(e) => import.failures.push((line, e)),
+        }
+    }
+    Ok(import)
+}
+
+/// Convert and add one data row
+fn add_row(
+    builder: &mut MmdbBuilder,
+    columns: &[(String, CsvType)],
+    record: &csv::StringRecord,
+) -> Result<(), DatabaseError> {
+    if record.len() != columns.len() {
+        return Err(DatabaseError::Unsupported(format!(
+            "Expected {} columns, found {}",
+            columns.len(),
+            record.len()
+        )));
+    }
+    let key = record[0].trim();
+    if key.is_empty() {
+        return Err(DatabaseError::Unsupported("Empty key".to_string()));
+    }
+
+    let mut data = HashMap::new();
+    for ((name, column_type), cell) in columns.iter().zip(record.iter()).skip(1) {
+        if !cell.is_empty() {
+            data.insert(name.clone(), column_type.parse(name, cell.trim())?);
+        }
+    }
+    builder
+        .add_entry(key, data)
+        .map_err(|e| DatabaseError::Unsupported(e.to_string()))
+}
+
+fn csv_error(e: csv::Error) -> DatabaseError {
+    if e.is_io_error() {
+        DatabaseError::Io(e.to_string())
+    } else {
+        DatabaseError::Unsupported(format!("Invalid CSV: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glob::MatchMode;
+    use crate::{Database, QueryResult};
+
+    #[test]
+    fn test_add_from_csv() {
+        let feed = "\
+indicator,score,category,active
+10.0.0.0/8,80,botnet,true
+*.evil.com,90,phishing,
+bad.example.com,high,spam,false
+10.1.0.0/16,5
+evil.org,7,malware,FALSE
+";
+        let schema = CsvSchema::new()
+            .column("score", CsvType::Uint32)
+            .column("active", CsvType::Bool);
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let import = builder.add_from_csv(feed.as_bytes(), &schema).unwrap();
+
+        assert_eq!(import.rows, 3);
+        let lines: Vec<usize> = import.failures.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![4, 5]);
+        assert!(import.failures[0].1.to_string().contains("score"));
+
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        let data = |key: &str| db.lookup(key).unwrap().unwrap().first_data().cloned();
+
+        let mut expected = HashMap::new();
+        expected.insert("score".to_string(), DataValue::Uint32(80));
+        expected.insert(
+            "category".to_string(),
+            DataValue::String("botnet".to_string()),
+        );
+        expected.insert("active".to_string(), DataValue::Bool(true));
+        assert_eq!(data("10.1.2.3"), Some(DataValue::from_hash_map(expected)));
+
+        let mut expected = HashMap::new();
+        expected.insert("score".to_string(), DataValue::Uint32(90));
+        expected.insert(
+            "category".to_string(),
+            DataValue::String("phishing".to_string()),
+        );
+        assert_eq!(
+            data("www.evil.com"),
+            Some(DataValue::from_hash_map(expected))
+        );
+        assert!(matches!(
+            db.lookup("bad.example.com").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+    }
+}
@@ -27,9 +27,17 @@
 //!
 //! See: <https://maxmind.github.io/MaxMind-DB/>
 
+use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
+/// Map payload of [`DataValue::Map`]
+///
+/// Entries keep their insertion order, and decoded maps keep the order the
+/// entries were encoded in, so iteration, `Debug`, and JSON output are stable.
+/// Equality ignores order.
+pub type DataMap = IndexMap<String, DataValue>;
+
 /// Data value that can be stored in the data section
 ///
 /// This enum represents all MMDB data types and can be used
@@ -52,8 +60,8 @@ pub enum DataValue {
     Uint16(u16),
     /// Unsigned 32-bit integer
     Uint32(u32),
-    /// Key-value map (string keys only per MMDB spec)
-    Map(HashMap<String, DataValue>),
+    /// Key-value map (string keys only per MMDB spec), in encoded order
+    Map(DataMap),
     /// Signed 32-bit integer
     Int32(i32),
     /// Unsigned 64-bit integer
@@ -68,6 +76,185 @@ pub enum DataValue {
     Float(f32),
 }
 
+impl DataValue {
+    /// Borrow the raw bytes of a `Bytes` value
+    ///
+    /// Bytes are stored with the MMDB bytes type (type 4): a size-prefixed
+    /// control byte followed by the raw payload, with no base64 or hex overhead.
+    /// Lookups return exactly the bytes that were added.
+    ///
+    /// Returns `None` for any other variant.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::DataValue;
+    ///
+    /// let fingerprint = DataValue::Bytes(vec![0xDE, 0xAD, 0xBE, 0xEF]);
+    /// assert_eq!(fingerprint.bytes(), Some(&[0xDE, 0xAD, 0xBE, 0xEF][..]));
+    /// assert_eq!(DataValue::Uint32(1).bytes(), None);
+    /// ```
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            DataValue::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Borrow the entries of a `Map` value, in encoded order
+    ///
+    /// Returns `None` for any other variant.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DataValue, RecordBuilder};
+    ///
+    /// let record = RecordBuilder::new().str("b", "2").str("a", "1").build();
+    /// let keys: Vec<&str> = record.as_map().unwrap().keys().map(String::as_str).collect();
+    /// assert_eq!(keys, ["b", "a"]);
+    /// ```
+    pub fn as_map(&self) -> Option<&DataMap> {
+        match self {
+            DataValue::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Build a `Map` value from a `HashMap`, with keys in sorted order
+    ///
+    /// `HashMap` iteration order is random, so keys are sorted to keep encoded
+    /// output deterministic. This is how the builders store entry data.
+    pub fn from_hash_map(map: HashMap<String, DataValue>) -> Self {
+        DataValue::Map(sorted_data_map(map))
+    }
+}
+
+/// Collect a `HashMap` into a [`DataMap`] with keys in sorted order
+pub(crate) fn sorted_data_map(map: HashMap<String, DataValue>) -> DataMap {
+    let mut entries: Vec<_> = map.into_iter().collect();
+    entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    entries.into_iter().collect()
+}
+
+/// Fluent builder for map records
+///
+/// Builds nested `DataValue::Map` records (such as GeoIP-style
+/// `country.names.en`) without constructing each `HashMap` by hand. Fields keep
+/// the order they were set in. Setting a key twice keeps the last value, in
+/// the position of the first.
+///
+/// # Example
+/// ```
+/// use matchy::{DataValue, DatabaseBuilder, MatchMode, RecordBuilder};
+///
+/// let record = RecordBuilder::new()
+///     .map("country", |m| {
+///         m.str("iso_code", "US")
+///             .map("names", |n| n.str("en", "United States"))
+///     })
+///     .uint32("geoname_id", 6252001);
+///
+/// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+/// builder.add_entry("8.8.8.0/24", record.into_map())?;
+///
+/// let value = RecordBuilder::new().str("iso_code", "US").build();
+/// assert!(matches!(value, DataValue::Map(_)));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordBuilder {
+    entries: DataMap,
+}
+
+impl RecordBuilder {
+    /// Create an empty record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a key to any value
+    pub fn value(mut self, key: impl Into<String>, value: DataValue) -> Self {
+        self.entries.insert(key.into(), value);
+        self
+    }
+
+    /// Set a string value
+    pub fn str(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.value(key, DataValue::String(value.into()))
+    }
+
+    /// Set an unsigned 16-bit integer value
+    pub fn uint16(self, key: impl Into<String>, value: u16) -> Self {
+        self.value(key, DataValue::Uint16(value))
+    }
+
+    /// Set an unsigned 32-bit integer value
+    pub fn uint32(self, key: impl Into<String>, value: u32) -> Self {
+        self.value(key, DataValue::Uint32(value))
+    }
+
+    /// Set an unsigned 64-bit integer value
+    pub fn uint64(self, key: impl Into<String>, value: u64) -> Self {
+        self.value(key, DataValue::Uint64(value))
+    }
+
+    /// Set a signed 32-bit integer value
+    pub fn int32(self, key: impl Into<String>, value: i32) -> Self {
+        self.value(key, DataValue::Int32(value))
+    }
+
+    /// Set a double precision float value
+    pub fn double(self, key: impl Into<String>, value: f64) -> Self {
+        self.value(key, DataValue::Double(value))
+    }
+
+    /// Set a boolean value
+    pub fn bool(self, key: impl Into<String>, value: bool) -> Self {
+        self.value(key, DataValue::Bool(value))
+    }
+
+    /// Set a raw bytes value
+    pub fn bytes(self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.value(key, DataValue::Bytes(value.into()))
+    }
+
+    /// Set an array value
+    pub fn array(self, key: impl Into<String>, items: impl IntoIterator<Item = DataValue>) -> Self {
+        self.value(key, DataValue::Array(items.into_iter().collect()))
+    }
+
+    /// Set a nested map built by `f`
+    pub fn map(
+        self,
+        key: impl Into<String>,
+        f: impl FnOnce(RecordBuilder) -> RecordBuilder,
+    ) -> Self {
+        let nested = f(RecordBuilder::new()).build();
+        self.value(key, nested)
+    }
+
+    /// Finish the record as a `DataValue::Map`
+    pub fn build(self) -> DataValue {
+        DataValue::Map(self.entries)
+    }
+
+    /// Finish the record as the entry map taken by `DatabaseBuilder::add_entry`
+    pub fn into_map(self) -> HashMap<String, DataValue> {
+        self.entries.into_iter().collect()
+    }
+}
+
+impl From<RecordBuilder> for DataValue {
+    fn from(record: RecordBuilder) -> Self {
+        record.build()
+    }
+}
+
+impl From<RecordBuilder> for HashMap<String, DataValue> {
+    fn from(record: RecordBuilder) -> Self {
+        record.into_map()
+    }
+}
+
 // Custom serialization that excludes Pointer (internal format detail)
 impl serde::Serialize for DataValue {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -185,11 +372,11 @@ impl<'de> serde::Deserialize<'de> for DataValue {
             where
                 A: serde::de::MapAccess<'de>,
             {
-                let mut hash_map = HashMap::new();
+                let mut entries = DataMap::new();
                 while let Some((key, value)) = map.next_entry()? {
-                    hash_map.insert(key, value);
+                    entries.insert(key, value);
                 }
-                Ok(DataValue::Map(hash_map))
+                Ok(DataValue::Map(entries))
             }
         }
 
@@ -214,7 +401,7 @@ impl Hash for DataValue {
             DataValue::Uint16(v) => v.hash(state),
             DataValue::Uint32(v) => v.hash(state),
             DataValue::Map(m) => {
-                // Hash maps require sorted keys for deterministic hashing
+                // Sort keys so hashing, like equality, ignores entry order
                 let mut keys: Vec<&String> = m.keys().collect();
                 keys.sort_unstable();
                 keys.len().hash(state);
@@ -241,6 +428,132 @@ impl Hash for DataValue {
     }
 }
 
+/// Lossless serde form of a [`DataValue`]
+///
+/// `DataValue`'s own serde impls map onto plain JSON (numbers, strings,
+/// objects), which is what JSON import and CLI output want, but a plain
+/// number can't say whether it was a `Uint16` or a `Uint64`. This wrapper
+/// tags every value with its type instead, so a round trip through any serde
+/// format gives back the exact variant:
+///
+/// ```json
+/// {"map": {"asn": {"uint32": 15169}, "tags": {"array": [{"string": "dns"}]}}}
+/// ```
+///
+/// Tags are the lowercase variant names. `Bytes` is an array of byte values
+/// and `Uint128` a decimal string, since JSON numbers can't hold it.
+/// `Pointer` can't be serialized.
+///
+/// ```
+/// use matchy::data_section::TypedDataValue;
+/// use matchy::DataValue;
+///
+/// let value = DataValue::Array(vec![DataValue::Uint16(1), DataValue::Uint64(1)]);
+/// let json = serde_json::to_value(TypedDataValue(value.clone()))?;
+/// let back: TypedDataValue = serde_json::from_value(json)?;
+/// assert_eq!(back.0, value);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedDataValue(pub DataValue);
+
+/// Externally tagged mirror of `DataValue` used by [`TypedDataValue`]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Typed {
+    String(String),
+    Double(f64),
+    Bytes(Vec<u8>),
+    Uint16(u16),
+    Uint32(u32),
+    Map(IndexMap<String, Typed>),
+    Int32(i32),
+    Uint64(u64),
+    Uint128(String),
+    Array(Vec<Typed>),
+    Bool(bool),
+    Float(f32),
+}
+
+impl Typed {
+    fn from_value(value: &DataValue) -> Result<Self, &'static str> {
+        Ok(match value {
+            DataValue::Pointer(_) => {
+                return Err("Pointer is an internal type and cannot be serialized")
+            }
+            DataValue::String(s) => Typed::String(s.clone()),
+            DataValue::Double(d) => Typed::Double(*d),
+            DataValue::Bytes(b) => Typed::Bytes(b.clone()),
+            DataValue::Uint16(n) => Typed::Uint16(*n),
+            DataValue::Uint32(n) => Typed::Uint32(*n),
+            DataValue::Map(m) => Typed::Map(
+                m.iter()
+                    .map(|(k, v)| Ok((k.clone(), Typed::from_value(v)?)))
+                    .collect::<Result<_, &'static str>>()?,
+            ),
+            DataValue::Int32(n) => Typed::Int32(*n),
+            DataValue::Uint64(n) => Typed::Uint64(*n),
+            DataValue::Uint128(n) => Typed::Uint128(n.to_string()),
+            DataValue::Array(a) => {
+                Typed::Array(a.iter().map(Typed::from_value).collect::<Result<_, _>>()?)
+            }
+            DataValue::Bool(b) => Typed::Bool(*b),
+            DataValue::Float(f) => Typed::Float(*f),
+        })
+    }
+
+    fn into_value(self) -> Result<DataValue, String> {
+        Ok(match self {
+            Typed::String(s) => DataValue::String(s),
+            Typed::Double(d) => DataValue::Double(d),
+            Typed::Bytes(b) => DataValue::Bytes(b),
+            Typed::Uint16(n) => DataValue::Uint16(n),
+            Typed::Uint32(n) => DataValue::Uint32(n),
+            Typed::Map(m) => DataValue::Map(
+                m.into_iter()
+                    .map(|(k, v)| Ok((k, v.into_value()?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Typed::Int32(n) => DataValue::Int32(n),
+            Typed::Uint64(n) => DataValue::Uint64(n),
+            Typed::Uint128(n) => DataValue::Uint128(
+                n.parse()
+                    .map_err(|_| format!("Invalid uint128 value: {:?}", n))?,
+            ),
+            Typed::Array(a) => DataValue::Array(
+                a.into_iter()
+                    .map(Typed::into_value)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Typed::Bool(b) => DataValue::Bool(b),
+            Typed::Float(f) => DataValue::Float(f),
+        })
+    }
+}
+
+impl serde::Serialize for TypedDataValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Typed::from_value(&self.0)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TypedDataValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Typed::deserialize(deserializer)?
+            .into_value()
+            .map(TypedDataValue)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// Data section encoder
 ///
 /// Builds a data section by encoding values and tracking offsets.
@@ -309,11 +622,44 @@ impl DataEncoder {
         offset
     }
 
+    /// Write a dictionary of shared strings at the current position
+    ///
+    /// Each string is encoded once and entered in the interning table, so
+    /// every later occurrence - as a map key or a value - becomes a pointer
+    /// into the dictionary. Pointers to the first 2KB of the data section take
+    /// 2 bytes, so call this on an empty encoder with the most frequently
+    /// used strings first. Strings already interned are skipped.
+    ///
+    /// Returns the number of bytes written. Does nothing without interning.
+    pub fn intern_dictionary<'a, I>(&mut self, strings: I) -> usize
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        if !self.intern_strings {
+            return 0;
+        }
+
+        let start = self.buffer.len();
+        for s in strings {
+            if !self.string_cache.contains_key(s) {
+                let offset = self.buffer.len() as u32;
+                Self::encode_string(s, &mut self.buffer);
+                self.string_cache.insert(s.to_string(), offset);
+            }
+        }
+        self.buffer.len() - start
+    }
+
     /// Get the final encoded data section
     pub fn into_bytes(self) -> Vec<u8> {
         self.buffer
     }
 
+    /// Encoded data so far, for decoding values before the section is finished
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
     /// Get current buffer size
     pub fn size(&self) -> usize {
         self.buffer.len()
@@ -451,14 +797,11 @@ impl DataEncoder {
     }
 
     // Type 7: Map (with interning)
-    fn encode_map_interned(&mut self, m: &HashMap<String, DataValue>) {
+    fn encode_map_interned(&mut self, m: &DataMap) {
         Self::encode_with_size(7, m.len(), &mut self.buffer);
 
-        // Encode key-value pairs (sorted by key for deterministic output)
-        let mut pairs: Vec<_> = m.iter().collect();
-        pairs.sort_by_key(|(k, _)| *k);
-
-        for (key, value) in pairs {
+        // Encode key-value pairs in map order (decoding preserves it)
+        for (key, value) in m {
             // Intern the map key
             if self.intern_strings {
                 if let Some(&existing_offset) = self.string_cache.get(key) {
@@ -478,14 +821,11 @@ impl DataEncoder {
     }
 
     // Type 7: Map (static version, no interning)
-    fn encode_map(m: &HashMap<String, DataValue>, buffer: &mut Vec<u8>) {
+    fn encode_map(m: &DataMap, buffer: &mut Vec<u8>) {
         Self::encode_with_size(7, m.len(), buffer);
 
-        // Encode key-value pairs (sorted by key for deterministic output)
-        let mut pairs: Vec<_> = m.iter().collect();
-        pairs.sort_by_key(|(k, _)| *k);
-
-        for (key, value) in pairs {
+        // Encode key-value pairs in map order (decoding preserves it)
+        for (key, value) in m {
             Self::encode_string(key, buffer);
             Self::encode_to_buffer(value, buffer);
         }
@@ -620,10 +960,17 @@ impl Default for DataEncoder {
     }
 }
 
+/// Deepest nesting of maps, arrays, and pointers the decoder will follow
+///
+/// Matches libmaxminddb's limit. Without it a crafted file with deeply nested
+/// containers, or a pointer cycle, would overflow the stack.
+const MAX_DATA_DEPTH: usize = 512;
+
 /// Data section decoder
 ///
 /// Decodes values from an encoded data section buffer.
 /// Fully compatible with MMDB format.
+#[derive(Debug, Clone, Copy)]
 pub struct DataDecoder<'a> {
     buffer: &'a [u8],
     base_offset: usize,
@@ -644,17 +991,198 @@ impl<'a> DataDecoder<'a> {
 
     /// Decode a value at the given offset
     pub fn decode(&self, offset: u32) -> Result<DataValue, &'static str> {
+        self.decode_nested(offset, 0)
+    }
+
+    /// Decode the value at `offset`, `depth` levels below the top-level value
+    fn decode_nested(&self, offset: u32, depth: usize) -> Result<DataValue, &'static str> {
         let mut cursor = offset as usize;
         if cursor < self.base_offset {
             return Err("Offset before base");
         }
         cursor -= self.base_offset;
-        let value = self.decode_at(&mut cursor)?;
+        let value = self.decode_at(&mut cursor, depth)?;
         // Recursively resolve pointers in the returned value
-        self.resolve_pointers(value)
+        self.resolve_pointers(value, depth)
+    }
+
+    /// Number of bytes the value at `offset` occupies
+    ///
+    /// Nothing is decoded. A pointer inside the value counts only its own
+    /// bytes; the value it points to lives elsewhere in the buffer.
+    pub fn encoded_len(&self, offset: u32) -> Result<usize, &'static str> {
+        let mut cursor = offset as usize;
+        if cursor < self.base_offset {
+            return Err("Offset before base");
+        }
+        cursor -= self.base_offset;
+        let start = cursor;
+        self.skip_value(&mut cursor, 0)?;
+        Ok(cursor - start)
     }
 
-    fn decode_at(&self, cursor: &mut usize) -> Result<DataValue, &'static str> {
+    /// Decode a value at the given offset as a borrowed view
+    ///
+    /// Unlike [`decode`](Self::decode), nothing is copied: strings and bytes
+    /// borrow from the buffer, and maps and arrays are walked lazily as they
+    /// are accessed. Pointers are followed on demand.
+    pub fn decode_ref(&self, offset: u32) -> Result<DataValueRef<'a>, &'static str> {
+        let mut cursor = offset as usize;
+        if cursor < self.base_offset {
+            return Err("Offset before base");
+        }
+        cursor -= self.base_offset;
+        self.decode_ref_at(&mut cursor, false, 0)
+    }
+
+    /// Decode a borrowed view at `cursor`
+    ///
+    /// Containers are not walked, so the cursor is left just past their
+    /// header; use [`skip_value`](Self::skip_value) to step over a whole value.
+    /// `depth` is the container nesting of the value, carried into the views
+    /// so that walking them stops at [`MAX_DATA_DEPTH`].
+    fn decode_ref_at(
+        &self,
+        cursor: &mut usize,
+        via_pointer: bool,
+        depth: usize,
+    ) -> Result<DataValueRef<'a>, &'static str> {
+        if *cursor >= self.buffer.len() {
+            return Err("Cursor out of bounds");
+        }
+
+        let ctrl = self.buffer[*cursor];
+        let type_id = ctrl >> 5;
+        let payload = ctrl & 0x1F;
+
+        match type_id {
+            1 => {
+                // Pointers never point at other pointers (MMDB spec)
+                if via_pointer {
+                    return Err("Pointer to pointer");
+                }
+                *cursor += 1;
+                let target = match self.decode_pointer(cursor, payload)? {
+                    DataValue::Pointer(target) => target as usize,
+                    _ => return Err("Invalid pointer"),
+                };
+                if target < self.base_offset {
+                    return Err("Pointer offset before base");
+                }
+                let mut target_cursor = target - self.base_offset;
+                self.decode_ref_at(&mut target_cursor, true, depth)
+            }
+            2 | 4 => {
+                *cursor += 1;
+                let len = self.decode_size(cursor, payload)?;
+                if *cursor + len > self.buffer.len() {
+                    return Err("String/bytes data out of bounds");
+                }
+                let slice = &self.buffer[*cursor..*cursor + len];
+                *cursor += len;
+                if type_id == 2 {
+                    let s = std::str::from_utf8(slice).map_err(|_| "Invalid UTF-8")?;
+                    Ok(DataValueRef::String(s))
+                } else {
+                    Ok(DataValueRef::Bytes(slice))
+                }
+            }
+            7 => {
+                if depth >= MAX_DATA_DEPTH {
+                    return Err("Data nested too deeply");
+                }
+                *cursor += 1;
+                let len = self.decode_size(cursor, payload)?;
+                Ok(DataValueRef::Map(MapRef {
+                    decoder: *self,
+                    start: *cursor,
+                    len,
+                    depth,
+                }))
+            }
+            0 if self.buffer.get(*cursor + 1) == Some(&4) => {
+                // Extended type 4: array
+                if depth >= MAX_DATA_DEPTH {
+                    return Err("Data nested too deeply");
+                }
+                *cursor += 2;
+                let len = self.decode_size(cursor, payload)?;
+                Ok(DataValueRef::Array(ArrayRef {
+                    decoder: *self,
+                    start: *cursor,
+                    len,
+                    depth,
+                }))
+            }
+            _ => match self.decode_at(cursor, depth)? {
+                DataValue::Double(v) => Ok(DataValueRef::Double(v)),
+                DataValue::Uint16(v) => Ok(DataValueRef::Uint16(v)),
+                DataValue::Uint32(v) => Ok(DataValueRef::Uint32(v)),
+                DataValue::Int32(v) => Ok(DataValueRef::Int32(v)),
+                DataValue::Uint64(v) => Ok(DataValueRef::Uint64(v)),
+                DataValue::Uint128(v) => Ok(DataValueRef::Uint128(v)),
+                DataValue::Bool(v) => Ok(DataValueRef::Bool(v)),
+                DataValue::Float(v) => Ok(DataValueRef::Float(v)),
+                _ => Err("Unexpected type"),
+            },
+        }
+    }
+
+    /// Advance `cursor` past the value stored there, without decoding it
+    ///
+    /// `depth` is the container nesting of the value; skipping fails past
+    /// [`MAX_DATA_DEPTH`] instead of recursing without bound.
+    fn skip_value(&self, cursor: &mut usize, depth: usize) -> Result<(), &'static str> {
+        if *cursor >= self.buffer.len() {
+            return Err("Cursor out of bounds");
+        }
+
+        let ctrl = self.buffer[*cursor];
+        let type_id = ctrl >> 5;
+        let payload = ctrl & 0x1F;
+
+        match type_id {
+            // The pointed-to value lives elsewhere; only the pointer is inline
+            1 => {
+                *cursor += 1;
+                self.decode_pointer(cursor, payload).map(|_| ())
+            }
+            2 | 4 => {
+                *cursor += 1;
+                let len = self.decode_size(cursor, payload)?;
+                if *cursor + len > self.buffer.len() {
+                    return Err("String/bytes data out of bounds");
+                }
+                *cursor += len;
+                Ok(())
+            }
+            7 => {
+                if depth >= MAX_DATA_DEPTH {
+                    return Err("Data nested too deeply");
+                }
+                *cursor += 1;
+                let len = self.decode_size(cursor, payload)?;
+                for _ in 0..len * 2 {
+                    self.skip_value(cursor, depth + 1)?;
+                }
+                Ok(())
+            }
+            0 if self.buffer.get(*cursor + 1) == Some(&4) => {
+                if depth >= MAX_DATA_DEPTH {
+                    return Err("Data nested too deeply");
+                }
+                *cursor += 2;
+                let len = self.decode_size(cursor, payload)?;
+                for _ in 0..len {
+                    self.skip_value(cursor, depth + 1)?;
+                }
+                Ok(())
+            }
+            _ => self.decode_at(cursor, depth).map(|_| ()),
+        }
+    }
+
+    fn decode_at(&self, cursor: &mut usize, depth: usize) -> Result<DataValue, &'static str> {
         if *cursor >= self.buffer.len() {
             return Err("Cursor out of bounds");
         }
@@ -666,14 +1194,14 @@ impl<'a> DataDecoder<'a> {
         let payload = ctrl & 0x1F;
 
         match type_id {
-            0 => self.decode_extended(cursor, payload),
+            0 => self.decode_extended(cursor, payload, depth),
             1 => self.decode_pointer(cursor, payload),
             2 => self.decode_string(cursor, payload),
             3 => self.decode_double(cursor),
             4 => self.decode_bytes(cursor, payload),
             5 => self.decode_uint16(cursor, payload),
             6 => self.decode_uint32(cursor, payload),
-            7 => self.decode_map(cursor, payload),
+            7 => self.decode_map(cursor, payload, depth),
             _ => Err("Invalid type"),
         }
     }
@@ -682,6 +1210,7 @@ impl<'a> DataDecoder<'a> {
         &self,
         cursor: &mut usize,
         size_from_ctrl: u8,
+        depth: usize,
     ) -> Result<DataValue, &'static str> {
         if *cursor >= self.buffer.len() {
             return Err("Extended type truncated");
@@ -697,7 +1226,7 @@ impl<'a> DataDecoder<'a> {
             8 => self.decode_int32(cursor, size_from_ctrl), // Extended type 1
             9 => self.decode_uint64(cursor, size_from_ctrl), // Extended type 2
             10 => self.decode_uint128(cursor, size_from_ctrl), // Extended type 3
-            11 => self.decode_array(cursor, size_from_ctrl), // Extended type 4
+            11 => self.decode_array(cursor, size_from_ctrl, depth), // Extended type 4
             14 => Ok(DataValue::Bool(size_from_ctrl != 0)), // Extended type 7
             15 => self.decode_float(cursor, size_from_ctrl), // Extended type 8
             _ => {
@@ -843,18 +1372,26 @@ impl<'a> DataDecoder<'a> {
         Ok(DataValue::Uint32(value))
     }
 
-    fn decode_map(&self, cursor: &mut usize, size_bits: u8) -> Result<DataValue, &'static str> {
+    fn decode_map(
+        &self,
+        cursor: &mut usize,
+        size_bits: u8,
+        depth: usize,
+    ) -> Result<DataValue, &'static str> {
+        if depth >= MAX_DATA_DEPTH {
+            return Err("Data nested too deeply");
+        }
         let count = self.decode_size(cursor, size_bits)?;
-        let mut map = HashMap::new();
+        let mut map = DataMap::with_capacity(count.min(1024));
 
         for _ in 0..count {
             // Decode key - can be String or Pointer (MMDB uses pointers for deduplication)
-            let key_value = self.decode_at(cursor)?;
+            let key_value = self.decode_at(cursor, depth + 1)?;
             let key = match key_value {
                 DataValue::String(s) => s,
                 DataValue::Pointer(offset) => {
                     // Follow pointer to get the actual key string
-                    match self.decode(offset)? {
+                    match self.decode_nested(offset, depth + 1)? {
                         DataValue::String(s) => s,
                         _ => return Err("Pointer in map key must point to string"),
                     }
@@ -862,7 +1399,7 @@ impl<'a> DataDecoder<'a> {
                 _ => return Err("Map key must be string or pointer to string"),
             };
 
-            let value = self.decode_at(cursor)?;
+            let value = self.decode_at(cursor, depth + 1)?;
             map.insert(key, value);
         }
 
@@ -942,12 +1479,20 @@ impl<'a> DataDecoder<'a> {
         Ok(DataValue::Uint128(value))
     }
 
-    fn decode_array(&self, cursor: &mut usize, size_bits: u8) -> Result<DataValue, &'static str> {
+    fn decode_array(
+        &self,
+        cursor: &mut usize,
+        size_bits: u8,
+        depth: usize,
+    ) -> Result<DataValue, &'static str> {
+        if depth >= MAX_DATA_DEPTH {
+            return Err("Data nested too deeply");
+        }
         let count = self.decode_size(cursor, size_bits)?;
         let mut array = Vec::with_capacity(count);
 
         for _ in 0..count {
-            array.push(self.decode_at(cursor)?);
+            array.push(self.decode_at(cursor, depth + 1)?);
         }
 
         Ok(DataValue::Array(array))
@@ -1005,7 +1550,11 @@ impl<'a> DataDecoder<'a> {
     }
 
     /// Recursively resolve all pointers in a decoded value
-    fn resolve_pointers(&self, value: DataValue) -> Result<DataValue, &'static str> {
+    fn resolve_pointers(&self, value: DataValue, depth: usize) -> Result<DataValue, &'static str> {
+        // Each pointer hop counts as a level, so a pointer cycle ends here too
+        if depth > MAX_DATA_DEPTH {
+            return Err("Data nested too deeply");
+        }
         match value {
             DataValue::Pointer(offset) => {
                 // Follow the pointer and recursively resolve
@@ -1014,14 +1563,14 @@ impl<'a> DataDecoder<'a> {
                     return Err("Pointer offset before base");
                 }
                 cursor -= self.base_offset;
-                let pointed_value = self.decode_at(&mut cursor)?;
-                self.resolve_pointers(pointed_value)
+                let pointed_value = self.decode_at(&mut cursor, depth + 1)?;
+                self.resolve_pointers(pointed_value, depth + 1)
             }
             DataValue::Map(entries) => {
                 // Recursively resolve pointers in map values
-                let mut resolved_map = HashMap::new();
+                let mut resolved_map = DataMap::with_capacity(entries.len());
                 for (key, val) in entries {
-                    resolved_map.insert(key, self.resolve_pointers(val)?);
+                    resolved_map.insert(key, self.resolve_pointers(val, depth + 1)?);
                 }
                 Ok(DataValue::Map(resolved_map))
             }
@@ -1029,7 +1578,7 @@ impl<'a> DataDecoder<'a> {
                 // Recursively resolve pointers in array elements
                 let mut resolved_array = Vec::new();
                 for item in items {
-                    resolved_array.push(self.resolve_pointers(item)?);
+                    resolved_array.push(self.resolve_pointers(item, depth + 1)?);
                 }
                 Ok(DataValue::Array(resolved_array))
             }
@@ -1039,6 +1588,269 @@ impl<'a> DataDecoder<'a> {
     }
 }
 
+/// Borrowed view of an encoded data value
+///
+/// Returned by [`DataDecoder::decode_ref`]. Strings and bytes borrow directly
+/// from the data section, and maps and arrays decode their entries only as
+/// they are accessed, so reading a single field of a large record does not
+/// allocate. Use [`to_value`](Self::to_value) to get a full [`DataValue`].
+#[derive(Debug, Clone, Copy)]
+pub enum DataValueRef<'a> {
+    /// UTF-8 string
+    String(&'a str),
+    /// IEEE 754 double precision float
+    Double(f64),
+    /// Raw byte array
+    Bytes(&'a [u8]),
+    /// Unsigned 16-bit integer
+    Uint16(u16),
+    /// Unsigned 32-bit integer
+    Uint32(u32),
+    /// Key-value map, decoded lazily
+    Map(MapRef<'a>),
+    /// Signed 32-bit integer
+    Int32(i32),
+    /// Unsigned 64-bit integer
+    Uint64(u64),
+    /// Unsigned 128-bit integer
+    Uint128(u128),
+    /// Array of values, decoded lazily
+    Array(ArrayRef<'a>),
+    /// Boolean value
+    Bool(bool),
+    /// IEEE 754 single precision float
+    Float(f32),
+}
+
+impl<'a> DataValueRef<'a> {
+    /// Borrow the string, if this is a `String` value
+    pub fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            DataValueRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Borrow the bytes, if this is a `Bytes` value
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match *self {
+            DataValueRef::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Get the map view, if this is a `Map` value
+    pub fn as_map(&self) -> Option<MapRef<'a>> {
+        match *self {
+            DataValueRef::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Get the array view, if this is an `Array` value
+    pub fn as_array(&self) -> Option<ArrayRef<'a>> {
+        match *self {
+            DataValueRef::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Decode the full value, copying strings and walking all containers
+    pub fn to_value(&self) -> Result<DataValue, &'static str> {
+        Ok(match *self {
+            DataValueRef::String(s) => DataValue::String(s.to_string()),
+            DataValueRef::Double(v) => DataValue::Double(v),
+            DataValueRef::Bytes(b) => DataValue::Bytes(b.to_vec()),
+            DataValueRef::Uint16(v) => DataValue::Uint16(v),
+            DataValueRef::Uint32(v) => DataValue::Uint32(v),
+            DataValueRef::Map(map) => {
+                let mut out = DataMap::with_capacity(map.len());
+                for entry in map.iter() {
+                    let (key, value) = entry?;
+                    out.insert(key.to_string(), value.to_value()?);
+                }
+                DataValue::Map(out)
+            }
+            DataValueRef::Int32(v) => DataValue::Int32(v),
+            DataValueRef::Uint64(v) => DataValue::Uint64(v),
+            DataValueRef::Uint128(v) => DataValue::Uint128(v),
+            DataValueRef::Array(array) => {
+                let mut out = Vec::with_capacity(array.len());
+                for item in array.iter() {
+                    out.push(item?.to_value()?);
+                }
+                DataValue::Array(out)
+            }
+            DataValueRef::Bool(v) => DataValue::Bool(v),
+            DataValueRef::Float(v) => DataValue::Float(v),
+        })
+    }
+}
+
+/// Lazily decoded map inside a [`DataValueRef`]
+#[derive(Debug, Clone, Copy)]
+pub struct MapRef<'a> {
+    decoder: DataDecoder<'a>,
+    start: usize,
+    len: usize,
+    depth: usize,
+}
+
+impl<'a> MapRef<'a> {
+    /// Number of entries in the map
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the map has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Look up a key, decoding entries in order until it is found
+    pub fn get(&self, key: &str) -> Result<Option<DataValueRef<'a>>, &'static str> {
+        for entry in self.iter() {
+            let (k, v) = entry?;
+            if k == key {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Iterate over entries in encoded order
+    pub fn iter(&self) -> MapRefIter<'a> {
+        MapRefIter {
+            decoder: self.decoder,
+            cursor: self.start,
+            remaining: self.len,
+            depth: self.depth + 1,
+        }
+    }
+}
+
+/// Iterator over the entries of a [`MapRef`]
+///
+/// Yields an error and stops if the underlying data is malformed.
+#[derive(Debug, Clone)]
+pub struct MapRefIter<'a> {
+    decoder: DataDecoder<'a>,
+    cursor: usize,
+    remaining: usize,
+    depth: usize,
+}
+
+impl<'a> MapRefIter<'a> {
+    fn next_value(&mut self) -> Result<DataValueRef<'a>, &'static str> {
+        let value = self
+            .decoder
+            .decode_ref_at(&mut { self.cursor }, false, self.depth)?;
+        self.decoder.skip_value(&mut self.cursor, self.depth)?;
+        Ok(value)
+    }
+}
+
+impl<'a> Iterator for MapRefIter<'a> {
+    type Item = Result<(&'a str, DataValueRef<'a>), &'static str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let key = self.next_value();
+        let entry = key.and_then(|key| {
+            let key = key.as_str().ok_or("Map key is not a string")?;
+            Ok((key, self.next_value()?))
+        });
+        if entry.is_err() {
+            self.remaining = 0;
+        }
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// Lazily decoded array inside a [`DataValueRef`]
+#[derive(Debug, Clone, Copy)]
+pub struct ArrayRef<'a> {
+    decoder: DataDecoder<'a>,
+    start: usize,
+    len: usize,
+    depth: usize,
+}
+
+impl<'a> ArrayRef<'a> {
+    /// Number of elements in the array
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the array has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the element at `index`, skipping over the ones before it
+    pub fn get(&self, index: usize) -> Result<Option<DataValueRef<'a>>, &'static str> {
+        match self.iter().nth(index) {
+            Some(item) => item.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Iterate over elements in order
+    pub fn iter(&self) -> ArrayRefIter<'a> {
+        ArrayRefIter {
+            decoder: self.decoder,
+            cursor: self.start,
+            remaining: self.len,
+            depth: self.depth + 1,
+        }
+    }
+}
+
+/// Iterator over the elements of an [`ArrayRef`]
+///
+/// Yields an error and stops if the underlying data is malformed.
+#[derive(Debug, Clone)]
+pub struct ArrayRefIter<'a> {
+    decoder: DataDecoder<'a>,
+    cursor: usize,
+    remaining: usize,
+    depth: usize,
+}
+
+impl<'a> Iterator for ArrayRefIter<'a> {
+    type Item = Result<DataValueRef<'a>, &'static str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let item = self
+            .decoder
+            .decode_ref_at(&mut { self.cursor }, false, self.depth)
+            .and_then(|value| {
+                self.decoder
+                    .skip_value(&mut self.cursor, self.depth)
+                    .map(|_| value)
+            });
+        if item.is_err() {
+            self.remaining = 0;
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1097,7 +1909,7 @@ mod tests {
     #[test]
     fn test_encode_decode_map() {
         let mut encoder = DataEncoder::new();
-        let mut map = HashMap::new();
+        let mut map = DataMap::new();
         map.insert("country".to_string(), DataValue::String("US".to_string()));
         map.insert("asn".to_string(), DataValue::Uint32(13335));
         map.insert("score".to_string(), DataValue::Double(0.95));
@@ -1150,12 +1962,43 @@ mod tests {
         assert_ne!(offset1, offset4);
     }
 
+    #[test]
+    fn test_typed_serde_round_trip() {
+        let mut map = DataMap::new();
+        map.insert("u16".to_string(), DataValue::Uint16(7));
+        map.insert("u32".to_string(), DataValue::Uint32(7));
+        map.insert("u64".to_string(), DataValue::Uint64(7));
+        map.insert("u128".to_string(), DataValue::Uint128(u128::MAX));
+        map.insert("i32".to_string(), DataValue::Int32(-7));
+        map.insert("f32".to_string(), DataValue::Float(0.1));
+        map.insert("f64".to_string(), DataValue::Double(0.1));
+        map.insert("bytes".to_string(), DataValue::Bytes(vec![0, 255]));
+        map.insert("bool".to_string(), DataValue::Bool(true));
+        map.insert(
+            "array".to_string(),
+            DataValue::Array(vec![DataValue::String("x".to_string())]),
+        );
+        let value = DataValue::Map(map);
+
+        let json = serde_json::to_value(TypedDataValue(value.clone())).unwrap();
+        assert_eq!(json["map"]["u32"], serde_json::json!({ "uint32": 7 }));
+        let back: TypedDataValue = serde_json::from_value(json).unwrap();
+        assert_eq!(back.0, value);
+
+        // The plain mapping stays untyped
+        let plain = serde_json::to_value(DataValue::Uint64(7)).unwrap();
+        assert_eq!(plain, serde_json::json!(7));
+
+        assert!(serde_json::to_value(TypedDataValue(DataValue::Pointer(0))).is_err());
+        assert!(serde_json::from_str::<TypedDataValue>(r#"{"uint128": "x"}"#).is_err());
+    }
+
     #[test]
     fn test_complex_nested_structure() {
         let mut encoder = DataEncoder::new();
 
         // Build threat intelligence data structure
-        let mut threat_data = HashMap::new();
+        let mut threat_data = DataMap::new();
         threat_data.insert(
             "threat_level".to_string(),
             DataValue::String("high".to_string()),
@@ -1167,7 +2010,7 @@ mod tests {
         threat_data.insert("confidence".to_string(), DataValue::Float(0.98));
         threat_data.insert("first_seen".to_string(), DataValue::Uint64(1704067200));
 
-        let mut indicators = HashMap::new();
+        let mut indicators = DataMap::new();
         indicators.insert("ip_count".to_string(), DataValue::Uint32(42));
         indicators.insert("domain_count".to_string(), DataValue::Uint32(15));
 
@@ -1218,7 +2061,7 @@ mod tests {
         let mut encoder = DataEncoder::new();
 
         // Create multiple maps with repeated string values
-        let mut map1 = HashMap::new();
+        let mut map1 = DataMap::new();
         map1.insert(
             "threat_level".to_string(),
             DataValue::String("high".to_string()),
@@ -1229,7 +2072,7 @@ mod tests {
         );
         map1.insert("score".to_string(), DataValue::Uint32(95));
 
-        let mut map2 = HashMap::new();
+        let mut map2 = DataMap::new();
         map2.insert(
             "threat_level".to_string(),
             DataValue::String("high".to_string()),
@@ -1240,7 +2083,7 @@ mod tests {
         );
         map2.insert("score".to_string(), DataValue::Uint32(88));
 
-        let mut map3 = HashMap::new();
+        let mut map3 = DataMap::new();
         map3.insert(
             "threat_level".to_string(),
             DataValue::String("high".to_string()),
@@ -1309,6 +2152,70 @@ mod tests {
         assert_eq!(decoded, array);
     }
 
+    #[test]
+    fn test_record_builder_nested() {
+        let record = RecordBuilder::new()
+            .map("country", |m| {
+                m.str("iso_code", "US")
+                    .map("names", |n| n.str("en", "United States").str("de", "USA"))
+            })
+            .uint32("geoname_id", 6252001)
+            .bool("is_anycast", false)
+            .array("tags", vec![DataValue::String("cdn".to_string())])
+            .build();
+
+        let mut names = DataMap::new();
+        names.insert(
+            "en".to_string(),
+            DataValue::String("United States".to_string()),
+        );
+        names.insert("de".to_string(), DataValue::String("USA".to_string()));
+        let mut country = DataMap::new();
+        country.insert("iso_code".to_string(), DataValue::String("US".to_string()));
+        country.insert("names".to_string(), DataValue::Map(names));
+        let mut expected = DataMap::new();
+        expected.insert("country".to_string(), DataValue::Map(country));
+        expected.insert("geoname_id".to_string(), DataValue::Uint32(6252001));
+        expected.insert("is_anycast".to_string(), DataValue::Bool(false));
+        expected.insert(
+            "tags".to_string(),
+            DataValue::Array(vec![DataValue::String("cdn".to_string())]),
+        );
+        assert_eq!(record, DataValue::Map(expected));
+
+        // Later values replace earlier ones
+        let map = RecordBuilder::new().str("k", "a").uint16("k", 2).into_map();
+        assert_eq!(map.get("k"), Some(&DataValue::Uint16(2)));
+    }
+
+    #[test]
+    fn test_map_order_preserved() {
+        let mut map = DataMap::new();
+        for key in ["zulu", "alpha", "mike"] {
+            map.insert(key.to_string(), DataValue::Bool(true));
+        }
+        let mut encoder = DataEncoder::new();
+        let offset = encoder.encode(&DataValue::Map(map));
+        let bytes = encoder.into_bytes();
+        let decoded = DataDecoder::new(&bytes, 0).decode(offset).unwrap();
+
+        let keys: Vec<&str> = decoded
+            .as_map()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, ["zulu", "alpha", "mike"]);
+
+        // HashMap input is stored with sorted keys
+        let mut unordered = HashMap::new();
+        unordered.insert("b".to_string(), DataValue::Uint16(2));
+        unordered.insert("a".to_string(), DataValue::Uint16(1));
+        let value = DataValue::from_hash_map(unordered);
+        let keys: Vec<&String> = value.as_map().unwrap().keys().collect();
+        assert_eq!(keys, ["a", "b"]);
+    }
+
     #[test]
     fn test_pointer_encoding() {
         // Test pointer resolution with actual data that pointers reference
@@ -1320,7 +2227,7 @@ mod tests {
 
         // Now create a map that uses a pointer to reference that data (simulating deduplication)
         // In MMDB format, pointers are typically used within maps for deduplicated keys/values
-        let mut map = HashMap::new();
+        let mut map = DataMap::new();
         map.insert(
             "direct".to_string(),
             DataValue::String("direct_value".to_string()),
@@ -1350,4 +2257,93 @@ mod tests {
             panic!("Expected Map, got {:?}", decoded);
         }
     }
+
+    #[test]
+    fn test_decode_ref() {
+        let mut encoder = DataEncoder::new();
+
+        let mut inner = DataMap::new();
+        inner.insert("code".to_string(), DataValue::String("US".to_string()));
+        let mut map = DataMap::new();
+        map.insert("country".to_string(), DataValue::Map(inner));
+        map.insert("score".to_string(), DataValue::Uint32(42));
+        map.insert(
+            "tags".to_string(),
+            DataValue::Array(vec![
+                DataValue::String("a".to_string()),
+                DataValue::Bytes(vec![1, 2, 3]),
+            ]),
+        );
+        map.insert("label".to_string(), DataValue::String("US".to_string()));
+        let original = DataValue::Map(map);
+        let offset = encoder.encode(&original);
+
+        let bytes = encoder.into_bytes();
+        let decoder = DataDecoder::new(&bytes, 0);
+        let value = decoder.decode_ref(offset).unwrap();
+        let map = value.as_map().unwrap();
+
+        assert_eq!(map.len(), 4);
+        assert!(matches!(
+            map.get("score").unwrap(),
+            Some(DataValueRef::Uint32(42))
+        ));
+        // "label" reuses the interned "US", so it is reached through a pointer
+        assert_eq!(map.get("label").unwrap().unwrap().as_str(), Some("US"));
+        let country = map.get("country").unwrap().unwrap().as_map().unwrap();
+        assert_eq!(country.get("code").unwrap().unwrap().as_str(), Some("US"));
+        let tags = map.get("tags").unwrap().unwrap().as_array().unwrap();
+        assert_eq!(tags.get(0).unwrap().unwrap().as_str(), Some("a"));
+        assert_eq!(
+            tags.get(1).unwrap().unwrap().as_bytes(),
+            Some(&[1u8, 2, 3][..])
+        );
+        assert!(tags.get(2).unwrap().is_none());
+        assert!(map.get("missing").unwrap().is_none());
+
+        let keys: Vec<&str> = map.iter().map(|e| e.unwrap().0).collect();
+        assert_eq!(keys, vec!["country", "score", "tags", "label"]);
+        assert_eq!(value.to_value().unwrap(), original);
+
+        // Truncated data surfaces as an error rather than a panic
+        let truncated = &bytes[..bytes.len() - 1];
+        let decoder = DataDecoder::new(truncated, 0);
+        let map = decoder.decode_ref(offset).unwrap().as_map().unwrap();
+        assert!(map.iter().any(|e| e.is_err()));
+    }
+
+    #[test]
+    fn test_nesting_depth_is_capped() {
+        // Arrays of one element nested far past MAX_DATA_DEPTH, ending in a uint16
+        let mut bytes = Vec::new();
+        for _ in 0..100_000 {
+            bytes.extend_from_slice(&[0x01, 0x04]);
+        }
+        bytes.push(0xA0);
+        let decoder = DataDecoder::new(&bytes, 0);
+        assert!(decoder.decode(0).is_err());
+        assert!(decoder.encoded_len(0).is_err());
+        assert!(decoder.decode_ref(0).unwrap().to_value().is_err());
+
+        // A map whose only value points back at the map itself
+        let cycle = [0xE1, 0x41, b'a', 0x20, 0x00];
+        let decoder = DataDecoder::new(&cycle, 0);
+        assert!(decoder.decode(0).is_err());
+        assert!(decoder.decode_ref(0).unwrap().to_value().is_err());
+
+        // Nesting within the cap still decodes
+        let mut value = DataValue::Uint16(7);
+        for _ in 0..100 {
+            value = DataValue::Array(vec![value]);
+        }
+        let mut encoder = DataEncoder::new();
+        let offset = encoder.encode(&value);
+        let bytes = encoder.into_bytes();
+        let decoder = DataDecoder::new(&bytes, 0);
+        assert_eq!(decoder.decode(offset).unwrap(), value);
+        assert_eq!(
+            decoder.decode_ref(offset).unwrap().to_value().unwrap(),
+            value
+        );
+    }
 }
@@ -8,18 +8,29 @@
 //! The database format is automatically detected and the appropriate
 //! lookup method is used transparently.
 
-use crate::data_section::DataValue;
+use crate::data_section::{DataDecoder, DataEncoder, DataMap, DataValue, DataValueRef};
+use crate::error::{ErrorKind, ParaglobError};
+use crate::extractor::{ExtractedItem, Extractor, Match};
 use crate::literal_hash::LiteralHash;
+use crate::mmdb::types::{IpVersion, METADATA_MARKER};
 use crate::mmdb::{MmdbError, MmdbHeader, SearchTree};
-use crate::paraglob_offset::Paraglob;
+use crate::mmdb_builder::{MmdbBuilder, PROVENANCE_FIELD};
+use crate::multi_database::MultiDatabase;
+use crate::numeric_index::NumericIndex;
+use crate::offset_format::{ParaglobHeader, PatternEntry, MAGIC, VERSION, VERSION_V1, VERSION_V2};
+use crate::paraglob_offset::{Paraglob, ParaglobBuilder, QueryBudget, QueryScratch};
+use crate::pattern_groups::PatternGroups;
+use crate::suffix_trie::SuffixTrie;
 use lru::LruCache;
 use memmap2::Mmap;
-use std::cell::RefCell;
 use std::fs::File;
 use std::hash::BuildHasherDefault;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+use zerocopy::FromBytes;
 
 /// Statistics for database queries and cache performance
 #[derive(Debug, Clone, Copy, Default)]
@@ -72,6 +83,11 @@ pub enum QueryResult {
         prefix_len: u8,
     },
     /// Pattern match result
+    ///
+    /// Matches are in a fixed precedence order: an exact literal match (if
+    /// any) first, then glob matches by ascending pattern ID, which is the
+    /// order the globs were added to the builder. When several globs match
+    /// equally well (e.g. `a*c` and `a?c` for `abc`), the earliest inserted wins.
     Pattern {
         /// Pattern IDs that matched
         pattern_ids: Vec<u32>,
@@ -82,6 +98,216 @@ pub enum QueryResult {
     NotFound,
 }
 
+impl QueryResult {
+    /// Data of the highest-precedence match
+    ///
+    /// For IP results this is the data of the most specific network. For
+    /// pattern results it is the data of the first match in precedence order
+    /// (see [`QueryResult::Pattern`]). Returns `None` for `NotFound` or when
+    /// the winning pattern has no data.
+    pub fn first_data(&self) -> Option<&DataValue> {
+        match self {
+            QueryResult::Ip { data, .. } => Some(data),
+            QueryResult::Pattern { data, .. } => data.first()?.as_ref(),
+            QueryResult::NotFound => None,
+        }
+    }
+
+    /// Prefix length of the network that matched an IP query
+    ///
+    /// For IPv4 queries this is an IPv4 prefix length (0-32) even in an
+    /// IPv6 tree. Returns `None` for pattern matches and `NotFound`.
+    ///
+    /// This is the depth of the search tree node holding the record, as
+    /// MaxMind readers report it, not necessarily the prefix that was
+    /// inserted: a more specific network inside a wider one splits the
+    /// wider network's nodes. With both `10.0.0.0/8` and `10.1.2.3/32`
+    /// inserted, `10.200.1.1` matches the `10.0.0.0/8` data with a prefix
+    /// length of 9 (`10.128.0.0/9`).
+    pub fn matched_prefix(&self) -> Option<u8> {
+        match self {
+            QueryResult::Ip { prefix_len, .. } => Some(*prefix_len),
+            _ => None,
+        }
+    }
+
+    /// Network that matched an IP query, as `(network address, prefix length)`
+    ///
+    /// `ip` must be the address that was looked up; the result only records
+    /// the prefix length, so the network is `ip` with its host bits cleared.
+    /// Like [`QueryResult::matched_prefix`] this is the tree node's network,
+    /// which may be narrower than the inserted one. Returns `None` for
+    /// pattern matches and `NotFound`.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    /// use std::net::IpAddr;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let ip: IpAddr = "10.1.2.3".parse()?;
+    /// let result = db.lookup_ip(ip)?.unwrap();
+    /// assert_eq!(result.matched_network(ip), Some(("10.0.0.0".parse()?, 8)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn matched_network(&self, ip: IpAddr) -> Option<(IpAddr, u8)> {
+        let prefix_len = self.matched_prefix()?;
+        let network = match ip {
+            IpAddr::V4(addr) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V4((u32::from(addr) & mask).into())
+            }
+            IpAddr::V6(addr) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V6((u128::from(addr) & mask).into())
+            }
+        };
+        Some((network, prefix_len))
+    }
+
+    /// Source of a field in the highest-precedence match's data
+    ///
+    /// Reads the per-field provenance stored by
+    /// [`MmdbBuilder::add_entry_with_provenance`]. Returns `None` if the
+    /// entry has no provenance or the field has no recorded source.
+    pub fn field_source(&self, field: &str) -> Option<&str> {
+        let DataValue::Map(data) = self.first_data()? else {
+            return None;
+        };
+        match data.get(PROVENANCE_FIELD)? {
+            DataValue::Map(sources) => match sources.get(field)? {
+                DataValue::String(source) => Some(source),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Encoded size in bytes of the highest-precedence match's data
+    ///
+    /// This is the size of the value's standalone MMDB encoding, so it ranks
+    /// entries by payload weight when auditing what makes a database large.
+    /// The bytes actually stored can be fewer: identical data is written once
+    /// and shared, and interned strings become pointers. Returns 0 when
+    /// [`first_data`](Self::first_data) is `None`.
+    pub fn data_size(&self) -> usize {
+        self.first_data().map_or(0, |data| {
+            let mut encoder = DataEncoder::new_without_interning();
+            encoder.encode(data);
+            encoder.size()
+        })
+    }
+}
+
+/// Query result with data borrowed from the database
+///
+/// Returned by [`Database::lookup_ref`]. Mirrors [`QueryResult`], but each
+/// data value is a [`DataValueRef`] that reads straight from the mapped data
+/// section instead of a fully decoded [`DataValue`].
+#[derive(Debug, Clone)]
+pub enum QueryResultRef<'a> {
+    /// IP address lookup result
+    Ip {
+        /// The data associated with this IP
+        data: DataValueRef<'a>,
+        /// Network prefix length (CIDR)
+        prefix_len: u8,
+    },
+    /// Pattern match result, in the same precedence order as [`QueryResult::Pattern`]
+    Pattern {
+        /// Pattern IDs that matched
+        pattern_ids: Vec<u32>,
+        /// Optional data for matched patterns
+        data: Vec<Option<DataValueRef<'a>>>,
+    },
+    /// Not found
+    NotFound,
+}
+
+impl<'a> QueryResultRef<'a> {
+    /// Data of the highest-precedence match
+    ///
+    /// See [`QueryResult::first_data`].
+    pub fn first_data(&self) -> Option<DataValueRef<'a>> {
+        match self {
+            QueryResultRef::Ip { data, .. } => Some(*data),
+            QueryResultRef::Pattern { data, .. } => *data.first()?,
+            QueryResultRef::NotFound => None,
+        }
+    }
+}
+
+/// A database hit found while scanning free-form text
+///
+/// Produced by [`Database::scan`]. The span uses the same semantics as
+/// [`extractor::Match::span`](crate::extractor::Match::span): a byte range
+/// into the scanned input with an exclusive end.
+#[derive(Debug, Clone)]
+pub struct ScanMatch {
+    /// Byte span in the input (start, end) - exclusive end
+    pub span: (usize, usize),
+    /// Type of extracted item (e.g., "IPv4", "Domain", "Email")
+    pub match_type: &'static str,
+    /// Matched text (canonical form for IP addresses)
+    pub matched_text: String,
+    /// Query result from the database
+    pub result: QueryResult,
+}
+
+/// An IOC-like item found while hunting through a document
+///
+/// Produced by [`Database::hunt`]. Unlike [`ScanMatch`], extracted items are
+/// reported even when the database has no entry for them.
+#[derive(Debug, Clone)]
+pub struct HuntMatch {
+    /// Byte span in the input (start, end) - exclusive end
+    pub span: (usize, usize),
+    /// Type of item (e.g., "IPv4", "Domain", "Email"), or "Token" for a
+    /// whitespace-delimited word that matched a literal or glob
+    pub match_type: &'static str,
+    /// Matched text (canonical form for IP addresses)
+    pub matched_text: String,
+    /// Query result from the database, `None` for an extracted item with no entry
+    pub result: Option<QueryResult>,
+}
+
+/// Matching mechanism that served a hit, from [`Database::matched_via`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchMechanism {
+    /// IP search tree (one walk of at most 128 nodes)
+    IpTree,
+    /// Literal hash table (O(1) exact match)
+    LiteralHash,
+    /// Aho-Corasick automaton plus glob verification (O(n) in the query length)
+    GlobAutomaton,
+}
+
+/// How [`Database::lookup_score`] combines per-match scores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreCombine {
+    /// Add all scores together (default)
+    #[default]
+    Sum,
+    /// Take the highest score
+    Max,
+}
+
+/// Longest domain-suffix match from [`Database::lookup_suffix`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuffixMatch {
+    /// The matched registered domain, lowercased (e.g. `example.com`)
+    pub suffix: String,
+    /// Data associated with the domain
+    pub data: DataValue,
+}
+
 /// Database format type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DatabaseFormat {
@@ -123,6 +349,15 @@ enum DatabaseStorage {
     Mmap(Mmap),
 }
 
+impl From<crate::mmap::FileBytes> for DatabaseStorage {
+    fn from(bytes: crate::mmap::FileBytes) -> Self {
+        match bytes {
+            crate::mmap::FileBytes::Mapped(mmap) => DatabaseStorage::Mmap(mmap),
+            crate::mmap::FileBytes::Buffered(bytes) => DatabaseStorage::Owned(bytes),
+        }
+    }
+}
+
 impl DatabaseStorage {
     fn as_slice(&self) -> &[u8] {
         match self {
@@ -166,8 +401,16 @@ impl PatternDataMappings {
 /// ~1-5 MB memory usage depending on result sizes
 const DEFAULT_QUERY_CACHE_SIZE: usize = 10_000;
 
+/// Default maximum length of string queries (1 MiB)
+/// Far above any real domain, URL, or hash, but bounds work on runaway input
+pub const DEFAULT_MAX_INPUT_LEN: usize = 1024 * 1024;
+
 /// Options for opening a database
+///
+/// Start from [`DatabaseOptions::default`] and set fields, or use
+/// [`DatabaseOpener`]; new options may be added in minor releases.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct DatabaseOptions {
     /// Path to the database file (optional for from_bytes)
     pub path: PathBuf,
@@ -177,6 +420,24 @@ pub struct DatabaseOptions {
 
     /// Optional in-memory bytes (for from_bytes builder)
     pub bytes: Option<Vec<u8>>,
+
+    /// Maximum number of compiled glob patterns to keep (None = unbounded)
+    pub glob_cache_capacity: Option<usize>,
+
+    /// Treat malformed IP-looking queries as strings instead of returning an error
+    pub lenient_ip_parsing: bool,
+
+    /// Maximum string query length in bytes (None = use default)
+    pub max_input_len: Option<usize>,
+
+    /// Touch every page of the file while opening, before the first query
+    pub prefault: bool,
+
+    /// Map a private copy of the file on platforms that lock mapped files
+    pub private_copy: bool,
+
+    /// Read the whole file into memory instead of memory-mapping it
+    pub read_into_memory: bool,
 }
 
 impl Default for DatabaseOptions {
@@ -185,10 +446,38 @@ impl Default for DatabaseOptions {
             path: PathBuf::new(),
             cache_capacity: Some(DEFAULT_QUERY_CACHE_SIZE),
             bytes: None,
+            glob_cache_capacity: None,
+            lenient_ip_parsing: false,
+            max_input_len: None,
+            prefault: false,
+            private_copy: false,
+            read_into_memory: false,
         }
     }
 }
 
+/// Time spent in each stage of opening a database
+///
+/// Returned by [`DatabaseOpener::open_timed`] to break down cold-start cost.
+/// Page faults taken by the first query are not included; time that query
+/// separately to see what [`prefault`](DatabaseOpener::prefault) saves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenTiming {
+    /// Opening and memory-mapping the file (zero for in-memory bytes)
+    pub mmap: Duration,
+    /// Reading one byte per page of the file (zero unless prefaulting)
+    pub prefault: Duration,
+    /// Detecting the format and parsing headers and section tables
+    pub parse: Duration,
+}
+
+impl OpenTiming {
+    /// Total time across all stages
+    pub fn total(&self) -> Duration {
+        self.mmap + self.prefault + self.parse
+    }
+}
+
 /// Builder for opening databases with custom configuration
 ///
 /// Created via `Database::from(path)`. Use the fluent API to configure
@@ -243,11 +532,109 @@ impl DatabaseOpener {
         self
     }
 
+    /// Bound the cache of compiled glob patterns
+    ///
+    /// Glob patterns are compiled on first use and kept for later queries.
+    /// For long-running processes matching against very large pattern sets,
+    /// a capacity bounds resident memory at the cost of occasionally
+    /// recompiling evicted patterns (least recently used first).
+    ///
+    /// Default: unbounded
+    pub fn glob_cache_capacity(mut self, capacity: usize) -> Self {
+        self.options.glob_cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Fall back to string matching for malformed IP-looking queries
+    ///
+    /// By default, `lookup` rejects queries such as `1.2.3.999` that look like
+    /// an IP address but fail to parse, returning [`DatabaseError::InvalidIp`].
+    /// With this option they are matched against literals and globs instead.
+    pub fn lenient_ip_parsing(mut self) -> Self {
+        self.options.lenient_ip_parsing = true;
+        self
+    }
+
+    /// Reject string queries longer than `max_len` bytes
+    ///
+    /// String lookups longer than the limit return
+    /// [`DatabaseError::InputTooLong`] without running the pattern matcher,
+    /// so a single giant input line cannot stall a service. IP lookups are
+    /// not affected. Pass `usize::MAX` to disable the limit.
+    ///
+    /// Default: [`DEFAULT_MAX_INPUT_LEN`] (1 MiB)
+    pub fn max_input_len(mut self, max_len: usize) -> Self {
+        self.options.max_input_len = Some(max_len);
+        self
+    }
+
+    /// Fault in every page of the file while opening
+    ///
+    /// Memory-mapped pages are loaded lazily, so without this the first
+    /// queries pay for page faults. Prefaulting moves that cost into `open()`,
+    /// which takes longer in proportion to file size.
+    ///
+    /// Default: off
+    pub fn prefault(mut self) -> Self {
+        self.options.prefault = true;
+        self
+    }
+
+    /// Keep the database file free to be replaced while it is open
+    ///
+    /// The supported way to update a database is to write the new file next
+    /// to the old one and `rename()` it into place, then reopen. On Unix this
+    /// is always safe: an open `Database` keeps the old inode alive, and
+    /// readers see either the old file or the new one, never a mix. Nothing
+    /// is copied there, and this option has no effect.
+    ///
+    /// On Windows a mapped file cannot be replaced or deleted, so the rename
+    /// fails while any process has the database open. With this option the
+    /// file is first copied to a private temporary file (deleted when the
+    /// `Database` is dropped) and the copy is mapped, leaving the original
+    /// free to be renamed over. Opening then costs a full read of the file
+    /// and the same amount of temporary disk space.
+    ///
+    /// Default: off
+    pub fn private_copy(mut self) -> Self {
+        self.options.private_copy = true;
+        self
+    }
+
+    /// Read the whole file into memory instead of memory-mapping it
+    ///
+    /// A mapped file that is truncated or rewritten in place while open
+    /// makes the next lookup touching the lost pages crash the process
+    /// (SIGBUS on Unix). Reading the file up front trades memory and open
+    /// time for immunity to that. Files on NFS and SMB/CIFS mounts (detected
+    /// on Linux), or that cannot be mapped at all, are already read this way.
+    ///
+    /// Default: off
+    pub fn read_into_memory(mut self) -> Self {
+        self.options.read_into_memory = true;
+        self
+    }
+
     /// Open the database with configured options
     pub fn open(self) -> Result<Database, DatabaseError> {
         Database::open_with_options(self.options)
     }
 
+    /// Open the database and report how long each stage took
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::Database;
+    ///
+    /// let (db, timing) = Database::from("threats.mxy").open_timed()?;
+    /// println!("mmap {:?}, parse {:?}", timing.mmap, timing.parse);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open_timed(self) -> Result<(Database, OpenTiming), DatabaseError> {
+        Database::open_with_options_timed(self.options)
+    }
+
     /// Create a database opener from bytes (for testing/benchmarking)
     ///
     /// This allows you to configure cache settings before loading.
@@ -287,22 +674,40 @@ pub struct Database {
     ip_header: Option<MmdbHeader>,
     /// Literal hash table for O(1) exact string lookups
     literal_hash: Option<LiteralHash<'static>>,
+    /// Reversed-label trie for longest domain-suffix lookups
+    suffix_trie: Option<SuffixTrie<'static>>,
+    /// Sorted index for integer-keyed lookups
+    numeric_index: Option<NumericIndex<'static>>,
+    /// Group ID of each literal and glob, if any entry is grouped
+    group_table: Option<PatternGroups<'static>>,
     /// Pattern matcher for glob patterns (Combined or PatternOnly databases)
-    /// Uses RefCell for interior mutability since find_all needs &mut self
-    pattern_matcher: Option<RefCell<Paraglob>>,
+    /// Behind a RwLock because `set_pattern_enabled` mutates it through `&self`
+    pattern_matcher: Option<RwLock<Paraglob>>,
     /// For combined databases: lazy mapping from pattern_id -> data offset in MMDB data section
     /// None for pattern-only databases (which use Paraglob's internal data)
     pattern_data_mappings: Option<PatternDataMappings>,
     /// LRU query cache for recent lookups (IP, string, pattern)
-    /// Uses RefCell for interior mutability on lookups
+    /// Behind a Mutex so lookups through `&self` can update it from any thread
     /// Uses FxHasher (same as literal hash table) for fast non-cryptographic hashing
     /// Significantly improves performance for repeated queries (80-95% hit rate typical)
     pub(crate) query_cache:
-        RefCell<LruCache<String, QueryResult, BuildHasherDefault<rustc_hash::FxHasher>>>,
+        Mutex<LruCache<String, QueryResult, BuildHasherDefault<rustc_hash::FxHasher>>>,
+    /// LRU cache for `lookup_pattern` and `lookup_string`, same size as `query_cache`
+    /// Kept apart because those match IP-shaped text as patterns, so the same
+    /// query text can have a different answer than it has through `lookup`
+    pattern_cache: Mutex<LruCache<String, QueryResult, BuildHasherDefault<rustc_hash::FxHasher>>>,
     /// Whether caching is enabled (false = skip cache operations entirely)
     cache_enabled: bool,
-    /// Query statistics (uses RefCell for interior mutability)
-    stats: RefCell<DatabaseStats>,
+    /// Whether malformed IP-looking queries fall back to string matching
+    lenient_ip_parsing: bool,
+    /// Maximum string query length in bytes
+    max_input_len: usize,
+    /// Fallback record from the metadata, read once at open
+    default_data: Option<DataValue>,
+    /// Pattern group names from the metadata, indexed by group ID
+    pattern_groups: Vec<String>,
+    /// Query statistics (behind a Mutex, like the caches)
+    stats: Mutex<DatabaseStats>,
 }
 
 impl Database {
@@ -360,6 +765,26 @@ impl Database {
         DatabaseOpener::from_bytes_builder(bytes)
     }
 
+    /// Open several databases behind a single query handle
+    ///
+    /// Databases are consulted in the order given. IP queries are routed to
+    /// databases whose tree covers the address family; string queries fan out
+    /// to every database with literal or glob data. See [`MultiDatabase`] for
+    /// the full precedence rules.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::Database;
+    ///
+    /// let multi = Database::open_multi(&["feed-v4.mxy", "feed-v6.mxy"])?;
+    /// let result = multi.lookup("8.8.8.8")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open_multi<P: AsRef<Path>>(paths: &[P]) -> Result<MultiDatabase, DatabaseError> {
+        MultiDatabase::open(paths)
+    }
+
     /// Clear the query cache
     ///
     /// Removes all cached query results. Useful for benchmarking or
@@ -380,7 +805,8 @@ impl Database {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn clear_cache(&self) {
-        self.query_cache.borrow_mut().clear();
+        lock(&self.query_cache).clear();
+        lock(&self.pattern_cache).clear();
     }
 
     /// Get current cache size (number of entries)
@@ -401,7 +827,7 @@ impl Database {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn cache_size(&self) -> usize {
-        self.query_cache.borrow().len()
+        lock(&self.query_cache).len() + lock(&self.pattern_cache).len()
     }
 
     /// Get database statistics
@@ -428,7 +854,7 @@ impl Database {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn stats(&self) -> DatabaseStats {
-        *self.stats.borrow()
+        *lock(&self.stats)
     }
 
     /// Get the match mode of the database (case-sensitive or case-insensitive)
@@ -450,7 +876,7 @@ impl Database {
     pub fn mode(&self) -> crate::glob::MatchMode {
         // If there's a pattern matcher, use its mode
         if let Some(ref pm) = self.pattern_matcher {
-            return pm.borrow().mode;
+            return read_lock(pm).mode;
         }
         // If there's a literal hash, use its mode
         if let Some(ref lh) = self.literal_hash {
@@ -464,22 +890,51 @@ impl Database {
     ///
     /// Most users should use `Database::from()` builder instead.
     pub fn open_with_options(options: DatabaseOptions) -> Result<Self, DatabaseError> {
+        Self::open_with_options_timed(options).map(|(db, _)| db)
+    }
+
+    /// Open with options, timing each stage (see [`DatabaseOpener::open_timed`])
+    pub fn open_with_options_timed(
+        options: DatabaseOptions,
+    ) -> Result<(Self, OpenTiming), DatabaseError> {
         let cache_capacity = options.cache_capacity;
+        let mut timing = OpenTiming::default();
 
         // Open the database - either from bytes or from file
-        let mut db = if let Some(bytes) = options.bytes {
+        let storage = if let Some(bytes) = options.bytes {
             // Load from bytes
-            Self::from_storage(DatabaseStorage::Owned(bytes))?
+            DatabaseStorage::Owned(bytes)
         } else {
             // Load from file
-            Self::open_internal(
-                options
-                    .path
-                    .to_str()
-                    .ok_or_else(|| DatabaseError::Io("Invalid path encoding".to_string()))?,
-            )?
+            let start = Instant::now();
+            let path = options
+                .path
+                .to_str()
+                .ok_or_else(|| DatabaseError::Io("Invalid path encoding".to_string()))?;
+            let storage =
+                if options.read_into_memory {
+                    DatabaseStorage::Owned(std::fs::read(path).map_err(|e| {
+                        DatabaseError::Io(format!("Failed to read {}: {}", path, e))
+                    })?)
+                } else if options.private_copy {
+                    Self::map_private_copy(path)?
+                } else {
+                    Self::map_file(path)?
+                };
+            timing.mmap = start.elapsed();
+            storage
         };
 
+        if options.prefault {
+            let start = Instant::now();
+            prefault_pages(storage.as_slice());
+            timing.prefault = start.elapsed();
+        }
+
+        let start = Instant::now();
+        let mut db = Self::from_storage(storage)?;
+        timing.parse = start.elapsed();
+
         // Configure cache size (0 means disable, None means use default)
         if let Some(capacity) = cache_capacity {
             if capacity == 0 {
@@ -487,7 +942,11 @@ impl Database {
                 db.cache_enabled = false;
             } else if capacity != DEFAULT_QUERY_CACHE_SIZE {
                 // Resize cache (use FxHasher for speed)
-                db.query_cache = std::cell::RefCell::new(lru::LruCache::with_hasher(
+                db.query_cache = Mutex::new(lru::LruCache::with_hasher(
+                    std::num::NonZeroUsize::new(capacity).unwrap(),
+                    BuildHasherDefault::<rustc_hash::FxHasher>::default(),
+                ));
+                db.pattern_cache = Mutex::new(lru::LruCache::with_hasher(
                     std::num::NonZeroUsize::new(capacity).unwrap(),
                     BuildHasherDefault::<rustc_hash::FxHasher>::default(),
                 ));
@@ -496,7 +955,20 @@ impl Database {
             // else: keep default size and enabled
         }
 
-        Ok(db)
+        db.lenient_ip_parsing = options.lenient_ip_parsing;
+
+        if let Some(max_len) = options.max_input_len {
+            db.max_input_len = max_len;
+        }
+
+        // Bound the compiled glob cache (a capacity of 0 is treated as 1)
+        if let Some(capacity) = options.glob_cache_capacity {
+            if let Some(ref pm) = db.pattern_matcher {
+                write_lock(pm).set_glob_cache_capacity(NonZeroUsize::new(capacity.max(1)));
+            }
+        }
+
+        Ok((db, timing))
     }
     /// Open a database file using memory mapping
     ///
@@ -532,16 +1004,72 @@ impl Database {
         Self::from(path).open()
     }
 
-    /// Internal: Open database
-    /// Used by database_opener
-    pub(crate) fn open_internal(path: &str) -> Result<Self, DatabaseError> {
-        let file = File::open(path)
+    /// Internal: Open and memory-map a database file
+    ///
+    /// Network-mounted and unmappable files are read into memory instead
+    /// (see [`crate::mmap::map_or_read`]).
+    fn map_file(path: &str) -> Result<DatabaseStorage, DatabaseError> {
+        let mut file = File::open(path)
             .map_err(|e| DatabaseError::Io(format!("Failed to open {}: {}", path, e)))?;
 
-        let mmap = unsafe { Mmap::map(&file) }
-            .map_err(|e| DatabaseError::Io(format!("Failed to mmap {}: {}", path, e)))?;
+        crate::mmap::map_or_read(&mut file, Path::new(path))
+            .map(DatabaseStorage::from)
+            .map_err(|e| DatabaseError::Io(format!("Failed to read {}: {}", path, e)))
+    }
+
+    /// Internal: Map a private copy of a database file (see [`DatabaseOpener::private_copy`])
+    #[cfg(windows)]
+    fn map_private_copy(path: &str) -> Result<DatabaseStorage, DatabaseError> {
+        use std::fs::OpenOptions;
+        use std::os::windows::fs::OpenOptionsExt;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        const FILE_SHARE_READ: u32 = 0x0000_0001;
+        const FILE_SHARE_DELETE: u32 = 0x0000_0004;
+        const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+        static NEXT_COPY: AtomicU64 = AtomicU64::new(0);
 
-        Self::from_storage(DatabaseStorage::Mmap(mmap))
+        let mut source = File::open(path)
+            .map_err(|e| DatabaseError::Io(format!("Failed to open {}: {}", path, e)))?;
+        let copy_path = std::env::temp_dir().join(format!(
+            "matchy-{}-{}.mxy",
+            std::process::id(),
+            NEXT_COPY.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        // Delete-on-close removes the copy once the mapping is dropped, and
+        // denying write sharing keeps other processes from modifying it
+        let mut copy = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_DELETE)
+            .custom_flags(FILE_FLAG_DELETE_ON_CLOSE)
+            .open(&copy_path)
+            .map_err(|e| {
+                DatabaseError::Io(format!("Failed to create {}: {}", copy_path.display(), e))
+            })?;
+        std::io::copy(&mut source, &mut copy).map_err(|e| {
+            DatabaseError::Io(format!(
+                "Failed to copy {} to {}: {}",
+                path,
+                copy_path.display(),
+                e
+            ))
+        })?;
+        drop(source);
+
+        unsafe { Mmap::map(&copy) }
+            .map(DatabaseStorage::Mmap)
+            .map_err(|e| {
+                DatabaseError::Io(format!("Failed to mmap {}: {}", copy_path.display(), e))
+            })
+    }
+
+    /// Internal: Map a database file (renaming over a mapped file is safe here)
+    #[cfg(not(windows))]
+    fn map_private_copy(path: &str) -> Result<DatabaseStorage, DatabaseError> {
+        Self::map_file(path)
     }
 
     /// Create database from raw bytes (for testing)
@@ -549,6 +1077,203 @@ impl Database {
         Self::from_storage(DatabaseStorage::Owned(data))
     }
 
+    /// Re-encode a database file written by an older release in the current format
+    ///
+    /// Pattern sections from before format v4 (the 16-byte `ACNodeHot`
+    /// automaton) no longer load. This reads the glob strings from the old
+    /// section, rebuilds the automaton, and splices the new section into the
+    /// file. The IP tree, data section, glob data offsets, and the literal,
+    /// suffix, and numeric sections are copied byte for byte, so every entry
+    /// keeps its data. A file that is already current is returned unchanged.
+    ///
+    /// `target_version` must be the current pattern format version
+    /// ([`offset_format::VERSION`](crate::offset_format::VERSION)); older
+    /// versions are read but never written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or is corrupt, if
+    /// `target_version` is not the current version, or for pattern-only
+    /// (`.pgb`) files that embed their own data.
+    pub fn reencode(path: impl AsRef<Path>, target_version: u32) -> Result<Vec<u8>, DatabaseError> {
+        if target_version != VERSION {
+            return Err(DatabaseError::UnsupportedVersion(format!(
+                "Cannot write format version {}; only v{} is supported",
+                target_version, VERSION
+            )));
+        }
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| DatabaseError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::reencode_bytes(data)
+    }
+
+    /// Internal: Replace an old pattern section in `data` with a current one
+    fn reencode_bytes(mut data: Vec<u8>) -> Result<Vec<u8>, DatabaseError> {
+        let match_mode = Self::read_match_mode_from_metadata(&data);
+
+        // Pattern-only files are a bare PARAGLOB buffer
+        if data.starts_with(MAGIC) {
+            let header = Self::read_legacy_header(&data)?;
+            if header.version == VERSION {
+                return Ok(data);
+            }
+            if header.version >= VERSION_V2 && header.mapping_count > 0 {
+                return Err(DatabaseError::Unsupported(
+                    "Cannot re-encode a pattern-only database with embedded data".to_string(),
+                ));
+            }
+            return Self::rebuild_paraglob(&data, match_mode);
+        }
+
+        let Some(offset) = Self::find_pattern_section_fast(&data) else {
+            return Ok(data);
+        };
+
+        // [total_size][paraglob_size][PARAGLOB data][pattern_count][data_offsets...]
+        let read_size = |pos: usize| {
+            data.get(pos..pos + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                .ok_or_else(|| {
+                    DatabaseError::Truncated("Pattern section header truncated".to_string())
+                })
+        };
+        let total_size = read_size(offset)?;
+        let paraglob_size = read_size(offset + 4)?;
+        let section_end = offset + total_size;
+        if section_end > data.len() || 8 + paraglob_size > total_size {
+            return Err(DatabaseError::Unsupported(
+                "Pattern section extends beyond file".to_string(),
+            ));
+        }
+
+        let paraglob = &data[offset + 8..offset + 8 + paraglob_size];
+        if Self::read_legacy_header(paraglob)?.version == VERSION {
+            return Ok(data);
+        }
+        let rebuilt = Self::rebuild_paraglob(paraglob, match_mode)?;
+
+        let mut section = Vec::with_capacity(8 + rebuilt.len() + total_size - paraglob_size);
+        section.extend_from_slice(&[0; 8]);
+        section.extend_from_slice(&rebuilt);
+        section.extend_from_slice(&data[offset + 8 + paraglob_size..section_end]);
+        let fit = |n: usize| {
+            u32::try_from(n).map_err(|_| {
+                DatabaseError::Unsupported("Re-encoded pattern section exceeds 4GB".to_string())
+            })
+        };
+        let new_total = fit(section.len())?;
+        let new_paraglob = fit(rebuilt.len())?;
+        section[0..4].copy_from_slice(&new_total.to_le_bytes());
+        section[4..8].copy_from_slice(&new_paraglob.to_le_bytes());
+
+        let new_end = offset + section.len();
+        data.splice(offset..section_end, section);
+        Self::shift_section_offsets(&mut data, section_end, new_end)?;
+        Ok(data)
+    }
+
+    /// Internal: Read a PARAGLOB header of any supported version
+    ///
+    /// v1 headers are shorter, but only fields shared with v1 are used here.
+    fn read_legacy_header(buffer: &[u8]) -> Result<ParaglobHeader, DatabaseError> {
+        let header = ParaglobHeader::read_from_prefix(buffer)
+            .map(|(header, _)| header)
+            .map_err(|_| {
+                DatabaseError::Truncated("Pattern section header truncated".to_string())
+            })?;
+        if &header.magic != MAGIC || !(VERSION_V1..=VERSION).contains(&header.version) {
+            return Err(DatabaseError::UnsupportedVersion(format!(
+                "Unsupported pattern section version: {}",
+                header.version
+            )));
+        }
+        Ok(header)
+    }
+
+    /// Internal: Rebuild a PARAGLOB buffer in the current format
+    ///
+    /// Pattern IDs are kept, so data offsets indexed by ID stay valid.
+    fn rebuild_paraglob(
+        buffer: &[u8],
+        match_mode: crate::glob::MatchMode,
+    ) -> Result<Vec<u8>, DatabaseError> {
+        let header = Self::read_legacy_header(buffer)?;
+        let corrupt =
+            |what: &str| DatabaseError::IntegrityMismatch(format!("Corrupt pattern {}", what));
+
+        let mut patterns = vec![None; header.pattern_count as usize];
+        for i in 0..header.pattern_count as usize {
+            let pos = header.patterns_offset as usize + i * std::mem::size_of::<PatternEntry>();
+            let (entry, _) = buffer
+                .get(pos..)
+                .and_then(|rest| PatternEntry::read_from_prefix(rest).ok())
+                .ok_or_else(|| corrupt("entry"))?;
+            let start = entry.pattern_string_offset as usize;
+            let bytes = buffer
+                .get(start..start + entry.pattern_string_length as usize)
+                .ok_or_else(|| corrupt("string"))?;
+            let pattern = std::str::from_utf8(bytes).map_err(|_| corrupt("string"))?;
+            *patterns
+                .get_mut(entry.pattern_id as usize)
+                .ok_or_else(|| corrupt("ID"))? = Some(pattern);
+        }
+
+        let add_error = |e: ParaglobError| DatabaseError::Unsupported(e.to_string());
+        let mut builder = ParaglobBuilder::new(match_mode);
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.ok_or_else(|| corrupt("ID"))?;
+            if builder.add_pattern(pattern).map_err(add_error)? as usize != id {
+                return Err(corrupt("ID"));
+            }
+        }
+        Ok(builder.build().map_err(add_error)?.buffer().to_vec())
+    }
+
+    /// Internal: Move section offsets in the metadata after a section was resized
+    ///
+    /// Offsets at or past `old_end` move to follow `new_end`. The metadata is
+    /// re-encoded in place at the end of the file.
+    fn shift_section_offsets(
+        data: &mut Vec<u8>,
+        old_end: usize,
+        new_end: usize,
+    ) -> Result<(), DatabaseError> {
+        if old_end == new_end {
+            return Ok(());
+        }
+        let metadata = crate::mmdb::MmdbMetadata::from_file(data)
+            .and_then(|m| m.as_value())
+            .map_err(DatabaseError::Format)?;
+        let DataValue::Map(mut map) = metadata else {
+            return Err(DatabaseError::Format(MmdbError::InvalidMetadata(
+                "Metadata is not a map".to_string(),
+            )));
+        };
+        for key in [
+            "literal_section_offset",
+            "suffix_section_offset",
+            "numeric_section_offset",
+            "group_section_offset",
+        ] {
+            if let Some(DataValue::Uint32(offset)) = map.get_mut(key) {
+                if *offset as usize >= old_end {
+                    *offset =
+                        u32::try_from(*offset as usize + new_end - old_end).map_err(|_| {
+                            DatabaseError::Unsupported("Section offset exceeds 4GB".to_string())
+                        })?;
+                }
+            }
+        }
+
+        let mut encoder = DataEncoder::new();
+        encoder.encode(&DataValue::Map(map));
+        let marker = crate::mmdb::find_metadata_marker(data).map_err(DatabaseError::Format)?;
+        data.truncate(marker + METADATA_MARKER.len());
+        data.extend_from_slice(&encoder.into_bytes());
+        Ok(())
+    }
+
     /// Internal: Create database from storage
     fn from_storage(storage: DatabaseStorage) -> Result<Self, DatabaseError> {
         // First, create the struct with minimal initialization
@@ -557,14 +1282,25 @@ impl Database {
             format: DatabaseFormat::IpOnly, // Temporary, will be set below
             ip_header: None,
             literal_hash: None,
+            suffix_trie: None,
+            numeric_index: None,
+            group_table: None,
             pattern_matcher: None,
             pattern_data_mappings: None,
-            query_cache: RefCell::new(LruCache::with_hasher(
+            query_cache: Mutex::new(LruCache::with_hasher(
+                NonZeroUsize::new(DEFAULT_QUERY_CACHE_SIZE).unwrap(),
+                BuildHasherDefault::<rustc_hash::FxHasher>::default(),
+            )),
+            pattern_cache: Mutex::new(LruCache::with_hasher(
                 NonZeroUsize::new(DEFAULT_QUERY_CACHE_SIZE).unwrap(),
                 BuildHasherDefault::<rustc_hash::FxHasher>::default(),
             )),
             cache_enabled: true, // Default: cache enabled
-            stats: RefCell::new(DatabaseStats::default()),
+            lenient_ip_parsing: false,
+            max_input_len: DEFAULT_MAX_INPUT_LEN,
+            default_data: None,
+            pattern_groups: Vec::new(),
+            stats: Mutex::new(DatabaseStats::default()),
         };
 
         // Now we can safely get 'static reference since db owns the data
@@ -580,10 +1316,9 @@ impl Database {
             }
             DatabaseFormat::PatternOnly => {
                 // Pattern-only: load from start of file
-                let pg = Self::load_pattern_section(data, 0).map_err(|e| {
-                    DatabaseError::Unsupported(format!("Failed to load pattern section: {}", e))
-                })?;
-                db.pattern_matcher = Some(RefCell::new(pg));
+                let pg = Self::load_pattern_section(data, 0)
+                    .map_err(|e| DatabaseError::section("pattern section", e))?;
+                db.pattern_matcher = Some(RwLock::new(pg));
             }
             DatabaseFormat::Combined => {
                 // Parse IP header first
@@ -591,14 +1326,9 @@ impl Database {
 
                 // Find and load pattern section after MMDB_PATTERN separator
                 if let Some(offset) = Self::find_pattern_section_fast(data) {
-                    let (pg, map) =
-                        Self::load_combined_pattern_section(data, offset).map_err(|e| {
-                            DatabaseError::Unsupported(format!(
-                                "Failed to load pattern section: {}",
-                                e
-                            ))
-                        })?;
-                    db.pattern_matcher = Some(RefCell::new(pg));
+                    let (pg, map) = Self::load_combined_pattern_section(data, offset)
+                        .map_err(|e| DatabaseError::section("pattern section", e))?;
+                    db.pattern_matcher = Some(RwLock::new(pg));
                     db.pattern_data_mappings = Some(map);
                 }
             }
@@ -610,9 +1340,47 @@ impl Database {
             let literal_data = &data[offset + 16..];
             // Read match mode from metadata
             let match_mode = Self::read_match_mode_from_metadata(data);
-            db.literal_hash = Some(LiteralHash::from_buffer(literal_data, match_mode).map_err(
-                |e| DatabaseError::Unsupported(format!("Failed to load literal hash: {}", e)),
-            )?);
+            db.literal_hash = Some(
+                LiteralHash::from_buffer(literal_data, match_mode)
+                    .map_err(|e| DatabaseError::section("literal hash", e))?,
+            );
+        }
+
+        // Load suffix trie section if present (metadata offset only, no legacy files)
+        if let Some(offset) = Self::find_suffix_section(data) {
+            db.suffix_trie = Some(
+                SuffixTrie::from_buffer(&data[offset..])
+                    .map_err(|e| DatabaseError::section("suffix section", e))?,
+            );
+        }
+
+        // Load numeric index section if present (metadata offset only, no legacy files)
+        if let Some(offset) = Self::find_numeric_section(data) {
+            db.numeric_index = Some(
+                NumericIndex::from_buffer(&data[offset..])
+                    .map_err(|e| DatabaseError::section("numeric section", e))?,
+            );
+        }
+
+        // Load pattern group table if present (metadata offset only, no legacy files)
+        if let Some(offset) = Self::find_group_section(data) {
+            db.group_table = Some(
+                PatternGroups::from_buffer(&data[offset..])
+                    .map_err(|e| DatabaseError::section("group section", e))?,
+            );
+        }
+
+        if let Some(DataValue::Map(mut metadata)) = db.metadata() {
+            db.default_data = metadata.shift_remove("default_data");
+            if let Some(DataValue::Array(groups)) = metadata.shift_remove("pattern_groups") {
+                db.pattern_groups = groups
+                    .into_iter()
+                    .map(|group| match group {
+                        DataValue::String(name) => name,
+                        _ => String::new(),
+                    })
+                    .collect();
+            }
         }
 
         Ok(db)
@@ -621,36 +1389,356 @@ impl Database {
     /// Look up a query string (IP address or string pattern)
     ///
     /// Automatically determines if the query is an IP address or string
-    /// and uses the appropriate lookup method.
+    /// (see [`classify_key`] for the exact rules) and uses the appropriate
+    /// lookup method:
+    /// - Anything that parses as a [`std::net::IpAddr`] (dotted-quad IPv4 or
+    ///   standard IPv6 text, without a `/prefix`) is looked up in the IP tree.
+    /// - Strings shaped like an IP that fail to parse are rejected (see below).
+    /// - Everything else, including bare numbers like `1234` and hex strings,
+    ///   is matched against literals and globs.
+    ///
+    /// When the kind of input is known in advance, [`lookup_pattern`](Self::lookup_pattern)
+    /// and [`lookup_ip_str`](Self::lookup_ip_str) skip this classification.
     ///
     /// Queries are cached using an LRU cache. Repeated queries return
     /// cached results without re-parsing or re-searching. Cache hit rates
     /// of 80-95% are typical in log processing workloads.
     ///
-    /// Returns `Ok(Some(result))` if found, `Ok(None)` if not found.
+    /// Returns `Ok(Some(result))` if found. A miss is `Ok(Some(QueryResult::NotFound))`;
+    /// `Ok(None)` means the database holds no data of the query's kind (no IP
+    /// tree for an IP query, no literals or globs for a string query).
+    ///
+    /// Queries that look like an IP address but fail to parse (e.g. `1.2.3.999`
+    /// or `::ffff:1.2.3.999`) return [`DatabaseError::InvalidIp`] rather than being
+    /// matched as strings, unless the database was opened with
+    /// [`lenient_ip_parsing`](DatabaseOpener::lenient_ip_parsing).
+    ///
+    /// Glob patterns and literals are matched against the whole query, so a hit
+    /// always spans `(0, query.len())`. To locate matches inside a larger piece
+    /// of text (e.g. a full log line), use [`scan`](Self::scan).
     pub fn lookup(&self, query: &str) -> Result<Option<QueryResult>, DatabaseError> {
-        // Check cache first (only if caching is enabled)
-        if self.cache_enabled {
-            if let Some(cached_result) = self.query_cache.borrow_mut().get(query) {
-                // Cache hit - update stats in single borrow
-                let mut stats = self.stats.borrow_mut();
-                stats.total_queries += 1;
-                stats.cache_hits += 1;
-                drop(stats);
-                return Ok(Some(cached_result.clone()));
-            }
-        }
+        self.lookup_with(query, None)
+    }
 
-        // Cache miss (or cache disabled) - perform actual lookup
-        let result = if let Ok(addr) = query.parse::<IpAddr>() {
-            self.lookup_ip_uncached(addr)?
-        } else {
-            self.lookup_string_uncached(query)?
-        };
+    /// Look up a query, matching globs with caller-owned buffers
+    ///
+    /// Identical to [`lookup`](Self::lookup), including caching and stats, but
+    /// glob matching runs in `scratch` instead of the database's internal
+    /// buffers. Keep one [`QueryScratch`] per worker thread and reuse it for
+    /// every query so its buffers stop growing after the first few lookups.
+    ///
+    /// The scratch only covers glob matching; this is not an allocation-free
+    /// lookup. The returned [`QueryResult`] owns its pattern IDs and data, and
+    /// while the query cache is on each miss also copies the query into a
+    /// cache key and clones the result into the cache. Open with
+    /// [`DatabaseOpener::no_cache`] to drop the cache allocations.
+    ///
+    /// `Database` is `Sync`, so worker threads can share one handle, each
+    /// with its own scratch. The query cache and stats sit behind a mutex
+    /// that every lookup takes briefly; under heavy contention, open one
+    /// handle per thread or turn the cache off.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode, QueryScratch};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let mut scratch = QueryScratch::new();
+    /// for domain in ["www.evil.com", "example.org"] {
+    ///     let result = db.lookup_into(domain, &mut scratch)?;
+    ///     println!("{domain}: {result:?}");
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_into(
+        &self,
+        query: &str,
+        scratch: &mut QueryScratch,
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        self.lookup_with(query, Some(scratch))
+    }
 
-        // Update all stats in single borrow to minimize overhead
+    /// Look up a query, falling back to the database's default record
+    ///
+    /// Returns the same result as [`lookup`](Self::lookup) on a hit. On a miss
+    /// it returns the record set with `DatabaseBuilder::with_default_data()`:
+    /// as `QueryResult::Ip { prefix_len: 0, .. }` for IP queries, the shape a
+    /// `0.0.0.0/0` catch-all would give, and as a `QueryResult::Pattern` with
+    /// no pattern IDs for string queries. Either way it is
+    /// [`first_data`](QueryResult::first_data). Without a default, misses are
+    /// `QueryResult::NotFound`.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut unknown = HashMap::new();
+    /// unknown.insert("category".to_string(), DataValue::String("unknown".to_string()));
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive).with_default_data(unknown);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let result = db.lookup_or_default("example.org")?;
+    /// assert_eq!(result.first_data(), db.default_data());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_or_default(&self, query: &str) -> Result<QueryResult, DatabaseError> {
+        match self.lookup(query)? {
+            Some(QueryResult::NotFound) | None => {}
+            Some(result) => return Ok(result),
+        }
+        let Some(data) = self.default_data.clone() else {
+            return Ok(QueryResult::NotFound);
+        };
+        Ok(match classify_key(query) {
+            KeyKind::Ip(_) => QueryResult::Ip {
+                data,
+                prefix_len: 0,
+            },
+            KeyKind::MalformedIp | KeyKind::String => QueryResult::Pattern {
+                pattern_ids: Vec::new(),
+                data: vec![Some(data)],
+            },
+        })
+    }
+
+    /// Look up a batch of queries
+    ///
+    /// Returns one result per key, in input order, with misses as `None`.
+    /// Each key is classified like [`lookup`](Self::lookup), and IP keys are
+    /// looked up before string keys so tree walks and glob matching each run
+    /// back to back. Stops at the first error, such as
+    /// [`DatabaseError::InvalidIp`].
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let results = db.lookup_many(&["www.evil.com", "192.0.2.1", "10.1.2.3"])?;
+    /// assert!(results[0].is_some());
+    /// assert!(results[1].is_none());
+    /// assert!(results[2].is_some());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_many<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+    ) -> Result<Vec<Option<QueryResult>>, DatabaseError> {
+        let mut out = Vec::with_capacity(keys.len());
+        self.lookup_many_into(keys, &mut out)?;
+        Ok(out)
+    }
+
+    /// Look up a batch of queries into a caller-owned vector
+    ///
+    /// Identical to [`lookup_many`](Self::lookup_many), but `out` is cleared
+    /// and refilled, so a vector reused across batches stops allocating once
+    /// it has grown to the batch size. On error `out` holds the results
+    /// completed so far, with `None` in the remaining slots.
+    pub fn lookup_many_into<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        out: &mut Vec<Option<QueryResult>>,
+    ) -> Result<(), DatabaseError> {
+        out.clear();
+        out.resize(keys.len(), None);
+        let mut scratch = QueryScratch::new();
+
+        for ips in [true, false] {
+            for (slot, key) in out.iter_mut().zip(keys) {
+                let key = key.as_ref();
+                if matches!(classify_key(key), KeyKind::Ip(_)) != ips {
+                    continue;
+                }
+                *slot = self
+                    .lookup_with(key, Some(&mut scratch))?
+                    .filter(|result| !matches!(result, QueryResult::NotFound));
+            }
+        }
+        Ok(())
+    }
+
+    /// Look up a batch of queries until a wall-clock deadline
+    ///
+    /// Queries are looked up in order, like [`lookup`](Self::lookup), until
+    /// all are done or `deadline` passes. The clock is read once per query,
+    /// so a single lookup is never interrupted and the batch may overrun the
+    /// deadline by at most one lookup; pair with
+    /// [`lookup_budgeted`](Self::lookup_budgeted) when single lookups must
+    /// be bounded too.
+    ///
+    /// Returns one slot per key and the number of keys looked up. Slots past
+    /// that count are `None`, as are misses. An error from any lookup (such as
+    /// [`DatabaseError::InvalidIp`]) ends the batch and is returned.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(5);
+    /// let (results, done) = db.lookup_many_until(&["www.evil.com", "example.org"], deadline)?;
+    /// for result in &results[..done] {
+    ///     println!("{:?}", result);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_many_until<S: AsRef<str>>(
+        &self,
+        keys: &[S],
+        deadline: Instant,
+    ) -> Result<(Vec<Option<QueryResult>>, usize), DatabaseError> {
+        let mut results = vec![None; keys.len()];
+        let mut scratch = QueryScratch::new();
+        let mut done = 0;
+        for (slot, key) in results.iter_mut().zip(keys) {
+            if Instant::now() >= deadline {
+                break;
+            }
+            *slot = self
+                .lookup_with(key.as_ref(), Some(&mut scratch))?
+                .filter(|result| !matches!(result, QueryResult::NotFound));
+            done += 1;
+        }
+        Ok((results, done))
+    }
+
+    /// Look up a stream of queries lazily, one per pull
+    ///
+    /// Each key is classified like [`lookup`](Self::lookup) when the returned
+    /// iterator asks for it, so memory stays constant however long `keys` is
+    /// and a slow consumer naturally holds back the producer. Results come
+    /// back in input order, paired with their key; misses are `Ok(None)`.
+    /// A single [`QueryScratch`] is reused for every key.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let keys = ["www.evil.com", "example.org"].map(String::from);
+    /// for (key, result) in db.lookup_stream(keys) {
+    ///     println!("{key}: {:?}", result?);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_stream<'a, I>(
+        &'a self,
+        keys: I,
+    ) -> impl Iterator<Item = (String, Result<Option<QueryResult>, DatabaseError>)> + 'a
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: 'a,
+    {
+        let mut scratch = QueryScratch::new();
+        keys.into_iter().map(move |key| {
+            let result = self
+                .lookup_with(&key, Some(&mut scratch))
+                .map(|result| result.filter(|r| !matches!(r, QueryResult::NotFound)));
+            (key, result)
+        })
+    }
+
+    /// Look up a query with a cap on glob matching work
+    ///
+    /// Classifies `query` like [`lookup`](Self::lookup), but string matching
+    /// stops once `budget` is spent. Returns the result and whether it was
+    /// truncated; a truncated result holds the matches found before the budget
+    /// ran out, a subset of what `lookup` would return. IP lookups and exact
+    /// literal hits are bounded already and never count against the budget.
+    ///
+    /// Budgeted lookups bypass the query cache and do not update
+    /// [`stats`](Self::stats), so a truncated result is never served to a later
+    /// unbudgeted lookup.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode, QueryBudget};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let budget = QueryBudget::new(100, 10_000);
+    /// let (result, truncated) = db.lookup_budgeted("www.evil.com", budget)?;
+    /// assert!(result.is_some() && !truncated);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_budgeted(
+        &self,
+        query: &str,
+        budget: QueryBudget,
+    ) -> Result<(Option<QueryResult>, bool), DatabaseError> {
+        self.check_input_len(query)?;
+        match classify_key(query) {
+            KeyKind::Ip(addr) => Ok((self.lookup_ip_uncached(addr)?, false)),
+            KeyKind::MalformedIp if !self.lenient_ip_parsing => {
+                Err(DatabaseError::InvalidIp(query.to_string()))
+            }
+            KeyKind::MalformedIp | KeyKind::String => {
+                self.lookup_string_budgeted(query, None, budget)
+            }
+        }
+    }
+
+    /// Fail with [`DatabaseError::InputTooLong`] if `query` exceeds `max_input_len`
+    fn check_input_len(&self, query: &str) -> Result<(), DatabaseError> {
+        if query.len() > self.max_input_len {
+            return Err(DatabaseError::InputTooLong(query.len()));
+        }
+        Ok(())
+    }
+
+    /// Shared body of `lookup` and `lookup_into`
+    fn lookup_with(
+        &self,
+        query: &str,
+        scratch: Option<&mut QueryScratch>,
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        // Reject oversized input before touching the cache or classifying it
+        self.check_input_len(query)?;
+
+        // Check cache first (only if caching is enabled)
+        if self.cache_enabled {
+            if let Some(cached_result) = lock(&self.query_cache).get(query) {
+                // Cache hit - update stats in single borrow
+                let mut stats = lock(&self.stats);
+                stats.total_queries += 1;
+                stats.cache_hits += 1;
+                drop(stats);
+                return Ok(Some(cached_result.clone()));
+            }
+        }
+
+        // Cache miss (or cache disabled) - perform actual lookup
+        let result = match classify_key(query) {
+            KeyKind::Ip(addr) => self.lookup_ip_uncached(addr)?,
+            KeyKind::MalformedIp if !self.lenient_ip_parsing => {
+                return Err(DatabaseError::InvalidIp(query.to_string()));
+            }
+            KeyKind::MalformedIp | KeyKind::String => {
+                self.lookup_string_uncached(query, scratch)?
+            }
+        };
+
+        // Update all stats in single borrow to minimize overhead
         {
-            let mut stats = self.stats.borrow_mut();
+            let mut stats = lock(&self.stats);
             stats.total_queries += 1;
 
             // Track query type based on result
@@ -680,180 +1768,1548 @@ impl Database {
         // Store in cache if result was found AND caching is enabled
         if self.cache_enabled {
             if let Some(ref res) = result {
-                self.query_cache
-                    .borrow_mut()
-                    .put(query.to_string(), res.clone());
+                lock(&self.query_cache).put(query.to_string(), res.clone());
             }
         }
 
         Ok(result)
     }
 
-    /// Look up an IP address (uncached internal method)
+    /// Look up a query, borrowing the result data from the database
     ///
-    /// Returns data associated with the IP address if found.
-    /// This is the internal uncached version used by `lookup()`.
-    fn lookup_ip_uncached(&self, addr: IpAddr) -> Result<Option<QueryResult>, DatabaseError> {
-        let header = match &self.ip_header {
-            Some(h) => h,
-            None => return Ok(None), // No IP data in this database
-        };
-
-        // Traverse tree
-        let tree = SearchTree::new(self.data.as_slice(), header);
-        let tree_result = tree.lookup(addr).map_err(DatabaseError::Format)?;
+    /// Classifies and matches `query` exactly like [`lookup`](Self::lookup),
+    /// but returns [`DataValueRef`]s that read from the mapped file on demand
+    /// rather than decoding every record up front. Pulling one or two fields
+    /// out of a large record then costs no allocation:
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let mut data = HashMap::new();
+    /// data.insert("threat".to_string(), DataValue::String("botnet".to_string()));
+    /// builder.add_entry("*.evil.com", data)?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let result = db.lookup_ref("www.evil.com")?.unwrap();
+    /// let data = result.first_data().unwrap().as_map().unwrap();
+    /// assert_eq!(data.get("threat")?.and_then(|v| v.as_str()), Some("botnet"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Results borrow `self`, so they are never cached and do not update
+    /// [`stats`](Self::stats). Pattern-only databases keep glob data inside
+    /// the pattern matcher rather than the mapped data section; looking up a
+    /// glob with data in one returns [`DatabaseError::Unsupported`].
+    pub fn lookup_ref(&self, query: &str) -> Result<Option<QueryResultRef<'_>>, DatabaseError> {
+        self.check_input_len(query)?;
+        match classify_key(query) {
+            KeyKind::Ip(addr) => self.lookup_ip_ref(addr),
+            KeyKind::MalformedIp if !self.lenient_ip_parsing => {
+                Err(DatabaseError::InvalidIp(query.to_string()))
+            }
+            KeyKind::MalformedIp | KeyKind::String => self.lookup_string_ref(query),
+        }
+    }
 
-        let tree_result = match tree_result {
-            Some(r) => r,
-            None => return Ok(Some(QueryResult::NotFound)),
+    /// Look up a query and return its data as raw MMDB-encoded bytes
+    ///
+    /// Classifies `query` like [`lookup`](Self::lookup) and returns the
+    /// encoded record of the highest-precedence match (the value
+    /// [`QueryResult::first_data`] would decode), sliced straight out of the
+    /// data section without decoding. Useful for passing records through to
+    /// other MMDB tooling or caching them opaquely.
+    ///
+    /// The bytes are exactly as stored, so any MMDB pointers in the record
+    /// (such as strings shared with earlier records) are offsets into this
+    /// database's data section and only resolve against it. Not cached.
+    ///
+    /// Returns `Ok(None)` on a miss, for a pattern without data, and for
+    /// pattern-only databases, which keep data outside an MMDB data section.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use matchy::data_section::DataDecoder;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut data = HashMap::new();
+    /// data.insert("score".to_string(), DataValue::Uint32(90));
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", data.clone())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let raw = db.lookup_raw_data("10.1.2.3")?.unwrap();
+    /// let decoded = DataDecoder::new(raw, 0).decode(0)?;
+    /// assert_eq!(decoded, DataValue::from_hash_map(data));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_raw_data(&self, query: &str) -> Result<Option<&[u8]>, DatabaseError> {
+        self.check_input_len(query)?;
+        let Some(header) = &self.ip_header else {
+            return Ok(None);
         };
 
-        // Decode data
-        let data = self.decode_ip_data(header, tree_result.data_offset)?;
+        let data_offset = match classify_key(query) {
+            KeyKind::Ip(addr) => SearchTree::new(self.data.as_slice(), header)
+                .lookup(addr)
+                .map_err(DatabaseError::Format)?
+                .map(|result| result.data_offset),
+            KeyKind::MalformedIp if !self.lenient_ip_parsing => {
+                return Err(DatabaseError::InvalidIp(query.to_string()));
+            }
+            KeyKind::MalformedIp | KeyKind::String => self.first_pattern_data_offset(query)?,
+        };
+        let Some(data_offset) = data_offset else {
+            return Ok(None);
+        };
 
-        Ok(Some(QueryResult::Ip {
-            data,
-            prefix_len: tree_result.prefix_len,
-        }))
+        let data_section = &self.data.as_slice()[header.tree_size + 16..];
+        let len = DataDecoder::new(data_section, 0)
+            .encoded_len(data_offset)
+            .map_err(|e| DatabaseError::Format(MmdbError::DecodeError(e.to_string())))?;
+        let start = data_offset as usize;
+        Ok(Some(&data_section[start..start + len]))
     }
 
-    /// Look up an IP address (public API, uses cache)
-    ///
-    /// Returns data associated with the IP address if found.
-    pub fn lookup_ip(&self, addr: IpAddr) -> Result<Option<QueryResult>, DatabaseError> {
-        // Convert to string for cache key
-        let query = addr.to_string();
+    /// Data offset of the highest-precedence literal or glob match
+    fn first_pattern_data_offset(&self, pattern: &str) -> Result<Option<u32>, DatabaseError> {
+        self.check_input_len(pattern)?;
 
-        // Check cache first
-        if let Some(cached_result) = self.query_cache.borrow_mut().get(&query) {
-            return Ok(Some(cached_result.clone()));
+        if let Some(literal_hash) = &self.literal_hash {
+            if let Some(pattern_id) = literal_hash.lookup(pattern) {
+                if let Some(data_offset) = literal_hash.get_data_offset(pattern_id) {
+                    return Ok(Some(data_offset));
+                }
+            }
         }
 
-        // Cache miss - do actual lookup
-        let result = self.lookup_ip_uncached(addr)?;
-
-        // Store in cache if found
-        if let Some(ref res) = result {
-            self.query_cache.borrow_mut().put(query, res.clone());
+        if let (Some(pg_cell), Some(mappings)) =
+            (&self.pattern_matcher, &self.pattern_data_mappings)
+        {
+            let pg = read_lock(pg_cell);
+            if let Some(&pattern_id) = pg.find_all(pattern).first() {
+                return Ok(mappings.get_offset(pattern_id, self.data.as_slice()));
+            }
         }
-
-        Ok(result)
+        Ok(None)
     }
 
-    /// Look up a string (literal or glob pattern) - uncached internal method
+    /// Look up a string against literals and globs only, skipping IP detection
     ///
-    /// Returns matching pattern IDs and associated data.
-    /// Checks both:
-    /// 1. Literal hash table for O(1) exact matches
-    /// 2. Glob patterns for wildcard matches
+    /// Use this in hot loops where the input is known to be a hostname, URL,
+    /// or other string. IP-shaped input such as `10.0.0.1` or `1.2.3.999` is
+    /// matched as text. Results are cached apart from [`lookup`](Self::lookup),
+    /// which answers those queries from the IP tree or with
+    /// [`DatabaseError::InvalidIp`].
     ///
-    /// A query can match both a literal AND a glob pattern simultaneously.
-    fn lookup_string_uncached(&self, pattern: &str) -> Result<Option<QueryResult>, DatabaseError> {
-        let mut all_pattern_ids = Vec::new();
-        let mut all_data_values = Vec::new();
+    /// Returns `Ok(None)` if the database has no literal or glob data.
+    pub fn lookup_pattern(&self, key: &str) -> Result<Option<QueryResult>, DatabaseError> {
+        self.lookup_string(key)
+    }
+
+    /// Look up a string against the literals and globs of one pattern group
+    ///
+    /// Matches like [`lookup_pattern`](Self::lookup_pattern), keeping only
+    /// the entries added to `group` with `DatabaseBuilder::add_entry_in_group()`,
+    /// in the same precedence order. Membership comes from the group table,
+    /// never from entry data. Returns `Ok(Some(QueryResult::NotFound))` when
+    /// nothing in the group matches, and `Ok(None)` if the database has no
+    /// group by that name (see [`pattern_groups`](Self::pattern_groups)).
+    /// Results are not cached.
+    pub fn lookup_in_group(
+        &self,
+        key: &str,
+        group: &str,
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        let (Some(group_id), Some(groups)) = (
+            self.pattern_groups.iter().position(|g| g == group),
+            &self.group_table,
+        ) else {
+            return Ok(None);
+        };
+        let group_id = Some(group_id as u16);
+        self.check_input_len(key)?;
+
+        let mut pattern_ids = Vec::new();
+        let mut data = Vec::new();
 
-        // 1. Try literal hash table first (O(1) lookup)
         if let Some(literal_hash) = &self.literal_hash {
-            if let Some(pattern_id) = literal_hash.lookup(pattern) {
-                // Found an exact match!
-                if let Some(data_offset) = literal_hash.get_data_offset(pattern_id) {
-                    let header = self.ip_header.as_ref().ok_or_else(|| {
-                        DatabaseError::Format(MmdbError::InvalidFormat(
-                            "Literal hash present but no IP header".to_string(),
-                        ))
-                    })?;
-                    let data = self.decode_ip_data(header, data_offset)?;
-                    all_pattern_ids.push(pattern_id);
-                    all_data_values.push(Some(data));
+            if let Some(pattern_id) = literal_hash.lookup(key) {
+                if groups.literal_group(pattern_id) == group_id {
+                    if let Some(data_offset) = literal_hash.get_data_offset(pattern_id) {
+                        let header = self.ip_header.as_ref().ok_or_else(|| {
+                            DatabaseError::Format(MmdbError::InvalidFormat(
+                                "Literal hash present but no IP header".to_string(),
+                            ))
+                        })?;
+                        pattern_ids.push(pattern_id);
+                        data.push(Some(self.decode_ip_data(header, data_offset)?));
+                    }
                 }
             }
         }
 
-        // 2. Check glob patterns (for wildcard matches)
         if let Some(pg_cell) = &self.pattern_matcher {
-            let pg = pg_cell.borrow();
-            let glob_pattern_ids = pg.find_all(pattern);
-
-            // Add glob matches
-            for &pattern_id in &glob_pattern_ids {
-                // For combined databases, use mappings to decode from MMDB data section
-                // For pattern-only databases, use Paraglob's internal data cache
-                let data = if let Some(mappings) = &self.pattern_data_mappings {
-                    // Combined database: decode from MMDB data section using lazy lookup
-                    if let Some(data_offset) = mappings.get_offset(pattern_id, self.data.as_slice())
-                    {
-                        let header = self.ip_header.as_ref().unwrap();
-                        Some(self.decode_ip_data(header, data_offset)?)
-                    } else {
-                        None
-                    }
-                } else {
-                    // Pattern-only database: use Paraglob's lazy data lookup
-                    pg.get_pattern_data(pattern_id)
-                };
-                all_pattern_ids.push(pattern_id);
-                all_data_values.push(data);
+            let pg = read_lock(pg_cell);
+            for pattern_id in pg.find_all(key) {
+                if groups.glob_group(pattern_id) == group_id {
+                    data.push(self.glob_data(&pg, pattern_id)?);
+                    pattern_ids.push(pattern_id);
+                }
             }
         }
 
-        // Return results
-        if all_pattern_ids.is_empty() {
-            // Only return NotFound if we actually have some pattern data
-            if self.literal_hash.is_some() || self.pattern_matcher.is_some() {
-                Ok(Some(QueryResult::NotFound))
-            } else {
-                Ok(None) // No pattern data in this database
-            }
+        Ok(Some(if pattern_ids.is_empty() {
+            QueryResult::NotFound
         } else {
-            Ok(Some(QueryResult::Pattern {
-                pattern_ids: all_pattern_ids,
-                data: all_data_values,
-            }))
-        }
+            QueryResult::Pattern { pattern_ids, data }
+        }))
     }
 
-    /// Look up a string (literal or glob pattern) - public API, uses cache
+    /// Look up an IP address given as text, skipping string matching
     ///
-    /// Returns matching pattern IDs and associated data.
-    pub fn lookup_string(&self, pattern: &str) -> Result<Option<QueryResult>, DatabaseError> {
-        // Check cache first
-        if let Some(cached_result) = self.query_cache.borrow_mut().get(pattern) {
-            return Ok(Some(cached_result.clone()));
+    /// Returns [`DatabaseError::InvalidIp`] if `key` is not a valid IPv4 or
+    /// IPv6 address, and `Ok(None)` if the database has no IP data.
+    pub fn lookup_ip_str(&self, key: &str) -> Result<Option<QueryResult>, DatabaseError> {
+        let addr = key
+            .parse::<IpAddr>()
+            .map_err(|_| DatabaseError::InvalidIp(key.to_string()))?;
+        self.lookup_ip(addr)
+    }
+
+    /// Copy every entry of this database into a new builder
+    ///
+    /// Useful for tests and interactive exploration: take an open database,
+    /// add a few entries, and load the result with [`Database::from_bytes`]
+    /// without touching the filesystem.
+    ///
+    /// IP networks, literals, globs (in their original precedence order),
+    /// domain suffixes, and numeric entries are copied, along with the match
+    /// mode, literal hash seed, pattern groups, and default record.
+    /// Descriptive metadata (database type,
+    /// description, version tag, comment) is not copied, since it describes
+    /// the original file.
+    ///
+    /// Networks are copied as stored in the search tree, so a CIDR with a more
+    /// specific network nested inside it may come back as several smaller
+    /// networks; lookups behave identically. Literals from case-insensitive
+    /// databases come back lowercased.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry's data is not a map, or the file is corrupt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let mut builder = db.to_builder()?;
+    /// builder.add_entry("*.test", HashMap::new())?;
+    /// let edited = Database::from_bytes(builder.build()?)?;
+    /// assert!(edited.lookup("10.1.2.3")?.unwrap().first_data().is_some());
+    /// assert!(edited.lookup("a.test")?.unwrap().first_data().is_some());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_builder(&self) -> Result<MmdbBuilder, DatabaseError> {
+        let (match_mode, literal_hash_seed) = self.builder_settings();
+        let mut builder = MmdbBuilder::new(match_mode).with_literal_hash_seed(literal_hash_seed);
+        if let Some(default_data) = &self.default_data {
+            builder = builder.with_default_map(Self::entry_map(default_data.clone())?);
         }
 
-        // Cache miss - do actual lookup
-        let result = self.lookup_string_uncached(pattern)?;
+        let add_error = |e: ParaglobError| DatabaseError::Unsupported(e.to_string());
 
-        // Store in cache if found
-        if let Some(ref res) = result {
-            self.query_cache
-                .borrow_mut()
-                .put(pattern.to_string(), res.clone());
+        // Register groups up front so they keep their IDs
+        for group in &self.pattern_groups {
+            builder.group_id(group).map_err(add_error)?;
         }
 
-        Ok(result)
-    }
+        self.for_each_grouped_entry(|kind, key, value, group| {
+            builder
+                .add_decoded(kind, &key, Self::entry_map(value)?, group)
+                .map_err(add_error)
+        })?;
 
-    /// Decode IP data at a given offset
-    /// Decode IP data at a given offset
-    fn decode_ip_data(&self, header: &MmdbHeader, offset: u32) -> Result<DataValue, DatabaseError> {
-        use crate::data_section::DataDecoder;
+        Ok(builder)
+    }
 
-        // Offsets from the tree are relative to the start of the data section (after the 16-byte separator)
-        // So we slice the buffer to start at tree_size + 16
-        let data_section_start = header.tree_size + 16;
-        let data_section = &self.data.as_slice()[data_section_start..];
+    /// Match mode and literal hash seed a rebuild of this database should use
+    pub(crate) fn builder_settings(&self) -> (crate::glob::MatchMode, u64) {
+        match &self.literal_hash {
+            Some(literal_hash) => (literal_hash.mode(), literal_hash.hash_seed()),
+            None => (Self::read_match_mode_from_metadata(self.data.as_slice()), 0),
+        }
+    }
 
-        // Offsets from tree are relative to data_section, which we've sliced
-        // So base_offset is 0 (the decoder will resolve pointers relative to the buffer start)
-        let decoder = DataDecoder::new(data_section, 0);
+    /// Render the logical contents of this database as deterministic text
+    ///
+    /// The output depends only on what the database answers, not on how it
+    /// is laid out on disk (record size, section order, hash seed, node
+    /// numbering), so two databases can be compared for logical equality with
+    /// a plain text diff. One entry per line, tab-separated:
+    ///
+    /// ```text
+    /// mode<TAB>case-sensitive
+    /// network<TAB>10.0.0.0/8<TAB>{"threat":"botnet"}
+    /// literal<TAB>"evil.com"<TAB>{}
+    /// glob<TAB>"*.evil.com"<TAB>{}
+    /// glob<TAB>"*.c2.net"<TAB>{}<TAB>"c2"
+    /// suffix<TAB>"example.org"<TAB>{}
+    /// numeric<TAB>15169<TAB>{}
+    /// ```
+    ///
+    /// Literals and globs added to a pattern group end with the group name.
+    /// A default record, if set, follows the mode line as `default<TAB>{...}`.
+    ///
+    /// Networks and numeric keys are in ascending order, literals and suffixes
+    /// are sorted, and globs stay in precedence order since reordering them
+    /// changes which pattern wins. Keys and strings are quoted with
+    /// `\`-escapes, map keys are sorted, integers print without their storage
+    /// width, and bytes print as `0x` hex.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is corrupt.
+    pub fn canonical_text(&self) -> Result<String, DatabaseError> {
+        let match_mode = match &self.literal_hash {
+            Some(literal_hash) => literal_hash.mode(),
+            None => Self::read_match_mode_from_metadata(self.data.as_slice()),
+        };
 
-        decoder
-            .decode(offset)
-            .map_err(|e| DatabaseError::Format(MmdbError::DecodeError(e.to_string())))
+        let mut networks = Vec::new();
+        let mut literals = Vec::new();
+        let mut globs = Vec::new();
+        let mut suffixes = Vec::new();
+        let mut numerics = Vec::new();
+        self.for_each_grouped_entry(|kind, key, value, group| {
+            let mut line = String::new();
+            if matches!(kind, EntryKind::Network | EntryKind::Numeric) {
+                line.push_str(&key);
+            } else {
+                canonical_string(&key, &mut line);
+            }
+            line.push('\t');
+            canonical_value(&value, &mut line);
+            if let Some(group) = group {
+                line.push('\t');
+                canonical_string(group, &mut line);
+            }
+            match kind {
+                EntryKind::Network => networks.push(line),
+                EntryKind::Literal => literals.push(line),
+                EntryKind::Glob => globs.push(line),
+                EntryKind::Suffix => suffixes.push(line),
+                EntryKind::Numeric => numerics.push(line),
+            }
+            Ok(())
+        })?;
+        literals.sort_unstable();
+        suffixes.sort_unstable();
+
+        let mut out = String::new();
+        out.push_str(match match_mode {
+            crate::glob::MatchMode::CaseSensitive => "mode\tcase-sensitive\n",
+            crate::glob::MatchMode::CaseInsensitive => "mode\tcase-insensitive\n",
+        });
+        if let Some(default_data) = &self.default_data {
+            out.push_str("default\t");
+            canonical_value(default_data, &mut out);
+            out.push('\n');
+        }
+        for (label, lines) in [
+            ("network", networks),
+            ("literal", literals),
+            ("glob", globs),
+            ("suffix", suffixes),
+            ("numeric", numerics),
+        ] {
+            for line in lines {
+                out.push_str(label);
+                out.push('\t');
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        Ok(out)
     }
 
-    /// Detect database format (optimized to avoid full file scan)
+    /// Write every entry as newline-delimited JSON
+    ///
+    /// Each line is one object with the entry `type` (`network`, `literal`,
+    /// `glob`, `suffix`, or `numeric`), its `key`, and its `data`:
+    ///
+    /// ```text
+    /// {"type":"network","key":"1.2.3.0/24","data":{"threat":"botnet"}}
+    /// {"type":"glob","key":"*.evil.com","data":{}}
+    /// ```
+    ///
+    /// Networks are written in CIDR form with host bits cleared, in address
+    /// order; the other entries follow in [`for_each_entry`](Self::for_each_entry)
+    /// order. Data uses the plain JSON form of [`DataValue`], so integer widths
+    /// are not recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is corrupt or writing to `w` fails.
+    pub fn export_ndjson<W: std::io::Write>(&self, w: &mut W) -> Result<(), DatabaseError> {
+        self.for_each_entry(|kind, key, data| {
+            let kind = match kind {
+                EntryKind::Network => "network",
+                EntryKind::Literal => "literal",
+                EntryKind::Glob => "glob",
+                EntryKind::Suffix => "suffix",
+                EntryKind::Numeric => "numeric",
+            };
+            let line = serde_json::json!({ "type": kind, "key": key, "data": data });
+            serde_json::to_writer(&mut *w, &line).map_err(|e| DatabaseError::Io(e.to_string()))?;
+            w.write_all(b"\n")
+                .map_err(|e| DatabaseError::Io(e.to_string()))
+        })?;
+        w.flush().map_err(|e| DatabaseError::Io(e.to_string()))
+    }
+
+    /// Visit every logical entry with its key and decoded data
+    ///
+    /// Networks come in address order, literals in pattern ID order, globs in
+    /// precedence order, then suffixes and numeric keys in ascending order.
+    /// Entries without data get an empty map.
+    pub(crate) fn for_each_entry(
+        &self,
+        mut f: impl FnMut(EntryKind, String, DataValue) -> Result<(), DatabaseError>,
+    ) -> Result<(), DatabaseError> {
+        self.for_each_grouped_entry(|kind, key, value, _| f(kind, key, value))
+    }
+
+    /// Visit every logical entry like [`for_each_entry`](Self::for_each_entry),
+    /// with the pattern group name of grouped literals and globs
+    pub(crate) fn for_each_grouped_entry(
+        &self,
+        mut f: impl FnMut(EntryKind, String, DataValue, Option<&str>) -> Result<(), DatabaseError>,
+    ) -> Result<(), DatabaseError> {
+        let data = self.data.as_slice();
+        let group_name = |group: Option<u16>| {
+            group.and_then(|id| self.pattern_groups.get(id as usize).map(String::as_str))
+        };
+
+        if let Some(header) = &self.ip_header {
+            let tree = SearchTree::new(data, header);
+            for (addr, prefix_len, data_offset) in tree.networks().map_err(DatabaseError::Format)? {
+                f(
+                    EntryKind::Network,
+                    format!("{}/{}", addr, prefix_len),
+                    self.decode_ip_data(header, data_offset)?,
+                    None,
+                )?;
+            }
+        }
+
+        if let Some(literal_hash) = &self.literal_hash {
+            let header = self.ip_header.as_ref().ok_or_else(|| {
+                DatabaseError::Format(MmdbError::InvalidFormat(
+                    "Literal hash present but no IP header".to_string(),
+                ))
+            })?;
+            let mut literals = literal_hash.entries();
+            literals.sort_unstable_by_key(|&(_, pattern_id)| pattern_id);
+            for (literal, pattern_id) in literals {
+                let value = match literal_hash.get_data_offset(pattern_id) {
+                    Some(offset) => self.decode_ip_data(header, offset)?,
+                    None => DataValue::Map(DataMap::new()),
+                };
+                let group = self
+                    .group_table
+                    .as_ref()
+                    .and_then(|groups| groups.literal_group(pattern_id));
+                f(
+                    EntryKind::Literal,
+                    literal.to_string(),
+                    value,
+                    group_name(group),
+                )?;
+            }
+        }
+
+        if let Some(pg_cell) = &self.pattern_matcher {
+            let pg = read_lock(pg_cell);
+            for pattern_id in 0..pg.pattern_count() as u32 {
+                let Some(pattern) = pg.get_pattern(pattern_id) else {
+                    continue;
+                };
+                let value = self
+                    .glob_data(&pg, pattern_id)?
+                    .unwrap_or_else(|| DataValue::Map(DataMap::new()));
+                let group = self
+                    .group_table
+                    .as_ref()
+                    .and_then(|groups| groups.glob_group(pattern_id));
+                f(EntryKind::Glob, pattern, value, group_name(group))?;
+            }
+        }
+
+        if let (Some(trie), Some(header)) = (&self.suffix_trie, &self.ip_header) {
+            for (domain, data_offset) in trie.entries() {
+                f(
+                    EntryKind::Suffix,
+                    domain,
+                    self.decode_ip_data(header, data_offset)?,
+                    None,
+                )?;
+            }
+        }
+
+        if let (Some(index), Some(header)) = (&self.numeric_index, &self.ip_header) {
+            for (key, data_offset) in index.entries() {
+                f(
+                    EntryKind::Numeric,
+                    key.to_string(),
+                    self.decode_ip_data(header, data_offset)?,
+                    None,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unwrap entry data into a map for the builder, keeping its field order
+    pub(crate) fn entry_map(value: DataValue) -> Result<DataMap, DatabaseError> {
+        match value {
+            DataValue::Map(map) => Ok(map),
+            other => Err(DatabaseError::Unsupported(format!(
+                "Entry data is not a map: {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Report which matching mechanism served each hit for a query
+    ///
+    /// Returns one entry per match of [`lookup`](Self::lookup) for the same
+    /// query, in the same order: `[IpTree]` for an IP hit, or for a pattern
+    /// hit `LiteralHash` for the exact literal match (if any) followed by one
+    /// `GlobAutomaton` per glob match. An empty vector means no match.
+    ///
+    /// This is diagnostic only: it re-runs the lookup without touching the
+    /// cache or statistics, so it is not meant for the hot path. Use it to see
+    /// how much of a query mix is served by cheap literal hits.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`lookup`](Self::lookup).
+    pub fn matched_via(&self, query: &str) -> Result<Vec<MatchMechanism>, DatabaseError> {
+        self.check_input_len(query)?;
+        match classify_key(query) {
+            KeyKind::Ip(addr) => {
+                return Ok(match self.lookup_ip_uncached(addr)? {
+                    Some(QueryResult::Ip { .. }) => vec![MatchMechanism::IpTree],
+                    _ => Vec::new(),
+                });
+            }
+            KeyKind::MalformedIp if !self.lenient_ip_parsing => {
+                return Err(DatabaseError::InvalidIp(query.to_string()));
+            }
+            KeyKind::MalformedIp | KeyKind::String => {}
+        }
+
+        let mut via = Vec::new();
+        if let Some(literal_hash) = &self.literal_hash {
+            // Mirrors lookup_string_uncached: a literal without data is not a hit
+            if let Some(pattern_id) = literal_hash.lookup(query) {
+                if literal_hash.get_data_offset(pattern_id).is_some() {
+                    via.push(MatchMechanism::LiteralHash);
+                }
+            }
+        }
+        if let Some(pg_cell) = &self.pattern_matcher {
+            let glob_matches = read_lock(pg_cell).find_all(query).len();
+            via.resize(via.len() + glob_matches, MatchMechanism::GlobAutomaton);
+        }
+        Ok(via)
+    }
+
+    /// Look up a key in the IP tree and the pattern sections independently
+    ///
+    /// Unlike [`lookup`](Self::lookup), no classification is done: the IP tree
+    /// is searched whenever `key` parses as an IP address, and literals and
+    /// globs are always searched, so an IP-shaped key stored as a literal is
+    /// found too.
+    ///
+    /// Returns `(ip_result, pattern_results)`:
+    /// - `ip_result` is the IP tree match, or `None` if `key` is not an IP, the
+    ///   database has no IP data, or no network contains it.
+    /// - `pattern_results` holds one `QueryResult::Pattern` per matching
+    ///   pattern, in the precedence order described on [`QueryResult::Pattern`].
+    pub fn lookup_both(
+        &self,
+        key: &str,
+    ) -> Result<(Option<QueryResult>, Vec<QueryResult>), DatabaseError> {
+        let ip_result = match key.parse::<IpAddr>() {
+            Ok(addr) => match self.lookup_ip(addr)? {
+                Some(QueryResult::NotFound) => None,
+                result => result,
+            },
+            Err(_) => None,
+        };
+
+        let pattern_results = match self.lookup_pattern(key)? {
+            Some(QueryResult::Pattern { pattern_ids, data }) => pattern_ids
+                .into_iter()
+                .zip(data)
+                .map(|(id, data)| QueryResult::Pattern {
+                    pattern_ids: vec![id],
+                    data: vec![data],
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok((ip_result, pattern_results))
+    }
+
+    /// Count the literal and glob entries matching a string
+    ///
+    /// The query is always treated as a string (as in
+    /// [`lookup_pattern`](Self::lookup_pattern)). No data is decoded and no
+    /// result is cached, so this is cheaper than `lookup` when only the number
+    /// of matches is needed. Queries longer than the configured maximum input
+    /// length count as zero matches.
+    pub fn count_matches(&self, query: &str) -> usize {
+        self.count_matches_up_to(query, usize::MAX)
+    }
+
+    /// Count matching literal and glob entries, stopping at `limit`
+    ///
+    /// Glob verification stops once `limit` matches are found, which bounds
+    /// the work for attacker-controlled inputs that match many patterns.
+    pub fn count_matches_up_to(&self, query: &str, limit: usize) -> usize {
+        if query.len() > self.max_input_len {
+            return 0;
+        }
+        let mut count = 0;
+        if let Some(literal_hash) = &self.literal_hash {
+            if literal_hash.lookup(query).is_some() {
+                count += 1;
+            }
+        }
+        if let Some(pg_cell) = &self.pattern_matcher {
+            if count < limit {
+                count += read_lock(pg_cell).count_matches_up_to(query, limit - count);
+            }
+        }
+        count.min(limit)
+    }
+
+    /// Find the longest registered domain suffix of a hostname
+    ///
+    /// Only consults entries added with
+    /// [`DatabaseBuilder::add_suffix`](crate::DatabaseBuilder::add_suffix).
+    /// Matching is on whole labels and ignores ASCII case, so a `example.com`
+    /// entry matches `example.com` and `www.Example.com` but not
+    /// `notexample.com`. A trailing dot on `host` is ignored.
+    ///
+    /// Returns `Ok(None)` if no suffix matches or the database has no suffix
+    /// entries. Suffix lookups are not cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_suffix("example.com", HashMap::new())?;
+    /// builder.add_suffix("ads.example.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let m = db.lookup_suffix("x.ads.example.com")?.unwrap();
+    /// assert_eq!(m.suffix, "ads.example.com");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_suffix(&self, host: &str) -> Result<Option<SuffixMatch>, DatabaseError> {
+        let (Some(trie), Some(header)) = (&self.suffix_trie, &self.ip_header) else {
+            return Ok(None);
+        };
+        let Some((start, data_offset)) = trie.longest_match(host) else {
+            return Ok(None);
+        };
+
+        let host = host.strip_suffix('.').unwrap_or(host);
+        Ok(Some(SuffixMatch {
+            suffix: host[start..].to_ascii_lowercase(),
+            data: self.decode_ip_data(header, data_offset)?,
+        }))
+    }
+
+    /// Look up an integer key
+    ///
+    /// Only consults entries added with
+    /// [`DatabaseBuilder::add_numeric_entry`](crate::DatabaseBuilder::add_numeric_entry),
+    /// which suits identifiers such as ASNs or port numbers. Numeric keys are
+    /// separate from string keys: `lookup("15169")` does not find them.
+    ///
+    /// Returns `Ok(None)` if the key is absent or the database has no numeric
+    /// entries. Numeric lookups are not cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let mut data = HashMap::new();
+    /// data.insert("org".to_string(), DataValue::String("Cloudflare".to_string()));
+    /// builder.add_numeric_entry(13335, data)?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// assert!(db.lookup_numeric(13335)?.is_some());
+    /// assert!(db.lookup_numeric(15169)?.is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_numeric(&self, key: u64) -> Result<Option<DataValue>, DatabaseError> {
+        let (Some(index), Some(header)) = (&self.numeric_index, &self.ip_header) else {
+            return Ok(None);
+        };
+        match index.get(key) {
+            Some(data_offset) => Ok(Some(self.decode_ip_data(header, data_offset)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Look up a query and return the data of every matching entry
+    ///
+    /// For IP queries this is the data of the most specific CIDR. For string
+    /// queries it is the data of every matching literal and glob, in the same
+    /// order as `QueryResult::Pattern`; patterns without data are skipped.
+    ///
+    /// Returns an empty vector when nothing matches.
+    pub fn lookup_all(&self, query: &str) -> Result<Vec<DataValue>, DatabaseError> {
+        Ok(match self.lookup(query)? {
+            Some(QueryResult::Ip { data, .. }) => vec![data],
+            Some(QueryResult::Pattern { data, .. }) => data.into_iter().flatten().collect(),
+            Some(QueryResult::NotFound) | None => Vec::new(),
+        })
+    }
+
+    /// Like [`lookup_all`](Self::lookup_all), with repeated data values removed
+    ///
+    /// Overlapping patterns often carry the same categorization: `evil.com`
+    /// and `*.evil.com` tagged `{category: malware}` would otherwise return the
+    /// same payload twice. Values are compared with `DataValue`'s `PartialEq`
+    /// (map entry order is ignored) and the first occurrence of each is kept,
+    /// in precedence order. Since only data is returned, which pattern
+    /// produced a value cannot be recovered; use [`lookup`](Self::lookup) when
+    /// pattern IDs matter.
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut data = HashMap::new();
+    /// data.insert("category".to_string(), DataValue::String("malware".to_string()));
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("evil.com", data.clone())?;
+    /// builder.add_entry("*evil.com", data)?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// assert_eq!(db.lookup_all("evil.com")?.len(), 2);
+    /// assert_eq!(db.lookup_all_distinct("evil.com")?.len(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_all_distinct(&self, query: &str) -> Result<Vec<DataValue>, DatabaseError> {
+        let mut distinct: Vec<DataValue> = Vec::new();
+        for data in self.lookup_all(query)? {
+            // Match counts are small, so a linear scan beats hashing here
+            if !distinct.contains(&data) {
+                distinct.push(data);
+            }
+        }
+        Ok(distinct)
+    }
+
+    /// Look up a query and reduce all matches to a single score
+    ///
+    /// Runs [`lookup_all`](Self::lookup_all), applies `scorer` to each match's
+    /// data, and combines the results:
+    /// - [`ScoreCombine::Sum`]: total of all scores
+    /// - [`ScoreCombine::Max`]: highest score (NaN scores are ignored)
+    ///
+    /// Returns `0.0` when nothing matches, and for [`ScoreCombine::Max`] also
+    /// when every score is NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::{Database, DataValue, ScoreCombine};
+    ///
+    /// let db = Database::from("threats.mxy").open()?;
+    ///
+    /// let score = db.lookup_score(
+    ///     "malware.evil.com",
+    ///     |data| match data {
+    ///         DataValue::Map(m) => match m.get("score") {
+    ///             Some(DataValue::Double(s)) => *s,
+    ///             _ => 0.0,
+    ///         },
+    ///         _ => 0.0,
+    ///     },
+    ///     ScoreCombine::Max,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lookup_score<F>(
+        &self,
+        query: &str,
+        scorer: F,
+        combine: ScoreCombine,
+    ) -> Result<f64, DatabaseError>
+    where
+        F: Fn(&DataValue) -> f64,
+    {
+        let scores = self
+            .lookup_all(query)?
+            .iter()
+            .map(scorer)
+            .collect::<Vec<_>>();
+        if scores.is_empty() {
+            return Ok(0.0);
+        }
+
+        Ok(match combine {
+            ScoreCombine::Sum => scores.iter().sum(),
+            ScoreCombine::Max => scores
+                .iter()
+                .copied()
+                .filter(|score| !score.is_nan())
+                .reduce(f64::max)
+                .unwrap_or(0.0),
+        })
+    }
+
+    /// Look up an IP address (uncached internal method)
+    ///
+    /// Returns data associated with the IP address if found.
+    /// This is the internal uncached version used by `lookup()`.
+    fn lookup_ip_uncached(&self, addr: IpAddr) -> Result<Option<QueryResult>, DatabaseError> {
+        let header = match &self.ip_header {
+            Some(h) => h,
+            None => return Ok(None), // No IP data in this database
+        };
+
+        // Traverse tree
+        let tree = SearchTree::new(self.data.as_slice(), header);
+        let tree_result = tree.lookup(addr).map_err(DatabaseError::Format)?;
+
+        let tree_result = match tree_result {
+            Some(r) => r,
+            None => return Ok(Some(QueryResult::NotFound)),
+        };
+
+        // Decode data
+        let data = self.decode_ip_data(header, tree_result.data_offset)?;
+
+        Ok(Some(QueryResult::Ip {
+            data,
+            prefix_len: tree_result.prefix_len,
+        }))
+    }
+
+    /// Look up an IP address (public API, uses cache)
+    ///
+    /// Returns data associated with the IP address if found. Use this when
+    /// the address is already parsed (e.g. from a packet decoder): it goes
+    /// straight to the search tree, skipping the query classification and
+    /// parsing done by [`lookup`](Self::lookup). With the query cache
+    /// enabled the address is still formatted once as the cache key; open
+    /// with [`DatabaseOpener::no_cache`] to avoid that too.
+    pub fn lookup_ip(&self, addr: IpAddr) -> Result<Option<QueryResult>, DatabaseError> {
+        if !self.cache_enabled {
+            return self.lookup_ip_uncached(addr);
+        }
+
+        // Convert to string for cache key
+        let query = addr.to_string();
+
+        // Check cache first
+        if let Some(cached_result) = lock(&self.query_cache).get(&query) {
+            return Ok(Some(cached_result.clone()));
+        }
+
+        // Cache miss - do actual lookup
+        let result = self.lookup_ip_uncached(addr)?;
+
+        // Store in cache if found
+        if let Some(ref res) = result {
+            lock(&self.query_cache).put(query, res.clone());
+        }
+
+        Ok(result)
+    }
+
+    /// Look up an IPv4 address
+    ///
+    /// Equivalent to [`lookup_ip`](Self::lookup_ip) with `IpAddr::V4(addr)`.
+    #[inline]
+    pub fn lookup_ipv4(&self, addr: Ipv4Addr) -> Result<Option<QueryResult>, DatabaseError> {
+        self.lookup_ip(IpAddr::V4(addr))
+    }
+
+    /// Look up an IPv6 address
+    ///
+    /// Equivalent to [`lookup_ip`](Self::lookup_ip) with `IpAddr::V6(addr)`.
+    #[inline]
+    pub fn lookup_ipv6(&self, addr: Ipv6Addr) -> Result<Option<QueryResult>, DatabaseError> {
+        self.lookup_ip(IpAddr::V6(addr))
+    }
+
+    /// List every network on the search tree path of an address
+    ///
+    /// Returns `(network, prefix_len)` pairs from `/0` down to the record
+    /// where a lookup of `addr` stops, including networks that carry no data
+    /// of their own. This shows how finely the tree is split around an
+    /// address, which helps check a feed's CIDR aggregation; use
+    /// [`lookup_ip`](Self::lookup_ip) to retrieve data.
+    ///
+    /// Returns an empty list for databases without IP data and for IPv6
+    /// addresses in IPv4-only databases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    /// use std::net::IpAddr;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// builder.add_entry("10.1.0.0/16", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let path = db.covering_networks("10.1.2.3".parse()?)?;
+    /// assert_eq!(path.first(), Some(&("0.0.0.0".parse::<IpAddr>()?, 0)));
+    /// assert_eq!(path.last(), Some(&("10.1.0.0".parse::<IpAddr>()?, 16)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn covering_networks(&self, addr: IpAddr) -> Result<Vec<(IpAddr, u8)>, DatabaseError> {
+        let Some(header) = &self.ip_header else {
+            return Ok(Vec::new());
+        };
+        SearchTree::new(self.data.as_slice(), header)
+            .covering_networks(addr)
+            .map_err(DatabaseError::Format)
+    }
+
+    /// Iterate over every IP network in the database with its data
+    ///
+    /// Walks the search tree in address order, like libmaxminddb's
+    /// `MMDB_next_node` iteration, yielding each network that holds data as
+    /// `(network, prefix_len, QueryResult::Ip)`. Networks are produced one at
+    /// a time; nothing is collected up front. In IPv6 databases, IPv4 entries
+    /// are yielded once, as IPv4 networks, and aliases of the IPv4 subtree
+    /// (such as `::ffff:0:0/96`) are skipped. Empty for databases without IP
+    /// data. A corrupt record yields an error and ends the walk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// builder.add_entry("192.168.0.0/16", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// for entry in db.networks() {
+    ///     let (network, prefix_len, _result) = entry?;
+    ///     println!("{}/{}", network, prefix_len);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn networks(
+        &self,
+    ) -> impl Iterator<Item = Result<(IpAddr, u8, QueryResult), DatabaseError>> + '_ {
+        self.ip_header.iter().flat_map(move |header| {
+            SearchTree::new(self.data.as_slice(), header)
+                .iter_networks()
+                .map(move |entry| {
+                    let (addr, prefix_len, data_offset) = entry.map_err(DatabaseError::Format)?;
+                    let data = self.decode_ip_data(header, data_offset)?;
+                    Ok((addr, prefix_len, QueryResult::Ip { data, prefix_len }))
+                })
+        })
+    }
+
+    /// Scan free-form text for database hits
+    ///
+    /// Runs the extractor over `text` and looks up every extracted IP, domain,
+    /// email, hash, or crypto address. Only items that match the database are
+    /// returned, ordered by position. Spans are byte ranges into `text` with an
+    /// exclusive end, identical to the extractor's [`Match::span`](crate::extractor::Match::span).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::Database;
+    /// use matchy::extractor::Extractor;
+    ///
+    /// let db = Database::from("threats.mxy").open()?;
+    /// let extractor = Extractor::new()?;
+    ///
+    /// let line = b"GET http://malware.evil.com/ from 10.1.2.3";
+    /// for m in db.scan(line, &extractor)? {
+    ///     println!("{} at {:?}", m.matched_text, m.span);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn scan(
+        &self,
+        text: &[u8],
+        extractor: &Extractor,
+    ) -> Result<Vec<ScanMatch>, DatabaseError> {
+        self.scan_with(text, extractor, None)
+    }
+
+    /// Scan free-form text for database hits, matching globs with caller-owned buffers
+    ///
+    /// Identical to [`scan`](Self::scan), but every extracted item is looked up
+    /// with [`lookup_into`](Self::lookup_into) using `scratch`. The same limits
+    /// apply: only glob matching uses the scratch.
+    pub fn scan_into(
+        &self,
+        text: &[u8],
+        extractor: &Extractor,
+        scratch: &mut QueryScratch,
+    ) -> Result<Vec<ScanMatch>, DatabaseError> {
+        self.scan_with(text, extractor, Some(scratch))
+    }
+
+    /// Shared body of `scan` and `scan_into`
+    fn scan_with(
+        &self,
+        text: &[u8],
+        extractor: &Extractor,
+        mut scratch: Option<&mut QueryScratch>,
+    ) -> Result<Vec<ScanMatch>, DatabaseError> {
+        let mut matches = Vec::new();
+
+        for item in extractor.extract_from_chunk(text) {
+            if let Some(result) = self.lookup_item(&item.item, scratch.as_deref_mut())? {
+                matches.push(ScanMatch {
+                    span: item.span,
+                    match_type: item.item.type_name(),
+                    matched_text: item.item.as_value(),
+                    result,
+                });
+            }
+        }
+
+        matches.sort_by_key(|m| m.span);
+        Ok(matches)
+    }
+
+    /// Extract items from a log line and look each one up
+    ///
+    /// Unlike [`scan`](Self::scan), every extracted item is returned, paired
+    /// with its lookup result, or `None` if the database has no match. Items
+    /// are in the order [`Extractor::extract_from_line`] yields them, and each
+    /// [`Match`] borrows from `line`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::Database;
+    /// use matchy::extractor::Extractor;
+    ///
+    /// let db = Database::from("threats.mxy").open()?;
+    /// let extractor = Extractor::new()?;
+    ///
+    /// let line = b"GET http://malware.evil.com/ from 10.1.2.3";
+    /// for (m, result) in db.enrich_line(line, &extractor)? {
+    ///     println!("{} {}: {:?}", m.item.type_name(), m.as_str(line), result);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn enrich_line<'a>(
+        &self,
+        line: &'a [u8],
+        extractor: &'a Extractor,
+    ) -> Result<Vec<(Match<'a>, Option<QueryResult>)>, DatabaseError> {
+        extractor
+            .extract_from_line(line)
+            .map(|item| {
+                let result = self.lookup_item(&item.item, None)?;
+                Ok((item, result))
+            })
+            .collect()
+    }
+
+    /// Hunt through a whole document for IOCs and database hits
+    ///
+    /// Combines extraction and enrichment in one pass:
+    ///
+    /// 1. Every item the extractor finds (IP, domain, email, hash, crypto
+    ///    address) is reported with its lookup result, or `None` on a miss.
+    /// 2. Every whitespace-delimited word not covered by an extracted item is
+    ///    looked up with [`lookup_pattern`](Self::lookup_pattern) and reported
+    ///    if it matches, so literals and globs such as `invoice_*.exe` are
+    ///    found even when the extractor doesn't recognize them.
+    ///
+    /// Globs match whole words, not arbitrary substrings; quotes, brackets,
+    /// and trailing punctuation are trimmed from each word first. Overlapping
+    /// spans are reported once, keeping the earliest and then the longest.
+    /// Words and items longer than the open-time
+    /// [`max_input_len`](DatabaseOpener::max_input_len) are treated as misses.
+    /// Results are ordered by position.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use matchy::Database;
+    /// use matchy::extractor::Extractor;
+    ///
+    /// let db = Database::from("threats.mxy").open()?;
+    /// let extractor = Extractor::new()?;
+    ///
+    /// let email = std::fs::read("suspicious.eml")?;
+    /// for hit in db.hunt(&email, &extractor)? {
+    ///     println!("{} {} at {:?}: {:?}", hit.match_type, hit.matched_text, hit.span, hit.result);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn hunt(
+        &self,
+        text: &[u8],
+        extractor: &Extractor,
+    ) -> Result<Vec<HuntMatch>, DatabaseError> {
+        let mut scratch = QueryScratch::new();
+
+        let mut items = extractor.extract_from_chunk(text);
+        items.sort_by_key(|item| (item.span.0, std::cmp::Reverse(item.span.1)));
+        let mut matches: Vec<HuntMatch> = Vec::with_capacity(items.len());
+        for item in items {
+            if matches.last().is_some_and(|m| item.span.0 < m.span.1) {
+                continue;
+            }
+            // An item over the input limit is reported as a miss rather
+            // than failing the whole document
+            let result = match self.lookup_item(&item.item, Some(&mut scratch)) {
+                Err(DatabaseError::InputTooLong(_)) => None,
+                result => result?,
+            };
+            matches.push(HuntMatch {
+                span: item.span,
+                match_type: item.item.type_name(),
+                matched_text: item.item.as_value(),
+                result,
+            });
+        }
+
+        // Extracted spans are now sorted and disjoint, so one binary search
+        // tells whether a word overlaps any of them
+        let extracted = matches.len();
+        for (start, end) in hunt_words(text) {
+            let next = matches[..extracted].partition_point(|m| m.span.1 <= start);
+            if next < extracted && matches[next].span.0 < end {
+                continue;
+            }
+            if end - start > self.max_input_len {
+                continue;
+            }
+            let Ok(word) = std::str::from_utf8(&text[start..end]) else {
+                continue;
+            };
+            if let Some(result) = self
+                .lookup_pattern(word)?
+                .filter(|result| !matches!(result, QueryResult::NotFound))
+            {
+                matches.push(HuntMatch {
+                    span: (start, end),
+                    match_type: "Token",
+                    matched_text: word.to_string(),
+                    result: Some(result),
+                });
+            }
+        }
+
+        matches.sort_by_key(|m| m.span);
+        Ok(matches)
+    }
+
+    /// Look up an extracted item by its kind, with misses as `None`
+    fn lookup_item(
+        &self,
+        item: &ExtractedItem,
+        scratch: Option<&mut QueryScratch>,
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        let result = match item {
+            ExtractedItem::Ipv4(ip) => self.lookup_ip(IpAddr::V4(*ip))?,
+            ExtractedItem::Ipv6(ip) => self.lookup_ip(IpAddr::V6(*ip))?,
+            ExtractedItem::Domain(s)
+            | ExtractedItem::Email(s)
+            | ExtractedItem::Hash(_, s)
+            | ExtractedItem::Bitcoin(s)
+            | ExtractedItem::Ethereum(s)
+            | ExtractedItem::Monero(s)
+            | ExtractedItem::Url(s) => self.lookup_with(s, scratch)?,
+        };
+        Ok(result.filter(|result| !matches!(result, QueryResult::NotFound)))
+    }
+
+    /// Look up a string (literal or glob pattern) - uncached internal method
+    ///
+    /// Returns matching pattern IDs and associated data.
+    /// Checks both:
+    /// 1. Literal hash table for O(1) exact matches
+    /// 2. Glob patterns for wildcard matches
+    ///
+    /// A query can match both a literal AND a glob pattern simultaneously.
+    /// Globs are matched in `scratch` when given, otherwise in the matcher's
+    /// own buffers.
+    fn lookup_string_uncached(
+        &self,
+        pattern: &str,
+        scratch: Option<&mut QueryScratch>,
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        self.lookup_string_budgeted(pattern, scratch, QueryBudget::UNLIMITED)
+            .map(|(result, _)| result)
+    }
+
+    /// String lookup that stops glob matching once `budget` is spent
+    ///
+    /// Also returns whether the budget ran out.
+    fn lookup_string_budgeted(
+        &self,
+        pattern: &str,
+        scratch: Option<&mut QueryScratch>,
+        budget: QueryBudget,
+    ) -> Result<(Option<QueryResult>, bool), DatabaseError> {
+        self.check_input_len(pattern)?;
+
+        let mut all_pattern_ids = Vec::new();
+        let mut all_data_values = Vec::new();
+
+        // 1. Try literal hash table first (O(1) lookup)
+        if let Some(literal_hash) = &self.literal_hash {
+            if let Some(pattern_id) = literal_hash.lookup(pattern) {
+                // Found an exact match!
+                if let Some(data_offset) = literal_hash.get_data_offset(pattern_id) {
+                    let header = self.ip_header.as_ref().ok_or_else(|| {
+                        DatabaseError::Format(MmdbError::InvalidFormat(
+                            "Literal hash present but no IP header".to_string(),
+                        ))
+                    })?;
+                    let data = self.decode_ip_data(header, data_offset)?;
+                    all_pattern_ids.push(pattern_id);
+                    all_data_values.push(Some(data));
+                }
+            }
+        }
+
+        // 2. Check glob patterns (for wildcard matches)
+        // find_all returns IDs in ascending order, which is glob insertion order
+        let mut truncated = false;
+        if let Some(pg_cell) = &self.pattern_matcher {
+            let pg = read_lock(pg_cell);
+            let owned_ids;
+            let glob_pattern_ids = match scratch {
+                Some(scratch) => {
+                    let (ids, stopped) = pg.find_all_budgeted_with(pattern, scratch, budget);
+                    truncated = stopped;
+                    ids
+                }
+                None => {
+                    let (ids, stopped) = pg.find_all_budgeted(pattern, budget);
+                    truncated = stopped;
+                    owned_ids = ids;
+                    owned_ids.as_slice()
+                }
+            };
+
+            // Add glob matches
+            for &pattern_id in glob_pattern_ids {
+                let data = self.glob_data(&pg, pattern_id)?;
+                all_pattern_ids.push(pattern_id);
+                all_data_values.push(data);
+            }
+        }
+
+        // Return results
+        let result = if all_pattern_ids.is_empty() {
+            // Only return NotFound if we actually have some pattern data
+            if self.literal_hash.is_some() || self.pattern_matcher.is_some() {
+                Some(QueryResult::NotFound)
+            } else {
+                None // No pattern data in this database
+            }
+        } else {
+            Some(QueryResult::Pattern {
+                pattern_ids: all_pattern_ids,
+                data: all_data_values,
+            })
+        };
+        Ok((result, truncated))
+    }
+
+    /// Look up a string (literal or glob pattern) - public API, uses cache
+    ///
+    /// Returns matching pattern IDs and associated data.
+    pub fn lookup_string(&self, pattern: &str) -> Result<Option<QueryResult>, DatabaseError> {
+        if !self.cache_enabled {
+            return self.lookup_string_uncached(pattern, None);
+        }
+
+        // Reject oversized input before it can become a cache key
+        self.check_input_len(pattern)?;
+
+        // Check cache first
+        if let Some(cached_result) = lock(&self.pattern_cache).get(pattern) {
+            return Ok(Some(cached_result.clone()));
+        }
+
+        // Cache miss - do actual lookup
+        let result = self.lookup_string_uncached(pattern, None)?;
+
+        // Store in cache if found
+        if let Some(ref res) = result {
+            lock(&self.pattern_cache).put(pattern.to_string(), res.clone());
+        }
+
+        Ok(result)
+    }
+
+    /// Get the data of a glob pattern
+    ///
+    /// For combined databases, use mappings to decode from MMDB data section.
+    /// For pattern-only databases, use Paraglob's internal data cache.
+    fn glob_data(
+        &self,
+        pg: &Paraglob,
+        pattern_id: u32,
+    ) -> Result<Option<DataValue>, DatabaseError> {
+        if let Some(mappings) = &self.pattern_data_mappings {
+            // Combined database: decode from MMDB data section using lazy lookup
+            match mappings.get_offset(pattern_id, self.data.as_slice()) {
+                Some(data_offset) => {
+                    let header = self.ip_header.as_ref().unwrap();
+                    Ok(Some(self.decode_ip_data(header, data_offset)?))
+                }
+                None => Ok(None),
+            }
+        } else {
+            // Pattern-only database: use Paraglob's lazy data lookup
+            Ok(pg.get_pattern_data(pattern_id))
+        }
+    }
+
+    /// Decode the data of every pattern, reporting any that fail
+    ///
+    /// `lookup` decodes pattern data lazily, so a corrupt data offset in the
+    /// pattern mapping table only shows up when that pattern matches. This
+    /// walks the whole table - every glob pattern and every literal - and
+    /// decodes each entry's data, which catches corruption that sampling
+    /// (as in [`validate_database`](crate::validation::validate_database))
+    /// can miss.
+    ///
+    /// Returns the failures as `(pattern_id, message)`. Glob and literal IDs
+    /// are numbered separately, so the message names the pattern. Databases
+    /// without pattern data, and pattern-only databases (which keep data in
+    /// the pattern section), always verify.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// builder.add_entry("evil.net", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    /// assert!(db.verify_all_pattern_data().is_ok());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn verify_all_pattern_data(&self) -> Result<(), Vec<(u32, String)>> {
+        let mut failures = Vec::new();
+        let Some(header) = &self.ip_header else {
+            return Ok(());
+        };
+
+        if let (Some(pg_cell), Some(mappings)) =
+            (&self.pattern_matcher, &self.pattern_data_mappings)
+        {
+            let pg = read_lock(pg_cell);
+            for pattern_id in 0..mappings.pattern_count as u32 {
+                let pattern = pg.get_pattern(pattern_id).unwrap_or_default();
+                match mappings.get_offset(pattern_id, self.data.as_slice()) {
+                    Some(data_offset) => {
+                        if let Err(e) = self.decode_ip_data(header, data_offset) {
+                            failures.push((
+                                pattern_id,
+                                format!(
+                                    "glob {:?}: data at offset {}: {}",
+                                    pattern, data_offset, e
+                                ),
+                            ));
+                        }
+                    }
+                    None => failures.push((
+                        pattern_id,
+                        format!(
+                            "glob {:?}: mapping entry is past the end of the file",
+                            pattern
+                        ),
+                    )),
+                }
+            }
+        }
+
+        if let Some(literal_hash) = &self.literal_hash {
+            let mut literals = literal_hash.entries();
+            literals.sort_unstable_by_key(|&(_, pattern_id)| pattern_id);
+            for (literal, pattern_id) in literals {
+                match literal_hash.get_data_offset(pattern_id) {
+                    Some(data_offset) => {
+                        if let Err(e) = self.decode_ip_data(header, data_offset) {
+                            failures.push((
+                                pattern_id,
+                                format!(
+                                    "literal {:?}: data at offset {}: {}",
+                                    literal, data_offset, e
+                                ),
+                            ));
+                        }
+                    }
+                    None => failures.push((
+                        pattern_id,
+                        format!("literal {:?}: no data mapping", literal),
+                    )),
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Borrowed counterpart of `lookup_ip_uncached`
+    fn lookup_ip_ref(&self, addr: IpAddr) -> Result<Option<QueryResultRef<'_>>, DatabaseError> {
+        let Some(header) = &self.ip_header else {
+            return Ok(None);
+        };
+
+        let tree = SearchTree::new(self.data.as_slice(), header);
+        let Some(tree_result) = tree.lookup(addr).map_err(DatabaseError::Format)? else {
+            return Ok(Some(QueryResultRef::NotFound));
+        };
+
+        Ok(Some(QueryResultRef::Ip {
+            data: self.decode_ip_data_ref(header, tree_result.data_offset)?,
+            prefix_len: tree_result.prefix_len,
+        }))
+    }
+
+    /// Borrowed counterpart of `lookup_string_uncached`
+    fn lookup_string_ref(
+        &self,
+        pattern: &str,
+    ) -> Result<Option<QueryResultRef<'_>>, DatabaseError> {
+        self.check_input_len(pattern)?;
+
+        let mut all_pattern_ids = Vec::new();
+        let mut all_data_values = Vec::new();
+
+        if let Some(literal_hash) = &self.literal_hash {
+            if let Some(pattern_id) = literal_hash.lookup(pattern) {
+                if let Some(data_offset) = literal_hash.get_data_offset(pattern_id) {
+                    let header = self.ip_header.as_ref().ok_or_else(|| {
+                        DatabaseError::Format(MmdbError::InvalidFormat(
+                            "Literal hash present but no IP header".to_string(),
+                        ))
+                    })?;
+                    all_pattern_ids.push(pattern_id);
+                    all_data_values.push(Some(self.decode_ip_data_ref(header, data_offset)?));
+                }
+            }
+        }
+
+        if let Some(pg_cell) = &self.pattern_matcher {
+            let pg = read_lock(pg_cell);
+            for pattern_id in pg.find_all(pattern) {
+                let data = match &self.pattern_data_mappings {
+                    Some(mappings) => match mappings.get_offset(pattern_id, self.data.as_slice()) {
+                        Some(data_offset) => {
+                            let header = self.ip_header.as_ref().unwrap();
+                            Some(self.decode_ip_data_ref(header, data_offset)?)
+                        }
+                        None => None,
+                    },
+                    None if pg.get_pattern_data(pattern_id).is_some() => {
+                        return Err(DatabaseError::Unsupported(
+                            "Borrowed glob data requires a combined database".to_string(),
+                        ));
+                    }
+                    None => None,
+                };
+                all_pattern_ids.push(pattern_id);
+                all_data_values.push(data);
+            }
+        }
+
+        if all_pattern_ids.is_empty() {
+            if self.literal_hash.is_some() || self.pattern_matcher.is_some() {
+                Ok(Some(QueryResultRef::NotFound))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Ok(Some(QueryResultRef::Pattern {
+                pattern_ids: all_pattern_ids,
+                data: all_data_values,
+            }))
+        }
+    }
+
+    /// Borrow IP data at a given offset without decoding it
+    fn decode_ip_data_ref(
+        &self,
+        header: &MmdbHeader,
+        offset: u32,
+    ) -> Result<DataValueRef<'_>, DatabaseError> {
+        let data_section = &self.data.as_slice()[header.tree_size + 16..];
+        DataDecoder::new(data_section, 0)
+            .decode_ref(offset)
+            .map_err(|e| DatabaseError::Format(MmdbError::DecodeError(e.to_string())))
+    }
+
+    /// Decode IP data at a given offset
+    fn decode_ip_data(&self, header: &MmdbHeader, offset: u32) -> Result<DataValue, DatabaseError> {
+        // Offsets from the tree are relative to the start of the data section (after the 16-byte separator)
+        // So we slice the buffer to start at tree_size + 16
+        let data_section_start = header.tree_size + 16;
+        let data_section = &self.data.as_slice()[data_section_start..];
+
+        // Offsets from tree are relative to data_section, which we've sliced
+        // So base_offset is 0 (the decoder will resolve pointers relative to the buffer start)
+        let decoder = DataDecoder::new(data_section, 0);
+
+        decoder
+            .decode(offset)
+            .map_err(|e| DatabaseError::Format(MmdbError::DecodeError(e.to_string())))
+    }
+
+    /// Detect database format (optimized to avoid full file scan)
     fn detect_format(data: &[u8]) -> Result<DatabaseFormat, DatabaseError> {
         // Check for paraglob magic at start (pattern-only format)
         let has_paraglob_start = data.len() >= 8 && &data[0..8] == b"PARAGLOB";
@@ -861,475 +3317,2718 @@ impl Database {
             return Ok(DatabaseFormat::PatternOnly);
         }
 
-        // Check for MMDB metadata marker (searches last 128KB only)
-        let has_mmdb = crate::mmdb::find_metadata_marker(data).is_ok();
-        if !has_mmdb {
-            return Err(DatabaseError::Format(MmdbError::InvalidFormat(
-                "Unknown database format (no MMDB or PARAGLOB marker)".to_string(),
+        // Check for MMDB metadata marker (searches last 128KB only)
+        let has_mmdb = crate::mmdb::find_metadata_marker(data).is_ok();
+        if !has_mmdb {
+            return Err(DatabaseError::Format(MmdbError::InvalidFormat(
+                "Unknown database format (no MMDB or PARAGLOB marker)".to_string(),
+            )));
+        }
+
+        // Fast path: Check metadata for section offsets (new format)
+        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
+            if let Ok(DataValue::Map(map)) = metadata.as_value() {
+                // If pattern_section_offset exists in metadata, use it to determine format
+                if let Some(DataValue::Uint32(pattern_offset)) = map.get("pattern_section_offset") {
+                    // New format with metadata offsets
+                    let has_patterns = *pattern_offset != 0;
+                    if let Some(DataValue::Uint32(literal_offset)) =
+                        map.get("literal_section_offset")
+                    {
+                        let has_literals = *literal_offset != 0;
+                        if has_patterns || has_literals {
+                            return Ok(DatabaseFormat::Combined);
+                        } else {
+                            return Ok(DatabaseFormat::IpOnly);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Slow path: Old format without metadata offsets - need to scan
+        // Check for MMDB_PATTERN separator (combined format)
+        let pattern_separator = b"MMDB_PATTERN\x00\x00\x00\x00";
+        let has_pattern_section = data.windows(16).any(|window| window == pattern_separator);
+
+        if has_pattern_section {
+            Ok(DatabaseFormat::Combined)
+        } else {
+            Ok(DatabaseFormat::IpOnly)
+        }
+    }
+
+    /// Get database format
+    pub fn format(&self) -> &str {
+        match self.format {
+            DatabaseFormat::IpOnly => "IP database",
+            DatabaseFormat::PatternOnly => "Pattern database",
+            DatabaseFormat::Combined => "Combined IP+Pattern database",
+        }
+    }
+
+    /// Check if database supports IP lookups
+    pub fn has_ip_data(&self) -> bool {
+        self.ip_header.is_some()
+    }
+
+    /// Get the IP version of the search tree (4 or 6)
+    ///
+    /// Returns None if the database has no IP tree. An IPv6 tree also
+    /// answers IPv4 queries.
+    pub fn ip_version(&self) -> Option<u8> {
+        self.ip_header.as_ref().map(|h| match h.ip_version {
+            IpVersion::V4 => 4,
+            IpVersion::V6 => 6,
+        })
+    }
+
+    /// Check if database supports string lookups (literals or patterns)
+    pub fn has_string_data(&self) -> bool {
+        self.literal_hash.is_some() || self.pattern_matcher.is_some()
+    }
+
+    /// Check if database supports literal (exact string) lookups
+    pub fn has_literal_data(&self) -> bool {
+        self.literal_hash.is_some()
+    }
+
+    /// Check if database supports glob pattern lookups
+    pub fn has_glob_data(&self) -> bool {
+        self.pattern_matcher.is_some()
+    }
+
+    /// Check if database supports domain suffix lookups
+    pub fn has_suffix_data(&self) -> bool {
+        self.suffix_trie.is_some()
+    }
+
+    /// Check if database supports numeric key lookups
+    pub fn has_numeric_data(&self) -> bool {
+        self.numeric_index.is_some()
+    }
+
+    /// Check if database supports pattern lookups (deprecated, use has_literal_data or has_glob_data)
+    #[deprecated(
+        since = "0.5.0",
+        note = "Use has_literal_data or has_glob_data instead"
+    )]
+    pub fn has_pattern_data(&self) -> bool {
+        self.has_string_data()
+    }
+
+    /// Get MMDB metadata if available
+    ///
+    /// Returns the full metadata as a DataValue map, or None if this is not
+    /// an MMDB-format database or if metadata cannot be parsed. Besides the
+    /// standard MMDB fields (`database_type`, `build_epoch`, `languages`,
+    /// `description`, `ip_version`, `node_count`, `record_size`), the map
+    /// holds matchy's own fields such as entry counts and section offsets.
+    /// The metadata is parsed on each call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive)
+    ///     .with_database_type("Example-ThreatIntel");
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// if let Some(DataValue::Map(metadata)) = db.metadata() {
+    ///     assert!(metadata.contains_key("node_count"));
+    /// }
+    /// assert_eq!(db.database_type().as_deref(), Some("Example-ThreatIntel"));
+    /// assert!(db.build_epoch().is_some());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn metadata(&self) -> Option<DataValue> {
+        if !self.has_ip_data() {
+            return None;
+        }
+
+        use crate::mmdb::MmdbMetadata;
+        let metadata = MmdbMetadata::from_file(self.data.as_slice()).ok()?;
+        metadata.as_value().ok()
+    }
+
+    /// Get the database type name from the metadata
+    ///
+    /// Set with `DatabaseBuilder::with_database_type()`.
+    pub fn database_type(&self) -> Option<String> {
+        self.metadata_string("database_type")
+    }
+
+    /// Get the build time from the metadata, in seconds since the Unix epoch
+    pub fn build_epoch(&self) -> Option<u64> {
+        match self.metadata()? {
+            DataValue::Map(map) => Self::extract_uint_from_datavalue(map.get("build_epoch")?),
+            _ => None,
+        }
+    }
+
+    /// Get the description for a language (e.g. "en") from the metadata
+    pub fn description(&self, language: &str) -> Option<String> {
+        match self.metadata()? {
+            DataValue::Map(map) => match map.get("description")? {
+                DataValue::Map(descriptions) => match descriptions.get(language)? {
+                    DataValue::String(s) => Some(s.clone()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Get the free-form version tag stored at build time
+    ///
+    /// Returns None if the database was built without a version tag.
+    /// Set with `DatabaseBuilder::with_version_tag()`.
+    pub fn version_tag(&self) -> Option<String> {
+        self.metadata_string("version_tag")
+    }
+
+    /// Get the free-form comment stored at build time
+    ///
+    /// Returns None if the database was built without a comment.
+    /// Set with `DatabaseBuilder::with_comment()`.
+    pub fn comment(&self) -> Option<String> {
+        self.metadata_string("comment")
+    }
+
+    /// Get the fallback record returned by [`lookup_or_default`](Self::lookup_or_default)
+    ///
+    /// Returns None if the database was built without one.
+    /// Set with `DatabaseBuilder::with_default_data()`.
+    pub fn default_data(&self) -> Option<&DataValue> {
+        self.default_data.as_ref()
+    }
+
+    /// Get the names of the pattern groups, in group ID order
+    ///
+    /// Empty if no entry was added with `DatabaseBuilder::add_entry_in_group()`.
+    pub fn pattern_groups(&self) -> &[String] {
+        &self.pattern_groups
+    }
+
+    /// Helper to read a string field from the metadata map
+    fn metadata_string(&self, key: &str) -> Option<String> {
+        match self.metadata()? {
+            DataValue::Map(map) => match map.get(key) {
+                Some(DataValue::String(s)) => Some(s.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Get pattern string by ID
+    ///
+    /// Returns the pattern string for a given pattern ID.
+    /// Returns None if the database has no pattern data or pattern ID is invalid.
+    pub fn get_pattern_string(&self, pattern_id: u32) -> Option<String> {
+        let pg_cell = self.pattern_matcher.as_ref()?;
+        let pg = read_lock(pg_cell);
+        pg.get_pattern(pattern_id)
+    }
+
+    /// Mute or unmute a glob pattern on this handle
+    ///
+    /// A disabled pattern is skipped during matching, so it stops appearing
+    /// in [`lookup`](Self::lookup) and the other string lookups until it is
+    /// enabled again. The file is not modified: the setting lives in memory,
+    /// applies only to this `Database` handle, and is lost when it is dropped.
+    /// The query cache is cleared so cached results reflect the change.
+    ///
+    /// `pattern_id` is a glob pattern ID, as in
+    /// [`get_pattern_string`](Self::get_pattern_string). Literal entries are
+    /// not affected, and unknown IDs are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode, QueryResult};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// db.set_pattern_enabled(0, false);
+    /// assert!(matches!(db.lookup("www.evil.com")?, Some(QueryResult::NotFound)));
+    /// db.set_pattern_enabled(0, true);
+    /// assert!(matches!(db.lookup("www.evil.com")?, Some(QueryResult::Pattern { .. })));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_pattern_enabled(&self, pattern_id: u32, enabled: bool) {
+        if let Some(pg_cell) = &self.pattern_matcher {
+            write_lock(pg_cell).set_pattern_enabled(pattern_id, enabled);
+            self.clear_cache();
+        }
+    }
+
+    /// Whether a glob pattern is enabled on this handle
+    ///
+    /// See [`set_pattern_enabled`](Self::set_pattern_enabled). Unknown IDs
+    /// report as enabled.
+    pub fn is_pattern_enabled(&self, pattern_id: u32) -> bool {
+        match &self.pattern_matcher {
+            Some(pg_cell) => read_lock(pg_cell).is_pattern_enabled(pattern_id),
+            None => true,
+        }
+    }
+
+    /// Get total number of glob patterns
+    ///
+    /// Returns the number of glob patterns in the database.
+    /// Returns 0 if the database has no pattern data.
+    pub fn pattern_count(&self) -> usize {
+        match &self.pattern_matcher {
+            Some(pg_cell) => {
+                let pg = read_lock(pg_cell);
+                pg.pattern_count()
+            }
+            None => 0,
+        }
+    }
+
+    /// Get number of glob patterns (alias for pattern_count)
+    ///
+    /// Returns the number of glob patterns in the database.
+    /// Returns 0 if the database has no glob pattern data.
+    pub fn glob_count(&self) -> usize {
+        // Try to get from metadata first (more accurate)
+        if let Some(DataValue::Map(map)) = self.metadata() {
+            if let Some(count) = map.get("glob_entry_count") {
+                if let Some(val) = Self::extract_uint_from_datavalue(count) {
+                    return val as usize;
+                }
+            }
+        }
+        // Fallback to pattern_count
+        self.pattern_count()
+    }
+
+    /// Get number of literal patterns
+    ///
+    /// Returns the number of literal (exact-match) patterns in the database.
+    /// Returns 0 if the database has no literal pattern data.
+    pub fn literal_count(&self) -> usize {
+        // Try to get from metadata first (more accurate)
+        if let Some(DataValue::Map(map)) = self.metadata() {
+            if let Some(count) = map.get("literal_entry_count") {
+                if let Some(val) = Self::extract_uint_from_datavalue(count) {
+                    return val as usize;
+                }
+            }
+        }
+        // Fallback to literal_hash entry count
+        match &self.literal_hash {
+            Some(lh) => lh.entry_count() as usize,
+            None => 0,
+        }
+    }
+
+    /// Get number of IP address entries
+    ///
+    /// Returns the number of IP entries in the database.
+    /// Returns 0 if the database has no IP data.
+    pub fn ip_count(&self) -> usize {
+        // Try to get from metadata first (most accurate)
+        if let Some(DataValue::Map(map)) = self.metadata() {
+            if let Some(count) = map.get("ip_entry_count") {
+                if let Some(val) = Self::extract_uint_from_datavalue(count) {
+                    return val as usize;
+                }
+            }
+        }
+        // No accurate fallback for IP count
+        0
+    }
+
+    /// Helper to extract unsigned integer from DataValue
+    fn extract_uint_from_datavalue(value: &DataValue) -> Option<u64> {
+        match value {
+            DataValue::Uint16(v) => Some(*v as u64),
+            DataValue::Uint32(v) => Some(*v as u64),
+            DataValue::Uint64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Find the pattern section using fast metadata lookup with fallback to scanning
+    /// Returns the offset to the start of pattern data (after MMDB_PATTERN marker)
+    fn find_pattern_section_fast(data: &[u8]) -> Option<usize> {
+        // Fast path: Try to read offset from metadata
+        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
+            if let Ok(DataValue::Map(map)) = metadata.as_value() {
+                if let Some(DataValue::Uint32(offset)) = map.get("pattern_section_offset") {
+                    let offset_val = *offset as usize;
+                    // 0 means no pattern section (fast negative result)
+                    if offset_val == 0 {
+                        return None;
+                    }
+                    return Some(offset_val);
+                }
+            }
+        }
+
+        // Slow path: Scan for separator (backwards compatibility)
+        eprintln!("Warning: Database lacks section offset metadata, falling back to full file scan (slower load time)");
+        Self::find_pattern_section_slow(data)
+    }
+
+    /// Find the pattern section by scanning (slow, for backwards compatibility)
+    /// Returns the offset to the start of pattern data (after MMDB_PATTERN marker)
+    fn find_pattern_section_slow(data: &[u8]) -> Option<usize> {
+        let separator = b"MMDB_PATTERN\x00\x00\x00\x00";
+
+        // Search for the separator
+        for i in 0..data.len().saturating_sub(16) {
+            if &data[i..i + 16] == separator {
+                // Pattern section starts after the 16-byte separator
+                return Some(i + 16);
+            }
+        }
+        None
+    }
+
+    /// Find the literal section using fast metadata lookup with fallback to scanning
+    /// Returns the offset to the start of MMDB_LITERAL marker
+    fn find_literal_section_fast(data: &[u8]) -> Option<usize> {
+        // Fast path: Try to read offset from metadata
+        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
+            if let Ok(DataValue::Map(map)) = metadata.as_value() {
+                if let Some(DataValue::Uint32(offset)) = map.get("literal_section_offset") {
+                    let offset_val = *offset as usize;
+                    // 0 means no literal section (fast negative result)
+                    if offset_val == 0 {
+                        return None;
+                    }
+                    // Metadata stores offset to start of data, but we need offset to marker
+                    // So subtract 16 bytes for the "MMDB_LITERAL" marker
+                    return Some(offset_val - 16);
+                }
+            }
+        }
+
+        // Slow path: Scan for separator (backwards compatibility)
+        if data.len() > 1024 * 1024 {
+            // Only warn for files > 1MB
+            eprintln!("Warning: Database lacks section offset metadata, falling back to full file scan (slower load time)");
+        }
+        Self::find_literal_section_slow(data)
+    }
+
+    /// Find the suffix trie section from metadata
+    /// Returns the offset to the start of the section data (after the marker)
+    ///
+    /// Suffix sections postdate the offset metadata, so there is no scan fallback.
+    fn find_suffix_section(data: &[u8]) -> Option<usize> {
+        let metadata = crate::mmdb::MmdbMetadata::from_file(data).ok()?;
+        match metadata.as_value() {
+            Ok(DataValue::Map(map)) => match map.get("suffix_section_offset") {
+                Some(DataValue::Uint32(offset)) if *offset != 0 => Some(*offset as usize),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Find the numeric index section from metadata
+    /// Returns the offset to the start of the section data (after the marker)
+    fn find_numeric_section(data: &[u8]) -> Option<usize> {
+        let metadata = crate::mmdb::MmdbMetadata::from_file(data).ok()?;
+        match metadata.as_value() {
+            Ok(DataValue::Map(map)) => match map.get("numeric_section_offset") {
+                Some(DataValue::Uint32(offset)) if *offset != 0 => Some(*offset as usize),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Find the pattern group section from metadata
+    /// Returns the offset to the start of the section data (after the marker)
+    fn find_group_section(data: &[u8]) -> Option<usize> {
+        let metadata = crate::mmdb::MmdbMetadata::from_file(data).ok()?;
+        match metadata.as_value() {
+            Ok(DataValue::Map(map)) => match map.get("group_section_offset") {
+                Some(DataValue::Uint32(offset)) if *offset != 0 => Some(*offset as usize),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Find the literal hash section by scanning (slow, for backwards compatibility)
+    /// Returns the offset to the start of MMDB_LITERAL marker
+    fn find_literal_section_slow(data: &[u8]) -> Option<usize> {
+        let separator = b"MMDB_LITERAL\x00\x00\x00\x00";
+
+        // Search for the separator
+        (0..data.len().saturating_sub(16)).find(|&i| &data[i..i + 16] == separator)
+    }
+
+    /// Load pattern section from data at given offset (for pattern-only databases)
+    /// The format at offset is: PARAGLOB magic + data
+    /// Uses zero-copy from_mmap for O(1) loading
+    fn load_pattern_section(data: &'static [u8], offset: usize) -> Result<Paraglob, ParaglobError> {
+        if offset >= data.len() {
+            return Err(ParaglobError::OutOfBounds(
+                "Pattern section offset out of bounds".to_string(),
+            ));
+        }
+
+        // Try to read match mode from metadata
+        let match_mode = Self::read_match_mode_from_metadata(data);
+
+        // For pattern-only databases, data starts with PARAGLOB magic
+        if offset == 0 && data.len() >= 8 && &data[0..8] == b"PARAGLOB" {
+            // Standard .pgb format - load with zero-copy
+            // SAFETY: data is 'static lifetime from mmap, valid for entire Database lifetime
+            return unsafe { Paraglob::from_mmap(data, match_mode) };
+        }
+
+        Err(ParaglobError::Format(
+            "Invalid pattern-only database format".to_string(),
+        ))
+    }
+
+    /// Load combined pattern section from data at given offset
+    /// The format at offset is: `[total_size][paraglob_size][PARAGLOB data][pattern_count][data_offsets...]`
+    /// Returns (Paraglob matcher, lazy PatternDataMappings)
+    /// Uses zero-copy and deferred parsing for O(1) load time
+    fn load_combined_pattern_section(
+        data: &'static [u8],
+        offset: usize,
+    ) -> Result<(Paraglob, PatternDataMappings), ParaglobError> {
+        if offset >= data.len() {
+            return Err(ParaglobError::OutOfBounds(
+                "Pattern section offset out of bounds".to_string(),
+            ));
+        }
+
+        // Try to read match mode from metadata
+        let match_mode = Self::read_match_mode_from_metadata(data);
+
+        // Read section header
+        if offset + 8 > data.len() {
+            return Err(ParaglobError::Truncated(
+                "Pattern section header truncated".to_string(),
+            ));
+        }
+
+        // Read sizes (little-endian u32)
+        let _total_size = u32::from_le_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ]);
+        let paraglob_size = u32::from_le_bytes([
+            data[offset + 4],
+            data[offset + 5],
+            data[offset + 6],
+            data[offset + 7],
+        ]) as usize;
+
+        // Paraglob data starts at offset + 8
+        let paraglob_start = offset + 8;
+        let paraglob_end = paraglob_start + paraglob_size;
+
+        if paraglob_end > data.len() {
+            return Err(ParaglobError::Truncated(format!(
+                "Paraglob section extends beyond file (start={}, size={}, file_len={})",
+                paraglob_start,
+                paraglob_size,
+                data.len()
             )));
         }
 
-        // Fast path: Check metadata for section offsets (new format)
+        // Extract and load paraglob data with zero-copy
+        let paraglob_data = &data[paraglob_start..paraglob_end];
+        // SAFETY: data is 'static lifetime from mmap, valid for entire Database lifetime
+        let paraglob = unsafe { Paraglob::from_mmap(paraglob_data, match_mode) };
+        let paraglob = paraglob?;
+
+        // Store mapping metadata WITHOUT parsing all offsets (O(1) instead of O(n))
+        let mappings_start = paraglob_end;
+        if mappings_start + 4 > data.len() {
+            return Err(ParaglobError::Truncated(
+                "Pattern mappings section truncated".to_string(),
+            ));
+        }
+
+        let pattern_count = u32::from_le_bytes([
+            data[mappings_start],
+            data[mappings_start + 1],
+            data[mappings_start + 2],
+            data[mappings_start + 3],
+        ]) as usize;
+
+        let offsets_start = mappings_start + 4;
+
+        // Validate the mapping section exists, but don't parse it
+        let total_mapping_bytes = pattern_count * 4;
+        if offsets_start + total_mapping_bytes > data.len() {
+            return Err(ParaglobError::OutOfBounds(format!(
+                "Pattern mappings section out of bounds (need {} bytes)",
+                total_mapping_bytes
+            )));
+        }
+
+        let mappings = PatternDataMappings {
+            mappings_offset: offsets_start,
+            pattern_count,
+        };
+
+        Ok((paraglob, mappings))
+    }
+
+    /// Read match mode from database metadata
+    /// Returns CaseSensitive as default if not found or on error
+    fn read_match_mode_from_metadata(data: &[u8]) -> crate::glob::MatchMode {
+        use crate::glob::MatchMode;
+
+        // Try to read metadata
         if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
             if let Ok(DataValue::Map(map)) = metadata.as_value() {
-                // If pattern_section_offset exists in metadata, use it to determine format
-                if let Some(DataValue::Uint32(pattern_offset)) = map.get("pattern_section_offset") {
-                    // New format with metadata offsets
-                    let has_patterns = *pattern_offset != 0;
-                    if let Some(DataValue::Uint32(literal_offset)) =
-                        map.get("literal_section_offset")
-                    {
-                        let has_literals = *literal_offset != 0;
-                        if has_patterns || has_literals {
-                            return Ok(DatabaseFormat::Combined);
-                        } else {
-                            return Ok(DatabaseFormat::IpOnly);
-                        }
-                    }
+                if let Some(DataValue::Uint16(mode_val)) = map.get("match_mode") {
+                    return match *mode_val {
+                        1 => MatchMode::CaseInsensitive,
+                        _ => MatchMode::CaseSensitive, // 0 or unknown = CaseSensitive (default)
+                    };
+                }
+            }
+        }
+
+        // Default to case-sensitive for backward compatibility with old databases
+        MatchMode::CaseSensitive
+    }
+}
+
+/// Database error type
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// I/O error
+    Io(String),
+    /// Format error
+    Format(MmdbError),
+    /// Unsupported operation
+    Unsupported(String),
+    /// Query looks like an IP address but does not parse as one
+    InvalidIp(String),
+    /// String query exceeds the maximum input length (holds the query length)
+    InputTooLong(usize),
+    /// A section or value ends before its declared size
+    Truncated(String),
+    /// An offset or length points outside its buffer
+    OutOfBounds(String),
+    /// A file or section uses a format version this build cannot handle
+    UnsupportedVersion(String),
+    /// Stored data contradicts itself
+    IntegrityMismatch(String),
+}
+
+/// Kind of logical entry stored in a database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntryKind {
+    /// IP network (CIDR block) in the search tree
+    Network,
+    /// Exact-match string in the literal hash table
+    Literal,
+    /// Glob pattern
+    Glob,
+    /// Domain suffix entry
+    Suffix,
+    /// Integer-keyed entry
+    Numeric,
+}
+
+/// Parse the decimal key [`Database::for_each_entry`] reports for numeric entries
+/// Lock a cache or the stats of a [`Database`]
+///
+/// A panic mid-lookup can at worst leave a stale cache entry or a skipped
+/// counter, so a poisoned lock is used as is.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock the pattern matcher of a [`Database`] for a query, ignoring poisoning
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock the pattern matcher of a [`Database`] to change it, ignoring poisoning
+fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Append a quoted, escaped string for canonical text output
+fn canonical_string(s: &str, out: &mut String) {
+    out.push_str(&format!("{:?}", s));
+}
+
+/// Append a deterministic rendering of a data value
+///
+/// Map keys are sorted; integer widths are not recorded, so the same logical
+/// value encoded as `Uint16` or `Uint32` renders identically.
+fn canonical_value(value: &DataValue, out: &mut String) {
+    match value {
+        DataValue::String(s) => canonical_string(s, out),
+        DataValue::Double(d) => out.push_str(&format!("{:?}", d)),
+        DataValue::Float(f) => out.push_str(&format!("{:?}", f)),
+        DataValue::Bytes(bytes) => {
+            out.push_str("0x");
+            for b in bytes {
+                out.push_str(&format!("{:02x}", b));
+            }
+        }
+        DataValue::Uint16(n) => out.push_str(&n.to_string()),
+        DataValue::Uint32(n) => out.push_str(&n.to_string()),
+        DataValue::Uint64(n) => out.push_str(&n.to_string()),
+        DataValue::Uint128(n) => out.push_str(&n.to_string()),
+        DataValue::Int32(n) => out.push_str(&n.to_string()),
+        DataValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        DataValue::Pointer(offset) => out.push_str(&format!("<pointer {}>", offset)),
+        DataValue::Map(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_unstable();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical_string(key, out);
+                out.push(':');
+                canonical_value(&map[key], out);
+            }
+            out.push('}');
+        }
+        DataValue::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical_value(item, out);
+            }
+            out.push(']');
+        }
+    }
+}
+
+/// Read one byte from every page so later lookups don't take page faults
+fn prefault_pages(data: &[u8]) {
+    const PAGE_SIZE: usize = 4096;
+    let mut sum = 0u8;
+    for offset in (0..data.len()).step_by(PAGE_SIZE) {
+        sum = sum.wrapping_add(data[offset]);
+    }
+    std::hint::black_box(sum);
+}
+
+/// How [`Database::lookup`] treats a query, from [`classify_key`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// A valid IP address, looked up in the IP tree
+    Ip(IpAddr),
+    /// Shaped like an IP address but invalid; rejected with
+    /// [`DatabaseError::InvalidIp`] unless lenient IP parsing is enabled, in
+    /// which case it is matched as a string
+    MalformedIp,
+    /// Matched against literals and globs
+    String,
+}
+
+/// Classify a query the way [`Database::lookup`] does
+///
+/// The rules, applied in order:
+///
+/// 1. If the whole query parses as a [`std::net::IpAddr`], it is
+///    [`KeyKind::Ip`]. This accepts dotted-quad IPv4 (exactly four decimal
+///    parts, no leading zeros) and standard IPv6 text including `::`,
+///    compressed forms, and embedded IPv4 (`::ffff:1.2.3.4`). No trimming
+///    is done, so surrounding whitespace makes it a string.
+/// 2. Otherwise, if the query is IP-shaped it is [`KeyKind::MalformedIp`]:
+///    - IPv4-shaped: four dot-separated parts of 1-3 ASCII digits each
+///      (`1.2.3.999`, `01.2.3.4`)
+///    - IPv6-shaped: colon-separated groups of 1-4 hex digits, the last of
+///      which may be an IPv4-shaped tail, with at most one `::`. Without the
+///      `::` there must be eight groups, an IPv4 tail counting as two; with
+///      it, at most eight (`1::2:3:4:5:6:7:8`, `::ffff:1.2.3.999`)
+/// 3. Everything else is [`KeyKind::String`].
+///
+/// Some consequences worth knowing:
+///
+/// | Query | Kind |
+/// |-------|------|
+/// | `::` | `Ip` (the unspecified address) |
+/// | `1.2.3.4.5` | `String` (four dots is not IPv4-shaped) |
+/// | `...`, `1...`, `1.2.3.` | `String` (every IPv4 part needs a digit) |
+/// | `1.2.3.4567` | `String` (an IPv4 part has at most three digits) |
+/// | `::::`, `a::b::c`, `2001:db8::1::1` | `String` (more than one `::`) |
+/// | `::g`, `1:2:3:4:5:6:7:12345` | `String` (groups are 1-4 hex digits) |
+/// | `1234`, `deadbeef` | `String` |
+/// | `aa:bb:cc:dd:ee:ff`, `12:30:45` | `String` (no `::` and not eight groups) |
+/// | `[2001:db8::1]` | `String` (brackets are not stripped) |
+/// | `fe80::1%eth0` | `String` (zone IDs are not supported) |
+/// | `1.2.3.4/24` | `String` (queries are addresses, not networks) |
+///
+/// To look up the network of a CIDR, query its address with
+/// [`Database::lookup_ip`]; to force string matching, use
+/// [`Database::lookup_pattern`].
+///
+/// # Examples
+///
+/// ```
+/// use matchy::{classify_key, KeyKind};
+///
+/// assert!(matches!(classify_key("10.0.0.1"), KeyKind::Ip(_)));
+/// assert_eq!(classify_key("1.2.3.999"), KeyKind::MalformedIp);
+/// assert_eq!(classify_key("evil.com"), KeyKind::String);
+/// ```
+pub fn classify_key(query: &str) -> KeyKind {
+    if let Ok(addr) = query.parse::<IpAddr>() {
+        KeyKind::Ip(addr)
+    } else if looks_like_ip(query) {
+        KeyKind::MalformedIp
+    } else {
+        KeyKind::String
+    }
+}
+
+/// Check whether a query is shaped like an IP address
+///
+/// IPv4-like: four dot-separated parts of 1-3 digits each.
+/// IPv6-like: colon-separated groups of 1-4 hex digits, the last of which may
+/// be an IPv4-like tail, with at most one `::` standing in for missing groups.
+/// Without a `::` there must be eight groups (an IPv4 tail counts as two);
+/// with one, at most eight. MAC addresses (`aa:bb:cc:dd:ee:ff`), times
+/// (`12:30:45`), and paths such as `a::b::c` are not IP-like.
+fn looks_like_ip(query: &str) -> bool {
+    looks_like_ipv4(query) || looks_like_ipv6(query)
+}
+
+/// Four dot-separated parts of 1-3 ASCII digits each
+fn looks_like_ipv4(text: &str) -> bool {
+    let mut parts = 0;
+    for part in text.split('.') {
+        if part.is_empty() || part.len() > 3 || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        parts += 1;
+    }
+    parts == 4
+}
+
+/// IPv6 group structure as described on [`looks_like_ip`]
+fn looks_like_ipv6(text: &str) -> bool {
+    if !text.contains(':') {
+        return false;
+    }
+    let (halves, compressed) = match text.split_once("::") {
+        Some((head, tail)) => ([head, tail], true),
+        None => ([text, ""], false),
+    };
+    let mut groups = 0;
+    for (half_index, half) in halves.into_iter().enumerate() {
+        if half.is_empty() {
+            continue;
+        }
+        let last_half = half_index == 1 || !compressed;
+        let mut fields = half.split(':').peekable();
+        while let Some(group) = fields.next() {
+            if last_half && fields.peek().is_none() && group.contains('.') {
+                if !looks_like_ipv4(group) {
+                    return false;
+                }
+                groups += 2;
+            } else if group.is_empty()
+                || group.len() > 4
+                || !group.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                return false;
+            } else {
+                groups += 1;
+            }
+        }
+    }
+    if compressed {
+        groups <= 8
+    } else {
+        groups == 8
+    }
+}
+
+/// Spans of the whitespace-delimited words of `text`, for [`Database::hunt`]
+///
+/// Quotes and brackets are trimmed from both ends of each word, and
+/// sentence punctuation from its end; words left empty are skipped.
+fn hunt_words(text: &[u8]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    const ENCLOSING: &[u8] = b"\"'`()<>[]{}";
+    const TRAILING: &[u8] = b".,;:!?";
+
+    let mut pos = 0;
+    std::iter::from_fn(move || loop {
+        while pos < text.len() && text[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos == text.len() {
+            return None;
+        }
+        let mut start = pos;
+        while pos < text.len() && !text[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut end = pos;
+        while start < end && ENCLOSING.contains(&text[start]) {
+            start += 1;
+        }
+        while start < end
+            && (ENCLOSING.contains(&text[end - 1]) || TRAILING.contains(&text[end - 1]))
+        {
+            end -= 1;
+        }
+        if start < end {
+            return Some((start, end));
+        }
+    })
+}
+
+impl DatabaseError {
+    /// Stable category of this error
+    ///
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, ErrorKind, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// let db = Database::from_bytes(builder.build()?)?;
+    ///
+    /// let err = db.lookup("10.0.0.999").unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidIp);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DatabaseError::Io(_) => ErrorKind::Io,
+            DatabaseError::Format(err) => match err {
+                MmdbError::IoError(_) => ErrorKind::Io,
+                MmdbError::InvalidIpAddress(_) => ErrorKind::InvalidIp,
+                MmdbError::MetadataNotFound
+                | MmdbError::InvalidFormat(_)
+                | MmdbError::InvalidMetadata(_)
+                | MmdbError::DecodeError(_)
+                | MmdbError::LookupError(_) => ErrorKind::Format,
+            },
+            DatabaseError::Unsupported(_) => ErrorKind::Unsupported,
+            DatabaseError::InvalidIp(_) => ErrorKind::InvalidIp,
+            DatabaseError::InputTooLong(_) => ErrorKind::InputTooLong,
+            DatabaseError::Truncated(_) => ErrorKind::Truncated,
+            DatabaseError::OutOfBounds(_) => ErrorKind::OutOfBounds,
+            DatabaseError::UnsupportedVersion(_) => ErrorKind::UnsupportedVersion,
+            DatabaseError::IntegrityMismatch(_) => ErrorKind::IntegrityMismatch,
+        }
+    }
+
+    /// Wrap an error from loading a section, keeping its kind
+    fn section(what: &str, err: ParaglobError) -> Self {
+        let message = format!("Failed to load {}: {}", what, err);
+        match err.kind() {
+            ErrorKind::Truncated => DatabaseError::Truncated(message),
+            ErrorKind::OutOfBounds => DatabaseError::OutOfBounds(message),
+            ErrorKind::UnsupportedVersion => DatabaseError::UnsupportedVersion(message),
+            _ => DatabaseError::Unsupported(message),
+        }
+    }
+}
+
+impl std::fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseError::Io(msg) => write!(f, "I/O error: {}", msg),
+            DatabaseError::Format(err) => write!(f, "Format error: {}", err),
+            DatabaseError::Unsupported(msg) | DatabaseError::UnsupportedVersion(msg) => {
+                write!(f, "Unsupported: {}", msg)
+            }
+            DatabaseError::Truncated(msg)
+            | DatabaseError::OutOfBounds(msg)
+            | DatabaseError::IntegrityMismatch(msg) => write!(f, "Format error: {}", msg),
+            DatabaseError::InvalidIp(query) => write!(f, "Invalid IP address: {}", query),
+            DatabaseError::InputTooLong(len) => {
+                write!(f, "Query of {} bytes exceeds maximum input length", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glob::MatchMode;
+    use std::collections::HashMap;
+
+    /// Build a case-sensitive database with an empty record for each key
+    fn build_db(keys: &[&str]) -> Database {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for key in keys {
+            builder.add_entry(key, HashMap::new()).unwrap();
+        }
+        Database::from_bytes(builder.build().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_detect_ip_database() {
+        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
+            .open()
+            .unwrap();
+        assert_eq!(db.format, DatabaseFormat::IpOnly);
+        assert!(db.has_ip_data());
+        assert!(!db.has_string_data());
+    }
+
+    #[test]
+    fn test_lookup_ip_address() {
+        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
+            .open()
+            .unwrap();
+
+        // Test IP lookup
+        let result = db.lookup("1.1.1.1").unwrap();
+        assert!(result.is_some());
+
+        if let Some(QueryResult::Ip { data, prefix_len }) = result {
+            assert!(prefix_len > 0);
+            assert!(prefix_len <= 32);
+
+            // Should have map data
+            match data {
+                DataValue::Map(map) => {
+                    assert!(!map.is_empty());
                 }
+                _ => panic!("Expected map data"),
             }
+        } else {
+            panic!("Expected IP result");
         }
+    }
 
-        // Slow path: Old format without metadata offsets - need to scan
-        // Check for MMDB_PATTERN separator (combined format)
-        let pattern_separator = b"MMDB_PATTERN\x00\x00\x00\x00";
-        let has_pattern_section = data.windows(16).any(|window| window == pattern_separator);
+    #[test]
+    fn test_lookup_ipv6() {
+        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
+            .open()
+            .unwrap();
 
-        if has_pattern_section {
-            Ok(DatabaseFormat::Combined)
-        } else {
-            Ok(DatabaseFormat::IpOnly)
+        let result = db.lookup("2001:4860:4860::8888").unwrap();
+        assert!(result.is_some());
+
+        if let Some(QueryResult::Ip { prefix_len, .. }) = result {
+            assert!(prefix_len > 0);
+            assert!(prefix_len <= 128);
         }
     }
 
-    /// Get database format
-    pub fn format(&self) -> &str {
-        match self.format {
-            DatabaseFormat::IpOnly => "IP database",
-            DatabaseFormat::PatternOnly => "Pattern database",
-            DatabaseFormat::Combined => "Combined IP+Pattern database",
+    #[test]
+    fn test_lookup_not_found() {
+        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
+            .open()
+            .unwrap();
+
+        let result = db.lookup("127.0.0.1").unwrap();
+        assert!(matches!(result, Some(QueryResult::NotFound)));
+    }
+
+    #[test]
+    fn test_version_tag_and_comment() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_version_tag("snapshot-42")
+            .with_comment("test feed");
+        builder.add_entry("1.2.3.4", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert_eq!(db.version_tag().as_deref(), Some("snapshot-42"));
+        assert_eq!(db.comment().as_deref(), Some("test feed"));
+
+        // Unset fields are absent
+        let db = build_db(&["1.2.3.4"]);
+        assert!(db.version_tag().is_none());
+        assert!(db.comment().is_none());
+    }
+
+    #[test]
+    fn test_lookup_or_default() {
+        let mut unknown = HashMap::new();
+        unknown.insert(
+            "category".to_string(),
+            DataValue::String("unknown".to_string()),
+        );
+        let mut builder =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_default_data(unknown.clone());
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        let default = DataValue::from_hash_map(unknown);
+        assert_eq!(db.default_data(), Some(&default));
+
+        // Hits are untouched, and lookup still reports misses
+        assert!(matches!(
+            db.lookup_or_default("10.1.2.3").unwrap(),
+            QueryResult::Ip { prefix_len: 8, .. }
+        ));
+        assert!(matches!(
+            db.lookup_or_default("www.evil.com").unwrap(),
+            QueryResult::Pattern { ref pattern_ids, .. } if !pattern_ids.is_empty()
+        ));
+        assert!(matches!(
+            db.lookup("example.org").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+
+        let result = db.lookup_or_default("8.8.8.8").unwrap();
+        assert!(matches!(result, QueryResult::Ip { prefix_len: 0, .. }));
+        assert_eq!(result.first_data(), Some(&default));
+        let result = db.lookup_or_default("example.org").unwrap();
+        assert_eq!(result.first_data(), Some(&default));
+
+        // The default survives a copy and shows up in the canonical dump
+        let copy = Database::from_bytes(db.to_builder().unwrap().build().unwrap()).unwrap();
+        assert_eq!(
+            copy.lookup_or_default("example.org").unwrap().first_data(),
+            Some(&default)
+        );
+        let rebuilt = Database::from_bytes(crate::delta::rebuild(&db).unwrap()).unwrap();
+        assert_eq!(rebuilt.default_data(), Some(&default));
+        let text = db.canonical_text().unwrap();
+        assert!(text.contains("\ndefault\t{\"category\":\"unknown\"}\n"));
+        assert_eq!(copy.canonical_text().unwrap(), text);
+
+        // Without a default, misses are NotFound
+        let db = build_db(&["*.evil.com"]);
+        assert_ne!(db.canonical_text().unwrap(), text);
+        assert!(db.default_data().is_none());
+        assert!(matches!(
+            db.lookup_or_default("example.org").unwrap(),
+            QueryResult::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_lookup_in_group() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder
+            .add_entry_in_group("*.evil.com", HashMap::new(), "c2")
+            .unwrap();
+        builder
+            .add_entry_in_group("*evil*", HashMap::new(), "phishing")
+            .unwrap();
+        builder
+            .add_entry_in_group("www.evil.com", HashMap::new(), "phishing")
+            .unwrap();
+        // A `_group` field in user data has no bearing on membership
+        let mut spoofed = HashMap::new();
+        spoofed.insert("_group".to_string(), DataValue::Uint16(0));
+        builder.add_entry("*.com", spoofed.clone()).unwrap();
+        assert!(builder
+            .add_entry_in_group("10.0.0.0/8", HashMap::new(), "c2")
+            .is_err());
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert_eq!(db.pattern_groups(), ["c2", "phishing"]);
+
+        let ids = |result: Option<QueryResult>| match result {
+            Some(QueryResult::Pattern { pattern_ids, .. }) => pattern_ids,
+            _ => Vec::new(),
+        };
+        let all = ids(db.lookup("www.evil.com").unwrap());
+        assert_eq!(all.len(), 4);
+
+        // Grouped entries keep their data exactly as added
+        match db.lookup("www.evil.com").unwrap() {
+            Some(QueryResult::Pattern { data, .. }) => {
+                assert_eq!(data[0], Some(DataValue::Map(DataMap::new())));
+                assert_eq!(data[3], Some(DataValue::from_hash_map(spoofed)));
+            }
+            other => panic!("expected pattern match, got {:?}", other),
         }
+
+        // The literal comes first, then the globs in insertion order
+        let c2 = ids(db.lookup_in_group("www.evil.com", "c2").unwrap());
+        assert_eq!(c2, vec![all[1]]);
+        let phishing = ids(db.lookup_in_group("www.evil.com", "phishing").unwrap());
+        assert_eq!(phishing, vec![all[0], all[2]]);
+
+        assert!(matches!(
+            db.lookup_in_group("example.com", "c2").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+        assert!(db
+            .lookup_in_group("www.evil.com", "spam")
+            .unwrap()
+            .is_none());
+
+        // Groups survive a copy through the builder
+        let copy = Database::from_bytes(db.to_builder().unwrap().build().unwrap()).unwrap();
+        assert_eq!(copy.pattern_groups(), ["c2", "phishing"]);
+        assert_eq!(
+            ids(copy.lookup_in_group("www.evil.com", "phishing").unwrap()),
+            phishing
+        );
+        assert_eq!(copy.canonical_text().unwrap(), db.canonical_text().unwrap());
     }
 
-    /// Check if database supports IP lookups
-    pub fn has_ip_data(&self) -> bool {
-        self.ip_header.is_some()
+    #[test]
+    fn test_metadata_accessors() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_database_type("Test-Feed")
+            .with_description("en", "Test feed")
+            .with_description("de", "Testfeed");
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        assert_eq!(db.database_type().as_deref(), Some("Test-Feed"));
+        assert_eq!(db.description("en").as_deref(), Some("Test feed"));
+        assert_eq!(db.description("de").as_deref(), Some("Testfeed"));
+        assert!(db.description("fr").is_none());
+        assert!(db.build_epoch().unwrap() > 0);
+
+        let Some(DataValue::Map(metadata)) = db.metadata() else {
+            panic!("expected metadata map");
+        };
+        assert!(metadata.contains_key("node_count"));
+        assert!(metadata.contains_key("languages"));
+        assert_eq!(
+            metadata.get("ip_version"),
+            Some(&DataValue::Uint16(db.ip_version().unwrap() as u16))
+        );
     }
 
-    /// Check if database supports string lookups (literals or patterns)
-    pub fn has_string_data(&self) -> bool {
-        self.literal_hash.is_some() || self.pattern_matcher.is_some()
+    #[test]
+    fn test_scan_spans_match_extractor() {
+        let db = build_db(&["10.0.0.0/8", "evil.example.com"]);
+        let extractor = Extractor::new().unwrap();
+
+        let text = b"connect evil.example.com from 10.1.2.3 and benign.org";
+        let matches = db.scan(text, &extractor).unwrap();
+        assert_eq!(matches.len(), 2);
+
+        assert_eq!(matches[0].match_type, "Domain");
+        let (start, end) = matches[0].span;
+        assert_eq!(&text[start..end], b"evil.example.com");
+
+        assert_eq!(matches[1].match_type, "IPv4");
+        let (start, end) = matches[1].span;
+        assert_eq!(&text[start..end], b"10.1.2.3");
+        assert!(matches!(matches[1].result, QueryResult::Ip { .. }));
     }
 
-    /// Check if database supports literal (exact string) lookups
-    pub fn has_literal_data(&self) -> bool {
-        self.literal_hash.is_some()
+    #[test]
+    fn test_hunt() {
+        let db = build_db(&["10.0.0.0/8", "evil.example.com", "invoice_*.exe"]);
+        let extractor = Extractor::new().unwrap();
+
+        let text = b"Open \"invoice_0042.exe\" from evil.example.com (10.1.2.3), not benign.org";
+        let hits = db.hunt(text, &extractor).unwrap();
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| {
+                assert_eq!(&text[h.span.0..h.span.1], h.matched_text.as_bytes());
+                (h.match_type, h.matched_text.as_str(), h.result.is_some())
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Token", "invoice_0042.exe", true),
+                ("Domain", "evil.example.com", true),
+                ("IPv4", "10.1.2.3", true),
+                ("Domain", "benign.org", false),
+            ]
+        );
     }
 
-    /// Check if database supports glob pattern lookups
-    pub fn has_glob_data(&self) -> bool {
-        self.pattern_matcher.is_some()
+    #[test]
+    fn test_hunt_skips_over_long_input() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_glob("*.exe", HashMap::new()).unwrap();
+        builder
+            .add_literal("evil.example.com", HashMap::new())
+            .unwrap();
+        let db = Database::from_bytes_builder(builder.build().unwrap())
+            .max_input_len(20)
+            .open()
+            .unwrap();
+        let extractor = Extractor::new().unwrap();
+
+        let long_word = format!("{}.exe", "a".repeat(30));
+        let long_domain = format!("{}.example.com", "b".repeat(30));
+        let text = format!("{} {} run.exe evil.example.com", long_word, long_domain);
+        let hits = db.hunt(text.as_bytes(), &extractor).unwrap();
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| (h.matched_text.as_str(), h.result.is_some()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (long_domain.as_str(), false),
+                ("run.exe", true),
+                ("evil.example.com", true),
+            ]
+        );
     }
 
-    /// Check if database supports pattern lookups (deprecated, use has_literal_data or has_glob_data)
-    #[deprecated(
-        since = "0.5.0",
-        note = "Use has_literal_data or has_glob_data instead"
-    )]
-    pub fn has_pattern_data(&self) -> bool {
-        self.has_string_data()
+    #[test]
+    fn test_enrich_line() {
+        let db = build_db(&["10.0.0.0/8", "*.evil.com"]);
+        let extractor = Extractor::new().unwrap();
+
+        let line = b"www.evil.com from 10.1.2.3 to benign.org";
+        let enriched = db.enrich_line(line, &extractor).unwrap();
+        let mut found: Vec<_> = enriched
+            .iter()
+            .map(|(m, result)| (m.as_str(line), result.is_some()))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("10.1.2.3", true),
+                ("benign.org", false),
+                ("www.evil.com", true)
+            ]
+        );
+        let ip = enriched.iter().find(|(m, _)| m.as_str(line) == "10.1.2.3");
+        assert!(matches!(ip, Some((_, Some(QueryResult::Ip { .. })))));
     }
 
-    /// Get MMDB metadata if available
-    ///
-    /// Returns the full metadata as a DataValue map, or None if this is not
-    /// an MMDB-format database or if metadata cannot be parsed.
-    pub fn metadata(&self) -> Option<DataValue> {
-        if !self.has_ip_data() {
-            return None;
+    #[test]
+    fn test_bytes_round_trip() {
+        let fingerprint: Vec<u8> = (0u8..20).map(|i| i.wrapping_mul(37)).collect();
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for key in ["192.0.2.0/24", "evil.example.com", "*.bad.net"] {
+            let mut data = HashMap::new();
+            data.insert(
+                "fingerprint".to_string(),
+                DataValue::Bytes(fingerprint.clone()),
+            );
+            builder.add_entry(key, data).unwrap();
         }
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        for query in ["192.0.2.7", "evil.example.com", "www.bad.net"] {
+            let data = match db.lookup(query).unwrap().unwrap() {
+                QueryResult::Ip { data, .. } => data,
+                QueryResult::Pattern { mut data, .. } => data.remove(0).unwrap(),
+                QueryResult::NotFound => panic!("{} not found", query),
+            };
+            let DataValue::Map(map) = data else {
+                panic!("expected map");
+            };
+            assert_eq!(map["fingerprint"].bytes(), Some(fingerprint.as_slice()));
+        }
+    }
 
-        use crate::mmdb::MmdbMetadata;
-        let metadata = MmdbMetadata::from_file(self.data.as_slice()).ok()?;
-        metadata.as_value().ok()
+    #[test]
+    fn test_glob_cache_capacity() {
+        // Plain `*.suffix` globs skip the compiled matcher, so use `?`
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for pattern in ["?.evil.com", "?.bad.net", "?.phish.org"] {
+            builder.add_entry(pattern, HashMap::new()).unwrap();
+        }
+        let db = Database::from_bytes_builder(builder.build().unwrap())
+            .no_cache()
+            .glob_cache_capacity(1)
+            .open()
+            .unwrap();
+
+        for query in ["a.evil.com", "b.bad.net", "c.phish.org", "d.evil.com"] {
+            assert!(matches!(
+                db.lookup(query).unwrap(),
+                Some(QueryResult::Pattern { .. })
+            ));
+        }
+        let pm = read_lock(db.pattern_matcher.as_ref().unwrap());
+        assert_eq!(pm.glob_cache_len(), 1);
     }
 
-    /// Get pattern string by ID
-    ///
-    /// Returns the pattern string for a given pattern ID.
-    /// Returns None if the database has no pattern data or pattern ID is invalid.
-    pub fn get_pattern_string(&self, pattern_id: u32) -> Option<String> {
-        let pg_cell = self.pattern_matcher.as_ref()?;
-        let pg = pg_cell.borrow();
-        pg.get_pattern(pattern_id)
+    #[test]
+    fn test_lookup_all_distinct() {
+        let category = |name: &str| {
+            let mut data = HashMap::new();
+            data.insert("category".to_string(), DataValue::String(name.to_string()));
+            data
+        };
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("evil.com", category("malware")).unwrap();
+        builder.add_entry("*evil.com", category("malware")).unwrap();
+        builder.add_entry("*.com", category("generic")).unwrap();
+        builder.add_entry("e*", category("malware")).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        assert_eq!(db.lookup_all("evil.com").unwrap().len(), 4);
+        let distinct = db.lookup_all_distinct("evil.com").unwrap();
+        assert_eq!(
+            distinct,
+            vec![
+                DataValue::from_hash_map(category("malware")),
+                DataValue::from_hash_map(category("generic")),
+            ]
+        );
+        // `e*` matches example.org, so use a query nothing matches
+        assert!(db.lookup_all_distinct("benign.org").unwrap().is_empty());
     }
 
-    /// Get total number of glob patterns
-    ///
-    /// Returns the number of glob patterns in the database.
-    /// Returns 0 if the database has no pattern data.
-    pub fn pattern_count(&self) -> usize {
-        match &self.pattern_matcher {
-            Some(pg_cell) => {
-                let pg = pg_cell.borrow();
-                pg.pattern_count()
-            }
-            None => 0,
+    #[test]
+    fn test_lookup_score() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (key, score) in [
+            ("evil.example.com", 5.0),
+            ("*.example.com", 2.0),
+            ("*.com", 0.5),
+            ("10.0.0.0/8", 7.0),
+        ] {
+            let mut data = HashMap::new();
+            data.insert("score".to_string(), DataValue::Double(score));
+            builder.add_entry(key, data).unwrap();
         }
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        let scorer = |data: &DataValue| match data {
+            DataValue::Map(m) => match m.get("score") {
+                Some(DataValue::Double(s)) => *s,
+                _ => 0.0,
+            },
+            _ => 0.0,
+        };
+
+        assert_eq!(db.lookup_all("evil.example.com").unwrap().len(), 3);
+        assert_eq!(db.lookup_all_distinct("evil.example.com").unwrap().len(), 3);
+        let sum = db
+            .lookup_score("evil.example.com", scorer, ScoreCombine::Sum)
+            .unwrap();
+        assert_eq!(sum, 7.5);
+        let max = db
+            .lookup_score("evil.example.com", scorer, ScoreCombine::Max)
+            .unwrap();
+        assert_eq!(max, 5.0);
+
+        let ip = db
+            .lookup_score("10.1.2.3", scorer, ScoreCombine::Sum)
+            .unwrap();
+        assert_eq!(ip, 7.0);
+        let none = db
+            .lookup_score("192.0.2.1", scorer, ScoreCombine::Max)
+            .unwrap();
+        assert_eq!(none, 0.0);
+
+        // NaN scores are skipped; if nothing else is left, Max is 0.0
+        let nan = db
+            .lookup_score("evil.example.com", |_| f64::NAN, ScoreCombine::Max)
+            .unwrap();
+        assert_eq!(nan, 0.0);
+        let partly_nan = db
+            .lookup_score(
+                "evil.example.com",
+                |data| match scorer(data) {
+                    s if s > 1.0 => f64::NAN,
+                    s => s,
+                },
+                ScoreCombine::Max,
+            )
+            .unwrap();
+        assert_eq!(partly_nan, 0.5);
     }
 
-    /// Get number of glob patterns (alias for pattern_count)
-    ///
-    /// Returns the number of glob patterns in the database.
-    /// Returns 0 if the database has no glob pattern data.
-    pub fn glob_count(&self) -> usize {
-        // Try to get from metadata first (more accurate)
-        if let Some(DataValue::Map(map)) = self.metadata() {
-            if let Some(count) = map.get("glob_entry_count") {
-                if let Some(val) = Self::extract_uint_from_datavalue(count) {
-                    return val as usize;
-                }
-            }
+    #[test]
+    fn test_malformed_ip_is_rejected() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("*3.999", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
+
+        let db = Database::from_bytes(bytes.clone()).unwrap();
+        assert!(matches!(
+            db.lookup("1.2.3.999"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+        assert!(matches!(
+            db.lookup("::ffff:1.2.3.999"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+        // Not IP-shaped, so still matched as strings
+        assert!(matches!(
+            db.lookup("v3.999").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        for query in [
+            "aa:bb:cc:dd:ee:ff",
+            "12:30:45",
+            "...",
+            "1...",
+            "::::",
+            "a::b::c",
+        ] {
+            assert!(
+                matches!(db.lookup(query).unwrap(), Some(QueryResult::NotFound)),
+                "{}",
+                query
+            );
         }
-        // Fallback to pattern_count
-        self.pattern_count()
+        assert!(matches!(
+            db.lookup("1234").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+
+        let lenient = Database::from_bytes_builder(bytes)
+            .lenient_ip_parsing()
+            .open()
+            .unwrap();
+        assert!(matches!(
+            lenient.lookup("1.2.3.999").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
     }
 
-    /// Get number of literal patterns
-    ///
-    /// Returns the number of literal (exact-match) patterns in the database.
-    /// Returns 0 if the database has no literal pattern data.
-    pub fn literal_count(&self) -> usize {
-        // Try to get from metadata first (more accurate)
-        if let Some(DataValue::Map(map)) = self.metadata() {
-            if let Some(count) = map.get("literal_entry_count") {
-                if let Some(val) = Self::extract_uint_from_datavalue(count) {
-                    return val as usize;
-                }
+    #[test]
+    fn test_equal_specificity_globs_first_inserted_wins() {
+        let build = |patterns: &[&str]| {
+            let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+            for pattern in patterns {
+                let mut data = HashMap::new();
+                data.insert(
+                    "pattern".to_string(),
+                    DataValue::String(pattern.to_string()),
+                );
+                builder.add_glob(pattern, data).unwrap();
             }
-        }
-        // Fallback to literal_hash entry count
-        match &self.literal_hash {
-            Some(lh) => lh.entry_count() as usize,
-            None => 0,
+            Database::from_bytes(builder.build().unwrap()).unwrap()
+        };
+        let winner = |db: &Database| match db.lookup("abc").unwrap().unwrap().first_data() {
+            Some(DataValue::Map(m)) => m["pattern"].clone(),
+            other => panic!("unexpected {:?}", other),
+        };
+
+        for _ in 0..10 {
+            let db = build(&["a*c", "a?c"]);
+            assert_eq!(winner(&db), DataValue::String("a*c".to_string()));
+            assert_eq!(db.lookup_all("abc").unwrap().len(), 2);
+
+            let db = build(&["a?c", "a*c"]);
+            assert_eq!(winner(&db), DataValue::String("a?c".to_string()));
         }
     }
 
-    /// Get number of IP address entries
-    ///
-    /// Returns the number of IP entries in the database.
-    /// Returns 0 if the database has no IP data.
-    pub fn ip_count(&self) -> usize {
-        // Try to get from metadata first (most accurate)
-        if let Some(DataValue::Map(map)) = self.metadata() {
-            if let Some(count) = map.get("ip_entry_count") {
-                if let Some(val) = Self::extract_uint_from_datavalue(count) {
-                    return val as usize;
-                }
+    #[test]
+    fn test_explicit_lookup_kinds() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_glob("10.0.*", HashMap::new()).unwrap();
+        builder.add_entry("1234", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        // Same input, different paths
+        assert!(matches!(
+            db.lookup_ip_str("10.0.0.1").unwrap(),
+            Some(QueryResult::Ip { .. })
+        ));
+        assert!(matches!(
+            db.lookup_pattern("10.0.0.1").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+
+        assert!(matches!(
+            db.lookup_pattern("1234").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        assert!(matches!(
+            db.lookup_ip_str("1234"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+        assert!(matches!(
+            db.lookup_ip_str("example.com"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+
+        // A pattern lookup must not leave a cached answer for lookup()
+        assert!(matches!(
+            db.lookup_pattern("10.0.0.999").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        assert!(matches!(
+            db.lookup("10.0.0.999"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+        let (ip, patterns) = db.lookup_both("10.0.1.999").unwrap();
+        assert!(ip.is_none() && patterns.len() == 1);
+        assert!(matches!(
+            db.lookup("10.0.1.999"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+
+        // ...nor read an IP answer cached by lookup()
+        db.clear_cache();
+        assert!(matches!(
+            db.lookup("10.0.0.1").unwrap(),
+            Some(QueryResult::Ip { .. })
+        ));
+        assert!(matches!(
+            db.lookup_pattern("10.0.0.1").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        assert_eq!(db.cache_size(), 2);
+    }
+
+    #[test]
+    fn test_lookup_suffix() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (domain, category) in [("example.com", "site"), ("ads.example.com", "ads")] {
+            let mut data = HashMap::new();
+            data.insert(
+                "category".to_string(),
+                DataValue::String(category.to_string()),
+            );
+            builder.add_suffix(domain, data).unwrap();
+        }
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("*.test", HashMap::new()).unwrap();
+        assert_eq!(builder.stats().suffix_entries, 2);
+
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert!(db.has_suffix_data());
+
+        let m = db
+            .lookup_suffix("Tracker.Ads.Example.com.")
+            .unwrap()
+            .unwrap();
+        assert_eq!(m.suffix, "ads.example.com");
+        match m.data {
+            DataValue::Map(map) => {
+                assert_eq!(
+                    map.get("category"),
+                    Some(&DataValue::String("ads".to_string()))
+                )
             }
+            _ => panic!("expected map"),
         }
-        // No accurate fallback for IP count
-        0
+        assert_eq!(
+            db.lookup_suffix("www.example.com").unwrap().unwrap().suffix,
+            "example.com"
+        );
+        assert!(db.lookup_suffix("notexample.com").unwrap().is_none());
+
+        // Suffix entries don't leak into the other sections
+        assert!(matches!(
+            db.lookup("www.example.com").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+        assert!(db
+            .lookup("10.1.2.3")
+            .unwrap()
+            .unwrap()
+            .first_data()
+            .is_some());
+
+        let plain = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let plain_bytes = plain.build().unwrap();
+        let plain_len = plain_bytes.len();
+        let db = Database::from_bytes(plain_bytes).unwrap();
+        assert!(!db.has_suffix_data());
+        assert!(db.lookup_suffix("example.com").unwrap().is_none());
+
+        // A rejected domain leaves no orphaned data behind
+        let mut bad = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let mut data = HashMap::new();
+        data.insert("category".to_string(), DataValue::String("x".repeat(64)));
+        assert!(bad.add_suffix("a..example.com", data).is_err());
+        assert_eq!(bad.build().unwrap().len(), plain_len);
     }
 
-    /// Helper to extract unsigned integer from DataValue
-    fn extract_uint_from_datavalue(value: &DataValue) -> Option<u64> {
-        match value {
-            DataValue::Uint16(v) => Some(*v as u64),
-            DataValue::Uint32(v) => Some(*v as u64),
-            DataValue::Uint64(v) => Some(*v),
-            _ => None,
+    #[test]
+    fn test_lookup_numeric() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (asn, org) in [(15169u64, "Google"), (13335, "Cloudflare")] {
+            let mut data = HashMap::new();
+            data.insert("org".to_string(), DataValue::String(org.to_string()));
+            builder.add_numeric_entry(asn, data).unwrap();
         }
+        builder.add_suffix("example.com", HashMap::new()).unwrap();
+        builder.add_entry("13335", HashMap::new()).unwrap();
+        assert_eq!(builder.stats().numeric_entries, 2);
+
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert!(db.has_numeric_data());
+
+        match db.lookup_numeric(13335).unwrap() {
+            Some(DataValue::Map(map)) => assert_eq!(
+                map.get("org"),
+                Some(&DataValue::String("Cloudflare".to_string()))
+            ),
+            other => panic!("expected map, got {:?}", other),
+        }
+        assert!(db.lookup_numeric(15169).unwrap().is_some());
+        assert!(db.lookup_numeric(64512).unwrap().is_none());
+        assert!(db.lookup_suffix("www.example.com").unwrap().is_some());
+
+        // Numeric keys and string keys are separate
+        match db.lookup("13335").unwrap() {
+            Some(QueryResult::Pattern { data, .. }) => {
+                assert_eq!(data[0], Some(DataValue::Map(DataMap::new())))
+            }
+            other => panic!("expected literal match, got {:?}", other),
+        }
+
+        // Numeric entries survive a round trip through the builder
+        let copy = Database::from_bytes(db.to_builder().unwrap().build().unwrap()).unwrap();
+        assert_eq!(
+            copy.lookup_numeric(15169).unwrap(),
+            db.lookup_numeric(15169).unwrap()
+        );
+        assert!(copy.canonical_text().unwrap().contains("numeric\t13335\t"));
+
+        let plain = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let db = Database::from_bytes(plain.build().unwrap()).unwrap();
+        assert!(!db.has_numeric_data());
+        assert!(db.lookup_numeric(15169).unwrap().is_none());
     }
 
-    /// Find the pattern section using fast metadata lookup with fallback to scanning
-    /// Returns the offset to the start of pattern data (after MMDB_PATTERN marker)
-    fn find_pattern_section_fast(data: &[u8]) -> Option<usize> {
-        // Fast path: Try to read offset from metadata
-        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
-            if let Ok(DataValue::Map(map)) = metadata.as_value() {
-                if let Some(DataValue::Uint32(offset)) = map.get("pattern_section_offset") {
-                    let offset_val = *offset as usize;
-                    // 0 means no pattern section (fast negative result)
-                    if offset_val == 0 {
-                        return None;
-                    }
-                    return Some(offset_val);
+    #[test]
+    fn test_lookup_both() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_literal("10.0.0.1", HashMap::new()).unwrap();
+        builder.add_glob("10.*", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        // Auto-detection only takes the IP path
+        assert!(matches!(
+            db.lookup("10.0.0.1").unwrap(),
+            Some(QueryResult::Ip { prefix_len: 8, .. })
+        ));
+
+        let (ip, patterns) = db.lookup_both("10.0.0.1").unwrap();
+        assert!(matches!(ip, Some(QueryResult::Ip { prefix_len: 8, .. })));
+        assert_eq!(patterns.len(), 2);
+        for result in &patterns {
+            match result {
+                QueryResult::Pattern { pattern_ids, data } => {
+                    assert_eq!(pattern_ids.len(), 1);
+                    assert_eq!(data.len(), 1);
                 }
+                _ => panic!("expected pattern result"),
             }
         }
 
-        // Slow path: Scan for separator (backwards compatibility)
-        eprintln!("Warning: Database lacks section offset metadata, falling back to full file scan (slower load time)");
-        Self::find_pattern_section_slow(data)
+        let (ip, patterns) = db.lookup_both("11.0.0.1").unwrap();
+        assert!(ip.is_none());
+        assert!(patterns.is_empty());
+
+        let (ip, patterns) = db.lookup_both("10.example").unwrap();
+        assert!(ip.is_none());
+        assert_eq!(patterns.len(), 1);
     }
 
-    /// Find the pattern section by scanning (slow, for backwards compatibility)
-    /// Returns the offset to the start of pattern data (after MMDB_PATTERN marker)
-    fn find_pattern_section_slow(data: &[u8]) -> Option<usize> {
-        let separator = b"MMDB_PATTERN\x00\x00\x00\x00";
+    #[test]
+    fn test_count_matches() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder
+            .add_literal("evil.example.com", HashMap::new())
+            .unwrap();
+        builder.add_glob("*.example.com", HashMap::new()).unwrap();
+        builder.add_glob("evil.*", HashMap::new()).unwrap();
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        assert_eq!(db.count_matches("evil.example.com"), 3);
+        assert_eq!(
+            db.count_matches("evil.example.com"),
+            db.lookup_all("evil.example.com").unwrap().len()
+        );
+        assert_eq!(db.count_matches_up_to("evil.example.com", 2), 2);
+        assert_eq!(db.count_matches("www.example.com"), 1);
+        assert_eq!(db.count_matches("10.0.0.1"), 0);
+    }
 
-        // Search for the separator
-        for i in 0..data.len().saturating_sub(16) {
-            if &data[i..i + 16] == separator {
-                // Pattern section starts after the 16-byte separator
-                return Some(i + 16);
-            }
+    #[test]
+    fn test_to_builder_round_trip() {
+        let entry = |source: &str| {
+            let mut data = HashMap::new();
+            data.insert("source".to_string(), DataValue::String(source.to_string()));
+            data
+        };
+        let mut builder = MmdbBuilder::new(MatchMode::CaseInsensitive).with_literal_hash_seed(42);
+        builder.add_entry("10.0.0.0/8", entry("net")).unwrap();
+        builder.add_entry("2001:db8::/32", entry("v6")).unwrap();
+        builder
+            .add_literal("Exact.example.com", entry("literal"))
+            .unwrap();
+        builder.add_glob("*.example.com", entry("glob-a")).unwrap();
+        builder.add_glob("*.com", entry("glob-b")).unwrap();
+        builder.add_suffix("example.org", entry("suffix")).unwrap();
+        let original = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        let mut builder = original.to_builder().unwrap();
+        builder.add_entry("192.168.0.0/16", entry("added")).unwrap();
+        let stats = builder.stats();
+        assert_eq!(stats.ip_entries, 3);
+        assert_eq!(stats.literal_entries, 1);
+        assert_eq!(stats.glob_entries, 2);
+        assert_eq!(stats.suffix_entries, 1);
+        let copy = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        for query in [
+            "10.1.2.3",
+            "2001:db8::1",
+            "exact.example.com",
+            "www.example.com",
+            "other.com",
+            "nothing.net",
+        ] {
+            assert_eq!(
+                copy.lookup_all(query).unwrap(),
+                original.lookup_all(query).unwrap(),
+                "{}",
+                query
+            );
         }
-        None
+        assert_eq!(
+            copy.lookup_suffix("a.example.org").unwrap(),
+            original.lookup_suffix("a.example.org").unwrap()
+        );
+        assert_eq!(
+            copy.lookup_all("192.168.1.1").unwrap(),
+            vec![DataValue::from_hash_map(entry("added"))]
+        );
     }
 
-    /// Find the literal section using fast metadata lookup with fallback to scanning
-    /// Returns the offset to the start of MMDB_LITERAL marker
-    fn find_literal_section_fast(data: &[u8]) -> Option<usize> {
-        // Fast path: Try to read offset from metadata
-        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
-            if let Ok(DataValue::Map(map)) = metadata.as_value() {
-                if let Some(DataValue::Uint32(offset)) = map.get("literal_section_offset") {
-                    let offset_val = *offset as usize;
-                    // 0 means no literal section (fast negative result)
-                    if offset_val == 0 {
-                        return None;
-                    }
-                    // Metadata stores offset to start of data, but we need offset to marker
-                    // So subtract 16 bytes for the "MMDB_LITERAL" marker
-                    return Some(offset_val - 16);
-                }
-            }
+    #[test]
+    fn test_to_builder_keeps_field_order() {
+        // Public builder methods sort keys, so stage decoded-style maps directly
+        let record: DataMap = ["zeta", "alpha", "mid"]
+            .into_iter()
+            .map(|key| (key.to_string(), DataValue::Bool(true)))
+            .collect();
+        let mut builder =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_default_map(record.clone());
+        for (kind, key) in [
+            (EntryKind::Network, "10.0.0.0/8"),
+            (EntryKind::Literal, "evil.com"),
+            (EntryKind::Glob, "*.evil.org"),
+            (EntryKind::Suffix, "example.net"),
+            (EntryKind::Numeric, "64512"),
+        ] {
+            builder
+                .add_decoded(kind, key, record.clone(), None)
+                .unwrap();
         }
+        let original = Database::from_bytes(builder.build().unwrap()).unwrap();
+        let copy = Database::from_bytes(original.to_builder().unwrap().build().unwrap()).unwrap();
 
-        // Slow path: Scan for separator (backwards compatibility)
-        if data.len() > 1024 * 1024 {
-            // Only warn for files > 1MB
-            eprintln!("Warning: Database lacks section offset metadata, falling back to full file scan (slower load time)");
+        let keys = |value: &DataValue| -> Vec<String> {
+            value.as_map().unwrap().keys().cloned().collect()
+        };
+        let expected = vec!["zeta", "alpha", "mid"];
+        for query in ["10.1.2.3", "evil.com", "www.evil.org"] {
+            let result = copy.lookup(query).unwrap().unwrap();
+            assert_eq!(keys(result.first_data().unwrap()), expected, "{}", query);
         }
-        Self::find_literal_section_slow(data)
+        let suffix = copy.lookup_suffix("a.example.net").unwrap().unwrap();
+        assert_eq!(keys(&suffix.data), expected);
+        let numeric = copy.lookup_numeric(64512).unwrap().unwrap();
+        assert_eq!(keys(&numeric), expected);
+        let default = copy.lookup_or_default("nothing.example").unwrap();
+        assert_eq!(keys(default.first_data().unwrap()), expected);
     }
 
-    /// Find the literal hash section by scanning (slow, for backwards compatibility)
-    /// Returns the offset to the start of MMDB_LITERAL marker
-    fn find_literal_section_slow(data: &[u8]) -> Option<usize> {
-        let separator = b"MMDB_LITERAL\x00\x00\x00\x00";
+    #[test]
+    fn test_max_input_len() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_glob("*.evil.com", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
+
+        let db = Database::from_bytes(bytes.clone()).unwrap();
+        let long = format!("{}.evil.com", "a".repeat(101));
+        assert_eq!(long.len(), 110);
+        assert!(db.lookup(&long).unwrap().is_some());
+
+        let db = Database::from_bytes_builder(bytes)
+            .max_input_len(32)
+            .open()
+            .unwrap();
+        assert!(db.lookup("x.evil.com").unwrap().is_some());
+        assert!(matches!(
+            db.lookup(&long),
+            Err(DatabaseError::InputTooLong(110))
+        ));
+        assert_eq!(db.count_matches(&long), 0);
+        assert_eq!(db.count_matches("x.evil.com"), 1);
+    }
 
-        // Search for the separator
-        (0..data.len().saturating_sub(16)).find(|&i| &data[i..i + 16] == separator)
+    #[test]
+    fn test_canonical_text() {
+        let mut data = HashMap::new();
+        data.insert("score".to_string(), DataValue::Uint32(7));
+        data.insert("name".to_string(), DataValue::String("x\ty".to_string()));
+
+        let mut a = MmdbBuilder::new(MatchMode::CaseSensitive);
+        a.add_ip("10.0.0.0/8", data.clone()).unwrap();
+        a.add_literal("zeta.com", HashMap::new()).unwrap();
+        a.add_literal("alpha.com", data.clone()).unwrap();
+        a.add_glob("*.evil.com", HashMap::new()).unwrap();
+        a.add_suffix("example.org", HashMap::new()).unwrap();
+
+        // Same content, different insertion order and hash seed
+        let mut b = MmdbBuilder::new(MatchMode::CaseSensitive).with_literal_hash_seed(42);
+        b.add_suffix("example.org", HashMap::new()).unwrap();
+        b.add_glob("*.evil.com", HashMap::new()).unwrap();
+        b.add_literal("alpha.com", data.clone()).unwrap();
+        b.add_literal("zeta.com", HashMap::new()).unwrap();
+        b.add_ip("10.0.0.0/8", data).unwrap();
+
+        let text_a = Database::from_bytes(a.build().unwrap())
+            .unwrap()
+            .canonical_text()
+            .unwrap();
+        let text_b = Database::from_bytes(b.build().unwrap())
+            .unwrap()
+            .canonical_text()
+            .unwrap();
+        assert_eq!(text_a, text_b);
+        assert_eq!(
+            text_a,
+            "mode\tcase-sensitive\n\
+             network\t10.0.0.0/8\t{\"name\":\"x\\ty\",\"score\":7}\n\
+             literal\t\"alpha.com\"\t{\"name\":\"x\\ty\",\"score\":7}\n\
+             literal\t\"zeta.com\"\t{}\n\
+             glob\t\"*.evil.com\"\t{}\n\
+             suffix\t\"example.org\"\t{}\n"
+        );
     }
 
-    /// Load pattern section from data at given offset (for pattern-only databases)
-    /// The format at offset is: PARAGLOB magic + data
-    /// Uses zero-copy from_mmap for O(1) loading
-    fn load_pattern_section(data: &'static [u8], offset: usize) -> Result<Paraglob, String> {
-        if offset >= data.len() {
-            return Err("Pattern section offset out of bounds".to_string());
-        }
+    #[test]
+    fn test_export_ndjson() {
+        let mut data = HashMap::new();
+        data.insert(
+            "threat".to_string(),
+            DataValue::String("botnet".to_string()),
+        );
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_ip("1.2.3.0/24", data.clone()).unwrap();
+        builder.add_ip("2001:db8::/32", HashMap::new()).unwrap();
+        builder.add_literal("evil.com", data).unwrap();
+        builder.add_glob("*.evil.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        let mut out = Vec::new();
+        db.export_ndjson(&mut out).unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({"type": "network", "key": "1.2.3.0/24", "data": {"threat": "botnet"}})
+        );
+        assert_eq!(lines[1]["key"], "2001:db8::/32");
+        assert_eq!(
+            lines[2],
+            serde_json::json!({"type": "literal", "key": "evil.com", "data": {"threat": "botnet"}})
+        );
+        assert_eq!(lines[3]["type"], "glob");
+        assert_eq!(lines[3]["key"], "*.evil.com");
+        assert_eq!(lines[3]["data"], serde_json::json!({}));
+    }
 
-        // Try to read match mode from metadata
-        let match_mode = Self::read_match_mode_from_metadata(data);
+    #[test]
+    fn test_matched_via() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_ip("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_literal("evil.com", HashMap::new()).unwrap();
+        builder.add_glob("*.com", HashMap::new()).unwrap();
+        builder.add_glob("*evil*", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        assert_eq!(
+            db.matched_via("10.1.2.3").unwrap(),
+            [MatchMechanism::IpTree]
+        );
+        assert_eq!(
+            db.matched_via("evil.com").unwrap(),
+            [
+                MatchMechanism::LiteralHash,
+                MatchMechanism::GlobAutomaton,
+                MatchMechanism::GlobAutomaton
+            ]
+        );
+        assert_eq!(
+            db.matched_via("good.com").unwrap(),
+            [MatchMechanism::GlobAutomaton]
+        );
+        assert!(db.matched_via("good.org").unwrap().is_empty());
+        assert!(db.matched_via("192.168.0.1").unwrap().is_empty());
+
+        // Diagnostic lookups don't count as queries
+        assert_eq!(db.stats().total_queries, 0);
+    }
 
-        // For pattern-only databases, data starts with PARAGLOB magic
-        if offset == 0 && data.len() >= 8 && &data[0..8] == b"PARAGLOB" {
-            // Standard .pgb format - load with zero-copy
-            // SAFETY: data is 'static lifetime from mmap, valid for entire Database lifetime
-            let result = unsafe { Paraglob::from_mmap(data, match_mode) };
-            return result.map_err(|e| format!("Failed to parse pattern-only database: {}", e));
-        }
+    #[test]
+    fn test_open_timed() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_literal("evil.com", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
 
-        Err("Invalid pattern-only database format".to_string())
+        let (db, timing) = Database::from_bytes_builder(bytes.clone())
+            .open_timed()
+            .unwrap();
+        assert_eq!(timing.mmap, Duration::ZERO);
+        assert_eq!(timing.prefault, Duration::ZERO);
+        assert_eq!(timing.total(), timing.parse);
+        assert!(db.lookup("evil.com").unwrap().is_some());
+
+        let (db, _) = Database::from_bytes_builder(bytes)
+            .prefault()
+            .open_timed()
+            .unwrap();
+        assert!(matches!(
+            db.lookup("evil.com").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
     }
 
-    /// Load combined pattern section from data at given offset
-    /// The format at offset is: `[total_size][paraglob_size][PARAGLOB data][pattern_count][data_offsets...]`
-    /// Returns (Paraglob matcher, lazy PatternDataMappings)
-    /// Uses zero-copy and deferred parsing for O(1) load time
-    fn load_combined_pattern_section(
-        data: &'static [u8],
-        offset: usize,
-    ) -> Result<(Paraglob, PatternDataMappings), String> {
-        if offset >= data.len() {
-            return Err("Pattern section offset out of bounds".to_string());
+    #[test]
+    fn test_verify_all_pattern_data() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (i, key) in ["*.evil.com", "bad-*.net", "evil.org"].iter().enumerate() {
+            let mut data = HashMap::new();
+            data.insert("id".to_string(), DataValue::Uint32(i as u32));
+            builder.add_entry(key, data).unwrap();
         }
+        let mut bytes = builder.build().unwrap();
+        let db = Database::from_bytes(bytes.clone()).unwrap();
+        assert!(db.verify_all_pattern_data().is_ok());
+
+        // Point the second glob's mapping past the end of the data section
+        let mappings_offset = db.pattern_data_mappings.as_ref().unwrap().mappings_offset;
+        bytes[mappings_offset + 4..mappings_offset + 8]
+            .copy_from_slice(&0x00FF_FFFFu32.to_le_bytes());
+        let db = Database::from_bytes(bytes).unwrap();
+        let failures = db.verify_all_pattern_data().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+        assert!(failures[0].1.contains("bad-*.net"));
+    }
 
-        // Try to read match mode from metadata
-        let match_mode = Self::read_match_mode_from_metadata(data);
+    #[test]
+    fn test_classify_key() {
+        let ip = |s: &str| KeyKind::Ip(s.parse().unwrap());
+        let cases = [
+            ("10.0.0.1", ip("10.0.0.1")),
+            ("::", ip("::")),
+            ("::ffff:1.2.3.4", ip("::ffff:1.2.3.4")),
+            ("2001:db8::1", ip("2001:db8::1")),
+            ("1.2.3.999", KeyKind::MalformedIp),
+            ("01.2.3.4", KeyKind::MalformedIp),
+            ("1::2:3:4:5:6:7:8", KeyKind::MalformedIp),
+            ("::ffff:1.2.3.999", KeyKind::MalformedIp),
+            ("1:2:3:4:5:6:1.2.3.999", KeyKind::MalformedIp),
+            ("1.2.3.", KeyKind::String),
+            ("...", KeyKind::String),
+            ("1...", KeyKind::String),
+            ("1.2.3.4567", KeyKind::String),
+            ("::::", KeyKind::String),
+            (":::", KeyKind::String),
+            ("a::b::c", KeyKind::String),
+            ("2001:db8::1::1", KeyKind::String),
+            (":1:2:3:4:5:6:7", KeyKind::String),
+            ("1::2:3:4:5:6:7:8:9", KeyKind::String),
+            ("1.2.3.4.5", KeyKind::String),
+            ("1.2.3.4/24", KeyKind::String),
+            ("[2001:db8::1]", KeyKind::String),
+            ("fe80::1%eth0", KeyKind::String),
+            (" 10.0.0.1", KeyKind::String),
+            ("::g", KeyKind::String),
+            ("aa:bb:cc:dd:ee:ff", KeyKind::String),
+            ("12:30:45", KeyKind::String),
+            ("1:2:3:4:5:6:7:8:9", KeyKind::String),
+            ("1:2:3:4:5:6:7:g", KeyKind::String),
+            ("1:2:3:4:5:6:7:12345", KeyKind::String),
+            ("1234", KeyKind::String),
+            ("", KeyKind::String),
+            ("evil.com", KeyKind::String),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(classify_key(query), expected, "{:?}", query);
+        }
+    }
 
-        // Read section header
-        if offset + 8 > data.len() {
-            return Err("Pattern section header truncated".to_string());
+    #[test]
+    fn test_lookup_ref() {
+        let record = |name: &str| {
+            let mut data = HashMap::new();
+            data.insert("name".to_string(), DataValue::String(name.to_string()));
+            data.insert("score".to_string(), DataValue::Uint32(7));
+            data
+        };
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", record("net")).unwrap();
+        builder.add_entry("evil.com", record("literal")).unwrap();
+        builder.add_entry("*.com", record("glob")).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        for query in ["10.1.2.3", "evil.com", "good.com", "nothing.org"] {
+            let owned = db.lookup(query).unwrap().unwrap();
+            let borrowed = db.lookup_ref(query).unwrap().unwrap();
+            assert_eq!(
+                borrowed.first_data().map(|d| d.to_value().unwrap()),
+                owned.first_data().cloned(),
+                "{query}"
+            );
         }
 
-        // Read sizes (little-endian u32)
-        let _total_size = u32::from_le_bytes([
-            data[offset],
-            data[offset + 1],
-            data[offset + 2],
-            data[offset + 3],
-        ]);
-        let paraglob_size = u32::from_le_bytes([
-            data[offset + 4],
-            data[offset + 5],
-            data[offset + 6],
-            data[offset + 7],
-        ]) as usize;
+        let Some(QueryResultRef::Ip { data, prefix_len }) = db.lookup_ref("10.1.2.3").unwrap()
+        else {
+            panic!("expected IP result");
+        };
+        assert_eq!(prefix_len, 8);
+        let name = data.as_map().unwrap().get("name").unwrap().unwrap();
+        assert_eq!(name.as_str(), Some("net"));
+
+        let Some(QueryResultRef::Pattern { pattern_ids, data }) =
+            db.lookup_ref("evil.com").unwrap()
+        else {
+            panic!("expected pattern result");
+        };
+        assert_eq!(pattern_ids.len(), 2);
+        let names: Vec<&str> = data
+            .iter()
+            .map(|d| d.unwrap().as_map().unwrap().get("name").unwrap().unwrap())
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["literal", "glob"]);
+
+        assert!(matches!(
+            db.lookup_ref("1.2.3.999"),
+            Err(DatabaseError::InvalidIp(_))
+        ));
+    }
 
-        // Paraglob data starts at offset + 8
-        let paraglob_start = offset + 8;
-        let paraglob_end = paraglob_start + paraglob_size;
+    #[test]
+    fn test_replace_while_open() {
+        let build = |label: &str| {
+            let mut data = HashMap::new();
+            data.insert("label".to_string(), DataValue::String(label.to_string()));
+            let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+            builder.add_entry("10.0.0.0/8", data).unwrap();
+            builder.build().unwrap()
+        };
+        let label = |db: &Database| match db.lookup("10.1.1.1").unwrap().unwrap().first_data() {
+            Some(DataValue::Map(m)) => m.get("label").cloned(),
+            _ => None,
+        };
 
-        if paraglob_end > data.len() {
-            return Err(format!(
-                "Paraglob section extends beyond file (start={}, size={}, file_len={})",
-                paraglob_start,
-                paraglob_size,
-                data.len()
-            ));
-        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.mxy");
+        let staged = dir.path().join("db.mxy.tmp");
+        std::fs::write(&path, build("old")).unwrap();
 
-        // Extract and load paraglob data with zero-copy
-        let paraglob_data = &data[paraglob_start..paraglob_end];
-        // SAFETY: data is 'static lifetime from mmap, valid for entire Database lifetime
-        let paraglob = unsafe { Paraglob::from_mmap(paraglob_data, match_mode) };
-        let paraglob = paraglob.map_err(|e| format!("Failed to parse paraglob section: {}", e))?;
+        let mapped = Database::from(&path).no_cache().open().unwrap();
+        let copied = Database::from(&path)
+            .no_cache()
+            .private_copy()
+            .open()
+            .unwrap();
 
-        // Store mapping metadata WITHOUT parsing all offsets (O(1) instead of O(n))
-        let mappings_start = paraglob_end;
-        if mappings_start + 4 > data.len() {
-            return Err("Pattern mappings section truncated".to_string());
+        // A private copy never blocks the rename; a plain mapping only on Windows
+        let mapped = if cfg!(windows) { None } else { Some(mapped) };
+        std::fs::write(&staged, build("new")).unwrap();
+        std::fs::rename(&staged, &path).unwrap();
+
+        let old = Some(DataValue::String("old".to_string()));
+        assert_eq!(label(&copied), old);
+        if let Some(mapped) = mapped {
+            assert_eq!(label(&mapped), old);
         }
+        let reopened = Database::from(&path).open().unwrap();
+        assert_eq!(label(&reopened), Some(DataValue::String("new".to_string())));
+    }
 
-        let pattern_count = u32::from_le_bytes([
-            data[mappings_start],
-            data[mappings_start + 1],
-            data[mappings_start + 2],
-            data[mappings_start + 3],
-        ]) as usize;
+    #[test]
+    fn test_read_into_memory() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.mxy");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let db = Database::from(&path)
+            .no_cache()
+            .read_into_memory()
+            .open()
+            .unwrap();
 
-        let offsets_start = mappings_start + 4;
+        // Truncating the file would fault a mapping; the buffered copy is unaffected
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(0)
+            .unwrap();
+        assert!(db.lookup("10.1.2.3").unwrap().is_some());
+        assert!(db.lookup("www.evil.com").unwrap().is_some());
+    }
 
-        // Validate the mapping section exists, but don't parse it
-        let total_mapping_bytes = pattern_count * 4;
-        if offsets_start + total_mapping_bytes > data.len() {
-            return Err(format!(
-                "Pattern mappings section out of bounds (need {} bytes)",
-                total_mapping_bytes
-            ));
-        }
+    #[test]
+    fn test_reencode_old_pattern_section() {
+        use crate::offset_format::VERSION_V3;
+
+        let mut data = HashMap::new();
+        data.insert("score".to_string(), DataValue::Uint32(7));
+        let mut builder = MmdbBuilder::new(MatchMode::CaseInsensitive);
+        builder.add_ip("10.0.0.0/8", data.clone()).unwrap();
+        builder.add_glob("*.evil.com", data.clone()).unwrap();
+        builder.add_glob("bad-*.net", HashMap::new()).unwrap();
+        builder.add_literal("exact.com", data.clone()).unwrap();
+        builder.add_suffix("example.org", data).unwrap();
+        let current = builder.build().unwrap();
+        let expected = Database::from_bytes(current.clone())
+            .unwrap()
+            .canonical_text()
+            .unwrap();
 
-        let mappings = PatternDataMappings {
-            mappings_offset: offsets_start,
-            pattern_count,
-        };
+        // Mark the pattern section as v3 and pad it, as an older, larger
+        // automaton would be; everything after it moves
+        let mut old = current.clone();
+        let offset = Database::find_pattern_section_fast(&old).unwrap();
+        let paraglob_size = u32::from_le_bytes(old[offset + 4..offset + 8].try_into().unwrap());
+        let total_size = u32::from_le_bytes(old[offset..offset + 4].try_into().unwrap());
+        old[offset + 16..offset + 20].copy_from_slice(&VERSION_V3.to_le_bytes());
+        let pad_at = offset + 8 + paraglob_size as usize;
+        old.splice(pad_at..pad_at, [0u8; 64]);
+        old[offset..offset + 4].copy_from_slice(&(total_size + 64).to_le_bytes());
+        old[offset + 4..offset + 8].copy_from_slice(&(paraglob_size + 64).to_le_bytes());
+        let old_end = offset + total_size as usize;
+        Database::shift_section_offsets(&mut old, old_end, old_end + 64).unwrap();
+        assert!(Database::from_bytes(old.clone()).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("old.mxy");
+        std::fs::write(&path, &old).unwrap();
+
+        let migrated = Database::reencode(&path, VERSION).unwrap();
+        assert_eq!(migrated, current);
+        let db = Database::from_bytes(migrated).unwrap();
+        assert_eq!(db.canonical_text().unwrap(), expected);
+        assert!(db.lookup("WWW.EVIL.COM").unwrap().is_some());
+
+        // Current files pass through; other target versions are refused
+        std::fs::write(&path, &current).unwrap();
+        assert_eq!(Database::reencode(&path, VERSION).unwrap(), current);
+        assert!(Database::reencode(&path, VERSION_V3).is_err());
+    }
 
-        Ok((paraglob, mappings))
+    #[test]
+    fn test_set_pattern_enabled() {
+        let db = build_db(&["*.evil.com", "*", "www.evil.com"]);
+
+        let ids = |db: &Database| match db.lookup("www.evil.com").unwrap() {
+            Some(QueryResult::Pattern { pattern_ids, .. }) => pattern_ids,
+            _ => Vec::new(),
+        };
+        // Literal first, then globs 0 and 1
+        assert_eq!(ids(&db).len(), 3);
+
+        // Muting the anchored glob and the pure wildcard leaves the literal
+        db.set_pattern_enabled(0, false);
+        db.set_pattern_enabled(1, false);
+        assert!(!db.is_pattern_enabled(0));
+        assert_eq!(ids(&db).len(), 1);
+        assert_eq!(db.count_matches("www.evil.com"), 1);
+
+        db.set_pattern_enabled(1, true);
+        assert_eq!(ids(&db).len(), 2);
+        assert!(db.is_pattern_enabled(1));
+
+        // Unknown IDs are ignored
+        db.set_pattern_enabled(1000, false);
+        assert!(db.is_pattern_enabled(1000));
     }
 
-    /// Read match mode from database metadata
-    /// Returns CaseSensitive as default if not found or on error
-    fn read_match_mode_from_metadata(data: &[u8]) -> crate::glob::MatchMode {
-        use crate::glob::MatchMode;
+    #[test]
+    fn test_networks_iter() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (i, cidr) in ["2001:db8::/32", "10.0.0.0/8", "10.1.0.0/16", "192.168.1.1"]
+            .iter()
+            .enumerate()
+        {
+            let mut data = HashMap::new();
+            data.insert("id".to_string(), DataValue::String(i.to_string()));
+            builder.add_entry(cidr, data).unwrap();
+        }
+        builder.add_entry("*.example.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
 
-        // Try to read metadata
-        if let Ok(metadata) = crate::mmdb::MmdbMetadata::from_file(data) {
-            if let Ok(DataValue::Map(map)) = metadata.as_value() {
-                if let Some(DataValue::Uint16(mode_val)) = map.get("match_mode") {
-                    return match *mode_val {
-                        1 => MatchMode::CaseInsensitive,
-                        _ => MatchMode::CaseSensitive, // 0 or unknown = CaseSensitive (default)
-                    };
-                }
-            }
+        let entries: Vec<_> = db.networks().collect::<Result<_, _>>().unwrap();
+        let header = db.ip_header.as_ref().unwrap();
+        let expected = SearchTree::new(db.data.as_slice(), header)
+            .networks()
+            .unwrap();
+        assert_eq!(entries.len(), expected.len());
+        for ((addr, len, result), (e_addr, e_len, _)) in entries.iter().zip(&expected) {
+            assert_eq!((addr, len), (e_addr, e_len));
+            assert_eq!(result.matched_prefix(), Some(*len));
         }
 
-        // Default to case-sensitive for backward compatibility with old databases
-        MatchMode::CaseSensitive
+        // 10.1.0.0/16 splits 10.0.0.0/8; the sibling halves keep the /8's data
+        let id_of = |net: &str| {
+            entries
+                .iter()
+                .find(|(addr, len, _)| format!("{}/{}", addr, len) == net)
+                .and_then(|(_, _, result)| match result.first_data()? {
+                    DataValue::Map(map) => map.get("id").cloned(),
+                    _ => None,
+                })
+        };
+        let id = |s: &str| Some(DataValue::String(s.to_string()));
+        assert_eq!(id_of("10.0.0.0/16"), id("1"));
+        assert_eq!(id_of("10.1.0.0/16"), id("2"));
+        assert_eq!(id_of("192.168.1.1/32"), id("3"));
+        assert_eq!(id_of("2001:db8::/32"), id("0"));
+        assert!(entries.iter().any(|(addr, _, _)| addr.is_ipv4()));
+
+        let mut empty = MmdbBuilder::new(MatchMode::CaseSensitive);
+        empty.add_entry("*.example.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(empty.build().unwrap()).unwrap();
+        assert_eq!(db.networks().count(), 0);
     }
-}
 
-/// Database error type
-#[derive(Debug)]
-pub enum DatabaseError {
-    /// I/O error
-    Io(String),
-    /// Format error
-    Format(MmdbError),
-    /// Unsupported operation
-    Unsupported(String),
-}
+    #[test]
+    fn test_covering_networks() {
+        let net = |s: &str, len: u8| (s.parse::<IpAddr>().unwrap(), len);
+
+        let db = build_db(&["10.0.0.0/8", "10.1.0.0/16"]);
+
+        // One network per level down to the /16 holding the data
+        let path = db.covering_networks("10.1.2.3".parse().unwrap()).unwrap();
+        assert_eq!(path.len(), 17);
+        assert_eq!(path[0], net("0.0.0.0", 0));
+        assert_eq!(path[1], net("0.0.0.0", 1));
+        assert_eq!(path[8], net("10.0.0.0", 8));
+        assert_eq!(path[16], net("10.1.0.0", 16));
+        assert!(path.windows(2).all(|w| w[0].1 + 1 == w[1].1));
+
+        // The /8 is split around the /16, so other addresses stop at /9
+        let path = db.covering_networks("10.200.0.1".parse().unwrap()).unwrap();
+        assert_eq!(path.last(), Some(&net("10.128.0.0", 9)));
+
+        // Walks that end on an empty record still report the path
+        let path = db
+            .covering_networks("192.168.1.1".parse().unwrap())
+            .unwrap();
+        assert_eq!(path, vec![net("0.0.0.0", 0), net("128.0.0.0", 1)]);
+        assert!(db
+            .covering_networks("2001:db8::1".parse().unwrap())
+            .unwrap()
+            .is_empty());
+
+        // IPv4 in an IPv6 tree is reported from the IPv4 subtree
+        let db = build_db(&["10.0.0.0/8", "2001:db8::/32"]);
+        let path = db.covering_networks("10.1.2.3".parse().unwrap()).unwrap();
+        assert_eq!(path.first(), Some(&net("0.0.0.0", 0)));
+        assert_eq!(path.last(), Some(&net("10.0.0.0", 8)));
+        let path = db
+            .covering_networks("2001:db8::1".parse().unwrap())
+            .unwrap();
+        assert_eq!(path.first(), Some(&net("::", 0)));
+        assert_eq!(path.last(), Some(&net("2001:db8::", 32)));
+    }
 
-impl std::fmt::Display for DatabaseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DatabaseError::Io(msg) => write!(f, "I/O error: {}", msg),
-            DatabaseError::Format(err) => write!(f, "Format error: {}", err),
-            DatabaseError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+    #[test]
+    fn test_escaped_glob_lookup() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry(r"file\*.txt", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        assert!(matches!(
+            db.lookup("file*.txt").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        for query in ["fileX.txt", r"file\*.txt"] {
+            assert!(matches!(
+                db.lookup(query).unwrap(),
+                Some(QueryResult::NotFound)
+            ));
         }
     }
-}
-
-impl std::error::Error for DatabaseError {}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_detect_ip_database() {
-        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
+    fn test_lookup_into_matches_lookup() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseInsensitive);
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        builder.add_entry("*malware*", HashMap::new()).unwrap();
+        builder
+            .add_entry("bad.example.org", HashMap::new())
+            .unwrap();
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
+        let db = Database::from_bytes_builder(bytes.clone())
+            .no_cache()
             .open()
             .unwrap();
-        assert_eq!(db.format, DatabaseFormat::IpOnly);
-        assert!(db.has_ip_data());
-        assert!(!db.has_string_data());
+        // A second database shares the scratch to show it isn't tied to one
+        let other = Database::from_bytes(bytes).unwrap();
+
+        let ids = |result: Option<QueryResult>| match result {
+            Some(QueryResult::Pattern { pattern_ids, .. }) => pattern_ids,
+            Some(QueryResult::Ip { .. }) => vec![u32::MAX],
+            _ => Vec::new(),
+        };
+
+        let mut scratch = QueryScratch::new();
+        for query in [
+            "WWW.EVIL.COM",
+            "malware.evil.com",
+            "bad.example.org",
+            "benign.org",
+            "10.1.2.3",
+            "www.evil.com",
+        ] {
+            let expected = ids(db.lookup(query).unwrap());
+            assert_eq!(ids(db.lookup_into(query, &mut scratch).unwrap()), expected);
+            assert_eq!(
+                ids(other.lookup_into(query, &mut scratch).unwrap()),
+                expected
+            );
+        }
+
+        let extractor = Extractor::new().unwrap();
+        let text = b"malware.evil.com from 10.1.2.3 and benign.org";
+        let spans =
+            |matches: Vec<ScanMatch>| matches.into_iter().map(|m| m.span).collect::<Vec<_>>();
+        assert_eq!(
+            spans(db.scan_into(text, &extractor, &mut scratch).unwrap()),
+            spans(db.scan(text, &extractor).unwrap())
+        );
     }
 
     #[test]
-    fn test_lookup_ip_address() {
-        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
-            .open()
-            .unwrap();
+    fn test_shared_across_threads() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<Database>();
+
+        let db = build_db(&["*.evil.com", "*malware*", "10.0.0.0/8"]);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut scratch = QueryScratch::new();
+                    for _ in 0..50 {
+                        assert!(matches!(
+                            db.lookup("www.evil.com").unwrap(),
+                            Some(QueryResult::Pattern { .. })
+                        ));
+                        assert!(matches!(
+                            db.lookup_into("malware.example", &mut scratch).unwrap(),
+                            Some(QueryResult::Pattern { .. })
+                        ));
+                        assert!(matches!(
+                            db.lookup("10.1.2.3").unwrap(),
+                            Some(QueryResult::Ip { .. })
+                        ));
+                    }
+                });
+            }
+        });
+        assert_eq!(db.stats().total_queries, 4 * 50 * 3);
+    }
 
-        // Test IP lookup
-        let result = db.lookup("1.1.1.1").unwrap();
-        assert!(result.is_some());
+    #[test]
+    fn test_lookup_ipv4_ipv6() {
+        let db = build_db(&["10.0.0.0/8", "2001:db8::/32"]);
+
+        let v4 = db.lookup_ipv4(Ipv4Addr::new(10, 1, 2, 3)).unwrap();
+        assert!(matches!(v4, Some(QueryResult::Ip { prefix_len: 8, .. })));
+        let v6 = db.lookup_ipv6("2001:db8::1".parse().unwrap()).unwrap();
+        assert!(matches!(v6, Some(QueryResult::Ip { prefix_len: 32, .. })));
+        assert!(matches!(
+            db.lookup_ipv4(Ipv4Addr::new(192, 0, 2, 1)).unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+    }
 
-        if let Some(QueryResult::Ip { data, prefix_len }) = result {
-            assert!(prefix_len > 0);
-            assert!(prefix_len <= 32);
+    #[test]
+    fn test_matched_network() {
+        let db = build_db(&[
+            "10.0.0.0/8",
+            "10.1.2.3/32",
+            "2001:db8::/32",
+            "*.example.com",
+        ]);
 
-            // Should have map data
-            match data {
-                DataValue::Map(map) => {
-                    assert!(!map.is_empty());
-                }
-                _ => panic!("Expected map data"),
-            }
-        } else {
-            panic!("Expected IP result");
-        }
+        let network = |query: &str| {
+            let ip: IpAddr = query.parse().unwrap();
+            let result = db.lookup_ip(ip).unwrap().unwrap();
+            result
+                .matched_network(ip)
+                .map(|(addr, len)| format!("{}/{}", addr, len))
+        };
+        // The /32 splits the /8, so the covering tree node is the /9
+        assert_eq!(network("10.200.1.1").as_deref(), Some("10.128.0.0/9"));
+        assert_eq!(network("10.1.2.3").as_deref(), Some("10.1.2.3/32"));
+        assert_eq!(network("2001:db8:1::5").as_deref(), Some("2001:db8::/32"));
+        assert_eq!(network("192.0.2.1"), None);
+
+        let result = db.lookup("www.example.com").unwrap().unwrap();
+        assert_eq!(result.matched_prefix(), None);
     }
 
     #[test]
-    fn test_lookup_ipv6() {
-        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
-            .open()
+    fn test_error_kind() {
+        let db = build_db(&["10.0.0.0/8"]);
+        assert_eq!(
+            db.lookup("10.0.0.999").unwrap_err().kind(),
+            ErrorKind::InvalidIp
+        );
+        let long = "a".repeat(DEFAULT_MAX_INPUT_LEN + 1);
+        assert_eq!(
+            db.lookup(&long).unwrap_err().kind(),
+            ErrorKind::InputTooLong
+        );
+
+        // Narrow kinds come from where the failure is raised
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_numeric_entry(7, HashMap::new()).unwrap();
+        let mut bytes = builder.build().unwrap();
+        let pos = bytes
+            .windows(4)
+            .position(|w| w == crate::numeric_index::NUMERIC_INDEX_MAGIC)
             .unwrap();
+        bytes[pos + 4] = 9;
+        let Err(err) = Database::from_bytes(bytes) else {
+            panic!("opened a database with an unknown numeric index version");
+        };
+        assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
+        assert_eq!(
+            Database::reencode("unused.mxy", 9).unwrap_err().kind(),
+            ErrorKind::UnsupportedVersion
+        );
+        assert_eq!(
+            DatabaseError::section("suffix section", ParaglobError::Truncated("x".into())).kind(),
+            ErrorKind::Truncated
+        );
+
+        // Message text never changes the kind
+        let kind = |msg: &str| DatabaseError::Unsupported(msg.to_string()).kind();
+        assert_eq!(
+            kind("Pattern section header truncated"),
+            ErrorKind::Unsupported
+        );
+        assert_eq!(kind("Invalid numeric key: x"), ErrorKind::Unsupported);
+        assert_eq!(ParaglobError::Io("disk full".into()).kind(), ErrorKind::Io);
+        assert_eq!(
+            ParaglobError::Format("Invalid rule file".into()).kind(),
+            ErrorKind::Format
+        );
+    }
 
-        let result = db.lookup("2001:4860:4860::8888").unwrap();
-        assert!(result.is_some());
+    #[test]
+    fn test_lookup_many() {
+        let db = build_db(&["10.0.0.0/8", "*.evil.com", "exact.org"]);
+
+        let keys = [
+            "www.evil.com",
+            "10.1.2.3",
+            "benign.org",
+            "192.0.2.1",
+            "exact.org",
+        ];
+        let mut out = vec![Some(QueryResult::NotFound); 8];
+        db.lookup_many_into(&keys, &mut out).unwrap();
+        assert_eq!(out.len(), keys.len());
+        assert!(matches!(out[0], Some(QueryResult::Pattern { .. })));
+        assert!(matches!(
+            out[1],
+            Some(QueryResult::Ip { prefix_len: 8, .. })
+        ));
+        assert!(out[2].is_none());
+        assert!(out[3].is_none());
+        assert!(matches!(out[4], Some(QueryResult::Pattern { .. })));
+
+        let single: Vec<_> = keys
+            .iter()
+            .map(|k| {
+                db.lookup(k)
+                    .unwrap()
+                    .filter(|r| !matches!(r, QueryResult::NotFound))
+            })
+            .map(|r| r.is_some())
+            .collect();
+        let batched: Vec<_> = db
+            .lookup_many(&keys)
+            .unwrap()
+            .iter()
+            .map(Option::is_some)
+            .collect();
+        assert_eq!(single, batched);
+
+        assert!(db.lookup_many(&["10.0.0.999"]).is_err());
+    }
 
-        if let Some(QueryResult::Ip { prefix_len, .. }) = result {
-            assert!(prefix_len > 0);
-            assert!(prefix_len <= 128);
-        }
+    #[test]
+    fn test_lookup_many_until() {
+        let db = build_db(&["*.evil.com"]);
+        let keys = ["www.evil.com", "benign.org", "a.evil.com"];
+
+        let later = Instant::now() + Duration::from_secs(60);
+        let (results, done) = db.lookup_many_until(&keys, later).unwrap();
+        assert_eq!(done, 3);
+        assert!(matches!(results[0], Some(QueryResult::Pattern { .. })));
+        assert!(results[1].is_none());
+        assert!(results[2].is_some());
+
+        // A deadline already passed does no work
+        let (results, done) = db.lookup_many_until(&keys, Instant::now()).unwrap();
+        assert_eq!(done, 0);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Option::is_none));
+
+        assert!(db.lookup_many_until(&["1.2.3.999"], later).is_err());
     }
 
     #[test]
-    fn test_lookup_not_found() {
-        let db = Database::from("tests/data/GeoLite2-Country.mmdb")
-            .open()
+    fn test_lookup_stream() {
+        let db = build_db(&["*.evil.com", "10.0.0.0/8"]);
+
+        // Keys are produced on demand; nothing past the second is pulled
+        let mut pulled = 0;
+        let keys = ["www.evil.com", "benign.org", "10.1.2.3", "1.2.3.999"]
+            .into_iter()
+            .inspect(|_| pulled += 1)
+            .map(String::from);
+        let mut stream = db.lookup_stream(keys);
+
+        let (key, result) = stream.next().unwrap();
+        assert_eq!(key, "www.evil.com");
+        assert!(matches!(result, Ok(Some(QueryResult::Pattern { .. }))));
+        let (key, result) = stream.next().unwrap();
+        assert_eq!(key, "benign.org");
+        assert!(matches!(result, Ok(None)));
+        drop(stream);
+        assert_eq!(pulled, 2);
+
+        let results: Vec<_> = db
+            .lookup_stream(["10.1.2.3", "1.2.3.999"].map(String::from))
+            .collect();
+        assert!(matches!(results[0].1, Ok(Some(QueryResult::Ip { .. }))));
+        assert!(matches!(results[1].1, Err(DatabaseError::InvalidIp(_))));
+    }
+
+    #[test]
+    fn test_data_size() {
+        let mut small = HashMap::new();
+        small.insert("k".to_string(), DataValue::String("abc".to_string()));
+        let mut large = HashMap::new();
+        large.insert("k".to_string(), DataValue::String("x".repeat(100)));
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", small).unwrap();
+        builder.add_entry("*.evil.com", large).unwrap();
+        builder.add_entry("nodata.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        // Map header (1) + "k" (2) + "abc" (4)
+        assert_eq!(db.lookup("10.1.2.3").unwrap().unwrap().data_size(), 7);
+        // A 100-byte string needs a one-byte extended length
+        assert_eq!(db.lookup("a.evil.com").unwrap().unwrap().data_size(), 105);
+        assert_eq!(db.lookup("nodata.com").unwrap().unwrap().data_size(), 1);
+        assert_eq!(QueryResult::NotFound.data_size(), 0);
+    }
+
+    #[test]
+    fn test_lookup_raw_data() {
+        let mut small = HashMap::new();
+        small.insert("k".to_string(), DataValue::String("abc".to_string()));
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", small.clone()).unwrap();
+        builder.add_entry("*.evil.com", small.clone()).unwrap();
+        builder.add_entry("nodata.com", HashMap::new()).unwrap();
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        // Map header (1) + "k" (2) + "abc" (4), and the same record for the glob
+        let raw = db.lookup_raw_data("10.1.2.3").unwrap().unwrap();
+        assert_eq!(raw.len(), 7);
+        assert_eq!(
+            DataDecoder::new(raw, 0).decode(0).unwrap(),
+            DataValue::from_hash_map(small)
+        );
+        assert_eq!(db.lookup_raw_data("a.evil.com").unwrap(), Some(raw));
+        assert_eq!(db.lookup_raw_data("nodata.com").unwrap(), Some(&[0xe0][..]));
+
+        assert_eq!(db.lookup_raw_data("192.0.2.1").unwrap(), None);
+        assert_eq!(db.lookup_raw_data("example.org").unwrap(), None);
+        assert!(db.lookup_raw_data("1.2.3.999").is_err());
+    }
+
+    #[test]
+    fn test_lookup_budgeted() {
+        let db = build_db(&["*.evil.com", "*malware*", "bad.example.org", "10.0.0.0/8"]);
+
+        let ids = |result: Option<QueryResult>| match result {
+            Some(QueryResult::Pattern { pattern_ids, .. }) => pattern_ids,
+            _ => Vec::new(),
+        };
+        let full = ids(db.lookup("malware.evil.com").unwrap());
+        assert_eq!(full.len(), 2);
+
+        let (result, truncated) = db
+            .lookup_budgeted("malware.evil.com", QueryBudget::UNLIMITED)
             .unwrap();
+        assert_eq!(ids(result), full);
+        assert!(!truncated);
 
-        let result = db.lookup("127.0.0.1").unwrap();
+        // Out of verifications: partial results, flagged as truncated
+        let (result, truncated) = db
+            .lookup_budgeted("malware.evil.com", QueryBudget::new(1, usize::MAX))
+            .unwrap();
+        assert_eq!(ids(result), full[..1]);
+        assert!(truncated);
+        let (result, truncated) = db
+            .lookup_budgeted("malware.evil.com", QueryBudget::new(0, usize::MAX))
+            .unwrap();
         assert!(matches!(result, Some(QueryResult::NotFound)));
+        assert!(truncated);
+
+        // Out of failure links: the scan stops before reaching ".evil.com"
+        let (result, truncated) = db
+            .lookup_budgeted("malware.evil.com", QueryBudget::new(usize::MAX, 0))
+            .unwrap();
+        assert_eq!(ids(result).len(), 1);
+        assert!(truncated);
+
+        // Exact literals never need verification, and IPs never spend budget
+        let (result, truncated) = db
+            .lookup_budgeted("bad.example.org", QueryBudget::new(0, usize::MAX))
+            .unwrap();
+        assert_eq!(ids(result).len(), 1);
+        assert!(!truncated);
+        let (result, truncated) = db
+            .lookup_budgeted("10.1.2.3", QueryBudget::new(0, 0))
+            .unwrap();
+        assert!(matches!(result, Some(QueryResult::Ip { .. })));
+        assert!(!truncated);
     }
 
     #[test]
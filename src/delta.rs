@@ -0,0 +1,789 @@
+//! Delta updates between database versions
+//!
+//! A [`Delta`] records the entries added, changed, and removed between an old
+//! and a new database, along with the new database's metadata. Shipping the
+//! delta instead of the full file saves bandwidth when a feed changes little
+//! from one release to the next.
+//!
+//! Both sides of a round trip go through [`rebuild`], which adds entries in a
+//! canonical order (networks, literals, suffixes, and numeric keys sorted by
+//! key; globs in precedence order) and keeps the recorded build time. Applying
+//! a delta to the old database therefore produces exactly the bytes of
+//! `rebuild(new)`.
+//!
+//! # Example
+//! ```
+//! use matchy::delta::{self, Delta};
+//! use matchy::{Database, DatabaseBuilder, MatchMode};
+//! use std::collections::HashMap;
+//!
+//! let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+//! builder.add_entry("evil.com", HashMap::new())?;
+//! let old = Database::from_bytes(builder.build()?)?;
+//!
+//! let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+//! builder.add_entry("evil.com", HashMap::new())?;
+//! builder.add_entry("10.0.0.0/8", HashMap::new())?;
+//! let new = Database::from_bytes(builder.build()?)?;
+//!
+//! let patch = Delta::between(&old, &new)?.to_bytes();
+//! let result = Delta::from_bytes(&patch)?.apply(&old)?;
+//! assert_eq!(result, delta::rebuild(&new)?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::data_section::{DataDecoder, DataEncoder, DataMap, DataValue};
+use crate::database::{Database, DatabaseError, EntryKind};
+use crate::glob::MatchMode;
+use crate::mmdb::MmdbError;
+use crate::mmdb_builder::MmdbBuilder;
+use std::collections::{HashMap, HashSet};
+
+/// Magic bytes at the start of a serialized delta
+const DELTA_MAGIC: &[u8; 8] = b"MXYDELTA";
+
+/// Serialized delta format version
+const DELTA_VERSION: u32 = 1;
+
+/// A database entry: kind, key, and data
+pub type DeltaEntry = (EntryKind, String, DataValue);
+
+/// A grouped literal or glob: kind, key, and group name
+type DeltaEntryGroup = (EntryKind, String, String);
+
+/// Entry changes between two databases
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    /// Entries only in the new database, sorted by kind and key
+    pub added: Vec<DeltaEntry>,
+    /// Entries whose data differs, with the new data, sorted by kind and key
+    pub changed: Vec<DeltaEntry>,
+    /// Entries only in the old database, sorted by kind and key
+    pub removed: Vec<(EntryKind, String)>,
+    /// Number of entries the base database must have
+    base_entry_count: u64,
+    /// New glob precedence order, unless it is the old order with removed
+    /// globs dropped and added globs appended
+    glob_order: Option<Vec<String>>,
+    /// Pattern group of every grouped literal and glob in the new database,
+    /// sorted by kind and key, unless the groups are the old ones with
+    /// removed entries dropped
+    groups: Option<Vec<DeltaEntryGroup>>,
+    /// Settings and metadata of the new database
+    metadata: RebuildMetadata,
+}
+
+impl Delta {
+    /// Compute the changes that turn `old` into `new`
+    ///
+    /// # Errors
+    /// Returns an error if either database is corrupt.
+    pub fn between(old: &Database, new: &Database) -> Result<Self, DatabaseError> {
+        let old_set = EntrySet::of(old)?;
+        let new_set = EntrySet::of(new)?;
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for ((kind, key), value) in &new_set.entries {
+            match old_set.entries.get(&(*kind, key.clone())) {
+                None => added.push((*kind, key.clone(), value.clone())),
+                Some(old_value) if old_value != value => {
+                    changed.push((*kind, key.clone(), value.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        let mut removed: Vec<(EntryKind, String)> = old_set
+            .entries
+            .keys()
+            .filter(|entry_key| !new_set.entries.contains_key(*entry_key))
+            .cloned()
+            .collect();
+        added.sort_unstable_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        changed.sort_unstable_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        removed.sort_unstable();
+
+        let removed_globs: HashSet<&str> = removed
+            .iter()
+            .filter(|(kind, _)| *kind == EntryKind::Glob)
+            .map(|(_, key)| key.as_str())
+            .collect();
+        let expected_order = merged_glob_order(&old_set.globs, &removed_globs, &added);
+        let glob_order = (expected_order != new_set.globs).then(|| new_set.globs.clone());
+
+        let mut expected_groups = old_set.groups;
+        for entry_key in &removed {
+            expected_groups.remove(entry_key);
+        }
+        let groups = (expected_groups != new_set.groups).then(|| {
+            let mut groups: Vec<DeltaEntryGroup> = new_set
+                .groups
+                .iter()
+                .map(|((kind, key), group)| (*kind, key.clone(), group.clone()))
+                .collect();
+            groups.sort_unstable();
+            groups
+        });
+
+        Ok(Self {
+            added,
+            changed,
+            removed,
+            base_entry_count: old_set.entries.len() as u64,
+            glob_order,
+            groups,
+            metadata: RebuildMetadata::of(new),
+        })
+    }
+
+    /// Apply the changes to `base` and build the resulting database
+    ///
+    /// The result is byte-identical to [`rebuild`] of the database the delta
+    /// was computed against.
+    ///
+    /// # Errors
+    /// Returns an error if `base` is not the database the delta was computed
+    /// from (an entry to remove or change is missing, an added entry already
+    /// exists, or the entry count differs), or if the build fails.
+    pub fn apply(&self, base: &Database) -> Result<Vec<u8>, DatabaseError> {
+        let mut set = EntrySet::of(base)?;
+        if set.entries.len() as u64 != self.base_entry_count {
+            return Err(wrong_base(format!(
+                "base has {} entries, delta expects {}",
+                set.entries.len(),
+                self.base_entry_count
+            )));
+        }
+
+        for (kind, key) in &self.removed {
+            if set.entries.remove(&(*kind, key.clone())).is_none() {
+                return Err(wrong_base(format!("entry to remove not found: {}", key)));
+            }
+            set.groups.remove(&(*kind, key.clone()));
+        }
+        if let Some(groups) = &self.groups {
+            set.groups = groups
+                .iter()
+                .map(|(kind, key, group)| ((*kind, key.clone()), group.clone()))
+                .collect();
+        }
+        for (kind, key, value) in &self.changed {
+            match set.entries.get_mut(&(*kind, key.clone())) {
+                Some(existing) => *existing = value.clone(),
+                None => return Err(wrong_base(format!("entry to change not found: {}", key))),
+            }
+        }
+        for (kind, key, value) in &self.added {
+            if set
+                .entries
+                .insert((*kind, key.clone()), value.clone())
+                .is_some()
+            {
+                return Err(wrong_base(format!("entry to add already exists: {}", key)));
+            }
+        }
+
+        set.globs = match &self.glob_order {
+            Some(order) => order.clone(),
+            None => {
+                let removed_globs: HashSet<&str> = self
+                    .removed
+                    .iter()
+                    .filter(|(kind, _)| *kind == EntryKind::Glob)
+                    .map(|(_, key)| key.as_str())
+                    .collect();
+                merged_glob_order(&set.globs, &removed_globs, &self.added)
+            }
+        };
+
+        set.build(&self.metadata)
+    }
+
+    /// Serialize the delta
+    ///
+    /// The format is the 8-byte magic `MXYDELTA`, a little-endian u32 format
+    /// version, and one map in MMDB data section encoding, so repeated
+    /// strings (keys, tags, sources) are stored once.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let entries = |entries: &[DeltaEntry]| {
+            DataValue::Array(
+                entries
+                    .iter()
+                    .map(|(kind, key, value)| {
+                        DataValue::Array(vec![
+                            DataValue::Uint16(kind_code(*kind)),
+                            DataValue::String(key.clone()),
+                            value.clone(),
+                        ])
+                    })
+                    .collect(),
+            )
+        };
+
+        let mut map = DataMap::new();
+        map.insert(
+            "base_entry_count".to_string(),
+            DataValue::Uint64(self.base_entry_count),
+        );
+        map.insert("metadata".to_string(), self.metadata.to_value());
+        map.insert("added".to_string(), entries(&self.added));
+        map.insert("changed".to_string(), entries(&self.changed));
+        map.insert(
+            "removed".to_string(),
+            DataValue::Array(
+                self.removed
+                    .iter()
+                    .map(|(kind, key)| {
+                        DataValue::Array(vec![
+                            DataValue::Uint16(kind_code(*kind)),
+                            DataValue::String(key.clone()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        );
+        if let Some(order) = &self.glob_order {
+            map.insert(
+                "glob_order".to_string(),
+                DataValue::Array(order.iter().cloned().map(DataValue::String).collect()),
+            );
+        }
+        if let Some(groups) = &self.groups {
+            map.insert(
+                "groups".to_string(),
+                DataValue::Array(
+                    groups
+                        .iter()
+                        .map(|(kind, key, group)| {
+                            DataValue::Array(vec![
+                                DataValue::Uint16(kind_code(*kind)),
+                                DataValue::String(key.clone()),
+                                DataValue::String(group.clone()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            );
+        }
+
+        let mut encoder = DataEncoder::new();
+        encoder.encode(&DataValue::Map(map));
+
+        let mut bytes = Vec::with_capacity(12 + encoder.size());
+        bytes.extend_from_slice(DELTA_MAGIC);
+        bytes.extend_from_slice(&DELTA_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&encoder.into_bytes());
+        bytes
+    }
+
+    /// Parse a delta written by [`to_bytes`](Self::to_bytes)
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not a delta or are corrupt.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DatabaseError> {
+        if bytes.len() < 12 || &bytes[..8] != DELTA_MAGIC {
+            return Err(invalid("not a matchy delta (bad magic)"));
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != DELTA_VERSION {
+            return Err(DatabaseError::UnsupportedVersion(format!(
+                "Invalid delta: unsupported delta version {}",
+                version
+            )));
+        }
+
+        let value = DataDecoder::new(&bytes[12..], 0)
+            .decode(0)
+            .map_err(invalid)?;
+        let DataValue::Map(map) = value else {
+            return Err(invalid("delta body is not a map"));
+        };
+
+        let base_entry_count = match map.get("base_entry_count") {
+            Some(DataValue::Uint64(n)) => *n,
+            _ => return Err(invalid("missing base_entry_count")),
+        };
+        let metadata = match map.get("metadata") {
+            Some(DataValue::Map(m)) => RebuildMetadata::from_map(m)?,
+            _ => return Err(invalid("missing metadata")),
+        };
+        let glob_order = match map.get("glob_order") {
+            Some(DataValue::Array(order)) => Some(
+                order
+                    .iter()
+                    .map(|v| match v {
+                        DataValue::String(s) => Ok(s.clone()),
+                        _ => Err(invalid("glob_order holds a non-string")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Some(_) => return Err(invalid("glob_order is not an array")),
+            None => None,
+        };
+
+        let groups = match map.get("groups") {
+            Some(_) => Some(
+                array_field(&map, "groups")?
+                    .into_iter()
+                    .map(|item| match item.as_slice() {
+                        [kind, DataValue::String(key), DataValue::String(group)] => {
+                            Ok((kind_from(kind)?, key.clone(), group.clone()))
+                        }
+                        _ => Err(invalid("malformed groups entry")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
+
+        let mut removed = Vec::new();
+        for item in array_field(&map, "removed")? {
+            match item.as_slice() {
+                [kind, DataValue::String(key)] => removed.push((kind_from(kind)?, key.clone())),
+                _ => return Err(invalid("malformed removed entry")),
+            }
+        }
+
+        Ok(Self {
+            added: entries_field(&map, "added")?,
+            changed: entries_field(&map, "changed")?,
+            removed,
+            base_entry_count,
+            glob_order,
+            groups,
+            metadata,
+        })
+    }
+}
+
+/// Build a database from the entries of `db` in canonical order
+///
+/// Keeps the match mode, literal hash seed, database type, descriptions,
+/// version tag, comment, pattern groups, default record, and build time of
+/// `db`, so rebuilding is deterministic. This is the reference a [`Delta`] round trip reproduces.
+///
+/// # Errors
+/// Returns an error if the database is corrupt or the build fails.
+pub fn rebuild(db: &Database) -> Result<Vec<u8>, DatabaseError> {
+    EntrySet::of(db)?.build(&RebuildMetadata::of(db))
+}
+
+/// Database-wide settings a rebuild carries over
+#[derive(Debug, Clone, PartialEq)]
+struct RebuildMetadata {
+    match_mode: MatchMode,
+    literal_hash_seed: u64,
+    build_epoch: u64,
+    database_type: Option<String>,
+    /// Descriptions as (language, text), sorted by language
+    description: Vec<(String, String)>,
+    version_tag: Option<String>,
+    comment: Option<String>,
+    /// Pattern group names in group ID order
+    pattern_groups: Vec<String>,
+    /// Record returned for keys that match nothing
+    default_data: Option<DataValue>,
+}
+
+impl RebuildMetadata {
+    fn of(db: &Database) -> Self {
+        let (match_mode, literal_hash_seed) = db.builder_settings();
+        let mut description = Vec::new();
+        if let Some(DataValue::Map(metadata)) = db.metadata() {
+            if let Some(DataValue::Map(descriptions)) = metadata.get("description") {
+                for (language, text) in descriptions {
+                    if let DataValue::String(text) = text {
+                        description.push((language.clone(), text.clone()));
+                    }
+                }
+            }
+        }
+        description.sort_unstable();
+
+        Self {
+            match_mode,
+            literal_hash_seed,
+            build_epoch: db.build_epoch().unwrap_or(0),
+            database_type: db.database_type(),
+            description,
+            version_tag: db.version_tag(),
+            comment: db.comment(),
+            pattern_groups: db.pattern_groups().to_vec(),
+            default_data: db.default_data().cloned(),
+        }
+    }
+
+    /// A builder configured with these settings
+    fn builder(&self) -> Result<MmdbBuilder, DatabaseError> {
+        let mut builder = MmdbBuilder::new(self.match_mode)
+            .with_literal_hash_seed(self.literal_hash_seed)
+            .with_build_epoch(self.build_epoch);
+        if let Some(database_type) = &self.database_type {
+            builder = builder.with_database_type(database_type.clone());
+        }
+        for (language, text) in &self.description {
+            builder = builder.with_description(language.clone(), text.clone());
+        }
+        if let Some(tag) = &self.version_tag {
+            builder = builder.with_version_tag(tag.clone());
+        }
+        if let Some(comment) = &self.comment {
+            builder = builder.with_comment(comment.clone());
+        }
+        if let Some(default_data) = &self.default_data {
+            builder = builder.with_default_map(Database::entry_map(default_data.clone())?);
+        }
+        for group in &self.pattern_groups {
+            builder
+                .group_id(group)
+                .map_err(|e| DatabaseError::Unsupported(e.to_string()))?;
+        }
+        Ok(builder)
+    }
+
+    fn to_value(&self) -> DataValue {
+        let mut map = DataMap::new();
+        let mode = match self.match_mode {
+            MatchMode::CaseSensitive => "case-sensitive",
+            MatchMode::CaseInsensitive => "case-insensitive",
+        };
+        map.insert(
+            "match_mode".to_string(),
+            DataValue::String(mode.to_string()),
+        );
+        map.insert(
+            "literal_hash_seed".to_string(),
+            DataValue::Uint64(self.literal_hash_seed),
+        );
+        map.insert(
+            "build_epoch".to_string(),
+            DataValue::Uint64(self.build_epoch),
+        );
+        let optional = [
+            ("database_type", &self.database_type),
+            ("version_tag", &self.version_tag),
+            ("comment", &self.comment),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value {
+                map.insert(field.to_string(), DataValue::String(value.clone()));
+            }
+        }
+        map.insert(
+            "description".to_string(),
+            DataValue::Map(
+                self.description
+                    .iter()
+                    .map(|(language, text)| (language.clone(), DataValue::String(text.clone())))
+                    .collect(),
+            ),
+        );
+        if let Some(default_data) = &self.default_data {
+            map.insert("default_data".to_string(), default_data.clone());
+        }
+        if !self.pattern_groups.is_empty() {
+            map.insert(
+                "pattern_groups".to_string(),
+                DataValue::Array(
+                    self.pattern_groups
+                        .iter()
+                        .cloned()
+                        .map(DataValue::String)
+                        .collect(),
+                ),
+            );
+        }
+        DataValue::Map(map)
+    }
+
+    fn from_map(map: &DataMap) -> Result<Self, DatabaseError> {
+        let string = |field: &str| match map.get(field) {
+            Some(DataValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let uint64 = |field: &str| match map.get(field) {
+            Some(DataValue::Uint64(n)) => Ok(*n),
+            _ => Err(invalid(&format!("missing metadata field {}", field))),
+        };
+
+        let match_mode = match string("match_mode").as_deref() {
+            Some("case-sensitive") => MatchMode::CaseSensitive,
+            Some("case-insensitive") => MatchMode::CaseInsensitive,
+            _ => return Err(invalid("missing or unknown match_mode")),
+        };
+        let mut description = Vec::new();
+        if let Some(DataValue::Map(descriptions)) = map.get("description") {
+            for (language, text) in descriptions {
+                if let DataValue::String(text) = text {
+                    description.push((language.clone(), text.clone()));
+                }
+            }
+        }
+        let pattern_groups = match map.get("pattern_groups") {
+            Some(DataValue::Array(groups)) => groups
+                .iter()
+                .map(|group| match group {
+                    DataValue::String(name) => Ok(name.clone()),
+                    _ => Err(invalid("pattern_groups holds a non-string")),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => return Err(invalid("pattern_groups is not an array")),
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            match_mode,
+            literal_hash_seed: uint64("literal_hash_seed")?,
+            build_epoch: uint64("build_epoch")?,
+            database_type: string("database_type"),
+            description,
+            version_tag: string("version_tag"),
+            comment: string("comment"),
+            pattern_groups,
+            default_data: map.get("default_data").cloned(),
+        })
+    }
+}
+
+/// All entries of a database, plus glob precedence order and pattern groups
+struct EntrySet {
+    entries: HashMap<(EntryKind, String), DataValue>,
+    /// Glob keys in precedence order
+    globs: Vec<String>,
+    /// Group name of each grouped literal and glob
+    groups: HashMap<(EntryKind, String), String>,
+}
+
+impl EntrySet {
+    fn of(db: &Database) -> Result<Self, DatabaseError> {
+        let mut entries = HashMap::new();
+        let mut globs = Vec::new();
+        let mut groups = HashMap::new();
+        db.for_each_grouped_entry(|kind, key, value, group| {
+            if kind == EntryKind::Glob && !entries.contains_key(&(kind, key.clone())) {
+                globs.push(key.clone());
+            }
+            if let Some(group) = group {
+                groups.insert((kind, key.clone()), group.to_string());
+            }
+            entries.insert((kind, key), value);
+            Ok(())
+        })?;
+        Ok(Self {
+            entries,
+            globs,
+            groups,
+        })
+    }
+
+    /// Build the entries in canonical order
+    fn build(self, metadata: &RebuildMetadata) -> Result<Vec<u8>, DatabaseError> {
+        let mut builder = metadata.builder()?;
+        let mut entries = self.entries;
+
+        let mut keyed: Vec<(EntryKind, String)> = entries
+            .keys()
+            .filter(|(kind, _)| *kind != EntryKind::Glob)
+            .cloned()
+            .collect();
+        keyed.sort_unstable();
+        let globs = self.globs.into_iter().map(|key| (EntryKind::Glob, key));
+        // Networks and literals, then globs in precedence order, then suffixes
+        let (before, suffixes): (Vec<_>, Vec<_>) = keyed
+            .into_iter()
+            .partition(|(kind, _)| *kind != EntryKind::Suffix);
+
+        for (kind, key) in before.into_iter().chain(globs).chain(suffixes) {
+            let value = entries
+                .remove(&(kind, key.clone()))
+                .ok_or_else(|| invalid(&format!("glob order names a missing glob: {}", key)))?;
+            let group = self.groups.get(&(kind, key.clone())).map(String::as_str);
+            builder
+                .add_decoded(kind, &key, Database::entry_map(value)?, group)
+                .map_err(|e| DatabaseError::Unsupported(e.to_string()))?;
+        }
+        if let Some(((_, key), _)) = entries.into_iter().next() {
+            return Err(invalid(&format!("glob missing from glob order: {}", key)));
+        }
+
+        builder
+            .build()
+            .map_err(|e| DatabaseError::Unsupported(e.to_string()))
+    }
+}
+
+/// Old glob order with removed globs dropped and added globs appended
+fn merged_glob_order(old: &[String], removed: &HashSet<&str>, added: &[DeltaEntry]) -> Vec<String> {
+    old.iter()
+        .filter(|key| !removed.contains(key.as_str()))
+        .cloned()
+        .chain(
+            added
+                .iter()
+                .filter(|(kind, _, _)| *kind == EntryKind::Glob)
+                .map(|(_, key, _)| key.clone()),
+        )
+        .collect()
+}
+
+fn kind_code(kind: EntryKind) -> u16 {
+    match kind {
+        EntryKind::Network => 0,
+        EntryKind::Literal => 1,
+        EntryKind::Glob => 2,
+        EntryKind::Suffix => 3,
+        EntryKind::Numeric => 4,
+    }
+}
+
+fn kind_from(value: &DataValue) -> Result<EntryKind, DatabaseError> {
+    match value {
+        DataValue::Uint16(0) => Ok(EntryKind::Network),
+        DataValue::Uint16(1) => Ok(EntryKind::Literal),
+        DataValue::Uint16(2) => Ok(EntryKind::Glob),
+        DataValue::Uint16(3) => Ok(EntryKind::Suffix),
+        DataValue::Uint16(4) => Ok(EntryKind::Numeric),
+        other => Err(invalid(&format!("unknown entry kind {:?}", other))),
+    }
+}
+
+fn array_field<'a>(
+    map: &'a DataMap,
+    field: &str,
+) -> Result<Vec<&'a Vec<DataValue>>, DatabaseError> {
+    match map.get(field) {
+        Some(DataValue::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                DataValue::Array(fields) => Ok(fields),
+                _ => Err(invalid(&format!("malformed {} entry", field))),
+            })
+            .collect(),
+        _ => Err(invalid(&format!("missing {}", field))),
+    }
+}
+
+fn entries_field(map: &DataMap, field: &str) -> Result<Vec<DeltaEntry>, DatabaseError> {
+    array_field(map, field)?
+        .into_iter()
+        .map(|item| match item.as_slice() {
+            [kind, DataValue::String(key), value] => {
+                Ok((kind_from(kind)?, key.clone(), value.clone()))
+            }
+            _ => Err(invalid(&format!("malformed {} entry", field))),
+        })
+        .collect()
+}
+
+fn invalid(message: &str) -> DatabaseError {
+    DatabaseError::Format(MmdbError::InvalidFormat(format!(
+        "Invalid delta: {}",
+        message
+    )))
+}
+
+fn wrong_base(message: String) -> DatabaseError {
+    DatabaseError::Unsupported(format!(
+        "Delta does not apply to this database: {}",
+        message
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(entries: &[(&str, &str)]) -> Database {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_database_type("Delta-Test")
+            .with_build_epoch(1_700_000_000);
+        for (key, tag) in entries {
+            let mut data = HashMap::new();
+            data.insert("tag".to_string(), DataValue::String(tag.to_string()));
+            builder.add_entry(key, data).unwrap();
+        }
+        Database::from_bytes(builder.build().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let old = build(&[
+            ("10.0.0.0/8", "a"),
+            ("evil.com", "a"),
+            ("gone.com", "a"),
+            ("*.evil.com", "a"),
+            ("bad-*", "a"),
+        ]);
+        let new = build(&[
+            ("10.0.0.0/8", "b"),
+            ("192.168.0.0/16", "b"),
+            ("evil.com", "a"),
+            // Reordered globs change precedence
+            ("bad-*", "a"),
+            ("*.evil.com", "a"),
+            ("*.new.net", "b"),
+        ]);
+
+        let delta = Delta::between(&old, &new).unwrap();
+        assert_eq!(delta.added.len(), 2);
+        assert_eq!(delta.changed.len(), 1);
+        assert_eq!(
+            delta.removed,
+            vec![(EntryKind::Literal, "gone.com".to_string())]
+        );
+        assert!(delta.glob_order.is_some());
+
+        let parsed = Delta::from_bytes(&delta.to_bytes()).unwrap();
+        assert_eq!(parsed, delta);
+
+        let result = parsed.apply(&old).unwrap();
+        assert_eq!(result, rebuild(&new).unwrap());
+        let result = Database::from_bytes(result).unwrap();
+        assert_eq!(
+            result.canonical_text().unwrap(),
+            new.canonical_text().unwrap()
+        );
+        assert_eq!(result.database_type().as_deref(), Some("Delta-Test"));
+    }
+
+    #[test]
+    fn test_delta_rejects_wrong_base() {
+        let old = build(&[("evil.com", "a")]);
+        let new = build(&[("evil.com", "b"), ("bad.com", "b")]);
+        let other = build(&[("other.com", "a")]);
+
+        let delta = Delta::between(&old, &new).unwrap();
+        assert!(delta.glob_order.is_none());
+        assert!(delta.apply(&other).is_err());
+        assert!(Delta::from_bytes(b"not a delta").is_err());
+
+        // Group changes alone make a non-empty delta
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_database_type("Delta-Test")
+            .with_build_epoch(1_700_000_000);
+        let mut data = HashMap::new();
+        data.insert("tag".to_string(), DataValue::String("b".to_string()));
+        builder
+            .add_entry_in_group("evil.com", data.clone(), "c2")
+            .unwrap();
+        builder.add_entry("bad.com", data).unwrap();
+        let grouped = Database::from_bytes(builder.build().unwrap()).unwrap();
+        let delta = Delta::between(&new, &grouped).unwrap();
+        assert!(delta.added.is_empty() && delta.changed.is_empty() && delta.removed.is_empty());
+        let result = Delta::from_bytes(&delta.to_bytes())
+            .unwrap()
+            .apply(&new)
+            .unwrap();
+        assert_eq!(result, rebuild(&grouped).unwrap());
+        let result = Database::from_bytes(result).unwrap();
+        assert_eq!(result.pattern_groups(), ["c2"]);
+        assert!(matches!(
+            result.lookup_in_group("evil.com", "c2").unwrap(),
+            Some(crate::QueryResult::Pattern { .. })
+        ));
+
+        // An unchanged database yields an empty delta
+        let delta = Delta::between(&new, &new).unwrap();
+        assert!(delta.added.is_empty() && delta.changed.is_empty() && delta.removed.is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use std::fmt;
 pub type Result<T> = std::result::Result<T, ParaglobError>;
 
 /// Main error type for paraglob operations
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParaglobError {
     /// Pattern-related errors
@@ -28,17 +29,90 @@ pub enum ParaglobError {
     /// Resource limit exceeded (e.g., too many states, too much memory)
     ResourceLimitExceeded(String),
 
+    /// A section or value ends before its declared size
+    Truncated(String),
+
+    /// An offset or length points outside its buffer
+    OutOfBounds(String),
+
+    /// A section uses a format version this build cannot read
+    UnsupportedVersion(String),
+
     /// General errors
     Other(String),
 }
 
+/// Stable category of an error, for branching in code
+///
+/// Returned by [`ParaglobError::kind`] and
+/// [`DatabaseError::kind`](crate::DatabaseError::kind). The kind follows from
+/// the error variant chosen where the failure is raised, never from the
+/// message, so it stays stable when wording changes. Match on the kind and
+/// keep the message for humans.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Reading, writing, or memory-mapping a file failed
+    Io,
+    /// Malformed input or database structure not covered by a narrower kind
+    Format,
+    /// A section or value ends before its declared size
+    Truncated,
+    /// A glob pattern failed to parse
+    InvalidPattern,
+    /// A query looks like an IP address but does not parse as one
+    InvalidIp,
+    /// A file or section uses a format version this build cannot handle
+    UnsupportedVersion,
+    /// Stored data contradicts itself (corrupt entries, mismatched IDs)
+    IntegrityMismatch,
+    /// An offset or length points outside its buffer
+    OutOfBounds,
+    /// A configured or built-in resource limit was exceeded
+    ResourceLimit,
+    /// A string query exceeds the maximum input length
+    InputTooLong,
+    /// The operation is not supported for this database or input
+    Unsupported,
+    /// Anything else
+    Other,
+}
+
+impl ParaglobError {
+    /// Stable category of this error
+    ///
+    /// ```
+    /// use matchy::{ErrorKind, ParaglobError};
+    ///
+    /// let err = ParaglobError::InvalidPattern("unclosed [".to_string());
+    /// assert_eq!(err.kind(), ErrorKind::InvalidPattern);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ParaglobError::InvalidPattern(_) => ErrorKind::InvalidPattern,
+            ParaglobError::Io(_) | ParaglobError::Mmap(_) => ErrorKind::Io,
+            ParaglobError::Format(_)
+            | ParaglobError::Validation(_)
+            | ParaglobError::SerializationError(_) => ErrorKind::Format,
+            ParaglobError::ResourceLimitExceeded(_) => ErrorKind::ResourceLimit,
+            ParaglobError::Truncated(_) => ErrorKind::Truncated,
+            ParaglobError::OutOfBounds(_) => ErrorKind::OutOfBounds,
+            ParaglobError::UnsupportedVersion(_) => ErrorKind::UnsupportedVersion,
+            ParaglobError::Other(_) => ErrorKind::Other,
+        }
+    }
+}
+
 impl fmt::Display for ParaglobError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ParaglobError::InvalidPattern(msg) => write!(f, "Invalid pattern: {}", msg),
             ParaglobError::Io(msg) => write!(f, "I/O error: {}", msg),
             ParaglobError::Mmap(msg) => write!(f, "Memory mapping error: {}", msg),
-            ParaglobError::Format(msg) => write!(f, "Format error: {}", msg),
+            ParaglobError::Format(msg)
+            | ParaglobError::Truncated(msg)
+            | ParaglobError::OutOfBounds(msg)
+            | ParaglobError::UnsupportedVersion(msg) => write!(f, "Format error: {}", msg),
             ParaglobError::Validation(msg) => write!(f, "Validation error: {}", msg),
             ParaglobError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
             ParaglobError::ResourceLimitExceeded(msg) => {
@@ -1,6 +1,6 @@
 //! Fast extraction of structured patterns from log lines and text data.
 //!
-//! This module provides high-speed extraction of domains, IP addresses (IPv4/IPv6),
+//! This module provides high-speed extraction of domains, URLs, IP addresses (IPv4/IPv6),
 //! and emails from arbitrary text using Aho-Corasick anchor pattern matching followed
 //! by fast boundary scanning.
 
@@ -9,6 +9,30 @@ use crate::glob::MatchMode;
 use crate::paraglob_offset::Paraglob;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
+/// Built-in domain denylist enabled by [`ExtractorBuilder::default_domain_denylist`]
+///
+/// File names whose extension happens to be a real TLD (`.md`, `.py`, `.rs`,
+/// `.sh`, ...) and show up constantly in logs, paths, and stack traces. Only
+/// exact names are listed, so real domains under those TLDs still extract.
+pub const DEFAULT_DOMAIN_DENYLIST: &[&str] = &[
+    "readme.md",
+    "changelog.md",
+    "license.md",
+    "contributing.md",
+    "setup.py",
+    "__init__.py",
+    "__main__.py",
+    "manage.py",
+    "main.rs",
+    "lib.rs",
+    "mod.rs",
+    "build.rs",
+    "install.sh",
+    "build.sh",
+    "entrypoint.sh",
+    "libc.so",
+];
+
 /// Builder for Extractor
 pub struct ExtractorBuilder {
     extract_domains: bool,
@@ -19,8 +43,13 @@ pub struct ExtractorBuilder {
     extract_bitcoin: bool,
     extract_ethereum: bool,
     extract_monero: bool,
+    extract_urls: bool,
     min_domain_labels: usize,
     require_word_boundaries: bool,
+    domain_denylist: Vec<String>,
+    default_domain_denylist: bool,
+    allow_unicode_domains: bool,
+    boundary_chars: [bool; 256],
 }
 
 impl ExtractorBuilder {
@@ -35,8 +64,13 @@ impl ExtractorBuilder {
             extract_bitcoin: true,
             extract_ethereum: true,
             extract_monero: true,
+            extract_urls: false,
             min_domain_labels: 2,
             require_word_boundaries: true,
+            domain_denylist: Vec::new(),
+            default_domain_denylist: false,
+            allow_unicode_domains: true,
+            boundary_chars: BOUNDARY_LOOKUP,
         }
     }
 
@@ -88,6 +122,19 @@ impl ExtractorBuilder {
         self
     }
 
+    /// Enable or disable URL extraction (off by default)
+    ///
+    /// Finds `scheme://authority/path?query#fragment` around each `://` and
+    /// reports the whole URL, stopping at whitespace, quotes, `<`, `>`, or a
+    /// backtick. Trailing sentence punctuation and an unbalanced `)` are left
+    /// out. The host must be an IP address or a domain with a known TLD, so
+    /// `https://notarealhost/x` is not extracted. The URL's host is still
+    /// reported separately by domain or IP extraction when those are enabled.
+    pub fn extract_urls(mut self, enable: bool) -> Self {
+        self.extract_urls = enable;
+        self
+    }
+
     /// Set minimum number of domain labels (e.g., 2 for "example.com")
     pub fn min_domain_labels(mut self, min: usize) -> Self {
         self.min_domain_labels = min;
@@ -100,11 +147,78 @@ impl ExtractorBuilder {
         self
     }
 
+    /// Drop extracted domains matching any of these patterns
+    ///
+    /// Entries are exact domains (`setup.py`) or globs (`*.min.js`,
+    /// `v?.example.com`), matched case-insensitively against the whole
+    /// candidate. Use this for noise such as version strings and file names
+    /// that the TLD and label rules cannot tell apart from real domains.
+    /// Repeated calls add to the list. Invalid globs are reported by
+    /// [`build`](Self::build).
+    pub fn domain_denylist(mut self, patterns: &[&str]) -> Self {
+        self.domain_denylist
+            .extend(patterns.iter().map(|p| p.to_string()));
+        self
+    }
+
+    /// Accept domains with non-ASCII labels written in native script
+    ///
+    /// When enabled (the default), internationalized hostnames such as
+    /// `münchen.de` are extracted as UTF-8. When disabled, only ASCII domains
+    /// (including punycode `xn--` labels) are extracted: a candidate whose
+    /// label continues into a non-ASCII letter is dropped rather than cut
+    /// short, while non-ASCII punctuation such as `…` or `→` simply ends the
+    /// domain.
+    pub fn allow_unicode_domains(mut self, allow: bool) -> Self {
+        self.allow_unicode_domains = allow;
+        self
+    }
+
+    /// Treat these bytes as word boundaries, in addition to the defaults
+    ///
+    /// The default boundaries are whitespace and `/ , ; : ( ) [ ] { } < > " ' @ =`.
+    /// Add the delimiters of a custom log format (such as `|` or a backtick)
+    /// so that IPs, domains, emails, hashes, and crypto addresses next to them
+    /// are still extracted. Repeated calls add to the set.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::extractor::Extractor;
+    ///
+    /// let extractor = Extractor::builder().boundary_chars(b"|`").build()?;
+    /// let line = b"src=10.0.0.1|dst=`evil.example.com`";
+    /// assert_eq!(extractor.extract_from_line(line).count(), 2);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn boundary_chars(mut self, chars: &[u8]) -> Self {
+        for &b in chars {
+            self.boundary_chars[b as usize] = true;
+        }
+        self
+    }
+
+    /// Use exactly these bytes as word boundaries, replacing the defaults
+    ///
+    /// Boundaries only gate matches while
+    /// [`require_word_boundaries`](Self::require_word_boundaries) is on (the
+    /// default), but hashes and crypto addresses are always split into tokens
+    /// on boundary bytes.
+    pub fn only_boundary_chars(mut self, chars: &[u8]) -> Self {
+        self.boundary_chars = [false; 256];
+        self.boundary_chars(chars)
+    }
+
+    /// Also drop domains on the built-in [`DEFAULT_DOMAIN_DENYLIST`]
+    pub fn default_domain_denylist(mut self, enable: bool) -> Self {
+        self.default_domain_denylist = enable;
+        self
+    }
+
     /// Build the Extractor
     pub fn build(self) -> Result<Extractor, ParaglobError> {
-        // Load embedded TLD automaton if domain extraction enabled
+        // Load embedded TLD automaton if domain or URL extraction enabled
         // TLD_AUTOMATON is compiled into the binary at build time
-        let tld_matcher = if self.extract_domains {
+        let tld_matcher = if self.extract_domains || self.extract_urls {
             let paraglob =
                 crate::serialization::from_bytes(TLD_AUTOMATON, MatchMode::CaseInsensitive)?;
             Some(paraglob)
@@ -112,12 +226,34 @@ impl ExtractorBuilder {
             None
         };
 
+        // Compile the domain denylist into a single matcher
+        let mut denylist: Vec<&str> = self.domain_denylist.iter().map(String::as_str).collect();
+        if self.default_domain_denylist {
+            denylist.extend_from_slice(DEFAULT_DOMAIN_DENYLIST);
+        }
+        let domain_denylist = if (self.extract_domains || self.extract_urls) && !denylist.is_empty()
+        {
+            // Paraglob accepts any text, so check glob syntax up front
+            for pattern in &denylist {
+                crate::glob::GlobPattern::new(pattern, MatchMode::CaseInsensitive)?;
+            }
+            Some(Paraglob::build_from_patterns(
+                &denylist,
+                MatchMode::CaseInsensitive,
+            )?)
+        } else {
+            None
+        };
+
         // Pre-build memchr finder for :: (IPv6)
         let double_colon_finder = memchr::memmem::Finder::new(b"::");
 
         // Pre-build memchr finder for 0x (Ethereum)
         let ox_finder = memchr::memmem::Finder::new(b"0x");
 
+        // Pre-build memchr finder for :// (URLs)
+        let scheme_sep_finder = memchr::memmem::Finder::new(b"://");
+
         Ok(Extractor {
             extract_domains: self.extract_domains,
             extract_emails: self.extract_emails,
@@ -127,11 +263,16 @@ impl ExtractorBuilder {
             extract_bitcoin: self.extract_bitcoin,
             extract_ethereum: self.extract_ethereum,
             extract_monero: self.extract_monero,
+            extract_urls: self.extract_urls,
             min_domain_labels: self.min_domain_labels,
             require_word_boundaries: self.require_word_boundaries,
+            allow_unicode_domains: self.allow_unicode_domains,
+            boundary_chars: self.boundary_chars,
             tld_matcher,
+            domain_denylist,
             double_colon_finder,
             ox_finder,
+            scheme_sep_finder,
         })
     }
 }
@@ -214,6 +355,7 @@ impl HashType {
 }
 
 /// Type of extracted pattern
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExtractedItem<'a> {
     /// Domain name (e.g., "example.com")
@@ -232,6 +374,8 @@ pub enum ExtractedItem<'a> {
     Ethereum(&'a str),
     /// Monero address
     Monero(&'a str),
+    /// URL with scheme, authority, path, and query (e.g., "https://example.com/a?b=c")
+    Url(&'a str),
 }
 
 impl<'a> ExtractedItem<'a> {
@@ -241,6 +385,7 @@ impl<'a> ExtractedItem<'a> {
     /// - `"Domain"`, `"Email"`, `"IPv4"`, `"IPv6"`
     /// - `"MD5"`, `"SHA1"`, `"SHA256"`, `"SHA384"` for hashes
     /// - `"Bitcoin"`, `"Ethereum"`, `"Monero"` for cryptocurrency addresses
+    /// - `"URL"`
     ///
     /// This is useful for logging, output formatting, and avoiding repetitive
     /// pattern matching across your codebase.
@@ -270,6 +415,7 @@ impl<'a> ExtractedItem<'a> {
             ExtractedItem::Bitcoin(_) => "Bitcoin",
             ExtractedItem::Ethereum(_) => "Ethereum",
             ExtractedItem::Monero(_) => "Monero",
+            ExtractedItem::Url(_) => "URL",
         }
     }
 
@@ -309,6 +455,7 @@ impl<'a> ExtractedItem<'a> {
             ExtractedItem::Bitcoin(s) => s.to_string(),
             ExtractedItem::Ethereum(s) => s.to_string(),
             ExtractedItem::Monero(s) => s.to_string(),
+            ExtractedItem::Url(s) => s.to_string(),
         }
     }
 }
@@ -328,6 +475,107 @@ impl<'a> Match<'a> {
         // Safe because we validated UTF-8 during extraction
         unsafe { std::str::from_utf8_unchecked(&input[self.span.0..self.span.1]) }
     }
+
+    /// Copy this match into an [`OwnedMatch`] that does not borrow the input
+    ///
+    /// Use this to keep matches after the input line is gone (e.g. to collect
+    /// them or send them across a channel). Hot loops should stay on the
+    /// borrowed [`Match`].
+    ///
+    /// # Example
+    /// ```
+    /// # use matchy::extractor::{Extractor, OwnedMatch};
+    /// # let extractor = Extractor::new().unwrap();
+    /// let owned: Vec<OwnedMatch> = {
+    ///     let line = b"Check example.com".to_vec();
+    ///     extractor.extract_from_line(&line).map(|m| m.to_owned()).collect()
+    /// };
+    /// assert_eq!(owned[0].item.as_value(), "example.com");
+    /// ```
+    pub fn to_owned(&self) -> OwnedMatch {
+        OwnedMatch {
+            item: ExtractedItemOwned::from(&self.item),
+            span: self.span,
+        }
+    }
+}
+
+/// Owned counterpart of [`ExtractedItem`]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractedItemOwned {
+    /// Domain name (e.g., "example.com")
+    Domain(String),
+    /// Email address (e.g., "user@example.com")
+    Email(String),
+    /// IPv4 address
+    Ipv4(Ipv4Addr),
+    /// IPv6 address
+    Ipv6(Ipv6Addr),
+    /// File hash (MD5, SHA1, or SHA256)
+    Hash(HashType, String),
+    /// Bitcoin address (all formats: legacy, P2SH, bech32)
+    Bitcoin(String),
+    /// Ethereum address
+    Ethereum(String),
+    /// Monero address
+    Monero(String),
+    /// URL with scheme, authority, path, and query
+    Url(String),
+}
+
+impl ExtractedItemOwned {
+    /// Borrow as an [`ExtractedItem`]
+    pub fn as_item(&self) -> ExtractedItem<'_> {
+        match self {
+            ExtractedItemOwned::Domain(s) => ExtractedItem::Domain(s),
+            ExtractedItemOwned::Email(s) => ExtractedItem::Email(s),
+            ExtractedItemOwned::Ipv4(ip) => ExtractedItem::Ipv4(*ip),
+            ExtractedItemOwned::Ipv6(ip) => ExtractedItem::Ipv6(*ip),
+            ExtractedItemOwned::Hash(hash_type, s) => ExtractedItem::Hash(*hash_type, s),
+            ExtractedItemOwned::Bitcoin(s) => ExtractedItem::Bitcoin(s),
+            ExtractedItemOwned::Ethereum(s) => ExtractedItem::Ethereum(s),
+            ExtractedItemOwned::Monero(s) => ExtractedItem::Monero(s),
+            ExtractedItemOwned::Url(s) => ExtractedItem::Url(s),
+        }
+    }
+
+    /// Get the human-readable type name (see [`ExtractedItem::type_name`])
+    pub fn type_name(&self) -> &'static str {
+        self.as_item().type_name()
+    }
+
+    /// Get the extracted value as a string (see [`ExtractedItem::as_value`])
+    pub fn as_value(&self) -> String {
+        self.as_item().as_value()
+    }
+}
+
+impl From<&ExtractedItem<'_>> for ExtractedItemOwned {
+    fn from(item: &ExtractedItem<'_>) -> Self {
+        match *item {
+            ExtractedItem::Domain(s) => ExtractedItemOwned::Domain(s.to_string()),
+            ExtractedItem::Email(s) => ExtractedItemOwned::Email(s.to_string()),
+            ExtractedItem::Ipv4(ip) => ExtractedItemOwned::Ipv4(ip),
+            ExtractedItem::Ipv6(ip) => ExtractedItemOwned::Ipv6(ip),
+            ExtractedItem::Hash(hash_type, s) => ExtractedItemOwned::Hash(hash_type, s.to_string()),
+            ExtractedItem::Bitcoin(s) => ExtractedItemOwned::Bitcoin(s.to_string()),
+            ExtractedItem::Ethereum(s) => ExtractedItemOwned::Ethereum(s.to_string()),
+            ExtractedItem::Monero(s) => ExtractedItemOwned::Monero(s.to_string()),
+            ExtractedItem::Url(s) => ExtractedItemOwned::Url(s.to_string()),
+        }
+    }
+}
+
+/// A single extracted match that owns its data
+///
+/// Created with [`Match::to_owned`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedMatch {
+    /// The extracted item
+    pub item: ExtractedItemOwned,
+    /// Byte span in the original input (start, end) - exclusive end
+    pub span: (usize, usize),
 }
 
 /// Fast pattern extractor using Aho-Corasick anchor matching
@@ -341,14 +589,22 @@ pub struct Extractor {
     extract_bitcoin: bool,
     extract_ethereum: bool,
     extract_monero: bool,
+    extract_urls: bool,
     min_domain_labels: usize,
     require_word_boundaries: bool,
+    allow_unicode_domains: bool,
+    /// Bytes that count as word boundaries, indexed by byte value
+    boundary_chars: [bool; 256],
     /// TLD matcher (Paraglob with all public suffixes)
     tld_matcher: Option<Paraglob>,
+    /// Domains to drop even when otherwise valid
+    domain_denylist: Option<Paraglob>,
     /// Pre-built memchr finder for :: (IPv6 compression)
     double_colon_finder: memchr::memmem::Finder<'static>,
     /// Pre-built memchr finder for 0x (Ethereum addresses)
     ox_finder: memchr::memmem::Finder<'static>,
+    /// Pre-built memchr finder for :// (URLs)
+    scheme_sep_finder: memchr::memmem::Finder<'static>,
 }
 
 impl Extractor {
@@ -362,6 +618,12 @@ impl Extractor {
         ExtractorBuilder::new()
     }
 
+    /// Check whether a byte is a word boundary for this extractor
+    #[inline(always)]
+    fn is_boundary(&self, b: u8) -> bool {
+        self.boundary_chars[b as usize]
+    }
+
     /// Extract patterns from a line using an iterator (zero-allocation)
     ///
     /// Returns an iterator that lazily extracts matches as you iterate.
@@ -397,7 +659,7 @@ impl Extractor {
         // Pre-compute word boundaries once if any boundary-dependent extractors are enabled
         // This eliminates redundant scans across Bitcoin, hash, and Monero extractors
         let boundaries = if self.extract_hashes || self.extract_bitcoin || self.extract_monero {
-            Some(find_word_boundaries(chunk))
+            Some(find_word_boundaries(chunk, &self.boundary_chars))
         } else {
             None
         };
@@ -441,6 +703,11 @@ impl Extractor {
             self.extract_monero_chunk_with_boundaries(chunk, &mut matches, boundaries_ref);
         }
 
+        // Extract URLs (://) in one pass
+        if self.extract_urls {
+            self.extract_urls_internal(chunk, &mut matches);
+        }
+
         matches
     }
 
@@ -484,11 +751,21 @@ impl Extractor {
         self.extract_monero
     }
 
+    /// Check if URL extraction is enabled
+    pub fn extract_urls(&self) -> bool {
+        self.extract_urls
+    }
+
     /// Get minimum domain labels requirement
     pub fn min_domain_labels(&self) -> usize {
         self.min_domain_labels
     }
 
+    /// Check if domains with non-ASCII labels are extracted
+    pub fn allow_unicode_domains(&self) -> bool {
+        self.allow_unicode_domains
+    }
+
     /// Extract domains from entire chunk in one pass
     fn extract_domains_chunk<'a>(&'a self, chunk: &'a [u8], matches: &mut Vec<Match<'a>>) {
         use memchr::memchr;
@@ -573,11 +850,20 @@ impl Extractor {
                 break;
             }
 
+            // ASCII-only: an IDN label would be cut in half, so drop it; any
+            // other non-ASCII character (e.g. `…`) just ends the domain
+            if b >= 0x80 && !self.allow_unicode_domains {
+                if char_before(line, start).is_some_and(char::is_alphanumeric) {
+                    return None;
+                }
+                break;
+            }
+
             start -= 1;
         }
 
         // Check word boundary at end if required (also uses fast lookup)
-        if self.require_word_boundaries && tld_end < line.len() && !is_boundary_fast(line[tld_end])
+        if self.require_word_boundaries && tld_end < line.len() && !self.is_boundary(line[tld_end])
         {
             return None; // Domain continues - not a real boundary
         }
@@ -620,7 +906,16 @@ impl Extractor {
         label_count += 1;
 
         // Check minimum label count
-        label_count >= self.min_domain_labels
+        if label_count < self.min_domain_labels {
+            return false;
+        }
+
+        // The line was UTF-8 validated before TLD matching, so this only fails
+        // on a span that splits a character, which never passes the TLD check
+        match (&self.domain_denylist, std::str::from_utf8(domain_bytes)) {
+            (Some(denylist), Ok(domain)) => denylist.find_all(domain).is_empty(),
+            _ => true,
+        }
     }
 
     /// Validate a single domain label (bytes between dots)
@@ -764,7 +1059,7 @@ impl Extractor {
         let mut octets = Vec::new();
 
         // Check word boundary at start if required
-        if self.require_word_boundaries && start > 0 && !is_word_boundary(line[start - 1]) {
+        if self.require_word_boundaries && start > 0 && !self.is_boundary(line[start - 1]) {
             return None;
         }
 
@@ -802,7 +1097,7 @@ impl Extractor {
         }
 
         // Check word boundary at end if required
-        if self.require_word_boundaries && pos < line.len() && !is_word_boundary(line[pos]) {
+        if self.require_word_boundaries && pos < line.len() && !self.is_boundary(line[pos]) {
             return None;
         }
 
@@ -846,7 +1141,7 @@ impl Extractor {
         }
 
         // Check word boundary at start if required
-        if self.require_word_boundaries && start > 0 && !is_word_boundary(line[start - 1]) {
+        if self.require_word_boundaries && start > 0 && !self.is_boundary(line[start - 1]) {
             return None;
         }
 
@@ -861,7 +1156,7 @@ impl Extractor {
         }
 
         // Check word boundary at end if required
-        if self.require_word_boundaries && end < line.len() && !is_word_boundary(line[end]) {
+        if self.require_word_boundaries && end < line.len() && !self.is_boundary(line[end]) {
             return None;
         }
 
@@ -890,13 +1185,7 @@ impl Extractor {
         // 2. Must have a valid TLD from the public suffix list
         //    This rejects IP addresses ("192.168.1.222") and fake TLDs ("Uv3.peer")
         if let Some(tld_matcher) = self.tld_matcher.as_ref() {
-            let tld_matches = tld_matcher.find_matches_with_positions_bytes(domain_part);
-            // Must have at least one TLD match that ends at the domain boundary
-            let has_valid_tld = tld_matches.iter().any(|(end_pos, _)| {
-                *end_pos == domain_part.len()
-                    && (*end_pos >= domain_part.len() || !is_domain_char(domain_part[*end_pos]))
-            });
-            if !has_valid_tld {
+            if !ends_with_known_tld(tld_matcher, domain_part) {
                 return None;
             }
         }
@@ -904,6 +1193,120 @@ impl Extractor {
         Some((start, end))
     }
 
+    /// Extract URLs around each `://` separator
+    fn extract_urls_internal<'a>(&self, line: &'a [u8], matches: &mut Vec<Match<'a>>) {
+        for sep_pos in self.scheme_sep_finder.find_iter(line) {
+            if let Some(url_span) = self.extract_url_at(line, sep_pos) {
+                if let Ok(url_str) = std::str::from_utf8(&line[url_span.0..url_span.1]) {
+                    matches.push(Match {
+                        item: ExtractedItem::Url(url_str),
+                        span: url_span,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Extract the URL whose `://` starts at `sep_pos`
+    fn extract_url_at(&self, line: &[u8], sep_pos: usize) -> Option<(usize, usize)> {
+        // Scheme: letters, digits, '+', '-', '.', starting with a letter
+        let mut start = sep_pos;
+        while start > 0 && is_scheme_char(line[start - 1]) {
+            start -= 1;
+        }
+        while start < sep_pos && !line[start].is_ascii_alphabetic() {
+            start += 1;
+        }
+        if start == sep_pos {
+            return None; // Empty scheme
+        }
+
+        // Check word boundary at start if required
+        if self.require_word_boundaries && start > 0 && !self.is_boundary(line[start - 1]) {
+            return None;
+        }
+
+        // Expand forwards to whitespace, quotes, or angle brackets
+        let authority_start = sep_pos + 3;
+        let mut end = authority_start;
+        while end < line.len() && !is_url_terminator(line[end]) {
+            end += 1;
+        }
+
+        // Trailing punctuation usually ends the sentence, not the URL; keep a
+        // closing paren only if it balances one inside (e.g. wiki links)
+        while end > authority_start {
+            match line[end - 1] {
+                b'.' | b',' | b';' | b':' | b'!' | b'?' => end -= 1,
+                b')' => {
+                    let url = &line[start..end];
+                    let opens = url.iter().filter(|&&b| b == b'(').count();
+                    let closes = url.iter().filter(|&&b| b == b')').count();
+                    if closes <= opens {
+                        break;
+                    }
+                    end -= 1;
+                }
+                _ => break,
+            }
+        }
+
+        // Authority runs to the first '/', '?', or '#'
+        let authority_end = line[authority_start..end]
+            .iter()
+            .position(|&b| matches!(b, b'/' | b'?' | b'#'))
+            .map_or(end, |i| authority_start + i);
+        let authority = &line[authority_start..authority_end];
+
+        if self.is_valid_url_host(authority) {
+            Some((start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Check the host of a URL authority (`user@host:port`)
+    ///
+    /// The host must be an IPv4 address, a bracketed IPv6 address, or a
+    /// domain that passes the same TLD and label checks as extracted domains.
+    fn is_valid_url_host(&self, authority: &[u8]) -> bool {
+        let host = match authority.iter().rposition(|&b| b == b'@') {
+            Some(at) => &authority[at + 1..],
+            None => authority,
+        };
+
+        if let Some(bracketed) = host.strip_prefix(b"[") {
+            let Some(close) = bracketed.iter().position(|&b| b == b']') else {
+                return false;
+            };
+            return std::str::from_utf8(&bracketed[..close])
+                .is_ok_and(|ip| ip.parse::<Ipv6Addr>().is_ok());
+        }
+
+        // Drop the port
+        let host = match host.iter().rposition(|&b| b == b':') {
+            Some(colon) if host[colon + 1..].iter().all(u8::is_ascii_digit) => &host[..colon],
+            Some(_) => return false,
+            None => host,
+        };
+        if host.is_empty() {
+            return false;
+        }
+
+        if std::str::from_utf8(host).is_ok_and(|ip| ip.parse::<Ipv4Addr>().is_ok()) {
+            return true;
+        }
+
+        let Some(tld_matcher) = self.tld_matcher.as_ref() else {
+            return false;
+        };
+        host.iter()
+            .all(|&b| is_domain_char_fast(b) && (b < 0x80 || self.allow_unicode_domains))
+            && host[0] != b'.'
+            && ends_with_known_tld(tld_matcher, host)
+            && self.is_valid_domain(host, (0, host.len()))
+    }
+
     /// Extract IPv6 addresses: only look for :: (double colon compression)
     ///
     /// Strategy: >95% of real IPv6 uses :: compression. This is the sweet spot:
@@ -1160,7 +1563,7 @@ impl Extractor {
         let boundaries = if let Some(b) = boundaries {
             b
         } else {
-            owned_boundaries = find_word_boundaries(chunk);
+            owned_boundaries = find_word_boundaries(chunk, &self.boundary_chars);
             &owned_boundaries
         };
 
@@ -1217,7 +1620,7 @@ impl Extractor {
         let boundaries = if let Some(b) = boundaries {
             b
         } else {
-            owned_boundaries = find_word_boundaries(chunk);
+            owned_boundaries = find_word_boundaries(chunk, &self.boundary_chars);
             &owned_boundaries
         };
 
@@ -1273,14 +1676,14 @@ impl Extractor {
             }
 
             // Check word boundary before if required
-            if self.require_word_boundaries && start > 0 && !is_boundary_fast(chunk[start - 1]) {
+            if self.require_word_boundaries && start > 0 && !self.is_boundary(chunk[start - 1]) {
                 continue;
             }
 
             let end = start + 42; // 0x + 40 hex chars
 
             // Check word boundary after
-            if self.require_word_boundaries && end < chunk.len() && !is_boundary_fast(chunk[end]) {
+            if self.require_word_boundaries && end < chunk.len() && !self.is_boundary(chunk[end]) {
                 continue;
             }
 
@@ -1315,7 +1718,7 @@ impl Extractor {
         let boundaries = if let Some(b) = boundaries {
             b
         } else {
-            owned_boundaries = find_word_boundaries(chunk);
+            owned_boundaries = find_word_boundaries(chunk, &self.boundary_chars);
             &owned_boundaries
         };
 
@@ -1452,6 +1855,11 @@ impl<'a> ExtractIter<'a> {
             extractor.extract_monero_chunk(line, &mut matches);
         }
 
+        // Extract URLs
+        if extractor.extract_urls {
+            extractor.extract_urls_internal(line, &mut matches);
+        }
+
         Self {
             extractor,
             line,
@@ -1494,7 +1902,7 @@ static TLD_AUTOMATON_ALIGNED: AlignedTldData =
 const TLD_AUTOMATON: &[u8] = &TLD_AUTOMATON_ALIGNED.0;
 
 /// Compile-time boundary character lookup table for O(1) checking
-/// This replaces the branch-heavy is_word_boundary() function with a single array lookup.
+/// Copied into each extractor, which may add to or replace it.
 /// Marked as boundary: whitespace, punctuation commonly found in logs
 static BOUNDARY_LOOKUP: [bool; 256] = {
     let mut table = [false; 256];
@@ -1559,29 +1967,46 @@ static DOMAIN_CHAR_LOOKUP: [bool; 256] = {
     table
 };
 
-/// Fast boundary check using lookup table (branch-free, O(1))
-#[inline(always)]
-fn is_boundary_fast(b: u8) -> bool {
-    // SAFETY: b is u8, so it's always a valid index into [0..256)
-    unsafe { *BOUNDARY_LOOKUP.get_unchecked(b as usize) }
-}
-
 /// Character classification helpers for fast boundary scanning
 #[inline]
 fn is_domain_char(b: u8) -> bool {
     b.is_ascii_alphanumeric() || b == b'-' || b == b'.'
 }
 
+/// Decode the UTF-8 character ending just before `end`, if there is one
+fn char_before(line: &[u8], end: usize) -> Option<char> {
+    let start = (end.saturating_sub(4)..end)
+        .rev()
+        .find(|&i| line[i] & 0xC0 != 0x80)?;
+    std::str::from_utf8(&line[start..end]).ok()?.chars().next()
+}
+
+/// Whether a public suffix from `tld_matcher` ends exactly at the end of `name`
+fn ends_with_known_tld(tld_matcher: &Paraglob, name: &[u8]) -> bool {
+    tld_matcher
+        .find_matches_with_positions_bytes(name)
+        .iter()
+        .any(|&(end_pos, _)| end_pos == name.len())
+}
+
+/// URL scheme characters (RFC 3986: ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ))
 #[inline]
-fn is_email_local_char(b: u8) -> bool {
-    // Simplified RFC 5322 - common chars in local part
-    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b'+')
+fn is_scheme_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')
+}
+
+/// Bytes that end a URL: whitespace, controls, quotes, and angle brackets
+#[inline]
+fn is_url_terminator(b: u8) -> bool {
+    b.is_ascii_whitespace()
+        || b.is_ascii_control()
+        || matches!(b, b'"' | b'\'' | b'<' | b'>' | b'`')
 }
 
 #[inline]
-fn is_word_boundary(b: u8) -> bool {
-    // Delegate to fast lookup table
-    is_boundary_fast(b)
+fn is_email_local_char(b: u8) -> bool {
+    // Simplified RFC 5322 - common chars in local part
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b'+')
 }
 
 /// Fast domain character check using lookup table (branch-free, O(1))
@@ -1640,7 +2065,7 @@ fn is_all_hex_simd(bytes: &[u8]) -> bool {
 /// A token is a sequence of non-boundary characters
 ///
 /// Public for use by processing infrastructure to pre-compute boundaries
-pub(crate) fn find_word_boundaries(chunk: &[u8]) -> Vec<usize> {
+pub(crate) fn find_word_boundaries(chunk: &[u8], boundary_chars: &[bool; 256]) -> Vec<usize> {
     let mut boundaries = Vec::new();
 
     if chunk.is_empty() {
@@ -1648,14 +2073,14 @@ pub(crate) fn find_word_boundaries(chunk: &[u8]) -> Vec<usize> {
     }
 
     // Track if we're currently inside a token
-    let mut in_token = !is_boundary_fast(chunk[0]);
+    let mut in_token = !boundary_chars[chunk[0] as usize];
     if in_token {
         boundaries.push(0); // Start of first token
     }
 
     // Scan for transitions
     for (i, &byte) in chunk.iter().enumerate().skip(1) {
-        let is_boundary = is_boundary_fast(byte);
+        let is_boundary = boundary_chars[byte as usize];
 
         if in_token && is_boundary {
             // End of token
@@ -1837,6 +2262,7 @@ mod tests {
                 ExtractedItem::Bitcoin(_) => assert_eq!(type_name, "Bitcoin"),
                 ExtractedItem::Ethereum(_) => assert_eq!(type_name, "Ethereum"),
                 ExtractedItem::Monero(_) => assert_eq!(type_name, "Monero"),
+                ExtractedItem::Url(_) => assert_eq!(type_name, "URL"),
             }
         }
     }
@@ -1865,6 +2291,36 @@ mod tests {
         assert_eq!(ip_match.unwrap().item.as_value(), "192.168.1.1");
     }
 
+    #[test]
+    fn test_match_to_owned() {
+        let extractor = Extractor::new().unwrap();
+
+        let owned: Vec<OwnedMatch> = {
+            let line = b"Check example.com user@test.com 192.168.1.1 2001:db8::1".to_vec();
+            let borrowed: Vec<_> = extractor.extract_from_line(&line).collect();
+            let owned: Vec<_> = borrowed.iter().map(|m| m.to_owned()).collect();
+            for (b, o) in borrowed.iter().zip(&owned) {
+                assert_eq!(o.item.as_item(), b.item);
+                assert_eq!(o.span, b.span);
+            }
+            owned
+        };
+
+        // Outlives the line it was extracted from
+        assert!(owned
+            .iter()
+            .any(|m| m.item == ExtractedItemOwned::Domain("example.com".to_string())));
+        assert!(owned
+            .iter()
+            .any(|m| m.item == ExtractedItemOwned::Ipv4(Ipv4Addr::new(192, 168, 1, 1))));
+        let email = owned
+            .iter()
+            .find(|m| matches!(m.item, ExtractedItemOwned::Email(_)))
+            .unwrap();
+        assert_eq!(email.item.type_name(), "Email");
+        assert_eq!(email.item.as_value(), "user@test.com");
+    }
+
     #[test]
     fn test_hash_type_name() {
         assert_eq!(HashType::Md5.type_name(), "MD5");
@@ -1924,9 +2380,10 @@ mod tests {
         assert!(is_email_local_char(b'+'));
         assert!(!is_email_local_char(b'@'));
 
-        assert!(is_word_boundary(b' '));
-        assert!(is_word_boundary(b','));
-        assert!(!is_word_boundary(b'a'));
+        let extractor = Extractor::new().unwrap();
+        assert!(extractor.is_boundary(b' '));
+        assert!(extractor.is_boundary(b','));
+        assert!(!extractor.is_boundary(b'a'));
     }
 
     #[test]
@@ -1979,6 +2436,88 @@ mod tests {
         assert_eq!(matches[0].as_str(line), "www.example.com");
     }
 
+    fn urls<'a>(extractor: &'a Extractor, line: &'a [u8]) -> Vec<&'a str> {
+        extractor
+            .extract_from_line(line)
+            .filter_map(|m| match m.item {
+                ExtractedItem::Url(url) => {
+                    assert_eq!(m.as_str(line), url, "span must cover the whole URL");
+                    Some(url)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_url_extraction() {
+        let extractor = Extractor::builder().extract_urls(true).build().unwrap();
+        assert!(extractor.extract_urls());
+        assert!(!Extractor::new().unwrap().extract_urls());
+
+        let line = br#"GET "https://www.example.com/a/b?q=1&r=2#top" from http://10.0.0.1:8080/x"#;
+        assert_eq!(
+            urls(&extractor, line),
+            vec![
+                "https://www.example.com/a/b?q=1&r=2#top",
+                "http://10.0.0.1:8080/x"
+            ]
+        );
+
+        // Userinfo, port, and bracketed IPv6 hosts
+        let line = b"ftp://user:pw@files.example.org:21/pub http://[2001:db8::1]/";
+        assert_eq!(
+            urls(&extractor, line),
+            vec![
+                "ftp://user:pw@files.example.org:21/pub",
+                "http://[2001:db8::1]/"
+            ]
+        );
+
+        // Sentence punctuation is dropped, balanced parens are kept
+        let line = b"See https://en.wikipedia.org/wiki/Foo_(bar). Or (https://example.com/x).";
+        assert_eq!(
+            urls(&extractor, line),
+            vec![
+                "https://en.wikipedia.org/wiki/Foo_(bar)",
+                "https://example.com/x"
+            ]
+        );
+
+        // The host is still extracted as a domain, and chunks agree with lines
+        let line = b"visit https://evil.example.com/login\nnext";
+        let chunk_urls: Vec<_> = extractor
+            .extract_from_chunk(line)
+            .into_iter()
+            .filter(|m| matches!(m.item, ExtractedItem::Url(_)))
+            .map(|m| m.span)
+            .collect();
+        assert_eq!(chunk_urls, vec![(6, 36)]);
+        assert!(extractor
+            .extract_from_line(line)
+            .any(|m| m.item == ExtractedItem::Domain("evil.example.com")));
+    }
+
+    #[test]
+    fn test_url_reject_unknown_host() {
+        let extractor = Extractor::builder().extract_urls(true).build().unwrap();
+
+        for line in [
+            &b"https://notarealhost/x"[..],
+            b"https://host.notarealtld/x",
+            b"https:///path",
+            b"://example.com",
+            b"http://example.com:port/",
+            b"xhttp\x01://example.com",
+        ] {
+            assert!(
+                urls(&extractor, line).is_empty(),
+                "{}",
+                String::from_utf8_lossy(line)
+            );
+        }
+    }
+
     #[test]
     fn test_domain_min_labels() {
         let extractor = Extractor::builder()
@@ -1995,6 +2534,43 @@ mod tests {
         assert_eq!(matches[0].as_str(line), "api.test.example.com");
     }
 
+    #[test]
+    fn test_domain_denylist() {
+        let line = b"GET /v2.example.com/setup.py from Api.Tracker.net and evil.example.com";
+        let domains = |extractor: &Extractor| -> Vec<String> {
+            extractor
+                .extract_from_line(line)
+                .filter(|m| matches!(m.item, ExtractedItem::Domain(_)))
+                .map(|m| m.as_str(line).to_string())
+                .collect()
+        };
+
+        let extractor = Extractor::builder()
+            .domain_denylist(&["v?.example.com", "api.tracker.net"])
+            .build()
+            .unwrap();
+        let found = domains(&extractor);
+        assert!(found.contains(&"setup.py".to_string()));
+        assert!(found.contains(&"evil.example.com".to_string()));
+        assert!(!found.iter().any(|d| d == "v2.example.com"));
+        assert!(!found
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case("api.tracker.net")));
+
+        let extractor = Extractor::builder()
+            .default_domain_denylist(true)
+            .build()
+            .unwrap();
+        let found = domains(&extractor);
+        assert!(!found.contains(&"setup.py".to_string()));
+        assert!(found.contains(&"v2.example.com".to_string()));
+
+        assert!(Extractor::builder()
+            .domain_denylist(&["[a-"])
+            .build()
+            .is_err());
+    }
+
     #[test]
     fn test_domain_extraction_log_line() {
         let extractor = Extractor::new().unwrap();
@@ -2077,6 +2653,59 @@ mod tests {
         assert!(domain.contains("ünchen") || domain.contains("xn--"));
     }
 
+    #[test]
+    fn test_ascii_only_domains() {
+        let extractor = Extractor::builder()
+            .allow_unicode_domains(false)
+            .build()
+            .unwrap();
+        assert!(!extractor.allow_unicode_domains());
+
+        let line = "Visit münchen.de, xn--mnchen-3ya.de and …evil.com".as_bytes();
+        let domains: Vec<&str> = extractor
+            .extract_from_line(line)
+            .filter_map(|m| match m.item {
+                ExtractedItem::Domain(d) => Some(d),
+                _ => None,
+            })
+            .collect();
+
+        // No "nchen.de" fragment from the IDN
+        assert_eq!(domains, vec!["xn--mnchen-3ya.de", "evil.com"]);
+    }
+
+    #[test]
+    fn test_custom_boundary_chars() {
+        let line = b"src=10.0.0.1|dst=`evil.example.com`";
+        let values = |extractor: &Extractor| -> Vec<String> {
+            let mut values = extractor
+                .extract_from_line(line)
+                .map(|m| m.item.as_value())
+                .collect::<Vec<_>>();
+            values.sort();
+            values
+        };
+
+        // '|' and '`' are not boundaries by default
+        let extractor = Extractor::new().unwrap();
+        assert!(!values(&extractor).contains(&"10.0.0.1".to_string()));
+
+        let extractor = Extractor::builder().boundary_chars(b"|`").build().unwrap();
+        assert!(extractor.is_boundary(b' '));
+        assert_eq!(values(&extractor), vec!["10.0.0.1", "evil.example.com"]);
+
+        // Replacing the set drops the defaults: the space no longer bounds the IP
+        let extractor = Extractor::builder()
+            .only_boundary_chars(b"|")
+            .build()
+            .unwrap();
+        assert!(!extractor.is_boundary(b' '));
+        assert!(extractor
+            .extract_from_line(b"ip 10.0.0.1|x")
+            .next()
+            .is_none());
+    }
+
     #[test]
     fn test_mixed_unicode_ascii_domains() {
         let extractor = Extractor::new().unwrap();
@@ -3074,6 +3703,38 @@ mod tests {
         assert_eq!(hashes.len(), 0, "Should reject UUIDs with dashes");
     }
 
+    #[test]
+    fn test_hash_no_substring_matches() {
+        let extractor = Extractor::new().unwrap();
+        let sha256 = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        let hashes = |line: &str| -> Vec<(HashType, String)> {
+            extractor
+                .extract_from_line(line.as_bytes())
+                .filter_map(|m| match m.item {
+                    ExtractedItem::Hash(ht, h) => Some((ht, h.to_string())),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // A 128-hex run is one SHA512, never two SHA256 halves
+        let sha512 = format!("{}{}", sha256, sha256);
+        assert_eq!(
+            hashes(&format!("digest {} end", sha512)),
+            vec![(HashType::Sha512, sha512.clone())]
+        );
+
+        // Hex runs that are not a hash length yield nothing
+        let run = format!("{}{}", sha512, sha256);
+        assert!(hashes(&format!("blob {} end", run)).is_empty());
+
+        // Hex embedded in a word is not captured
+        assert!(hashes(&format!("commit_{} end", sha256)).is_empty());
+        assert!(hashes(&format!("rev{}x end", &sha256[..40])).is_empty());
+        assert!(hashes(&format!("id-{} end", &sha256[..32])).is_empty());
+    }
+
     // ===== CRYPTOCURRENCY ADDRESS TESTS =====
 
     #[test]
@@ -11,8 +11,15 @@
 //! - `[abc]` - Matches one character from the set (a, b, or c)
 //! - `[!abc]` or `[^abc]` - Matches one character NOT in the set
 //! - `[a-z]` - Matches one character in the range (a through z)
+//! - `{a,b}` - Matches either alternative; alternatives may hold wildcards and nest
 //! - `\x` - Escapes special character x (literal *)
 //!
+//! Brace alternations are expanded when the pattern is parsed, so
+//! `*.{com,net}` behaves exactly like the two patterns `*.com` and `*.net`.
+//! A brace group without a top-level comma (`{a}`) or without a closing
+//! brace is literal text. At most [`MAX_BRACE_EXPANSION`] alternatives are
+//! allowed per pattern.
+//!
 //! # Examples
 //!
 //! ```
@@ -34,12 +41,25 @@
 //! let pattern = GlobPattern::new("file[!0-9].txt", MatchMode::CaseSensitive)?;
 //! assert!(pattern.matches("fileA.txt"));
 //! assert!(!pattern.matches("file1.txt"));
+//!
+//! // Brace alternation
+//! let pattern = GlobPattern::new("*.{com,net}", MatchMode::CaseSensitive)?;
+//! assert!(pattern.matches("evil.com"));
+//! assert!(pattern.matches("evil.net"));
+//! assert!(!pattern.matches("evil.org"));
 //! # Ok::<(), matchy::ParaglobError>(())
 //! ```
 
 use crate::error::ParaglobError;
 use std::fmt;
 
+/// Most alternatives a single pattern may expand to
+///
+/// Each brace group multiplies the count, so `{a,b}{c,d}{e,f}` is 8
+/// alternatives; patterns over this limit are rejected instead of building
+/// an oversized automaton.
+pub const MAX_BRACE_EXPANSION: usize = 1024;
+
 /// Match mode for glob patterns.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MatchMode {
@@ -84,8 +104,8 @@ pub enum CharClassItem {
 pub struct GlobPattern {
     /// Original pattern string
     pattern: String,
-    /// Parsed segments
-    segments: Vec<GlobSegment>,
+    /// Parsed segments of each brace alternative (one entry without braces)
+    alternatives: Vec<Vec<GlobSegment>>,
     /// Match mode
     mode: MatchMode,
 }
@@ -100,7 +120,8 @@ impl GlobPattern {
     ///
     /// # Errors
     ///
-    /// Returns an error if the pattern is malformed (e.g., unclosed brackets).
+    /// Returns an error if the pattern is malformed (e.g., unclosed brackets)
+    /// or expands to more than [`MAX_BRACE_EXPANSION`] alternatives.
     ///
     /// # Examples
     ///
@@ -112,10 +133,13 @@ impl GlobPattern {
     /// # Ok::<(), matchy::ParaglobError>(())
     /// ```
     pub fn new(pattern: &str, mode: MatchMode) -> Result<Self, ParaglobError> {
-        let segments = Self::parse(pattern, mode)?;
+        let alternatives = expand_braces(pattern)?
+            .iter()
+            .map(|alternative| Self::parse(alternative, mode))
+            .collect::<Result<_, _>>()?;
         Ok(Self {
             pattern: pattern.to_string(),
-            segments,
+            alternatives,
             mode,
         })
     }
@@ -131,8 +155,18 @@ impl GlobPattern {
     }
 
     /// Returns the parsed segments.
+    ///
+    /// For a pattern with brace alternations these are the segments of the
+    /// first alternative; see [`alternatives`](Self::alternatives).
     pub fn segments(&self) -> &[GlobSegment] {
-        &self.segments
+        &self.alternatives[0]
+    }
+
+    /// Returns the parsed segments of every brace alternative, in order.
+    ///
+    /// A pattern without brace alternations has exactly one.
+    pub fn alternatives(&self) -> &[Vec<GlobSegment>] {
+        &self.alternatives
     }
 
     /// Checks if the pattern matches the given text.
@@ -152,7 +186,46 @@ impl GlobPattern {
         // Limit backtracking steps to prevent OOM with pathological patterns
         // This prevents exponential backtracking in patterns like *a*b*c*d*e*
         let mut steps_remaining = 100_000;
-        self.matches_impl(text, 0, 0, &mut steps_remaining)
+        self.alternatives
+            .iter()
+            .any(|segments| self.matches_impl(segments, text, 0, 0, &mut steps_remaining, None))
+    }
+
+    /// Returns the text matched by each wildcard, or `None` if the pattern doesn't match.
+    ///
+    /// Every `*`, `?`, and `[...]` in the pattern captures one entry, in
+    /// pattern order, like regex capture groups. When a `*` could span
+    /// different amounts of text, it takes the shortest that lets the rest of
+    /// the pattern match. Patterns without wildcards capture nothing. With
+    /// brace alternations, the wildcards are those of the first alternative
+    /// that matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matchy::glob::{GlobPattern, MatchMode};
+    ///
+    /// let pattern = GlobPattern::new("*.evil.com", MatchMode::CaseSensitive)?;
+    /// assert_eq!(pattern.captures("sub.evil.com"), Some(vec!["sub"]));
+    /// assert_eq!(pattern.captures("evil.org"), None);
+    ///
+    /// let pattern = GlobPattern::new("log-??-*.txt", MatchMode::CaseSensitive)?;
+    /// assert_eq!(pattern.captures("log-07-app.txt"), Some(vec!["0", "7", "app"]));
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn captures<'t>(&self, text: &'t str) -> Option<Vec<&'t str>> {
+        let mut steps_remaining = 100_000;
+        let mut spans = Vec::new();
+        let matched = self.alternatives.iter().any(|segments| {
+            spans.clear();
+            self.matches_impl(segments, text, 0, 0, &mut steps_remaining, Some(&mut spans))
+        });
+        matched.then(|| {
+            spans
+                .into_iter()
+                .map(|(start, end)| &text[start..end])
+                .collect()
+        })
     }
 
     /// Recursive matching implementation.
@@ -161,16 +234,20 @@ impl GlobPattern {
     ///
     /// # Arguments
     ///
+    /// * `segments` - The alternative being matched
     /// * `text` - The text to match against
     /// * `text_pos` - Current position in the text (byte offset)
     /// * `seg_idx` - Current segment index in the pattern
     /// * `steps_remaining` - Mutable counter to limit backtracking steps
+    /// * `spans` - If set, records the byte range matched by each wildcard
     fn matches_impl(
         &self,
+        segments: &[GlobSegment],
         text: &str,
         text_pos: usize,
         seg_idx: usize,
         steps_remaining: &mut usize,
+        mut spans: Option<&mut Vec<(usize, usize)>>,
     ) -> bool {
         // Check step limit to prevent OOM from exponential backtracking
         if *steps_remaining == 0 {
@@ -179,11 +256,11 @@ impl GlobPattern {
         *steps_remaining -= 1;
 
         // If we've consumed all segments, we match if we've also consumed all text
-        if seg_idx >= self.segments.len() {
+        if seg_idx >= segments.len() {
             return text_pos >= text.len();
         }
 
-        match &self.segments[seg_idx] {
+        match &segments[seg_idx] {
             GlobSegment::Literal(lit) => {
                 // Try to match literal at current position
                 let remaining = &text[text_pos..];
@@ -221,7 +298,14 @@ impl GlobPattern {
                 };
 
                 if matches {
-                    self.matches_impl(text, text_pos + advance_bytes, seg_idx + 1, steps_remaining)
+                    self.matches_impl(
+                        segments,
+                        text,
+                        text_pos + advance_bytes,
+                        seg_idx + 1,
+                        steps_remaining,
+                        spans,
+                    )
                 } else {
                     false
                 }
@@ -230,7 +314,8 @@ impl GlobPattern {
             GlobSegment::Question => {
                 // Match exactly one character
                 if let Some(ch) = text[text_pos..].chars().next() {
-                    self.matches_impl(text, text_pos + ch.len_utf8(), seg_idx + 1, steps_remaining)
+                    let span = (text_pos, text_pos + ch.len_utf8());
+                    self.matches_wildcard(segments, text, span, seg_idx, steps_remaining, spans)
                 } else {
                     false
                 }
@@ -268,12 +353,8 @@ impl GlobPattern {
                     let matches = if *negated { !in_class } else { in_class };
 
                     if matches {
-                        self.matches_impl(
-                            text,
-                            text_pos + ch.len_utf8(),
-                            seg_idx + 1,
-                            steps_remaining,
-                        )
+                        let span = (text_pos, text_pos + ch.len_utf8());
+                        self.matches_wildcard(segments, text, span, seg_idx, steps_remaining, spans)
                     } else {
                         false
                     }
@@ -287,7 +368,10 @@ impl GlobPattern {
                 // Try matching with zero characters first (greedy is handled by trying longest first)
 
                 // Special case: if star is at the end, it matches everything remaining
-                if seg_idx + 1 >= self.segments.len() {
+                if seg_idx + 1 >= segments.len() {
+                    if let Some(spans) = spans {
+                        spans.push((text_pos, text.len()));
+                    }
                     return true;
                 }
 
@@ -297,7 +381,14 @@ impl GlobPattern {
                 // slicing in the middle of UTF-8 characters
                 let mut pos = text_pos;
                 loop {
-                    if self.matches_impl(text, pos, seg_idx + 1, steps_remaining) {
+                    if self.matches_wildcard(
+                        segments,
+                        text,
+                        (text_pos, pos),
+                        seg_idx,
+                        steps_remaining,
+                        spans.as_deref_mut(),
+                    ) {
                         return true;
                     }
 
@@ -316,6 +407,35 @@ impl GlobPattern {
         }
     }
 
+    /// Continues matching after the wildcard at `seg_idx` consumed `span`,
+    /// recording the span if the rest of the pattern matches.
+    fn matches_wildcard(
+        &self,
+        segments: &[GlobSegment],
+        text: &str,
+        span: (usize, usize),
+        seg_idx: usize,
+        steps_remaining: &mut usize,
+        spans: Option<&mut Vec<(usize, usize)>>,
+    ) -> bool {
+        let Some(spans) = spans else {
+            return self.matches_impl(segments, text, span.1, seg_idx + 1, steps_remaining, None);
+        };
+        spans.push(span);
+        if self.matches_impl(
+            segments,
+            text,
+            span.1,
+            seg_idx + 1,
+            steps_remaining,
+            Some(spans),
+        ) {
+            return true;
+        }
+        spans.pop();
+        false
+    }
+
     /// Parses a glob pattern string into segments.
     fn parse(pattern: &str, _mode: MatchMode) -> Result<Vec<GlobSegment>, ParaglobError> {
         let mut segments = Vec::new();
@@ -470,6 +590,124 @@ impl fmt::Display for GlobPattern {
     }
 }
 
+/// Expands the brace alternations in a pattern into plain glob patterns.
+///
+/// Returns the alternatives in order, with escapes and character classes
+/// left as they were. A pattern without brace alternations comes back
+/// unchanged as the only alternative. `\{` and braces inside `[...]` are
+/// literal, as is a group with no top-level comma or no closing brace.
+///
+/// # Errors
+///
+/// Returns an error if the pattern expands to more than
+/// [`MAX_BRACE_EXPANSION`] alternatives.
+///
+/// # Examples
+///
+/// ```
+/// use matchy::glob::expand_braces;
+///
+/// assert_eq!(
+///     expand_braces("log-{2023,2024}-*.txt")?,
+///     vec!["log-2023-*.txt", "log-2024-*.txt"]
+/// );
+/// assert_eq!(expand_braces("a{b,c{d,e}}")?, vec!["ab", "acd", "ace"]);
+/// assert_eq!(expand_braces(r"\{a,b}")?, vec![r"\{a,b}"]);
+/// # Ok::<(), matchy::ParaglobError>(())
+/// ```
+pub fn expand_braces(pattern: &str) -> Result<Vec<String>, ParaglobError> {
+    let Some((open, close, commas)) = find_brace_group(pattern) else {
+        return Ok(vec![pattern.to_string()]);
+    };
+
+    let prefix = &pattern[..open];
+    let suffixes = expand_braces(&pattern[close + 1..])?;
+    let mut bounds = vec![open];
+    bounds.extend(commas);
+    bounds.push(close);
+
+    let mut expanded = Vec::new();
+    for window in bounds.windows(2) {
+        for alternative in expand_braces(&pattern[window[0] + 1..window[1]])? {
+            if expanded.len() + suffixes.len() > MAX_BRACE_EXPANSION {
+                return Err(ParaglobError::InvalidPattern(format!(
+                    "Brace expansion of '{}' exceeds {} alternatives",
+                    pattern, MAX_BRACE_EXPANSION
+                )));
+            }
+            for suffix in &suffixes {
+                expanded.push(format!("{}{}{}", prefix, alternative, suffix));
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+/// Finds the first brace group with a top-level comma.
+///
+/// Returns the byte offsets of its `{`, its `}`, and its top-level commas.
+fn find_brace_group(pattern: &str) -> Option<(usize, usize, Vec<usize>)> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'[' => i = skip_char_class(bytes, i),
+            b'{' => {
+                if let Some((close, commas)) = match_brace(bytes, i) {
+                    if !commas.is_empty() {
+                        return Some((i, close, commas));
+                    }
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Finds the `}` closing the `{` at `open`, and the commas directly inside it.
+fn match_brace(bytes: &[u8], open: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => {
+                i = skip_char_class(bytes, i);
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some((i, commas)),
+            b'}' => depth -= 1,
+            b',' if depth == 0 => commas.push(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the offset just past the character class starting at `open`.
+///
+/// Follows [`GlobPattern`]'s parser: a `]` right after `[`, `[!`, or `[^` is
+/// part of the class. An unclosed class runs to the end of the pattern.
+fn skip_char_class(bytes: &[u8], open: usize) -> usize {
+    let mut i = open + 1;
+    if matches!(bytes.get(i), Some(b'!' | b'^')) {
+        i += 1;
+    }
+    if bytes.get(i) == Some(&b']') {
+        i += 1;
+    }
+    while i < bytes.len() && bytes[i] != b']' {
+        i += 1;
+    }
+    (i + 1).min(bytes.len())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,8 +846,51 @@ mod tests {
     #[test]
     fn test_complex_pattern() {
         let pattern = GlobPattern::new("**/[a-z]*.{txt,md}", MatchMode::CaseSensitive).unwrap();
-        // Note: This pattern has literal {txt,md} - we don't support brace expansion yet
-        assert!(pattern.matches("some/path/file.{txt,md}"));
+        assert!(pattern.matches("some/path/file.txt"));
+        assert!(pattern.matches("some/path/file.md"));
+        assert!(!pattern.matches("some/path/file.{txt,md}"));
+    }
+
+    #[test]
+    fn test_brace_expansion() {
+        let pattern = GlobPattern::new("log-{2023,2024}-*.txt", MatchMode::CaseSensitive).unwrap();
+        assert_eq!(pattern.alternatives().len(), 2);
+        assert!(pattern.matches("log-2024-app.txt"));
+        assert!(!pattern.matches("log-2022-app.txt"));
+        assert_eq!(pattern.captures("log-2023-db.txt"), Some(vec!["db"]));
+
+        // Nested groups, wildcards inside alternatives, empty alternatives
+        let pattern = GlobPattern::new("*.{co{m,},n?t}", MatchMode::CaseInsensitive).unwrap();
+        assert!(pattern.matches("evil.COM"));
+        assert!(pattern.matches("evil.co"));
+        assert!(pattern.matches("evil.nXt"));
+        assert!(!pattern.matches("evil.org"));
+
+        // Escaped braces, single-item groups, and braces in classes are literal
+        let pattern = GlobPattern::new(r"\{a,b}", MatchMode::CaseSensitive).unwrap();
+        assert!(pattern.matches("{a,b}"));
+        assert!(!pattern.matches("a"));
+        assert!(GlobPattern::new("{a}", MatchMode::CaseSensitive)
+            .unwrap()
+            .matches("{a}"));
+        assert!(GlobPattern::new("x{a", MatchMode::CaseSensitive)
+            .unwrap()
+            .matches("x{a"));
+        assert_eq!(expand_braces("[{,}]").unwrap(), vec!["[{,}]"]);
+        assert_eq!(expand_braces(r"{a\,b,c}").unwrap(), vec![r"a\,b", "c"]);
+    }
+
+    #[test]
+    fn test_brace_expansion_limit() {
+        // 4^5 = 1024 is allowed, 4^6 is not
+        assert_eq!(
+            expand_braces(&"{a,b,c,d}".repeat(5)).unwrap().len(),
+            MAX_BRACE_EXPANSION
+        );
+        assert!(matches!(
+            GlobPattern::new(&"{a,b,c,d}".repeat(6), MatchMode::CaseSensitive),
+            Err(ParaglobError::InvalidPattern(_))
+        ));
     }
 
     #[test]
@@ -716,4 +997,28 @@ mod tests {
         let text2 = "abcdefghijklmnop";
         assert!(pattern.matches(text2));
     }
+
+    #[test]
+    fn test_captures() {
+        let pattern = GlobPattern::new("*.evil.com", MatchMode::CaseInsensitive).unwrap();
+        assert_eq!(pattern.captures("Sub.EVIL.com"), Some(vec!["Sub"]));
+        assert_eq!(pattern.captures("a.b.evil.com"), Some(vec!["a.b"]));
+        assert_eq!(pattern.captures("evil.com"), None);
+
+        // Every wildcard captures, stars take the shortest span that fits
+        let pattern = GlobPattern::new("*-[0-9]?*", MatchMode::CaseSensitive).unwrap();
+        assert_eq!(
+            pattern.captures("ab-c-12xyz"),
+            Some(vec!["ab-c", "1", "2", "xyz"])
+        );
+
+        // Trailing star takes the rest, possibly nothing
+        let pattern = GlobPattern::new("héllo*", MatchMode::CaseSensitive).unwrap();
+        assert_eq!(pattern.captures("héllo wörld"), Some(vec![" wörld"]));
+        assert_eq!(pattern.captures("héllo"), Some(vec![""]));
+
+        // No wildcards, nothing to capture
+        let pattern = GlobPattern::new("exact", MatchMode::CaseSensitive).unwrap();
+        assert_eq!(pattern.captures("exact"), Some(vec![]));
+    }
 }
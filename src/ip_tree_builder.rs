@@ -5,7 +5,8 @@
 
 use crate::mmdb::types::RecordSize;
 use crate::ParaglobError;
-use std::net::IpAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 /// IP tree builder using arena allocation
 pub struct IpTreeBuilder {
@@ -82,6 +83,36 @@ impl IpTreeBuilder {
             .reserve(capacity.saturating_sub(self.nodes.len()));
     }
 
+    /// Number of nodes allocated so far, including the root
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Change the record size used by [`build`](Self::build)
+    ///
+    /// Records are only encoded at build time, so this can be called after
+    /// inserting, once the final node count and data section size are known.
+    pub fn set_record_size(&mut self, record_size: RecordSize) {
+        self.record_size = record_size;
+    }
+
+    /// Insert an inclusive `start-end` address range with associated data offset
+    ///
+    /// The range is split with [`range_to_cidrs`] and every block points at the
+    /// same data. Returns the number of CIDR blocks inserted.
+    pub fn insert_range(
+        &mut self,
+        start: IpAddr,
+        end: IpAddr,
+        data_offset: u32,
+    ) -> Result<usize, ParaglobError> {
+        let cidrs = range_to_cidrs(start, end)?;
+        for &(addr, prefix_len) in &cidrs {
+            self.insert(addr, prefix_len, data_offset)?;
+        }
+        Ok(cidrs.len())
+    }
+
     /// Insert an IP address or CIDR range with associated data offset
     ///
     /// # Arguments
@@ -130,6 +161,35 @@ impl IpTreeBuilder {
         }
     }
 
+    /// Find the data offset of the most specific prefix containing `addr`
+    ///
+    /// Walks the in-memory tree the same way a reader walks the built one.
+    /// IPv6 addresses never match in an IPv4 tree.
+    pub fn lookup(&self, addr: IpAddr) -> Option<u32> {
+        let (bits, depth) = match (addr, self.ip_version) {
+            (IpAddr::V4(v4), IpVersion::V4) => ((ipv4_to_bits(v4) as u128) << 96, 32),
+            (IpAddr::V4(v4), IpVersion::V6) => (ipv4_to_bits(v4) as u128, 128),
+            (IpAddr::V6(v6), IpVersion::V6) => (bits_to_u128(ipv6_to_bits(v6)), 128),
+            (IpAddr::V6(_), IpVersion::V4) => return None,
+        };
+
+        let mut node_id = 0usize;
+        for depth in 0..depth {
+            let node = self.nodes.get(node_id)?;
+            let child = if (bits >> (127 - depth)) & 1 == 0 {
+                node.left
+            } else {
+                node.right
+            };
+            match child {
+                NodePointer::Node(child_id) => node_id = child_id as usize,
+                NodePointer::Data(data_offset, _) => return Some(data_offset),
+                NodePointer::Empty => return None,
+            }
+        }
+        None
+    }
+
     /// Insert bits into tree using iterative approach (avoids borrow checker issues)
     fn insert_bits_u128(
         &mut self,
@@ -332,7 +392,12 @@ impl IpTreeBuilder {
     ///
     /// Returns: (tree_bytes, node_count)
     pub fn build(&self) -> Result<(Vec<u8>, u32), ParaglobError> {
-        let node_count = self.nodes.len() as u32;
+        let node_count = u32::try_from(self.nodes.len()).map_err(|_| {
+            ParaglobError::SerializationError(format!(
+                "IP tree has {} nodes, exceeding the u32 format limit",
+                self.nodes.len()
+            ))
+        })?;
         let node_size = self.record_size.node_bytes();
         let tree_size = node_count as usize * node_size;
 
@@ -354,8 +419,18 @@ impl IpTreeBuilder {
         node: &Node,
         node_count: u32,
     ) -> Result<(), ParaglobError> {
-        let left_value = self.pointer_to_value(node.left, node_count);
-        let right_value = self.pointer_to_value(node.right, node_count);
+        let left_value = self.pointer_to_value(node.left, node_count)?;
+        let right_value = self.pointer_to_value(node.right, node_count)?;
+
+        // A value wider than the record would be silently truncated on write
+        let max_value = self.record_size.max_value();
+        if left_value.max(right_value) > max_value {
+            return Err(ParaglobError::SerializationError(format!(
+                "Record value {} does not fit in {}-bit records",
+                left_value.max(right_value),
+                self.record_size as u8
+            )));
+        }
 
         match self.record_size {
             RecordSize::Bits24 => self.write_24bit_node(tree, node_id, left_value, right_value),
@@ -366,8 +441,12 @@ impl IpTreeBuilder {
 
     /// Convert node pointer to numeric value
     /// Note: prefix_len is discarded here - it's only used during building
-    fn pointer_to_value(&self, pointer: NodePointer, node_count: u32) -> u32 {
-        match pointer {
+    fn pointer_to_value(
+        &self,
+        pointer: NodePointer,
+        node_count: u32,
+    ) -> Result<u32, ParaglobError> {
+        Ok(match pointer {
             NodePointer::Empty => node_count, // "not found" marker
             NodePointer::Node(id) => {
                 // Validate node ID is within bounds
@@ -387,14 +466,14 @@ impl IpTreeBuilder {
                 node_count
                     .checked_add(16)
                     .and_then(|base| base.checked_add(offset))
-                    .unwrap_or_else(|| {
-                        panic!(
-                        "Data pointer overflow: node_count={} + 16 + offset={} exceeds u32::MAX",
-                        node_count, offset
-                    )
-                    })
+                    .ok_or_else(|| {
+                        ParaglobError::SerializationError(format!(
+                            "Data pointer overflow: node_count={} + 16 + offset={} exceeds u32::MAX",
+                            node_count, offset
+                        ))
+                    })?
             }
-        }
+        })
     }
 
     /// Write 24-bit node (6 bytes per node)
@@ -495,6 +574,188 @@ impl IpTreeBuilder {
     }
 }
 
+/// Split an inclusive address range into the minimal set of CIDR blocks
+///
+/// Ranges like `1.2.3.0-1.2.3.255` (one block) or `1.2.3.5-1.2.3.20` (several)
+/// appear in many GeoIP and ASN feeds. Blocks are returned in address order.
+///
+/// # Errors
+/// Returns an error if `start` and `end` are different address families or
+/// `start > end`.
+pub fn range_to_cidrs(start: IpAddr, end: IpAddr) -> Result<Vec<(IpAddr, u8)>, ParaglobError> {
+    let (start_bits, end_bits, width) = match (start, end) {
+        (IpAddr::V4(s), IpAddr::V4(e)) => (ipv4_to_bits(s) as u128, ipv4_to_bits(e) as u128, 32),
+        (IpAddr::V6(s), IpAddr::V6(e)) => (
+            bits_to_u128(ipv6_to_bits(s)),
+            bits_to_u128(ipv6_to_bits(e)),
+            128,
+        ),
+        _ => {
+            return Err(ParaglobError::InvalidPattern(format!(
+                "IP range mixes address families: {}-{}",
+                start, end
+            )))
+        }
+    };
+    if start_bits > end_bits {
+        return Err(ParaglobError::InvalidPattern(format!(
+            "IP range start is after end: {}-{}",
+            start, end
+        )));
+    }
+
+    let mut cidrs = Vec::new();
+    let mut current = start_bits;
+    loop {
+        // Largest block aligned at `current` that doesn't run past `end`
+        let mut host_bits = current.trailing_zeros().min(width);
+        let last = loop {
+            let mask = if host_bits == 128 {
+                u128::MAX
+            } else {
+                (1u128 << host_bits) - 1
+            };
+            if current | mask <= end_bits {
+                break current | mask;
+            }
+            host_bits -= 1;
+        };
+
+        let addr = if width == 32 {
+            IpAddr::V4(Ipv4Addr::from(current as u32))
+        } else {
+            IpAddr::V6(Ipv6Addr::from(current))
+        };
+        cidrs.push((addr, (width - host_bits) as u8));
+
+        if last == end_bits {
+            return Ok(cidrs);
+        }
+        current = last + 1;
+    }
+}
+
+/// In-memory longest-prefix-match table from networks to caller data
+///
+/// Uses the same trie as [`IpTreeBuilder`], but keeps values of any type
+/// alongside it instead of encoding them into an MMDB data section. Nothing is
+/// serialized, so it suits routing or classification tables built and queried
+/// in-process.
+///
+/// Networks are `(address, prefix_len)` pairs, as elsewhere in the crate. IPv4
+/// and IPv6 networks can be mixed; as in a database, an IPv4-mapped IPv6 query
+/// does not match IPv4 networks.
+///
+/// # Example
+/// ```
+/// use matchy::IpSet;
+/// use std::net::IpAddr;
+///
+/// let set = IpSet::from_networks([
+///     (("10.0.0.0".parse()?, 8), "corp"),
+///     (("10.1.0.0".parse()?, 16), "lab"),
+///     (("2001:db8::".parse()?, 32), "docs"),
+/// ])?;
+///
+/// assert_eq!(set.get("10.1.2.3".parse::<IpAddr>()?), Some(&"lab"));
+/// assert_eq!(set.get("10.2.0.1".parse::<IpAddr>()?), Some(&"corp"));
+/// assert_eq!(set.get("192.0.2.1".parse::<IpAddr>()?), None);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct IpSet<T> {
+    tree: IpTreeBuilder,
+    /// Values, indexed by the offsets stored in the tree
+    values: Vec<T>,
+    /// Value index of each network, keyed by masked tree bits and tree prefix length
+    networks: HashMap<(u128, u8), u32>,
+}
+
+impl<T> IpSet<T> {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self {
+            tree: IpTreeBuilder::new_v6(RecordSize::Bits32),
+            values: Vec::new(),
+            networks: HashMap::new(),
+        }
+    }
+
+    /// Build a set from `((address, prefix_len), value)` pairs
+    ///
+    /// Later duplicates of a network replace earlier ones.
+    pub fn from_networks<I>(networks: I) -> Result<Self, ParaglobError>
+    where
+        I: IntoIterator<Item = ((IpAddr, u8), T)>,
+    {
+        let mut set = Self::new();
+        for ((addr, prefix_len), value) in networks {
+            set.insert(addr, prefix_len, value)?;
+        }
+        Ok(set)
+    }
+
+    /// Map a network to `value`
+    ///
+    /// Host bits below the prefix are ignored. Returns the previous value if
+    /// the same network was already present.
+    pub fn insert(
+        &mut self,
+        addr: IpAddr,
+        prefix_len: u8,
+        value: T,
+    ) -> Result<Option<T>, ParaglobError> {
+        let (bits, tree_prefix) = match addr {
+            IpAddr::V4(v4) if prefix_len <= 32 => (ipv4_to_bits(v4) as u128, 96 + prefix_len),
+            IpAddr::V6(v6) if prefix_len <= 128 => (bits_to_u128(ipv6_to_bits(v6)), prefix_len),
+            _ => {
+                return Err(ParaglobError::InvalidPattern(format!(
+                    "Invalid prefix length: {}/{}",
+                    addr, prefix_len
+                )))
+            }
+        };
+        let mask = u128::MAX.checked_shl(128 - tree_prefix as u32).unwrap_or(0);
+        let key = (bits & mask, tree_prefix);
+
+        if let Some(&index) = self.networks.get(&key) {
+            return Ok(Some(std::mem::replace(
+                &mut self.values[index as usize],
+                value,
+            )));
+        }
+
+        let index = u32::try_from(self.values.len()).map_err(|_| {
+            ParaglobError::ResourceLimitExceeded("IpSet holds too many networks".to_string())
+        })?;
+        self.tree.insert(addr, prefix_len, index)?;
+        self.values.push(value);
+        self.networks.insert(key, index);
+        Ok(None)
+    }
+
+    /// Value of the most specific network containing `addr`
+    pub fn get(&self, addr: IpAddr) -> Option<&T> {
+        let index = self.tree.lookup(addr)?;
+        self.values.get(index as usize)
+    }
+
+    /// Number of distinct networks
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether the set holds no networks
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T> Default for IpSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Node {
     fn new_empty() -> Self {
         Self {
@@ -647,6 +908,75 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_range_to_cidrs() {
+        let cidrs = |start: &str, end: &str| -> Vec<String> {
+            range_to_cidrs(start.parse().unwrap(), end.parse().unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|(addr, prefix_len)| format!("{}/{}", addr, prefix_len))
+                .collect()
+        };
+
+        assert_eq!(cidrs("1.2.3.0", "1.2.3.255"), ["1.2.3.0/24"]);
+        assert_eq!(cidrs("1.2.3.4", "1.2.3.4"), ["1.2.3.4/32"]);
+        assert_eq!(
+            cidrs("1.2.3.5", "1.2.3.20"),
+            [
+                "1.2.3.5/32",
+                "1.2.3.6/31",
+                "1.2.3.8/29",
+                "1.2.3.16/30",
+                "1.2.3.20/32"
+            ]
+        );
+        assert_eq!(cidrs("0.0.0.0", "255.255.255.255"), ["0.0.0.0/0"]);
+        assert_eq!(
+            cidrs("::", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"),
+            ["::/0"]
+        );
+        assert_eq!(
+            cidrs("2001:db8::1", "2001:db8::3"),
+            ["2001:db8::1/128", "2001:db8::2/127"]
+        );
+
+        let v4: IpAddr = "10.0.0.0".parse().unwrap();
+        let v6: IpAddr = "::1".parse().unwrap();
+        let later: IpAddr = "10.0.0.9".parse().unwrap();
+        assert!(range_to_cidrs(v4, v6).is_err());
+        assert!(range_to_cidrs(later, v4).is_err());
+    }
+
+    #[test]
+    fn test_insert_range() {
+        let mut builder = IpTreeBuilder::new_v4(RecordSize::Bits24);
+        let inserted = builder
+            .insert_range(
+                "10.0.0.1".parse().unwrap(),
+                "10.0.0.6".parse().unwrap(),
+                100,
+            )
+            .unwrap();
+        assert_eq!(inserted, 4); // .1/32, .2/31, .4/31, .6/32
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_record_overflow_is_an_error() {
+        let mut builder = IpTreeBuilder::new_v4(RecordSize::Bits24);
+        // Data offset too large for a 24-bit record once node_count + 16 is added
+        builder
+            .insert("10.0.0.0".parse().unwrap(), 8, (1 << 24) - 10)
+            .unwrap();
+        assert!(matches!(
+            builder.build(),
+            Err(ParaglobError::SerializationError(_))
+        ));
+
+        builder.set_record_size(RecordSize::Bits28);
+        assert!(builder.build().is_ok());
+    }
+
     #[test]
     fn test_ipv6_in_ipv4_tree_fails() {
         use std::net::Ipv6Addr;
@@ -658,4 +988,29 @@ mod tests {
         let result = builder.insert(addr, 128, 100);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_ip_set() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        let mut set = IpSet::new();
+        // More specific first, so the /8 has to backfill around it
+        assert_eq!(set.insert(ip("10.1.0.0"), 16, 16).unwrap(), None);
+        assert_eq!(set.insert(ip("10.0.0.0"), 8, 8).unwrap(), None);
+        assert_eq!(set.insert(ip("10.1.2.3"), 32, 32).unwrap(), None);
+        assert_eq!(set.insert(ip("2001:db8::"), 32, 6).unwrap(), None);
+        // Host bits are ignored, so this replaces the /16
+        assert_eq!(set.insert(ip("10.1.9.9"), 16, 17).unwrap(), Some(16));
+        assert_eq!(set.len(), 4);
+
+        assert_eq!(set.get(ip("10.1.2.3")), Some(&32));
+        assert_eq!(set.get(ip("10.1.2.4")), Some(&17));
+        assert_eq!(set.get(ip("10.200.0.1")), Some(&8));
+        assert_eq!(set.get(ip("11.0.0.1")), None);
+        assert_eq!(set.get(ip("2001:db8::1")), Some(&6));
+        assert_eq!(set.get(ip("2001:db9::1")), None);
+
+        assert!(set.insert(ip("10.0.0.0"), 33, 0).is_err());
+        assert!(IpSet::<u8>::new().is_empty());
+    }
 }
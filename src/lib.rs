@@ -88,10 +88,15 @@
 /// AC literal ID hash table for O(1) lookups
 pub mod ac_literal_hash;
 pub mod ac_offset;
+/// CSV feed importer
+#[cfg(feature = "csv")]
+pub mod csv_importer;
 /// Data section encoding/decoding for v2 format
 pub mod data_section;
 /// Unified database API
 pub mod database;
+/// Delta updates between database versions
+pub mod delta;
 /// Endianness handling for cross-platform zero-copy support
 pub mod endian;
 /// Error types for Paraglob operations
@@ -112,8 +117,14 @@ pub mod mmap;
 mod mmdb;
 /// Unified MMDB builder
 pub mod mmdb_builder;
+/// Query several databases through a single handle
+pub mod multi_database;
+/// Sorted integer index for numeric keys (ASNs, ports, numeric codes)
+pub mod numeric_index;
 pub mod offset_format;
 pub mod paraglob_offset;
+/// Group IDs of literal and glob patterns
+pub mod pattern_groups;
 /// Batch processing infrastructure for efficient file analysis
 ///
 /// General-purpose building blocks for sequential or parallel line-oriented processing:
@@ -121,6 +132,9 @@ pub mod paraglob_offset;
 /// - `Worker` - Processes batches with extraction + matching  
 /// - `LineBatch`, `MatchResult`, `LineMatch` - Data structures
 pub mod processing;
+/// TOML rule file importer
+#[cfg(feature = "toml")]
+pub mod rules_importer;
 pub mod serialization;
 /// SIMD-accelerated utilities for pattern matching
 ///
@@ -128,6 +142,8 @@ pub mod serialization;
 /// - ASCII lowercase conversion (4-8x faster than iterator chains)
 /// - Byte searching and comparison
 pub mod simd_utils;
+/// Reversed-label suffix trie for longest domain-suffix matching
+pub mod suffix_trie;
 /// Database validation for untrusted files
 ///
 /// Provides comprehensive validation of `.mxy` database files including:
@@ -135,6 +151,7 @@ pub mod simd_utils;
 /// - **Standard**: All offsets, UTF-8 validation, basic structure
 /// - **Strict**: Deep graph analysis, cycles, redundancy checks
 /// - **Audit**: Tracks all unsafe code paths and trust assumptions
+/// - **Exhaustive**: Strict checks over every data record instead of a sample
 ///
 /// Use [`validation::ValidationLevel::Audit`] to see where unsafe operations
 /// occur and what validation would be bypassed in `--trusted` mode.
@@ -147,13 +164,39 @@ pub mod c_api;
 
 /// Unified database for IP and pattern lookups
 pub use crate::database::{
-    Database, DatabaseError, DatabaseOpener, DatabaseOptions, DatabaseStats, QueryResult,
+    Database, DatabaseError, DatabaseOpener, DatabaseOptions, DatabaseStats, HuntMatch,
+    MatchMechanism, OpenTiming, QueryResult, QueryResultRef, ScanMatch, ScoreCombine, SuffixMatch,
 };
 
+/// Query classification used by [`Database::lookup`]
+pub use crate::database::{classify_key, KeyKind};
+
+/// Reusable query buffers for [`Database::lookup_into`] and [`Database::scan_into`],
+/// and per-query work limits for [`Database::lookup_budgeted`]
+pub use crate::paraglob_offset::{QueryBudget, QueryScratch};
+
+/// Several databases queried through a single handle
+pub use crate::multi_database::MultiDatabase;
+
+/// In-memory longest-prefix-match table with caller-defined values
+pub use crate::ip_tree_builder::IpSet;
+
 /// Data value type for database entries
 pub use crate::data_section::DataValue;
 
-pub use crate::error::ParaglobError;
+/// Type-tagged serde form of `DataValue` for lossless round trips
+pub use crate::data_section::TypedDataValue;
+
+/// Borrowed, lazily decoded views of entry data
+pub use crate::data_section::{ArrayRef, DataValueRef, MapRef};
+
+/// Insertion-ordered map payload of `DataValue::Map`
+pub use crate::data_section::DataMap;
+
+/// Fluent builder for nested map records
+pub use crate::data_section::RecordBuilder;
+
+pub use crate::error::{ErrorKind, ParaglobError};
 pub use crate::glob::MatchMode;
 
 /// Unified database builder for creating databases with IP addresses and patterns
@@ -192,10 +235,31 @@ pub use crate::mmdb_builder::MmdbBuilder as DatabaseBuilder;
 /// type control.
 pub use crate::mmdb_builder::EntryType;
 
+/// Projected database size from [`DatabaseBuilder::estimate_size`]
+pub use crate::mmdb_builder::SizeEstimate;
+
+/// How [`DatabaseBuilder`] handles repeated keys
+pub use crate::mmdb_builder::DuplicatePolicy;
+
+/// Which IP families [`DatabaseBuilder::with_ip_version`] puts in the search tree
+pub use crate::mmdb_builder::IpVersionMode;
+
+/// Where [`DatabaseBuilder::add_entry_as`] stores a key
+pub use crate::mmdb_builder::ForcedKind;
+
+/// Why [`DatabaseBuilder::find_dead_patterns`] reports a glob
+pub use crate::mmdb_builder::DeadReason;
+
+/// Non-fatal issue reported by [`DatabaseBuilder::build_with_report`]
+pub use crate::mmdb_builder::BuildWarning;
+
+/// Data field holding per-field sources from [`DatabaseBuilder::add_entry_with_provenance`]
+pub use crate::mmdb_builder::PROVENANCE_FIELD;
+
 // Legacy pattern-only APIs - kept for internal use and backward compatibility
 // These are not the primary public API anymore. Use Database and DatabaseBuilder instead.
 #[doc(hidden)]
-pub use crate::paraglob_offset::{Paraglob, ParaglobBuilder};
+pub use crate::paraglob_offset::{ACNodeInfo, Paraglob, ParaglobBuilder};
 #[doc(hidden)]
 pub use crate::serialization::{load, save};
 
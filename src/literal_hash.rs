@@ -11,13 +11,17 @@
 //! ```text
 //! [Header]
 //!   magic: [u8; 4]           // "LHSH"
-//!   version: u32              // 1
+//!   version: u32              // 1 (unseeded) or 2 (seeded)
 //!   entry_count: u32          // Number of literal patterns
 //!   table_size: u32           // Hash table size (entry_count * 1.25)
 //!   strings_offset: u32       // Offset to string pool
 //!   strings_size: u32         // Size of string pool
 //!   num_shards: u32           // Number of shards (power of 2)
 //!   shard_bits: u32           // Bits used for sharding (log2(num_shards))
+//!   hash_seed: u64            // XXH64 seed (version 2 only)
+//!
+//! [Shard Offsets]
+//!   offsets: [u32; num_shards + 1]
 //!
 //! [Hash Table]
 //!   Sharded table: [Shard0][Shard1]...[ShardN]
@@ -36,6 +40,17 @@
 //!   mappings: [(pattern_id: u32, data_offset: u32); count]
 //! ```
 //!
+//! # Hash Seeding
+//!
+//! By default keys are hashed with XXH64 and seed 0, and the table is written
+//! as version 1. If an attacker can choose the literal keys (e.g. a public
+//! service that builds databases from user input), they can precompute keys
+//! that collide in one shard and degrade lookups to linear probing. Building
+//! with [`LiteralHashBuilder::with_seed`] and a secret, random seed defeats
+//! such precomputed collisions. Seeded tables are written as version 2 with
+//! the seed in the header, so lookups need no extra configuration; older
+//! readers reject them rather than returning wrong results.
+//!
 use crate::error::ParaglobError;
 use crate::glob::MatchMode;
 use rayon::prelude::*;
@@ -49,6 +64,15 @@ pub const LITERAL_HASH_MAGIC: &[u8; 4] = b"LHSH";
 /// Current version of the literal hash format
 pub const LITERAL_HASH_VERSION: u32 = 1;
 
+/// Version of the literal hash format with a hash seed in the header
+pub const LITERAL_HASH_VERSION_SEEDED: u32 = 2;
+
+/// Header size of version 1: 4 byte magic + 7 u32 fields
+const HEADER_SIZE_V1: usize = 32;
+
+/// Header size of version 2: version 1 header + u64 seed
+const HEADER_SIZE_V2: usize = 40;
+
 /// Empty slot marker
 const EMPTY_SLOT: u32 = 0xFFFFFFFF;
 
@@ -72,6 +96,8 @@ pub struct LiteralHashHeader {
     pub num_shards: u32,
     /// Bits used for sharding (log2(num_shards))
     pub shard_bits: u32,
+    /// XXH64 seed (0 for version 1 tables)
+    pub hash_seed: u64,
 }
 
 /// Single hash table entry
@@ -121,6 +147,7 @@ struct Shard {
 pub struct LiteralHashBuilder {
     patterns: Vec<(String, u32, u64)>, // (pattern, pattern_id, hash)
     mode: MatchMode,
+    seed: u64,
 }
 
 impl LiteralHashBuilder {
@@ -129,9 +156,24 @@ impl LiteralHashBuilder {
         Self {
             patterns: Vec::new(),
             mode,
+            seed: 0,
         }
     }
 
+    /// Hash keys with a custom XXH64 seed
+    ///
+    /// This is a security measure against hash flooding: with a secret seed,
+    /// colliding keys cannot be precomputed. The seed is stored in the table
+    /// header. A seed of 0 (the default) produces the unseeded version 1
+    /// format, readable by older versions of this crate.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        for (pattern, _, hash) in &mut self.patterns {
+            *hash = compute_hash(pattern, seed);
+        }
+        self
+    }
+
     /// Add a literal pattern
     pub fn add_pattern(&mut self, pattern: &str, pattern_id: u32) {
         // Normalize pattern based on match mode and pre-compute hash
@@ -139,7 +181,7 @@ impl LiteralHashBuilder {
             MatchMode::CaseSensitive => pattern.to_string(),
             MatchMode::CaseInsensitive => pattern.to_lowercase(),
         };
-        let hash = compute_hash(&normalized);
+        let hash = compute_hash(&normalized, self.seed);
         self.patterns.push((normalized, pattern_id, hash));
     }
 
@@ -261,7 +303,11 @@ impl LiteralHashBuilder {
         eprintln!("[LiteralHash] Shards concatenated ({:?})", start.elapsed());
 
         // Calculate offsets
-        let header_size = 32; // Fixed: 4 bytes magic + 7 u32 fields
+        let (version, header_size) = if self.seed == 0 {
+            (LITERAL_HASH_VERSION, HEADER_SIZE_V1)
+        } else {
+            (LITERAL_HASH_VERSION_SEEDED, HEADER_SIZE_V2)
+        };
         let shard_table_size = (num_shards + 1) * 4; // Shard offset table
         let table_bytes_size = table_size * mem::size_of::<HashEntry>();
         let strings_offset = header_size + shard_table_size + table_bytes_size;
@@ -279,13 +325,14 @@ impl LiteralHashBuilder {
         let entry_count = final_table.iter().filter(|e| !e.is_empty()).count();
         let header = LiteralHashHeader {
             magic: *LITERAL_HASH_MAGIC,
-            version: LITERAL_HASH_VERSION,
+            version,
             entry_count: entry_count as u32,
             table_size: table_size as u32,
             strings_offset: strings_offset as u32,
             strings_size: strings_size as u32,
             num_shards: num_shards as u32,
             shard_bits,
+            hash_seed: self.seed,
         };
 
         buffer.extend_from_slice(&header.magic);
@@ -296,6 +343,9 @@ impl LiteralHashBuilder {
         buffer.extend_from_slice(&header.strings_size.to_le_bytes());
         buffer.extend_from_slice(&header.num_shards.to_le_bytes());
         buffer.extend_from_slice(&header.shard_bits.to_le_bytes());
+        if header.version == LITERAL_HASH_VERSION_SEEDED {
+            buffer.extend_from_slice(&header.hash_seed.to_le_bytes());
+        }
 
         // Write shard offset table
         for offset in &shard_offsets {
@@ -382,9 +432,7 @@ pub struct LiteralHash<'a> {
 impl<'a> LiteralHash<'a> {
     /// Load from memory-mapped buffer
     pub fn from_buffer(buffer: &'a [u8], mode: MatchMode) -> Result<Self, ParaglobError> {
-        // Header size: 4 + 7*4 = 32 bytes (magic + 7 u32 fields)
-        const HEADER_SIZE: usize = 32;
-        if buffer.len() < HEADER_SIZE {
+        if buffer.len() < HEADER_SIZE_V1 {
             return Err(ParaglobError::InvalidPattern(
                 "Buffer too small for literal hash header".to_string(),
             ));
@@ -400,12 +448,24 @@ impl<'a> LiteralHash<'a> {
         }
 
         let version = u32::from_le_bytes(buffer[4..8].try_into().unwrap());
-        if version != LITERAL_HASH_VERSION {
-            return Err(ParaglobError::InvalidPattern(format!(
-                "Unsupported literal hash version: {}",
-                version
-            )));
-        }
+        let (header_size, hash_seed) = match version {
+            LITERAL_HASH_VERSION => (HEADER_SIZE_V1, 0),
+            LITERAL_HASH_VERSION_SEEDED => {
+                if buffer.len() < HEADER_SIZE_V2 {
+                    return Err(ParaglobError::InvalidPattern(
+                        "Buffer too small for seeded literal hash header".to_string(),
+                    ));
+                }
+                let seed = u64::from_le_bytes(buffer[32..40].try_into().unwrap());
+                (HEADER_SIZE_V2, seed)
+            }
+            _ => {
+                return Err(ParaglobError::UnsupportedVersion(format!(
+                    "Unsupported literal hash version: {}",
+                    version
+                )));
+            }
+        };
 
         let entry_count = u32::from_le_bytes(buffer[8..12].try_into().unwrap());
         let table_size = u32::from_le_bytes(buffer[12..16].try_into().unwrap());
@@ -423,11 +483,9 @@ impl<'a> LiteralHash<'a> {
             strings_size,
             num_shards,
             shard_bits,
+            hash_seed,
         };
 
-        // Header is 32 bytes: 4 byte magic + 7 u32 fields
-        let header_size = 32;
-
         // Read shard offset table (num_shards + 1 entries)
         let shard_table_start = header_size;
         let shard_table_size = (num_shards as usize + 1) * 4;
@@ -435,7 +493,7 @@ impl<'a> LiteralHash<'a> {
         for i in 0..=num_shards as usize {
             let offset_pos = shard_table_start + i * 4;
             if offset_pos + 4 > buffer.len() {
-                return Err(ParaglobError::InvalidPattern(
+                return Err(ParaglobError::Truncated(
                     "Shard offset table truncated".to_string(),
                 ));
             }
@@ -472,7 +530,7 @@ impl<'a> LiteralHash<'a> {
             MatchMode::CaseSensitive => query.to_string(),
             MatchMode::CaseInsensitive => query.to_lowercase(),
         };
-        let hash = compute_hash(&normalized_query);
+        let hash = compute_hash(&normalized_query, self.header.hash_seed);
 
         // Compute shard and shard bounds using offset table
         let num_shards = self.header.num_shards as usize;
@@ -544,6 +602,48 @@ impl<'a> LiteralHash<'a> {
         std::str::from_utf8(&self.buffer[str_start..str_end]).ok()
     }
 
+    /// List all stored literals as `(literal, pattern_id)`, in table order
+    ///
+    /// Literals are returned as stored, i.e. lowercased for case-insensitive tables.
+    pub fn entries(&self) -> Vec<(&str, u32)> {
+        let entry_size = mem::size_of::<HashEntry>();
+        let mut entries = Vec::with_capacity(self.header.entry_count as usize);
+
+        for slot in 0..self.header.table_size as usize {
+            let entry_offset = self.table_start + slot * entry_size;
+            let Some(entry_bytes) = self.buffer.get(entry_offset..entry_offset + entry_size) else {
+                break;
+            };
+            let string_offset = u32::from_le_bytes(entry_bytes[8..12].try_into().unwrap());
+            if string_offset == EMPTY_SLOT {
+                continue;
+            }
+            let pattern_id = u32::from_le_bytes(entry_bytes[12..16].try_into().unwrap());
+            if let Some(literal) = self.read_string(string_offset as usize) {
+                entries.push((literal, pattern_id));
+            }
+        }
+        entries
+    }
+
+    /// List every `(pattern_id, data_offset)` mapping, in stored order
+    pub fn data_offsets(&self) -> Vec<(u32, u32)> {
+        let read = |pos: usize| {
+            self.buffer
+                .get(pos..pos + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        };
+        let count = read(self.mappings_start).unwrap_or(0) as usize;
+        let mappings_data_start = self.mappings_start + 4;
+
+        (0..count)
+            .map_while(|i| {
+                let offset = mappings_data_start + i * 8;
+                Some((read(offset)?, read(offset + 4)?))
+            })
+            .collect()
+    }
+
     /// Get data offset for a pattern ID
     pub fn get_data_offset(&self, pattern_id: u32) -> Option<u32> {
         if self.mappings_start + 4 > self.buffer.len() {
@@ -585,6 +685,11 @@ impl<'a> LiteralHash<'a> {
     pub fn table_size(&self) -> u32 {
         self.header.table_size
     }
+
+    /// Get the hash seed (0 for unseeded tables)
+    pub fn hash_seed(&self) -> u64 {
+        self.header.hash_seed
+    }
 }
 
 /// Build a single shard from its entries
@@ -664,12 +769,10 @@ fn build_shard_auto_size(shard_id: usize, entries: Vec<(String, u32, u64)>) -> S
     }
 }
 
-/// Compute XXH64 with fixed seed for stable, portable on-disk hashing
-const HASH_SEED_1: u64 = 0;
-
+/// Compute XXH64 for stable, portable on-disk hashing (seed 0 unless configured)
 #[inline]
-fn compute_hash(s: &str) -> u64 {
-    xxh64(s.as_bytes(), HASH_SEED_1)
+fn compute_hash(s: &str, seed: u64) -> u64 {
+    xxh64(s.as_bytes(), seed)
 }
 
 #[cfg(test)]
@@ -695,6 +798,71 @@ mod tests {
         assert_eq!(hash.get_data_offset(0), Some(100));
         assert_eq!(hash.get_data_offset(1), Some(200));
         assert_eq!(hash.get_data_offset(2), Some(300));
+
+        let mut offsets = hash.data_offsets();
+        offsets.sort_unstable();
+        assert_eq!(offsets, pattern_data);
+    }
+
+    #[test]
+    fn test_seeded_hash_table() {
+        let patterns = [("alpha", 0u32), ("beta", 1), ("gamma", 2)];
+        let mappings = [(0, 10), (1, 20), (2, 30)];
+
+        // Default seed keeps the version 1 layout
+        let mut builder = LiteralHashBuilder::new(MatchMode::CaseSensitive);
+        for (p, id) in patterns {
+            builder.add_pattern(p, id);
+        }
+        let unseeded = builder.build(&mappings).unwrap();
+        assert_eq!(
+            u32::from_le_bytes(unseeded[4..8].try_into().unwrap()),
+            LITERAL_HASH_VERSION
+        );
+
+        // Seed set after patterns were added still applies to all of them
+        let mut builder = LiteralHashBuilder::new(MatchMode::CaseInsensitive);
+        for (p, id) in patterns {
+            builder.add_pattern(p, id);
+        }
+        let seeded = builder.with_seed(0x5eed).build(&mappings).unwrap();
+        assert_eq!(
+            u32::from_le_bytes(seeded[4..8].try_into().unwrap()),
+            LITERAL_HASH_VERSION_SEEDED
+        );
+        // The seed is stored after the version 1 header fields. The total
+        // length isn't compared: the seed changes which shards are non-empty.
+        assert_eq!(
+            &seeded[HEADER_SIZE_V1..HEADER_SIZE_V2],
+            &0x5eed_u64.to_le_bytes()
+        );
+
+        // Lookups rehash the query with the stored seed
+        let hash = LiteralHash::from_buffer(&seeded, MatchMode::CaseInsensitive).unwrap();
+        assert_eq!(hash.hash_seed(), 0x5eed);
+        for (p, id) in patterns {
+            assert_eq!(hash.lookup(p), Some(id));
+        }
+        assert_eq!(hash.lookup("BETA"), Some(1));
+        assert_eq!(hash.lookup("delta"), None);
+        assert_eq!(hash.get_data_offset(2), Some(30));
+
+        let hash = LiteralHash::from_buffer(&unseeded, MatchMode::CaseSensitive).unwrap();
+        assert_eq!(hash.hash_seed(), 0);
+        assert_eq!(hash.lookup("gamma"), Some(2));
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut builder = LiteralHashBuilder::new(MatchMode::CaseInsensitive);
+        builder.add_pattern("Alpha", 0);
+        builder.add_pattern("beta", 1);
+        let bytes = builder.build(&[(0, 10), (1, 20)]).unwrap();
+        let hash = LiteralHash::from_buffer(&bytes, MatchMode::CaseInsensitive).unwrap();
+
+        let mut entries = hash.entries();
+        entries.sort_by_key(|&(_, id)| id);
+        assert_eq!(entries, vec![("alpha", 0), ("beta", 1)]);
     }
 
     #[test]
@@ -460,15 +460,28 @@ impl MispImporter {
             .with_database_type("MISP-ThreatIntel")
             .with_description("en", "Threat intelligence database from MISP JSON feeds");
 
+        self.add_to_builder(&mut builder, minimal_metadata)?;
+
+        Ok(builder)
+    }
+
+    /// Add the imported indicators to an existing builder
+    ///
+    /// Use this to merge MISP data with entries from other sources into one
+    /// database. The builder's metadata and match mode are left unchanged.
+    pub fn add_to_builder(
+        &self,
+        builder: &mut MmdbBuilder,
+        minimal_metadata: bool,
+    ) -> Result<(), ParaglobError> {
         for event in &self.events {
             if minimal_metadata {
-                self.process_event_minimal(event, &mut builder)?;
+                self.process_event_minimal(event, builder)?;
             } else {
-                self.process_event(event, &mut builder)?;
+                self.process_event(event, builder)?;
             }
         }
-
-        Ok(builder)
+        Ok(())
     }
 
     /// Process event with minimal metadata (just threat level and tags)
@@ -993,4 +1006,28 @@ mod tests {
         assert_eq!(importer.events.len(), 1);
         assert_eq!(importer.events[0].attributes.len(), 1);
     }
+    #[test]
+    fn test_add_to_existing_builder() {
+        let json = r#"{
+            "Event": {
+                "info": "Test Event",
+                "Attribute": [
+                    {
+                        "type": "domain",
+                        "value": "evil.example"
+                    }
+                ]
+            }
+        }"#;
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+
+        let importer = MispImporter::from_json(json).unwrap();
+        importer.add_to_builder(&mut builder, false).unwrap();
+
+        let stats = builder.stats();
+        assert_eq!(stats.ip_entries, 1);
+        assert_eq!(stats.literal_entries, 1);
+    }
 }
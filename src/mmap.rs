@@ -13,6 +13,12 @@
 //! - Using safe Rust types for all public APIs
 //! - Providing bounds-checked accessors
 //!
+//! A mapping faults (SIGBUS on Unix) if the file is truncated while mapped,
+//! and network filesystems can drop pages out from under it. Files on NFS,
+//! SMB/CIFS, and similar mounts are therefore read into memory instead of
+//! mapped (detected on Linux), and so is any file the kernel refuses to map,
+//! with a warning on stderr.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -29,10 +35,88 @@ use crate::offset_format::{ParaglobHeader, MAGIC, VERSION};
 use memmap2::Mmap;
 use std::fmt;
 use std::fs::File;
-use std::io;
+use std::io::{self, Read};
 use std::mem;
+use std::ops::Deref;
 use std::path::Path;
 
+/// Contents of an opened file, mapped or read into memory
+pub(crate) enum FileBytes {
+    /// Memory-mapped (the usual case)
+    Mapped(Mmap),
+    /// Read into memory because mapping is unavailable or unsafe
+    Buffered(Vec<u8>),
+}
+
+impl Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Mapped(mmap) => mmap,
+            FileBytes::Buffered(bytes) => bytes,
+        }
+    }
+}
+
+/// Map a file, falling back to reading it into memory
+///
+/// Files on network filesystems are read rather than mapped, since a mapping
+/// there can fault when the server side changes. If `mmap` itself fails
+/// (some read-only overlay and FUSE mounts do not support it), the file is
+/// read with a warning rather than failing the open.
+pub(crate) fn map_or_read(file: &mut File, path: &Path) -> io::Result<FileBytes> {
+    if !is_network_filesystem(file) {
+        match unsafe { Mmap::map(&*file) } {
+            Ok(mmap) => return Ok(FileBytes::Mapped(mmap)),
+            Err(e) => eprintln!(
+                "Warning: failed to mmap {} ({}), reading it into memory instead",
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(FileBytes::Buffered(bytes))
+}
+
+/// Whether a file lives on a network filesystem (NFS, SMB/CIFS, AFS, 9p, ...)
+#[cfg(target_os = "linux")]
+fn is_network_filesystem(file: &File) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    const NFS_SUPER_MAGIC: u32 = 0x6969;
+    const SMB_SUPER_MAGIC: u32 = 0x517B;
+    const CIFS_MAGIC_NUMBER: u32 = 0xFF53_4D42;
+    const SMB2_MAGIC_NUMBER: u32 = 0xFE53_4D42;
+    const AFS_SUPER_MAGIC: u32 = 0x5346_414F;
+    const CODA_SUPER_MAGIC: u32 = 0x7375_7245;
+    const V9FS_MAGIC: u32 = 0x0102_1997;
+
+    let mut stat: libc::statfs = unsafe { mem::zeroed() };
+    if unsafe { libc::fstatfs(file.as_raw_fd(), &mut stat) } != 0 {
+        return false;
+    }
+    matches!(
+        stat.f_type as u32,
+        NFS_SUPER_MAGIC
+            | SMB_SUPER_MAGIC
+            | CIFS_MAGIC_NUMBER
+            | SMB2_MAGIC_NUMBER
+            | AFS_SUPER_MAGIC
+            | CODA_SUPER_MAGIC
+            | V9FS_MAGIC
+    )
+}
+
+/// Whether a file lives on a network filesystem (not detected on this platform)
+#[cfg(not(target_os = "linux"))]
+fn is_network_filesystem(_file: &File) -> bool {
+    false
+}
+
 /// Validate a Paraglob header from a buffer
 fn validate_paraglob_header(buffer: &[u8]) -> Result<&ParaglobHeader, String> {
     // Check buffer size
@@ -130,8 +214,8 @@ impl From<io::Error> for MmapError {
 /// `MmapFile` instances, but a single instance should not be shared across
 /// threads without synchronization.
 pub struct MmapFile {
-    /// The memory-mapped file
-    mmap: Mmap,
+    /// The memory-mapped (or, as a fallback, buffered) file
+    mmap: FileBytes,
     /// Size of the mapped region
     size: usize,
 }
@@ -141,7 +225,7 @@ impl MmapFile {
     ///
     /// This function:
     /// 1. Opens the file
-    /// 2. Memory-maps it
+    /// 2. Memory-maps it (or reads it, see the [module docs](crate::mmap))
     /// 3. Validates the Paraglob header
     ///
     /// # Errors
@@ -160,8 +244,8 @@ impl MmapFile {
     /// # Ok::<(), matchy::mmap::MmapError>(())
     /// ```
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MmapError> {
-        let file = File::open(path.as_ref())?;
-        let mmap = unsafe { Mmap::map(&file)? };
+        let mut file = File::open(path.as_ref())?;
+        let mmap = map_or_read(&mut file, path.as_ref())?;
         let size = mmap.len();
 
         // Check minimum size for Paraglob header
@@ -180,8 +264,8 @@ impl MmapFile {
 
         // Apply platform-specific memory optimizations
         #[cfg(unix)]
-        unsafe {
-            Self::optimize_mmap(&mmap, size);
+        if let FileBytes::Mapped(ref mapped) = mmap {
+            unsafe { Self::optimize_mmap(mapped, size) };
         }
 
         Ok(MmapFile { mmap, size })
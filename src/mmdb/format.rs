@@ -10,7 +10,7 @@
 //! - Data decoding only allocates when returning results to users
 
 use super::types::{IpVersion, MmdbError, RecordSize, METADATA_MARKER};
-use crate::data_section::{DataDecoder, DataValue};
+use crate::data_section::{DataDecoder, DataMap, DataValue};
 
 /// MMDB file header - minimal heap usage
 ///
@@ -151,10 +151,7 @@ pub fn find_metadata_marker(data: &[u8]) -> Result<usize, MmdbError> {
 
 // Helper functions to extract values from metadata map (temporary during parsing)
 
-fn extract_uint(
-    map: &std::collections::HashMap<String, DataValue>,
-    key: &str,
-) -> Result<u64, MmdbError> {
+fn extract_uint(map: &DataMap, key: &str) -> Result<u64, MmdbError> {
     match map.get(key) {
         Some(DataValue::Uint16(n)) => Ok(*n as u64),
         Some(DataValue::Uint32(n)) => Ok(*n as u64),
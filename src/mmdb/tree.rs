@@ -9,6 +9,7 @@
 
 use super::format::MmdbHeader;
 use super::types::{MmdbError, RecordSize};
+use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 /// Result of an IP lookup
@@ -21,6 +22,7 @@ pub struct LookupResult {
 }
 
 /// Search tree for IP address lookups
+#[derive(Clone, Copy)]
 pub struct SearchTree<'a> {
     /// The raw file data containing the tree
     data: &'a [u8],
@@ -124,6 +126,140 @@ impl<'a> SearchTree<'a> {
         Ok(None)
     }
 
+    /// Enumerate every network in the tree as `(address, prefix_len, data_offset)`
+    ///
+    /// Networks are returned sorted by address. In IPv6 trees, networks under
+    /// `::/96` are reported as IPv4, since that is where IPv4 entries are
+    /// stored. Nodes reachable through more than one path (aliases) are only
+    /// walked once.
+    pub fn networks(&self) -> Result<Vec<(IpAddr, u8, u32)>, MmdbError> {
+        use super::types::IpVersion;
+
+        let max_depth = match self.header.ip_version {
+            IpVersion::V4 => 32,
+            IpVersion::V6 => 128,
+        };
+
+        let mut networks = Vec::new();
+        if self.header.node_count == 0 {
+            return Ok(networks);
+        }
+        let mut visited = HashSet::new();
+        // (node, address bits left-aligned in 128 bits, depth)
+        let mut stack = vec![(0u32, 0u128, 0u8)];
+
+        while let Some((node, bits, depth)) = stack.pop() {
+            if depth >= max_depth || !visited.insert(node) {
+                continue;
+            }
+            for side in 0..2u8 {
+                let record = self.read_record(node as usize, side)?;
+                let child_bits = bits | ((side as u128) << (127 - depth as u32));
+
+                if record == self.header.node_count {
+                    continue;
+                } else if record < self.header.node_count {
+                    stack.push((record, child_bits, depth + 1));
+                } else {
+                    let data_offset = self.calculate_data_offset(record)?;
+                    let (addr, prefix_len) = self.network_addr(child_bits, depth + 1);
+                    networks.push((addr, prefix_len, data_offset));
+                }
+            }
+        }
+
+        networks.sort_unstable();
+        Ok(networks)
+    }
+
+    /// Walk every network in the tree lazily, in address order
+    ///
+    /// Yields the same `(address, prefix_len, data_offset)` triples as
+    /// [`networks`](Self::networks), one at a time; memory use is bounded by
+    /// the tree depth. In IPv6 trees the IPv4 subtree is walked once, under
+    /// `::/96`, and aliases that point back into it (such as
+    /// `::ffff:0:0/96`) are skipped.
+    pub fn iter_networks(&self) -> NetworkIter<'a> {
+        use super::types::IpVersion;
+
+        let (max_depth, ipv4_start) = match self.header.ip_version {
+            IpVersion::V4 => (32, None),
+            IpVersion::V6 => (128, self.ipv4_subtree_node()),
+        };
+        let stack = if self.header.node_count == 0 {
+            Vec::new()
+        } else {
+            vec![(0, 0, 0)]
+        };
+        NetworkIter {
+            tree: *self,
+            stack,
+            max_depth,
+            ipv4_start,
+        }
+    }
+
+    /// List every network on the tree path an address takes
+    ///
+    /// Starts at the root (`/0`) and adds one network per node the lookup
+    /// walks through, ending with the record where the walk stops, whether
+    /// that record holds data or is empty. IPv4 addresses in IPv6 trees start
+    /// from the IPv4 subtree and are reported as IPv4 networks. An IPv6
+    /// address in an IPv4 tree has no path.
+    pub fn covering_networks(&self, ip: IpAddr) -> Result<Vec<(IpAddr, u8)>, MmdbError> {
+        use super::types::IpVersion;
+
+        // Address bits left-aligned in 128 bits, and how many leading tree
+        // levels are skipped in the output
+        let (bits, skip, max_depth) = match (ip, self.header.ip_version) {
+            (IpAddr::V4(addr), IpVersion::V4) => ((ipv4_to_bits(addr) as u128) << 96, 0, 32),
+            (IpAddr::V4(addr), IpVersion::V6) => (ipv4_to_bits(addr) as u128, 96, 128),
+            (IpAddr::V6(addr), IpVersion::V6) => (u128::from(addr), 0, 128),
+            (IpAddr::V6(_), IpVersion::V4) => return Ok(Vec::new()),
+        };
+
+        let mut networks = Vec::new();
+        if self.header.node_count == 0 {
+            return Ok(networks);
+        }
+        let network_at = |depth: u8| {
+            let mask = u128::MAX.checked_shl(128 - depth as u32).unwrap_or(0);
+            self.network_addr(bits & mask, depth)
+        };
+
+        let mut node = 0u32;
+        for depth in 0..max_depth {
+            if depth >= skip {
+                networks.push(network_at(depth));
+            }
+            let bit = ((bits >> (127 - depth as u32)) & 1) as u8;
+            let record = self.read_record(node as usize, bit)?;
+            if record < self.header.node_count {
+                node = record;
+                continue;
+            }
+            // Data or empty record: the walk ends one level down
+            if depth + 1 > skip {
+                networks.push(network_at(depth + 1));
+            }
+            break;
+        }
+        Ok(networks)
+    }
+
+    /// Convert left-aligned tree bits at a depth into an address and prefix
+    fn network_addr(&self, bits: u128, depth: u8) -> (IpAddr, u8) {
+        use super::types::IpVersion;
+
+        match self.header.ip_version {
+            IpVersion::V4 => (IpAddr::V4(Ipv4Addr::from((bits >> 96) as u32)), depth),
+            IpVersion::V6 if depth >= 96 && bits >> 32 == 0 => {
+                (IpAddr::V4(Ipv4Addr::from(bits as u32)), depth - 96)
+            }
+            IpVersion::V6 => (IpAddr::V6(Ipv6Addr::from(bits)), depth),
+        }
+    }
+
     /// Read a record from a node
     ///
     /// Each node contains two records. `side` determines which:
@@ -247,6 +383,22 @@ impl<'a> SearchTree<'a> {
         Ok(offset)
     }
 
+    /// Node at `::/96` where an IPv6 tree stores IPv4, if the tree has one
+    ///
+    /// Unlike [`find_ipv4_start_node`](Self::find_ipv4_start_node), returns
+    /// `None` when the walk hits data or an empty record before depth 96.
+    fn ipv4_subtree_node(&self) -> Option<u32> {
+        let mut node = 0u32;
+        for _ in 0..96 {
+            let record = self.read_record(node as usize, 0).ok()?;
+            if record >= self.header.node_count {
+                return None;
+            }
+            node = record;
+        }
+        Some(node)
+    }
+
     /// Find the IPv4 start node in an IPv6 tree
     ///
     /// Per MMDB spec, IPv4 addresses in IPv6 trees are accessed via the
@@ -277,6 +429,62 @@ impl<'a> SearchTree<'a> {
     }
 }
 
+/// Lazy in-order walk over the networks of a [`SearchTree`]
+///
+/// Returned by [`SearchTree::iter_networks`]. After an error from a corrupt
+/// record the walk ends.
+pub struct NetworkIter<'a> {
+    tree: SearchTree<'a>,
+    /// Records still to visit: (record, address bits left-aligned in 128 bits, depth)
+    stack: Vec<(u32, u128, u8)>,
+    max_depth: u8,
+    /// IPv4 subtree node of an IPv6 tree, only walked from `::/96`
+    ipv4_start: Option<u32>,
+}
+
+impl Iterator for NetworkIter<'_> {
+    type Item = Result<(IpAddr, u8, u32), MmdbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node_count = self.tree.header.node_count;
+
+        while let Some((record, bits, depth)) = self.stack.pop() {
+            if record == node_count {
+                continue;
+            }
+            if record > node_count {
+                let result = self.tree.calculate_data_offset(record).map(|data_offset| {
+                    let (addr, prefix_len) = self.tree.network_addr(bits, depth);
+                    (addr, prefix_len, data_offset)
+                });
+                if result.is_err() {
+                    self.stack.clear();
+                }
+                return Some(result);
+            }
+            if depth >= self.max_depth
+                || (self.ipv4_start == Some(record) && (depth != 96 || bits != 0))
+            {
+                continue;
+            }
+            // Right child first so the left (lower) half is popped first
+            for side in [1u8, 0] {
+                match self.tree.read_record(record as usize, side) {
+                    Ok(child) => {
+                        let child_bits = bits | ((side as u128) << (127 - depth as u32));
+                        self.stack.push((child, child_bits, depth + 1));
+                    }
+                    Err(e) => {
+                        self.stack.clear();
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
 /// Convert IPv4 address to 32-bit integer
 fn ipv4_to_bits(addr: Ipv4Addr) -> u32 {
     let octets = addr.octets();
@@ -89,6 +89,15 @@ impl RecordSize {
         }
     }
 
+    /// Largest value a single record can hold
+    pub fn max_value(self) -> u32 {
+        match self {
+            RecordSize::Bits24 => (1 << 24) - 1,
+            RecordSize::Bits28 => (1 << 28) - 1,
+            RecordSize::Bits32 => u32::MAX,
+        }
+    }
+
     /// Create from bit size
     pub fn from_bits(bits: u16) -> Result<Self, MmdbError> {
         match bits {
@@ -3,20 +3,36 @@
 //! Builds MMDB-format databases containing both IP address data and pattern matching data.
 //! Automatically detects whether input rows are IP addresses (including CIDRs) or patterns.
 
-use crate::data_section::{DataEncoder, DataValue};
+#[cfg(feature = "csv")]
+use crate::csv_importer::{CsvImport, CsvSchema};
+use crate::data_section::{sorted_data_map, DataDecoder, DataEncoder, DataMap, DataValue};
+use crate::database::{Database, DatabaseError, EntryKind};
 use crate::error::ParaglobError;
-use crate::glob::MatchMode;
-use crate::ip_tree_builder::IpTreeBuilder;
+use crate::glob::{GlobPattern, GlobSegment, MatchMode};
+use crate::ip_tree_builder::{range_to_cidrs, IpTreeBuilder};
 use crate::literal_hash::LiteralHashBuilder;
 use crate::mmdb::types::RecordSize;
-use crate::paraglob_offset::ParaglobBuilder;
+use crate::numeric_index::NumericIndexBuilder;
+use crate::offset_format::{offset_u32, ACNodeHot, ParaglobHeader, PatternEntry};
+use crate::paraglob_offset::{is_pure_wildcard, ParaglobBuilder};
+use crate::pattern_groups::{PatternGroupsBuilder, MAX_PATTERN_GROUPS};
+use crate::suffix_trie::{normalize_domain, SuffixTrieBuilder};
 use rustc_hash::FxHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
-use std::net::IpAddr;
+use std::io::BufRead;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Allowance for the metadata map, marker, and section separators in size estimates
+const METADATA_SIZE_ESTIMATE: usize = 1024;
+
+/// Data field holding per-field sources, written by
+/// [`MmdbBuilder::add_entry_with_provenance`]
+pub const PROVENANCE_FIELD: &str = "_provenance";
 
 /// Entry type classification
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EntryType {
     /// IP address or CIDR block with prefix length
     IpAddress {
@@ -31,11 +47,147 @@ pub enum EntryType {
     Glob(String),
 }
 
+/// How the builder handles an entry whose key was already added
+///
+/// Keys are compared after classification, so `10.0.0.1` and `10.0.0.1/32`
+/// are the same key, while a literal and a glob with the same text are not.
+/// Suffix entries are not tracked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Add every entry, even if its key repeats (default)
+    #[default]
+    KeepAll,
+    /// Ignore entries whose key was already added
+    KeepFirst,
+    /// Replace the data of an existing key with the later entry's data
+    KeepLast,
+    /// Reject a repeated key with an error
+    Error,
+}
+
+/// Which IP address families a database's search tree holds
+///
+/// Set with [`MmdbBuilder::with_ip_version`]. Recorded in the `ip_version`
+/// metadata field (4 or 6).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpVersionMode {
+    /// IPv4 tree if every network is IPv4, IPv6 tree otherwise (default)
+    #[default]
+    Auto,
+    /// IPv4 tree; IPv6 entries are refused
+    V4Only,
+    /// IPv6 tree; IPv4 entries are refused
+    V6Only,
+    /// IPv6 tree with IPv4 networks mapped into it, even if every network is IPv4
+    DualStack,
+}
+
+impl IpVersionMode {
+    /// Whether a network of this family may be added
+    fn accepts(self, addr: IpAddr) -> bool {
+        match self {
+            IpVersionMode::V4Only => addr.is_ipv4(),
+            IpVersionMode::V6Only => addr.is_ipv6(),
+            IpVersionMode::Auto | IpVersionMode::DualStack => true,
+        }
+    }
+
+    /// Whether the tree is IPv6, given whether any network is IPv6
+    fn uses_v6_tree(self, has_v6: bool) -> bool {
+        match self {
+            IpVersionMode::Auto => has_v6,
+            IpVersionMode::V4Only => false,
+            IpVersionMode::V6Only | IpVersionMode::DualStack => true,
+        }
+    }
+}
+
+/// Where [`MmdbBuilder::add_entry_as`] stores a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForcedKind {
+    /// The IP tree; the key must be an address, CIDR, IPv4 wildcard, or range
+    Ip,
+    /// The pattern sections: a glob if the key has glob syntax, otherwise a literal
+    Pattern,
+}
+
+/// Why [`MmdbBuilder::find_dead_patterns`] reports a glob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadReason {
+    /// An earlier glob (with this ID) matches every input this one matches
+    ShadowedByGlob(u32),
+    /// The glob matches exactly one string, which is also a literal entry
+    ShadowedByLiteral(String),
+}
+
+/// Non-fatal issue found by [`MmdbBuilder::build_with_report`]
+///
+/// The [`Display`](std::fmt::Display) rendering is meant for logs; match on
+/// the variant to gate builds on specific categories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildWarning {
+    /// A CIDR had bits set past its prefix length, which were ignored
+    HostBitsMasked {
+        /// The CIDR as added, e.g. `10.1.2.3/8`
+        cidr: String,
+    },
+    /// A key was added more than once
+    ///
+    /// Reported once per repeat. What happened to the repeat depends on the
+    /// [`DuplicatePolicy`].
+    DuplicateKey {
+        /// The repeated key
+        key: String,
+    },
+    /// A glob has no literal to anchor it, so every query must check it
+    PureWildcard {
+        /// The glob pattern
+        pattern: String,
+    },
+    /// A network of the wrong family was dropped
+    ///
+    /// Only reported with [`MmdbBuilder::with_lenient_ip_version`].
+    IpFamilySkipped {
+        /// The network as classified, e.g. `2001:db8::/32`
+        network: String,
+    },
+    /// Entries with identical data shared one stored copy
+    DataDeduplicated {
+        /// Number of entries whose data was already stored
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildWarning::HostBitsMasked { cidr } => {
+                write!(f, "host bits set in {} were masked", cidr)
+            }
+            BuildWarning::DuplicateKey { key } => write!(f, "duplicate key: {}", key),
+            BuildWarning::PureWildcard { pattern } => write!(
+                f,
+                "glob {} has no literal anchor and is checked on every query",
+                pattern
+            ),
+            BuildWarning::IpFamilySkipped { network } => {
+                write!(f, "{} skipped: wrong IP version for this database", network)
+            }
+            BuildWarning::DataDeduplicated { count } => {
+                write!(f, "{} entries reused identical data", count)
+            }
+        }
+    }
+}
+
 /// Lightweight entry reference (just entry type + offset, no data)
 #[derive(Debug, Clone)]
 struct EntryRef {
     entry_type: EntryType,
     data_offset: u32,
+    /// Index into `pattern_groups` (literal and glob entries only)
+    group: Option<u16>,
 }
 
 /// Unified database builder
@@ -51,6 +203,45 @@ pub struct MmdbBuilder {
     database_type: Option<String>,
     /// Optional custom description (language -> text)
     description: HashMap<String, String>,
+    /// Optional free-form version tag (e.g. feed snapshot or git SHA)
+    version_tag: Option<String>,
+    /// Optional free-form comment
+    comment: Option<String>,
+    /// Optional fallback record for keys with no match
+    default_data: Option<DataMap>,
+    /// Pattern group names, indexed by group ID
+    pattern_groups: Vec<String>,
+    /// Domain suffix entries (domain and all subdomains)
+    suffixes: SuffixTrieBuilder,
+    /// Integer-keyed entries (ASNs, ports, numeric codes)
+    numerics: NumericIndexBuilder,
+    /// XXH64 seed for the literal hash table (0 = unseeded)
+    literal_hash_seed: u64,
+    /// What to do when an entry's key repeats
+    duplicate_policy: DuplicatePolicy,
+    /// Index into `entries` by key (only maintained when duplicates are checked)
+    key_index: HashMap<EntryType, usize>,
+    /// Number of repeated keys seen under a checking policy
+    duplicate_count: usize,
+    /// Repeated keys seen under a checking policy, in the order they were added
+    duplicate_keys: Vec<String>,
+    /// Number of entries whose data was already stored
+    deduplicated_data: usize,
+    /// Write shared strings as a dictionary at the start of the data section
+    string_interning: bool,
+    /// Unique data values awaiting encoding (string interning or
+    /// compression-friendly layout only)
+    pending_data: Vec<DataValue>,
+    /// Fixed build time for reproducible output (None = current time)
+    build_epoch: Option<u64>,
+    /// Order data records by content so similar ones are adjacent
+    compression_friendly_layout: bool,
+    /// Which IP families the tree holds
+    ip_version: IpVersionMode,
+    /// Drop networks of the wrong family instead of failing
+    lenient_ip_version: bool,
+    /// Networks dropped for being the wrong family, in the order they were added
+    skipped_ip_family: Vec<String>,
 }
 
 impl MmdbBuilder {
@@ -63,6 +254,25 @@ impl MmdbBuilder {
             match_mode,
             database_type: None,
             description: HashMap::new(),
+            version_tag: None,
+            comment: None,
+            default_data: None,
+            pattern_groups: Vec::new(),
+            suffixes: SuffixTrieBuilder::new(),
+            numerics: NumericIndexBuilder::new(),
+            literal_hash_seed: 0,
+            duplicate_policy: DuplicatePolicy::KeepAll,
+            key_index: HashMap::new(),
+            duplicate_count: 0,
+            duplicate_keys: Vec::new(),
+            deduplicated_data: 0,
+            string_interning: false,
+            pending_data: Vec::new(),
+            build_epoch: None,
+            compression_friendly_layout: false,
+            ip_version: IpVersionMode::Auto,
+            lenient_ip_version: false,
+            skipped_ip_family: Vec::new(),
         }
     }
 
@@ -106,26 +316,713 @@ impl MmdbBuilder {
         self
     }
 
+    /// Seed the hash used by the literal (exact match) table
+    ///
+    /// This is a security measure for services that build databases from
+    /// untrusted keys: with a secret, random seed an attacker cannot
+    /// precompute literals that collide in the hash table and slow down
+    /// lookups (hash flooding). The seed is stored in the literal section
+    /// header, so opening the database needs no extra configuration.
+    ///
+    /// The default seed of 0 preserves the existing on-disk format. Seeded
+    /// literal sections cannot be read by older versions of matchy.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, MatchMode};
+    ///
+    /// let seed = 0x9e37_79b9_7f4a_7c15; // use a random value per build
+    /// let builder = DatabaseBuilder::new(MatchMode::CaseSensitive).with_literal_hash_seed(seed);
+    /// ```
+    pub fn with_literal_hash_seed(mut self, seed: u64) -> Self {
+        self.literal_hash_seed = seed;
+        self
+    }
+
+    /// Choose how entries with a key that was already added are handled
+    ///
+    /// Useful when merging several feeds that may list the same indicator.
+    /// Applies to entries added after this call through `add_entry`,
+    /// `add_literal`, `add_glob`, and `add_ip`.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, DuplicatePolicy, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive)
+    ///     .with_duplicate_policy(DuplicatePolicy::KeepFirst);
+    /// builder.add_entry("evil.com", HashMap::new())?;
+    /// builder.add_entry("evil.com", HashMap::new())?;
+    /// assert_eq!(builder.stats().total_entries, 1);
+    /// assert_eq!(builder.stats().duplicate_entries, 1);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    /// Choose which IP families the search tree holds
+    ///
+    /// An IPv4 tree is smaller and faster for consumers that never query IPv6.
+    /// With [`IpVersionMode::V4Only`] or [`IpVersionMode::V6Only`], adding a
+    /// network of the other family is an error, or is skipped with a warning
+    /// after [`with_lenient_ip_version`](Self::with_lenient_ip_version).
+    /// Applies to entries added after this call.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, IpVersionMode, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive)
+    ///     .with_ip_version(IpVersionMode::V4Only);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// assert!(builder.add_entry("2001:db8::/32", HashMap::new()).is_err());
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn with_ip_version(mut self, mode: IpVersionMode) -> Self {
+        self.ip_version = mode;
+        self
+    }
+
+    /// Skip networks refused by [`with_ip_version`](Self::with_ip_version)
+    /// instead of failing
+    ///
+    /// Each skipped network is reported as [`BuildWarning::IpFamilySkipped`]
+    /// by [`build_with_report`](Self::build_with_report).
+    pub fn with_lenient_ip_version(mut self) -> Self {
+        self.lenient_ip_version = true;
+        self
+    }
+
+    /// Store repeated strings once, in a dictionary, and refer to them by pointer
+    ///
+    /// Data deduplication only helps when whole records are identical. Fields
+    /// drawn from a small set of values (a `category`, `threat_level`, or
+    /// `source`) repeat across records that otherwise differ. In this mode the
+    /// builder counts every string (map keys and values) across the unique
+    /// records at build time and writes those used more than once at the start
+    /// of the data section, most frequent first. Records then refer to them
+    /// with MMDB pointers, which take 2 bytes for the first 2KB of the
+    /// dictionary. Lookups resolve the pointers transparently, so the file
+    /// stays readable by any MMDB reader. If the dictionary would not shrink
+    /// the data section, because every shared string already first appears in
+    /// its first 2KB, the default layout is kept and the dictionary is empty.
+    ///
+    /// Without this option strings are still interned, but at their first
+    /// occurrence, so a string first seen late in a large build costs 3-5
+    /// bytes per reference. The trade-off is memory: unique records are kept
+    /// decoded until `build()` instead of being encoded as they are added.
+    ///
+    /// Best called before adding entries: data added earlier is decoded again
+    /// and kept with the rest until `build()`.
+    /// [`estimate_size`](Self::estimate_size) reports the dictionary size.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive).with_string_interning();
+    /// for i in 0..300 {
+    ///     // "malware" first appears after 150 records of unique categories
+    ///     let category = if i < 150 { format!("unknown-{}", i) } else { "malware".to_string() };
+    ///     let mut data = HashMap::new();
+    ///     data.insert("category".to_string(), DataValue::String(category));
+    ///     data.insert("id".to_string(), DataValue::Uint32(i));
+    ///     builder.add_entry(&format!("host{}.example.com", i), data)?;
+    /// }
+    /// // "category", "id", and "malware"
+    /// assert_eq!(builder.estimate_size().dictionary_strings, 3);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn with_string_interning(mut self) -> Self {
+        let was_deferred = self.defers_encoding();
+        self.string_interning = true;
+        self.relayout_encoded_data(was_deferred);
+        self
+    }
+
+    /// Lay out the data section so the file compresses better
+    ///
+    /// Records are normally written in the order entries are first seen,
+    /// which scatters similar records (same keys, same category strings)
+    /// across the data section. With this option the unique records are
+    /// sorted by their encoded bytes before they are written, so records
+    /// sharing keys and leading values sit next to each other and general
+    /// purpose compressors (gzip, zstd) find longer matches in a smaller
+    /// window. Lookups are unaffected: entries point at wherever their
+    /// record ends up.
+    ///
+    /// Like [`with_string_interning`](Self::with_string_interning), with
+    /// which it combines, unique records are kept decoded until `build()`.
+    /// The uncompressed size barely changes; only compressed distribution
+    /// artifacts get smaller.
+    ///
+    /// Best called before adding entries: data added earlier is decoded again
+    /// and kept with the rest until `build()`.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder =
+    ///     DatabaseBuilder::new(MatchMode::CaseSensitive).with_compression_friendly_layout();
+    /// let mut data = HashMap::new();
+    /// data.insert("category".to_string(), DataValue::String("malware".to_string()));
+    /// builder.add_entry("evil.com", data)?;
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn with_compression_friendly_layout(mut self) -> Self {
+        let was_deferred = self.defers_encoding();
+        self.compression_friendly_layout = true;
+        self.relayout_encoded_data(was_deferred);
+        self
+    }
+
+    /// Record a fixed build time instead of the current time
+    ///
+    /// `build_epoch` (seconds since the Unix epoch) is the only metadata
+    /// field that changes from run to run, so fixing it makes builds
+    /// reproducible: the same entries, added in the same order, produce a
+    /// byte-identical file.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, MatchMode};
+    ///
+    /// let builder = DatabaseBuilder::new(MatchMode::CaseSensitive).with_build_epoch(1_700_000_000);
+    /// ```
+    pub fn with_build_epoch(mut self, epoch: u64) -> Self {
+        self.build_epoch = Some(epoch);
+        self
+    }
+
+    /// Set a free-form version tag stored in the metadata
+    ///
+    /// Useful for recording provenance such as a feed snapshot date or git SHA.
+    /// Read it back with [`Database::version_tag`](crate::Database::version_tag).
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::mmdb_builder::MmdbBuilder;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+    ///     .with_version_tag("feed-2024-06-01");
+    /// ```
+    pub fn with_version_tag(mut self, tag: impl Into<String>) -> Self {
+        self.version_tag = Some(tag.into());
+        self
+    }
+
+    /// Set a free-form human-readable comment stored in the metadata
+    ///
+    /// Read it back with [`Database::comment`](crate::Database::comment).
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::mmdb_builder::MmdbBuilder;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+    ///     .with_comment("Built from abuse.ch + internal blocklist");
+    /// ```
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Set the record returned for keys that match nothing
+    ///
+    /// Stored in the metadata under `default_data` rather than as a catch-all
+    /// entry, so it never shows up as a real match in
+    /// [`Database::lookup`](crate::Database::lookup). Returned by
+    /// [`Database::lookup_or_default`](crate::Database::lookup_or_default).
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::mmdb_builder::MmdbBuilder;
+    /// use matchy::glob::MatchMode;
+    /// use matchy::DataValue;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut unknown = HashMap::new();
+    /// unknown.insert("category".to_string(), DataValue::String("unknown".to_string()));
+    /// let builder = MmdbBuilder::new(MatchMode::CaseSensitive).with_default_data(unknown);
+    /// ```
+    pub fn with_default_data(self, data: HashMap<String, DataValue>) -> Self {
+        self.with_default_map(sorted_data_map(data))
+    }
+
+    /// Set the default record from a map whose field order is kept
+    pub(crate) fn with_default_map(mut self, data: DataMap) -> Self {
+        self.default_data = Some(data);
+        self
+    }
+
     /// Add an entry with auto-detection
     ///
     /// Automatically detects whether the key is an IP address, literal string, or glob pattern.
-    /// For explicit control, use `add_ip()`, `add_literal()`, or `add_glob()`.
+    /// For explicit control, use `add_ip()`, `add_literal()`, `add_glob()`, or
+    /// [`add_entry_as`](Self::add_entry_as).
+    ///
+    /// Classification follows [`detect_entry_type`](Self::detect_entry_type):
+    /// a key that parses as an IP address or network always goes to the IP
+    /// tree, so `192.168.1.1` is an IP entry, never a literal. Only keys that
+    /// are not IPs are checked for glob characters.
     pub fn add_entry(
         &mut self,
         key: &str,
         data: HashMap<String, DataValue>,
     ) -> Result<(), ParaglobError> {
+        let data = sorted_data_map(data);
+        if let Some(cidrs) = Self::split_ip_range(key.strip_prefix("ip:").unwrap_or(key))? {
+            return self.push_ip_blocks(cidrs, data);
+        }
         let entry_type = Self::detect_entry_type(key)?;
-        let data_offset = self.encode_and_deduplicate_data(data);
+        self.push_entry(entry_type, data)
+    }
+
+    /// Add an entry with its kind chosen by the caller instead of detected
+    ///
+    /// Use this when a feed's keys are known to be of one kind, so no key is
+    /// misclassified:
+    ///
+    /// - [`ForcedKind::Ip`] stores `key` in the IP tree. CIDRs, IPv4
+    ///   wildcards and ranges work as with [`add_ip`](Self::add_ip).
+    /// - [`ForcedKind::Pattern`] stores `key` as a pattern, even if it is a
+    ///   valid IP: a glob if it has glob syntax, otherwise a literal.
+    ///
+    /// [`Database::lookup`](crate::Database::lookup) sends IP-shaped queries to
+    /// the IP tree, so an IP-shaped pattern is found with
+    /// [`lookup_pattern`](crate::Database::lookup_pattern) or
+    /// [`lookup_both`](crate::Database::lookup_both). Type prefixes such as
+    /// `ip:` are not stripped; the key is used as given.
+    ///
+    /// # Errors
+    /// Returns an error if `key` is not a valid network for [`ForcedKind::Ip`].
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, ForcedKind, MatchMode, QueryResult};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry_as("192.168.1.1", HashMap::new(), ForcedKind::Pattern)?;
+    ///
+    /// let db = Database::from_bytes(builder.build()?)?;
+    /// assert!(matches!(
+    ///     db.lookup_pattern("192.168.1.1")?,
+    ///     Some(QueryResult::Pattern { .. })
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_entry_as(
+        &mut self,
+        key: &str,
+        data: HashMap<String, DataValue>,
+        kind: ForcedKind,
+    ) -> Result<(), ParaglobError> {
+        match kind {
+            ForcedKind::Ip => self.add_ip(key, data),
+            ForcedKind::Pattern => {
+                self.push_entry(Self::detect_pattern_type(key), sorted_data_map(data))
+            }
+        }
+    }
 
+    /// Add an entry whose fields are tagged with the source they came from
+    ///
+    /// `provenance` maps field names in `data` to a source (a feed, file, or
+    /// vendor name). It is stored with the data as a [`PROVENANCE_FIELD`] map,
+    /// which [`QueryResult::field_source`](crate::QueryResult::field_source)
+    /// reads back. Fields without a source are left out of the map. Source
+    /// names repeat across entries, so this pairs well with
+    /// [`with_string_interning`](Self::with_string_interning).
+    ///
+    /// Returns an error if `provenance` names a field that is not in `data`.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut data = HashMap::new();
+    /// data.insert("asn".to_string(), DataValue::Uint32(64512));
+    /// data.insert("threat_level".to_string(), DataValue::String("high".to_string()));
+    /// let mut provenance = HashMap::new();
+    /// provenance.insert("asn".to_string(), "feed-a".to_string());
+    /// provenance.insert("threat_level".to_string(), "feed-b".to_string());
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry_with_provenance("10.0.0.0/8", data, provenance)?;
+    ///
+    /// let db = Database::from_bytes(builder.build()?)?;
+    /// let result = db.lookup("10.1.2.3")?.unwrap();
+    /// assert_eq!(result.field_source("asn"), Some("feed-a"));
+    /// assert_eq!(result.field_source("threat_level"), Some("feed-b"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_entry_with_provenance(
+        &mut self,
+        key: &str,
+        mut data: HashMap<String, DataValue>,
+        provenance: HashMap<String, String>,
+    ) -> Result<(), ParaglobError> {
+        if let Some(field) = provenance.keys().find(|field| !data.contains_key(*field)) {
+            return Err(ParaglobError::InvalidPattern(format!(
+                "Provenance given for missing field: {}",
+                field
+            )));
+        }
+
+        if !provenance.is_empty() {
+            let sources = provenance
+                .into_iter()
+                .map(|(field, source)| (field, DataValue::String(source)))
+                .collect();
+            data.insert(
+                PROVENANCE_FIELD.to_string(),
+                DataValue::from_hash_map(sources),
+            );
+        }
+        self.add_entry(key, data)
+    }
+
+    /// Add a literal or glob entry to a named pattern group
+    ///
+    /// Groups let one database hold several rule sets (say `phishing` and
+    /// `c2`) that can be queried separately with
+    /// [`Database::lookup_in_group`](crate::Database::lookup_in_group).
+    /// Each group name gets a small numeric ID, kept in a table indexed by
+    /// pattern ID in its own section, so `data` is stored exactly as given
+    /// and the per-entry cost is two bytes whatever the name's length. The
+    /// names are listed in the `pattern_groups` metadata field. Ungrouped
+    /// entries added with [`add_entry`](Self::add_entry) belong to no group.
+    ///
+    /// # Errors
+    /// Returns an error if `key` is an IP address or network, or if there
+    /// are already 65535 groups.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, MatchMode, QueryResult};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry_in_group("*.evil.com", HashMap::new(), "c2")?;
+    /// builder.add_entry_in_group("login-*.com", HashMap::new(), "phishing")?;
+    ///
+    /// let db = Database::from_bytes(builder.build()?)?;
+    /// assert!(matches!(
+    ///     db.lookup_in_group("www.evil.com", "c2")?,
+    ///     Some(QueryResult::Pattern { .. })
+    /// ));
+    /// assert!(matches!(
+    ///     db.lookup_in_group("www.evil.com", "phishing")?,
+    ///     Some(QueryResult::NotFound)
+    /// ));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_entry_in_group(
+        &mut self,
+        key: &str,
+        data: HashMap<String, DataValue>,
+        group: &str,
+    ) -> Result<(), ParaglobError> {
+        let entry_type = Self::detect_entry_type(key)?;
+        if !matches!(entry_type, EntryType::Literal(_) | EntryType::Glob(_)) {
+            return Err(ParaglobError::InvalidPattern(format!(
+                "Only literal and glob entries can be grouped: {}",
+                key
+            )));
+        }
+        let group_id = self.group_id(group)?;
+        self.push_grouped_entry(entry_type, sorted_data_map(data), Some(group_id))
+    }
+
+    /// Add an entry copied out of a built database, in a group if `group` is given
+    ///
+    /// The key is already classified as `kind`, and `data` keeps the field
+    /// order it was decoded with. Only literals and globs can be grouped.
+    pub(crate) fn add_decoded(
+        &mut self,
+        kind: EntryKind,
+        key: &str,
+        data: DataMap,
+        group: Option<&str>,
+    ) -> Result<(), ParaglobError> {
+        let entry_type = match kind {
+            EntryKind::Literal => EntryType::Literal(key.to_string()),
+            EntryKind::Glob => EntryType::Glob(key.to_string()),
+            _ if group.is_some() => {
+                return Err(ParaglobError::InvalidPattern(format!(
+                    "Only literal and glob entries can be grouped: {}",
+                    key
+                )))
+            }
+            EntryKind::Network => Self::parse_ip_entry(key)?,
+            EntryKind::Suffix => return self.push_suffix(key, data),
+            EntryKind::Numeric => {
+                let key = key.parse().map_err(|_| {
+                    ParaglobError::InvalidPattern(format!("Invalid numeric key: {}", key))
+                })?;
+                self.push_numeric(key, data);
+                return Ok(());
+            }
+        };
+        let group_id = group.map(|group| self.group_id(group)).transpose()?;
+        self.push_grouped_entry(entry_type, data, group_id)
+    }
+
+    /// ID of a group name, assigning the next free ID to a new name
+    pub(crate) fn group_id(&mut self, group: &str) -> Result<u16, ParaglobError> {
+        if let Some(id) = self.pattern_groups.iter().position(|g| g == group) {
+            return Ok(id as u16);
+        }
+        if self.pattern_groups.len() >= MAX_PATTERN_GROUPS {
+            return Err(ParaglobError::ResourceLimitExceeded(format!(
+                "Too many pattern groups (max {})",
+                MAX_PATTERN_GROUPS
+            )));
+        }
+        self.pattern_groups.push(group.to_string());
+        Ok((self.pattern_groups.len() - 1) as u16)
+    }
+
+    /// Add a classified entry, applying the duplicate policy
+    fn push_entry(&mut self, entry_type: EntryType, data: DataMap) -> Result<(), ParaglobError> {
+        self.push_grouped_entry(entry_type, data, None)
+    }
+
+    /// Add a classified entry in an optional pattern group, applying the
+    /// duplicate policy
+    fn push_grouped_entry(
+        &mut self,
+        entry_type: EntryType,
+        data: DataMap,
+        group: Option<u16>,
+    ) -> Result<(), ParaglobError> {
+        let family = match entry_type {
+            EntryType::IpAddress { addr, .. } => Some(addr),
+            EntryType::Literal(_) | EntryType::Glob(_) => None,
+        };
+        if let Some(addr) = family.filter(|&addr| !self.ip_version.accepts(addr)) {
+            let network = Self::entry_key(&entry_type);
+            if self.lenient_ip_version {
+                self.skipped_ip_family.push(network);
+                return Ok(());
+            }
+            let (family, allowed) = if addr.is_ipv4() {
+                ("IPv4", "IPv6")
+            } else {
+                ("IPv6", "IPv4")
+            };
+            return Err(ParaglobError::InvalidPattern(format!(
+                "{} network {} not allowed in an {}-only database",
+                family, network, allowed
+            )));
+        }
+
+        if self.duplicate_policy != DuplicatePolicy::KeepAll {
+            if let Some(&index) = self.key_index.get(&entry_type) {
+                self.duplicate_count += 1;
+                self.duplicate_keys.push(Self::entry_key(&entry_type));
+                match self.duplicate_policy {
+                    DuplicatePolicy::KeepFirst => return Ok(()),
+                    DuplicatePolicy::KeepLast => {
+                        self.entries[index].data_offset = self.encode_and_deduplicate_data(data);
+                        self.entries[index].group = group;
+                        return Ok(());
+                    }
+                    DuplicatePolicy::Error => {
+                        return Err(ParaglobError::InvalidPattern(format!(
+                            "Duplicate entry: {}",
+                            Self::entry_key(&entry_type)
+                        )));
+                    }
+                    DuplicatePolicy::KeepAll => {}
+                }
+            }
+            self.key_index
+                .insert(entry_type.clone(), self.entries.len());
+        }
+
+        let data_offset = self.encode_and_deduplicate_data(data);
         self.entries.push(EntryRef {
             entry_type,
             data_offset,
+            group,
         });
+        Ok(())
+    }
+
+    /// Key text of a classified entry, for error messages
+    fn entry_key(entry_type: &EntryType) -> String {
+        match entry_type {
+            EntryType::IpAddress { addr, prefix_len } => format!("{}/{}", addr, prefix_len),
+            EntryType::Literal(pattern) | EntryType::Glob(pattern) => pattern.clone(),
+        }
+    }
 
+    /// Add many entries with auto-detection, stopping at the first error
+    ///
+    /// Each item is a `(key, data)` pair handled exactly like [`add_entry`](Self::add_entry).
+    /// Entries added before the failing one remain in the builder.
+    pub fn add_entries<I, K>(&mut self, entries: I) -> Result<(), ParaglobError>
+    where
+        I: IntoIterator<Item = (K, HashMap<String, DataValue>)>,
+        K: AsRef<str>,
+    {
+        for (key, data) in entries {
+            self.add_entry(key.as_ref(), data)?;
+        }
         Ok(())
     }
 
+    /// Add many entries with auto-detection, skipping entries that fail
+    ///
+    /// Every valid entry is added to the builder. Invalid entries are skipped and
+    /// reported as `(index, error)` pairs, where `index` is the zero-based position
+    /// of the entry in the input iterator. An empty result means everything was added.
+    ///
+    /// # Example
+    /// ```
+    /// # use matchy::{DatabaseBuilder, MatchMode};
+    /// # use std::collections::HashMap;
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let rows = vec![
+    ///     ("1.2.3.4", HashMap::new()),
+    ///     ("glob:[unclosed", HashMap::new()),
+    ///     ("*.evil.com", HashMap::new()),
+    /// ];
+    ///
+    /// let failures = builder.add_entries_lenient(rows);
+    /// assert_eq!(failures.len(), 1);
+    /// assert_eq!(failures[0].0, 1);
+    /// assert_eq!(builder.stats().total_entries, 2);
+    /// ```
+    pub fn add_entries_lenient<I, K>(&mut self, entries: I) -> Vec<(usize, ParaglobError)>
+    where
+        I: IntoIterator<Item = (K, HashMap<String, DataValue>)>,
+        K: AsRef<str>,
+    {
+        let mut failures = Vec::new();
+        for (index, (key, data)) in entries.into_iter().enumerate() {
+            if let Err(e) = self.add_entry(key.as_ref(), data) {
+                failures.push((index, e));
+            }
+        }
+        failures
+    }
+
+    /// Import newline-delimited JSON (JSONL), one entry per line
+    ///
+    /// Each line must be a JSON object. The string in `key_field` becomes the
+    /// entry key (auto-detected like [`add_entry`](Self::add_entry)) and every
+    /// other field becomes entry data. Top-level `null` fields are dropped.
+    /// Blank lines are ignored.
+    ///
+    /// Malformed lines are skipped and reported as `(line_number, error)` pairs
+    /// with 1-based line numbers; all other lines are still imported. Only a
+    /// failure to read from `reader`, or a duplicate key under
+    /// [`DuplicatePolicy::Error`], aborts the import.
+    ///
+    /// # Example
+    /// ```
+    /// # use matchy::{DatabaseBuilder, MatchMode};
+    /// let feed = r#"{"indicator": "*.evil.com", "threat": "phishing"}
+    /// {"indicator": "10.0.0.0/8", "threat": "botnet", "score": 80}
+    /// not json
+    /// "#;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let failures = builder.import_jsonl(feed.as_bytes(), "indicator")?;
+    /// assert_eq!(failures.len(), 1);
+    /// assert_eq!(failures[0].0, 3);
+    /// assert_eq!(builder.stats().total_entries, 2);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn import_jsonl<R: BufRead>(
+        &mut self,
+        reader: R,
+        key_field: &str,
+    ) -> Result<Vec<(usize, ParaglobError)>, ParaglobError> {
+        let mut failures = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let duplicates = self.duplicate_count;
+            if let Err(e) = self.import_jsonl_line(&line, key_field) {
+                if self.duplicate_count > duplicates {
+                    return Err(e);
+                }
+                failures.push((index + 1, e));
+            }
+        }
+        Ok(failures)
+    }
+
+    /// Parse and add a single JSONL record
+    fn import_jsonl_line(&mut self, line: &str, key_field: &str) -> Result<(), ParaglobError> {
+        let mut object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(line)
+            .map_err(|e| ParaglobError::Format(format!("Invalid JSON: {}", e)))?;
+
+        let key = match object.remove(key_field) {
+            Some(serde_json::Value::String(key)) => key,
+            Some(_) => {
+                return Err(ParaglobError::Format(format!(
+                    "Field '{}' is not a string",
+                    key_field
+                )))
+            }
+            None => {
+                return Err(ParaglobError::Format(format!(
+                    "Missing key field '{}'",
+                    key_field
+                )))
+            }
+        };
+
+        let data = object
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(name, value)| {
+                serde_json::from_value::<DataValue>(value)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|e| ParaglobError::Format(format!("Field '{}': {}", name, e)))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        self.add_entry(&key, data)
+    }
+
+    /// Import a CSV feed: the first column is the key, the rest are typed data
+    ///
+    /// The first row must name the columns. Each later row's first cell is the
+    /// entry key (auto-detected like [`add_entry`](Self::add_entry)); every
+    /// other non-empty cell becomes a data field named by its header and
+    /// converted with `schema`. See [`csv_importer`](crate::csv_importer).
+    ///
+    /// Bad rows (unparseable cells, wrong column count, invalid keys) are
+    /// skipped and reported in [`CsvImport::failures`] with 1-based line
+    /// numbers; all other rows are still imported. Only a failure to read
+    /// from `reader` aborts the import.
+    #[cfg(feature = "csv")]
+    pub fn add_from_csv<R: std::io::Read>(
+        &mut self,
+        reader: R,
+        schema: &CsvSchema,
+    ) -> Result<CsvImport, DatabaseError> {
+        crate::csv_importer::import_csv(self, reader, schema)
+    }
+
     /// Add a literal string pattern (exact match only, no wildcards)
     ///
     /// Use this when the string contains characters like '*', '?', or '[' that should be
@@ -148,12 +1045,10 @@ impl MmdbBuilder {
         pattern: &str,
         data: HashMap<String, DataValue>,
     ) -> Result<(), ParaglobError> {
-        let data_offset = self.encode_and_deduplicate_data(data);
-        self.entries.push(EntryRef {
-            entry_type: EntryType::Literal(pattern.to_string()),
-            data_offset,
-        });
-        Ok(())
+        self.push_entry(
+            EntryType::Literal(pattern.to_string()),
+            sorted_data_map(data),
+        )
     }
 
     /// Add a glob pattern (with wildcard matching)
@@ -177,31 +1072,513 @@ impl MmdbBuilder {
         pattern: &str,
         data: HashMap<String, DataValue>,
     ) -> Result<(), ParaglobError> {
+        self.push_entry(EntryType::Glob(pattern.to_string()), sorted_data_map(data))
+    }
+
+    /// Add a domain suffix entry
+    ///
+    /// Matches the domain itself and every subdomain of it, on whole labels and
+    /// ignoring ASCII case. Suffix entries live in their own section and are
+    /// queried with [`Database::lookup_suffix`](crate::Database::lookup_suffix),
+    /// which returns the longest registered suffix of a hostname. This is far
+    /// more compact than one `*.domain` glob per entry for large
+    /// categorization feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the domain is empty or contains an empty label.
+    ///
+    /// # Example
+    /// ```
+    /// # use matchy::{DatabaseBuilder, MatchMode, DataValue};
+    /// # use std::collections::HashMap;
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let mut data = HashMap::new();
+    /// data.insert("category".to_string(), DataValue::String("ads".to_string()));
+    ///
+    /// // Matches doubleclick.net, ad.doubleclick.net, ...
+    /// builder.add_suffix("doubleclick.net", data)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_suffix(
+        &mut self,
+        domain: &str,
+        data: HashMap<String, DataValue>,
+    ) -> Result<(), ParaglobError> {
+        self.push_suffix(domain, sorted_data_map(data))
+    }
+
+    /// Add a domain suffix entry with data whose field order is kept
+    fn push_suffix(&mut self, domain: &str, data: DataMap) -> Result<(), ParaglobError> {
+        // Reject bad domains before their data lands in the data section
+        normalize_domain(domain)?;
         let data_offset = self.encode_and_deduplicate_data(data);
-        self.entries.push(EntryRef {
-            entry_type: EntryType::Glob(pattern.to_string()),
-            data_offset,
-        });
+        self.suffixes.add_domain(domain, data_offset)
+    }
+
+    /// Add an integer-keyed entry
+    ///
+    /// For identifiers that are naturally numbers, such as ASNs, port numbers,
+    /// or numeric country codes. Numeric entries live in their own sorted
+    /// section and are queried with
+    /// [`Database::lookup_numeric`](crate::Database::lookup_numeric), so
+    /// they neither go through string formatting nor collide with literal
+    /// keys. If the same key is added twice, the first data is kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use matchy::{DatabaseBuilder, MatchMode, DataValue};
+    /// # use std::collections::HashMap;
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let mut data = HashMap::new();
+    /// data.insert("org".to_string(), DataValue::String("Google LLC".to_string()));
+    ///
+    /// builder.add_numeric_entry(15169, data)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_numeric_entry(
+        &mut self,
+        key: u64,
+        data: HashMap<String, DataValue>,
+    ) -> Result<(), ParaglobError> {
+        self.push_numeric(key, sorted_data_map(data));
         Ok(())
     }
 
-    /// Encode data and deduplicate to save memory
-    fn encode_and_deduplicate_data(&mut self, data: HashMap<String, DataValue>) -> u32 {
-        // Fast hash computation without string allocation
-        let data_value = DataValue::Map(data);
-        let mut hasher = FxHasher::default();
-        data_value.hash(&mut hasher);
-        let hash = hasher.finish();
+    /// Add an integer-keyed entry with data whose field order is kept
+    fn push_numeric(&mut self, key: u64, data: DataMap) {
+        let data_offset = self.encode_and_deduplicate_data(data);
+        self.numerics.add(key, data_offset);
+    }
+
+    /// Remove a staged entry by key before building
+    ///
+    /// The key is classified like [`add_entry`](Self::add_entry), type
+    /// prefixes included, and every staged network, literal, or glob with that
+    /// key is removed; a `start-end` range removes each of its CIDR blocks.
+    /// Networks compare after masking host bits, so `10.0.0.0/8` removes an
+    /// entry added as `10.1.2.3/8`. Returns whether
+    /// anything was removed. Suffix and numeric entries are not affected.
+    ///
+    /// Data that was already encoded for a removed entry stays in the data
+    /// section.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("*.cdn.example.com", HashMap::new())?;
+    /// builder.add_entry("evil.com", HashMap::new())?;
+    ///
+    /// // Suppress a known false positive from the feed
+    /// assert!(builder.remove_entry("*.cdn.example.com"));
+    /// assert!(!builder.remove_entry("not-there.com"));
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn remove_entry(&mut self, key: &str) -> bool {
+        let targets = match Self::split_ip_range(key.strip_prefix("ip:").unwrap_or(key)) {
+            Ok(Some(cidrs)) => cidrs
+                .into_iter()
+                .map(|(addr, prefix_len)| EntryType::IpAddress { addr, prefix_len })
+                .collect(),
+            Ok(None) => match Self::detect_entry_type(key) {
+                Ok(target) => vec![target],
+                Err(_) => return false,
+            },
+            Err(_) => return false,
+        };
+        let networks: HashSet<_> = targets
+            .iter()
+            .filter_map(|target| match target {
+                EntryType::IpAddress { addr, prefix_len } => {
+                    Some(Self::network_key(*addr, *prefix_len))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let before = self.entries.len();
+        self.entries.retain(|entry| match &entry.entry_type {
+            EntryType::IpAddress { addr, prefix_len } => {
+                !networks.contains(&Self::network_key(*addr, *prefix_len))
+            }
+            entry_type => !targets.contains(entry_type),
+        });
+        let removed = self.entries.len() != before;
+        if removed {
+            self.rebuild_key_index();
+        }
+        removed
+    }
+
+    /// Keep only the staged entries for which `f` returns true
+    ///
+    /// `f` gets each network, literal, or glob entry's key and data. Keys are
+    /// the literal or glob text and `address/prefix` for networks, including
+    /// each block of a split range. Suffix and numeric entries are not visited.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// let mut low = HashMap::new();
+    /// low.insert("score".to_string(), DataValue::Uint32(10));
+    /// builder.add_entry("maybe.example", low)?;
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    ///
+    /// // Drop low-confidence indicators
+    /// builder.retain(|_key, data| {
+    ///     !matches!(data.get("score"), Some(DataValue::Uint32(score)) if *score < 50)
+    /// });
+    /// assert!(!builder.remove_entry("maybe.example"));
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &HashMap<String, DataValue>) -> bool,
+    {
+        // Entries with identical data share an offset, so decode each once
+        let mut decoded: HashMap<u32, HashMap<String, DataValue>> = HashMap::new();
+        let entries = mem::take(&mut self.entries);
+        let before = entries.len();
+        let kept: Vec<EntryRef> = entries
+            .into_iter()
+            .filter(|entry| {
+                let data = decoded
+                    .entry(entry.data_offset)
+                    .or_insert_with(|| self.staged_data(entry.data_offset));
+                f(&Self::entry_key(&entry.entry_type), data)
+            })
+            .collect();
+        self.entries = kept;
+        if self.entries.len() != before {
+            self.rebuild_key_index();
+        }
+    }
+
+    /// Data of a staged entry, decoded from the encoder or the pending values
+    fn staged_data(&self, data_offset: u32) -> HashMap<String, DataValue> {
+        let value = if self.defers_encoding() {
+            self.pending_data.get(data_offset as usize).cloned()
+        } else {
+            DataDecoder::new(self.data_encoder.as_bytes(), 0)
+                .decode(data_offset)
+                .ok()
+        };
+        match value {
+            Some(DataValue::Map(map)) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        }
+    }
+
+    /// Point the duplicate-checking index at the current entry positions
+    fn rebuild_key_index(&mut self) {
+        if self.duplicate_policy == DuplicatePolicy::KeepAll {
+            return;
+        }
+        self.key_index = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (entry.entry_type.clone(), index))
+            .collect();
+    }
+
+    /// Fold every entry of a built database into this builder
+    ///
+    /// Reads all networks, literals, globs, suffixes, and numeric keys out of
+    /// `db` and adds them, so a small overlay builder can be layered on top
+    /// of a large base database without re-adding the base from its source.
+    ///
+    /// Entries already in the builder win: a key from `db` that the builder
+    /// already holds (the same network after masking host bits, the same
+    /// literal, glob, suffix, or numeric key) is skipped. Merged globs are
+    /// added after the builder's own, so on an equally good match the
+    /// builder's glob takes precedence. Networks that merely overlap are both
+    /// kept and resolved by longest prefix as usual. Pattern groups are
+    /// matched by name: a merged entry joins the builder's group of the same
+    /// name, or a new group with the next free ID. Other metadata
+    /// (description, default record) is not merged.
+    ///
+    /// # Errors
+    /// Fails if `db` uses a different match mode, its data is not map-shaped,
+    /// or an entry is rejected by this builder (e.g. an IPv6 network in an
+    /// IPv4-only builder). Entries merged before the error remain.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{Database, DatabaseBuilder, DataValue, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let tag = |v: &str| HashMap::from([("src".to_string(), DataValue::String(v.into()))]);
+    ///
+    /// let mut base = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// base.add_entry("10.0.0.0/8", tag("base"))?;
+    /// base.add_entry("evil.com", tag("base"))?;
+    /// let base = Database::from_bytes(base.build()?)?;
+    ///
+    /// let mut overlay = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// overlay.add_entry("evil.com", tag("overlay"))?;
+    /// overlay.merge_from(&base)?;
+    /// let db = Database::from_bytes(overlay.build()?)?;
+    ///
+    /// let src = |q: &str| db.lookup(q).unwrap().unwrap().first_data().cloned();
+    /// assert_eq!(src("evil.com"), Some(DataValue::from_hash_map(tag("overlay"))));
+    /// assert_eq!(src("10.1.2.3"), Some(DataValue::from_hash_map(tag("base"))));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn merge_from(&mut self, db: &Database) -> Result<(), DatabaseError> {
+        let (match_mode, _) = db.builder_settings();
+        if match_mode != self.match_mode {
+            return Err(DatabaseError::Unsupported(format!(
+                "Cannot merge a {:?} database into a {:?} builder",
+                match_mode, self.match_mode
+            )));
+        }
+        let add_error = |e: ParaglobError| DatabaseError::Unsupported(e.to_string());
+
+        let mut networks = HashSet::new();
+        let mut patterns = HashSet::new();
+        for entry in &self.entries {
+            match &entry.entry_type {
+                EntryType::IpAddress { addr, prefix_len } => {
+                    networks.insert(Self::network_key(*addr, *prefix_len));
+                }
+                EntryType::Literal(_) | EntryType::Glob(_) => {
+                    patterns.insert(entry.entry_type.clone());
+                }
+            }
+        }
+
+        db.for_each_grouped_entry(|kind, key, value, group| {
+            let data = Database::entry_map(value)?;
+            let entry_type = match kind {
+                EntryKind::Network => Self::parse_ip_entry(&key).map_err(add_error)?,
+                EntryKind::Literal => EntryType::Literal(key),
+                EntryKind::Glob => EntryType::Glob(key),
+                // Suffixes and numeric keys already keep the first data added
+                EntryKind::Suffix | EntryKind::Numeric => {
+                    return self.add_decoded(kind, &key, data, None).map_err(add_error)
+                }
+            };
+            let present = match &entry_type {
+                EntryType::IpAddress { addr, prefix_len } => {
+                    networks.contains(&Self::network_key(*addr, *prefix_len))
+                }
+                _ => patterns.contains(&entry_type),
+            };
+            if present {
+                return Ok(());
+            }
+            let group = group
+                .map(|group| self.group_id(group))
+                .transpose()
+                .map_err(add_error)?;
+            self.push_grouped_entry(entry_type, data, group)
+                .map_err(add_error)
+        })
+    }
+
+    /// A network with its host bits cleared, for comparing keys
+    fn network_key(addr: IpAddr, prefix_len: u8) -> (IpAddr, u8) {
+        let addr = match addr {
+            IpAddr::V4(v4) => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V4((u32::from(v4) & mask).into())
+            }
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V6((u128::from(v6) & mask).into())
+            }
+        };
+        (addr, prefix_len)
+    }
+
+    /// Encode data and deduplicate to save memory
+    fn encode_and_deduplicate_data(&mut self, data: DataMap) -> u32 {
+        // Fast hash computation without string allocation
+        let data_value = DataValue::Map(data);
+        let mut hasher = FxHasher::default();
+        data_value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // Check cache
+        if let Some(&offset) = self.data_cache.get(&hash) {
+            self.deduplicated_data += 1;
+            return offset;
+        }
+
+        // Encode and cache. With deferred encoding, the offset is an index
+        // into `pending_data` until `build()` encodes the values.
+        let offset = if self.defers_encoding() {
+            self.pending_data.push(data_value);
+            (self.pending_data.len() - 1) as u32
+        } else {
+            self.data_encoder.encode(&data_value)
+        };
+        self.data_cache.insert(hash, offset);
+        offset
+    }
+
+    /// Whether data values are kept until `build()` instead of encoded on add
+    fn defers_encoding(&self) -> bool {
+        self.string_interning || self.compression_friendly_layout
+    }
+
+    /// Move data encoded before a layout option was switched on into the new layout
+    ///
+    /// Entries, suffixes, and numeric keys keep pointing at the same values:
+    /// those are moved to `pending_data` if encoding is now deferred, and
+    /// re-encoded with the current settings otherwise. Nothing moves if
+    /// encoding was already deferred, since `build()` applies every setting.
+    fn relayout_encoded_data(&mut self, was_deferred: bool) {
+        let old = mem::take(&mut self.data_encoder);
+        if was_deferred || self.data_cache.is_empty() {
+            return;
+        }
+
+        let bytes = old.into_bytes();
+        let decoder = DataDecoder::new(&bytes, 0);
+        let mut offsets: Vec<u32> = self.data_cache.values().copied().collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        let defers = self.defers_encoding();
+        let mut moved = HashMap::with_capacity(offsets.len());
+        for offset in offsets {
+            // Only data nested past the decoder's depth cap fails here, and
+            // lookups could not read it either
+            let value = decoder
+                .decode(offset)
+                .unwrap_or_else(|_| DataValue::Map(DataMap::new()));
+            let new_offset = if defers {
+                self.pending_data.push(value);
+                (self.pending_data.len() - 1) as u32
+            } else {
+                self.data_encoder.encode(&value)
+            };
+            moved.insert(offset, new_offset);
+        }
+
+        for entry in &mut self.entries {
+            entry.data_offset = moved[&entry.data_offset];
+        }
+        for offset in self.data_cache.values_mut() {
+            *offset = moved[offset];
+        }
+        self.suffixes.remap_data_offsets(|offset| moved[&offset]);
+        self.numerics.remap_data_offsets(|offset| moved[&offset]);
+    }
+
+    /// Encode the pending values and point entries at their real offsets
+    fn encode_pending_data(&mut self) {
+        let pending = mem::take(&mut self.pending_data);
+        let (encoder, offsets, _, _) = self.encode_pending(pending);
+        self.data_encoder = encoder;
+
+        for entry in &mut self.entries {
+            entry.data_offset = offsets[entry.data_offset as usize];
+        }
+        self.suffixes
+            .remap_data_offsets(|index| offsets[index as usize]);
+        self.numerics
+            .remap_data_offsets(|index| offsets[index as usize]);
+    }
+
+    /// Encode deferred values in the configured layout
+    ///
+    /// Returns the encoder, each value's offset (by its index in `values`),
+    /// and the number of strings and bytes in the dictionary.
+    fn encode_pending(&self, values: Vec<DataValue>) -> (DataEncoder, Vec<u32>, usize, usize) {
+        let order: Vec<usize> = if self.compression_friendly_layout {
+            let mut order: Vec<usize> = (0..values.len()).collect();
+            order.sort_by_cached_key(|&index| {
+                let mut encoder = DataEncoder::new();
+                encoder.encode(&values[index]);
+                encoder.into_bytes()
+            });
+            order
+        } else {
+            (0..values.len()).collect()
+        };
+
+        let mut slots: Vec<Option<DataValue>> = values.into_iter().map(Some).collect();
+        let sorted: Vec<DataValue> = order
+            .iter()
+            .map(|&index| slots[index].take().expect("each index appears once"))
+            .collect();
+
+        let (encoder, sorted_offsets, strings, size) = if self.string_interning {
+            // Each dictionary string costs a pointer where its first use would
+            // have been; that only pays off once references pass the 2KB mark
+            let dictionary = Self::encode_with_dictionary(&sorted);
+            let plain = Self::encode_plain(&sorted);
+            if dictionary.0.size() <= plain.0.size() {
+                dictionary
+            } else {
+                plain
+            }
+        } else {
+            Self::encode_plain(&sorted)
+        };
+
+        let mut offsets = vec![0; order.len()];
+        for (&index, offset) in order.iter().zip(sorted_offsets) {
+            offsets[index] = offset;
+        }
+        (encoder, offsets, strings, size)
+    }
+
+    /// Encode values in order, interning strings at their first occurrence
+    fn encode_plain(values: &[DataValue]) -> (DataEncoder, Vec<u32>, usize, usize) {
+        let mut encoder = DataEncoder::new();
+        let offsets = values.iter().map(|value| encoder.encode(value)).collect();
+        (encoder, offsets, 0, 0)
+    }
 
-        // Check cache
-        if let Some(&offset) = self.data_cache.get(&hash) {
-            return offset;
+    /// Encode values behind a dictionary of their shared strings
+    ///
+    /// Returns the encoder, each value's offset, and the number of strings
+    /// and bytes in the dictionary.
+    fn encode_with_dictionary(values: &[DataValue]) -> (DataEncoder, Vec<u32>, usize, usize) {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values {
+            Self::count_strings(value, &mut counts);
         }
 
-        // Encode and cache
-        let offset = self.data_encoder.encode(&data_value);
-        self.data_cache.insert(hash, offset);
-        offset
+        // A 1-byte string encodes to 2 bytes, no larger than a pointer
+        let mut dictionary: Vec<(&str, usize)> = counts
+            .into_iter()
+            .filter(|(s, count)| *count > 1 && s.len() > 1)
+            .collect();
+        dictionary.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut encoder = DataEncoder::new();
+        let dictionary_size = encoder.intern_dictionary(dictionary.iter().map(|(s, _)| *s));
+        let offsets = values.iter().map(|value| encoder.encode(value)).collect();
+        (encoder, offsets, dictionary.len(), dictionary_size)
+    }
+
+    /// Count occurrences of each string (map keys included) in a value
+    fn count_strings<'a>(value: &'a DataValue, counts: &mut HashMap<&'a str, usize>) {
+        match value {
+            DataValue::String(s) => *counts.entry(s.as_str()).or_default() += 1,
+            DataValue::Map(m) => {
+                for (key, value) in m {
+                    *counts.entry(key.as_str()).or_default() += 1;
+                    Self::count_strings(value, counts);
+                }
+            }
+            DataValue::Array(a) => {
+                for value in a {
+                    Self::count_strings(value, counts);
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Add an IP address or CIDR block
@@ -209,6 +1586,15 @@ impl MmdbBuilder {
     /// Use this to explicitly mark an entry as an IP address. Will return an error
     /// if the string is not a valid IP address or CIDR notation.
     ///
+    /// Besides plain addresses and CIDRs, two range notations common in ACL
+    /// feeds are accepted and stored as the equivalent CIDR:
+    /// - IPv4 wildcards with whole trailing octets: `10.0.*.*` is `10.0.0.0/16`.
+    ///   Wildcards must be trailing (`10.*.0.*` is rejected).
+    /// - Inclusive address ranges `start-end`: `10.0.0.0-10.0.255.255` is
+    ///   `10.0.0.0/16`. Ranges that don't align to one CIDR (`1.2.3.5-1.2.3.20`)
+    ///   are split into the minimal set of CIDR blocks as they are added, all
+    ///   sharing the same data. Both ends must be the same family, with `start <= end`.
+    ///
     /// # Arguments
     /// * `ip_or_cidr` - IP address or CIDR range (e.g., "192.168.1.0/24")
     /// * `data` - HashMap of key-value pairs to associate with the IP
@@ -232,13 +1618,35 @@ impl MmdbBuilder {
         ip_or_cidr: &str,
         data: HashMap<String, DataValue>,
     ) -> Result<(), ParaglobError> {
+        let data = sorted_data_map(data);
+        if let Some(cidrs) = Self::split_ip_range(ip_or_cidr)? {
+            return self.push_ip_blocks(cidrs, data);
+        }
         let entry_type = Self::parse_ip_entry(ip_or_cidr)?;
-        let data_offset = self.encode_and_deduplicate_data(data);
+        self.push_entry(entry_type, data)
+    }
 
-        self.entries.push(EntryRef {
-            entry_type,
-            data_offset,
-        });
+    /// CIDR blocks of a `start-end` range that spans more than one block
+    ///
+    /// Returns `None` for every other key, including ranges that form exactly
+    /// one block, which [`parse_ip_entry`](Self::parse_ip_entry) handles.
+    fn split_ip_range(key: &str) -> Result<Option<Vec<(IpAddr, u8)>>, ParaglobError> {
+        let Some((start, end)) = Self::parse_ip_range(key) else {
+            return Ok(None);
+        };
+        let cidrs = range_to_cidrs(start, end)?;
+        Ok((cidrs.len() > 1).then_some(cidrs))
+    }
+
+    /// Add one IP entry per block, all sharing the same data
+    fn push_ip_blocks(
+        &mut self,
+        cidrs: Vec<(IpAddr, u8)>,
+        data: DataMap,
+    ) -> Result<(), ParaglobError> {
+        for (addr, prefix_len) in cidrs {
+            self.push_entry(EntryType::IpAddress { addr, prefix_len }, data.clone())?;
+        }
         Ok(())
     }
 
@@ -266,12 +1674,57 @@ impl MmdbBuilder {
             }
         }
 
+        if let Some((addr, prefix_len)) = Self::parse_ipv4_wildcard(key) {
+            return Ok(EntryType::IpAddress { addr, prefix_len });
+        }
+
+        if let Some((start, end)) = Self::parse_ip_range(key) {
+            let cidrs = range_to_cidrs(start, end)?;
+            if let [(addr, prefix_len)] = cidrs[..] {
+                return Ok(EntryType::IpAddress { addr, prefix_len });
+            }
+            return Err(ParaglobError::InvalidPattern(format!(
+                "IP range spans {} CIDR blocks and has no single entry type: {}",
+                cidrs.len(),
+                key
+            )));
+        }
+
         Err(ParaglobError::InvalidPattern(format!(
             "Invalid IP address or CIDR: {}",
             key
         )))
     }
 
+    /// Parse IPv4 wildcard notation (`10.0.*.*`) as an address and prefix length
+    ///
+    /// Only whole trailing octets may be wildcards.
+    fn parse_ipv4_wildcard(key: &str) -> Option<(IpAddr, u8)> {
+        let octets: Vec<&str> = key.split('.').collect();
+        if octets.len() != 4 {
+            return None;
+        }
+        let fixed = octets.iter().take_while(|&&octet| octet != "*").count();
+        if fixed == 4 || octets[fixed..].iter().any(|&octet| octet != "*") {
+            return None;
+        }
+
+        let mut bytes = [0u8; 4];
+        for (byte, octet) in bytes.iter_mut().zip(&octets[..fixed]) {
+            if octet.is_empty() || !octet.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            *byte = octet.parse().ok()?;
+        }
+        Some((IpAddr::V4(Ipv4Addr::from(bytes)), (fixed * 8) as u8))
+    }
+
+    /// Split `start-end` range notation into its two addresses
+    fn parse_ip_range(key: &str) -> Option<(IpAddr, IpAddr)> {
+        let (start, end) = key.split_once('-')?;
+        Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+    }
+
     /// Auto-detect if key is an IP/CIDR, literal, or glob pattern
     ///
     /// Supports explicit type prefixes for disambiguation:
@@ -280,7 +1733,10 @@ impl MmdbBuilder {
     /// - `ip:` - Force IP address parsing (strips prefix)
     ///
     /// Without a prefix, auto-detection is used:
-    /// 1. Try parsing as IP address/CIDR
+    /// 1. Try parsing as IP address/CIDR, IPv4 wildcard (`10.0.*.*`), or
+    ///    address range (`10.0.0.0-10.0.255.255`); see [`add_ip`](Self::add_ip).
+    ///    A range spanning several CIDR blocks has no single type and is an
+    ///    error here, though [`add_entry`](Self::add_entry) accepts it.
     /// 2. If contains glob chars (*, ?, [), validate as glob pattern
     /// 3. Otherwise treat as literal string
     ///
@@ -330,23 +1786,135 @@ impl MmdbBuilder {
             return Self::parse_ip_entry(key);
         }
 
+        // An address range that doesn't form a CIDR is an error, not a literal
+        if Self::parse_ip_range(key).is_some() {
+            return Self::parse_ip_entry(key);
+        }
+
+        Ok(Self::detect_pattern_type(key))
+    }
+
+    /// Classify a non-IP key as a glob or a literal
+    ///
+    /// Keys with glob syntax that compiles are globs; everything else,
+    /// including invalid globs, is a literal.
+    fn detect_pattern_type(key: &str) -> EntryType {
         // Check for glob pattern characters - but validate they form a valid glob
-        if key.contains('*') || key.contains('?') || key.contains('[') {
+        if key.contains('*')
+            || key.contains('?')
+            || key.contains('[')
+            || crate::glob::expand_braces(key).is_ok_and(|alternatives| alternatives.len() > 1)
+        {
             // Try to actually parse it as a glob to see if it's valid
             // Use CaseSensitive for validation (mode doesn't matter for syntax checking)
             if crate::glob::GlobPattern::new(key, crate::glob::MatchMode::CaseSensitive).is_ok() {
-                return Ok(EntryType::Glob(key.to_string()));
+                return EntryType::Glob(key.to_string());
             }
             // If it contains glob-like chars but isn't a valid glob, treat as literal
         }
 
         // Otherwise, treat as literal string
-        Ok(EntryType::Literal(key.to_string()))
+        EntryType::Literal(key.to_string())
+    }
+
+    /// Build the database and list the non-fatal issues found along the way
+    ///
+    /// Produces the same bytes as [`build`](Self::build). Warnings come in a
+    /// fixed order: masked host bits, repeated keys, and skipped networks in
+    /// the order the entries were added, then pure wildcard globs, then a single
+    /// [`BuildWarning::DataDeduplicated`] if any data was shared.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{BuildWarning, DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.1.2.3/8", HashMap::new())?;
+    /// builder.add_entry("*", HashMap::new())?;
+    ///
+    /// let (_bytes, warnings) = builder.build_with_report()?;
+    /// assert!(warnings.contains(&BuildWarning::PureWildcard { pattern: "*".to_string() }));
+    /// assert!(warnings
+    ///     .iter()
+    ///     .any(|w| matches!(w, BuildWarning::HostBitsMasked { .. })));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn build_with_report(self) -> Result<(Vec<u8>, Vec<BuildWarning>), ParaglobError> {
+        let warnings = self.build_warnings();
+        Ok((self.build()?, warnings))
+    }
+
+    /// Collect the warnings `build_with_report` returns
+    fn build_warnings(&self) -> Vec<BuildWarning> {
+        let mut warnings = Vec::new();
+
+        for entry in &self.entries {
+            if let EntryType::IpAddress { addr, prefix_len } = entry.entry_type {
+                let (bits, width) = match addr {
+                    IpAddr::V4(v4) => (u32::from(v4) as u128, 32),
+                    IpAddr::V6(v6) => (u128::from(v6), 128),
+                };
+                let host_mask = u128::MAX
+                    .checked_shr(128 - (width - prefix_len as u32))
+                    .unwrap_or(0);
+                if bits & host_mask != 0 {
+                    warnings.push(BuildWarning::HostBitsMasked {
+                        cidr: format!("{}/{}", addr, prefix_len),
+                    });
+                }
+            }
+        }
+
+        // Checking policies record repeats as they happen; KeepAll keeps
+        // every entry, so repeats are still in `entries`
+        if self.duplicate_policy == DuplicatePolicy::KeepAll {
+            let mut seen = HashSet::new();
+            for entry in &self.entries {
+                if !seen.insert(&entry.entry_type) {
+                    warnings.push(BuildWarning::DuplicateKey {
+                        key: Self::entry_key(&entry.entry_type),
+                    });
+                }
+            }
+        } else {
+            warnings.extend(
+                self.duplicate_keys
+                    .iter()
+                    .map(|key| BuildWarning::DuplicateKey { key: key.clone() }),
+            );
+        }
+
+        warnings.extend(self.skipped_ip_family.iter().map(|network| {
+            BuildWarning::IpFamilySkipped {
+                network: network.clone(),
+            }
+        }));
+
+        for entry in &self.entries {
+            if let EntryType::Glob(pattern) = &entry.entry_type {
+                if is_pure_wildcard(pattern) {
+                    warnings.push(BuildWarning::PureWildcard {
+                        pattern: pattern.clone(),
+                    });
+                }
+            }
+        }
+
+        if self.deduplicated_data > 0 {
+            warnings.push(BuildWarning::DataDeduplicated {
+                count: self.deduplicated_data,
+            });
+        }
+        warnings
     }
 
     /// Build the unified MMDB database
     pub fn build(mut self) -> Result<Vec<u8>, ParaglobError> {
         // Data is already encoded - just extract from the builder
+        if self.defers_encoding() {
+            self.encode_pending_data();
+        }
         let data_section = self.data_encoder.into_bytes();
 
         // Clear cache to free memory
@@ -365,36 +1933,29 @@ impl MmdbBuilder {
                     ip_entries.push((*addr, *prefix_len, entry.data_offset));
                 }
                 EntryType::Literal(pattern) => {
-                    literal_entries.push((pattern.as_str(), entry.data_offset));
+                    literal_entries.push((pattern.as_str(), entry.data_offset, entry.group));
                 }
                 EntryType::Glob(pattern) => {
-                    glob_entries.push((pattern.as_str(), entry.data_offset));
+                    glob_entries.push((pattern.as_str(), entry.data_offset, entry.group));
                 }
             }
         }
 
+        // Data pointers are u32 offsets; a larger data section has already wrapped
+        offset_u32(data_section.len(), "data section size")?;
+
         // Always build IP tree structure (even if empty) to maintain MMDB format
         // This ensures pattern-only databases still work with the Database API
         let (ip_tree_bytes, node_count, record_size, ip_version) = if !ip_entries.is_empty() {
             // Determine IP version needed
-            let needs_v6 = ip_entries.iter().any(|(addr, _, _)| addr.is_ipv6());
+            let needs_v6 = self
+                .ip_version
+                .uses_v6_tree(ip_entries.iter().any(|(addr, _, _)| addr.is_ipv6()));
 
-            // Choose record size based on expected tree size
-            // For /32 IPs, worst case is ~ip_count nodes
-            // 24-bit: max 16,777,216 nodes (16M IPs)
-            // 28-bit: max 268,435,456 nodes (268M IPs)
-            // 32-bit: max 4,294,967,296 nodes (4.2B IPs)
+            // Choose record size based on expected tree size (revisited after
+            // insertion, when the real node count is known)
             let estimated_nodes = ip_entries.len();
-            let record_size = if estimated_nodes > 200_000_000 {
-                // Over 200M IPs - use 32-bit for safety
-                RecordSize::Bits32
-            } else if estimated_nodes > 15_000_000 {
-                // Over 15M IPs - use 28-bit
-                RecordSize::Bits28
-            } else {
-                // Under 15M IPs - use 24-bit (most common)
-                RecordSize::Bits24
-            };
+            let mut record_size = Self::record_size_for(estimated_nodes);
 
             // Sort IPs by prefix length (more specific first), then by address
             // This minimizes tree reorganization and backfill operations
@@ -416,6 +1977,23 @@ impl MmdbBuilder {
                 tree_builder.insert(*addr, *prefix_len, *data_offset)?;
             }
 
+            // Widen records if the largest data pointer (node_count + 16 +
+            // data section size) wouldn't fit, rather than truncating it
+            let max_record = tree_builder.node_count() + 16 + data_section.len();
+            while max_record > record_size.max_value() as usize {
+                record_size = match record_size {
+                    RecordSize::Bits24 => RecordSize::Bits28,
+                    RecordSize::Bits28 => RecordSize::Bits32,
+                    RecordSize::Bits32 => {
+                        return Err(ParaglobError::SerializationError(
+                            "database exceeds 4GB format limit: IP tree records overflow u32"
+                                .to_string(),
+                        ))
+                    }
+                };
+            }
+            tree_builder.set_record_size(record_size);
+
             // Build the tree
             let (tree_bytes, node_cnt) = tree_builder.build()?;
 
@@ -424,19 +2002,33 @@ impl MmdbBuilder {
         } else {
             // Empty IP tree - create minimal valid tree
             let record_size = RecordSize::Bits24;
-            let tree_builder = IpTreeBuilder::new_v4(record_size);
-            let (tree_bytes, node_cnt) = tree_builder.build()?;
-            (tree_bytes, node_cnt, record_size, 4)
+            if self.ip_version.uses_v6_tree(false) {
+                let (tree_bytes, node_cnt) = IpTreeBuilder::new_v6(record_size).build()?;
+                (tree_bytes, node_cnt, record_size, 6)
+            } else {
+                let (tree_bytes, node_cnt) = IpTreeBuilder::new_v4(record_size).build()?;
+                (tree_bytes, node_cnt, record_size, 4)
+            }
         };
 
+        // Group IDs by pattern ID, only written when some entry is grouped
+        let has_groups = !self.pattern_groups.is_empty();
+        let mut groups = PatternGroupsBuilder::new(
+            if has_groups { literal_entries.len() } else { 0 },
+            if has_groups { glob_entries.len() } else { 0 },
+        );
+
         // Build glob pattern section if we have glob entries (NOT literals)
         let (has_globs, glob_section_bytes) = if !glob_entries.is_empty() {
             let mut pattern_builder = ParaglobBuilder::new(self.match_mode);
             let mut pattern_data = Vec::with_capacity(glob_entries.len());
 
-            for (pattern, data_offset) in &glob_entries {
+            for (pattern, data_offset, group) in &glob_entries {
                 let pattern_id = pattern_builder.add_pattern(pattern)?;
                 pattern_data.push((pattern_id, *data_offset));
+                if let Some(group) = group {
+                    groups.set_glob(pattern_id, *group);
+                }
             }
 
             let paraglob = pattern_builder.build()?;
@@ -460,8 +2052,8 @@ impl MmdbBuilder {
             }
 
             // Fill in sizes
-            let total_size = section.len() as u32;
-            let paraglob_size = paraglob_bytes.len() as u32;
+            let total_size = offset_u32(section.len(), "pattern section size")?;
+            let paraglob_size = offset_u32(paraglob_bytes.len(), "paraglob size")?;
             section[0..4].copy_from_slice(&total_size.to_le_bytes());
             section[4..8].copy_from_slice(&paraglob_size.to_le_bytes());
 
@@ -472,12 +2064,18 @@ impl MmdbBuilder {
 
         // Build literal hash table section for literal_entries
         let (has_literals, literal_section_bytes) = if !literal_entries.is_empty() {
-            let mut literal_builder = LiteralHashBuilder::new(self.match_mode);
+            let mut literal_builder =
+                LiteralHashBuilder::new(self.match_mode).with_seed(self.literal_hash_seed);
             let mut literal_pattern_data = Vec::with_capacity(literal_entries.len());
 
-            for (next_pattern_id, (literal, data_offset)) in literal_entries.iter().enumerate() {
+            for (next_pattern_id, (literal, data_offset, group)) in
+                literal_entries.iter().enumerate()
+            {
                 literal_builder.add_pattern(literal, next_pattern_id as u32);
                 literal_pattern_data.push((next_pattern_id as u32, *data_offset));
+                if let Some(group) = group {
+                    groups.set_literal(next_pattern_id as u32, *group);
+                }
             }
 
             let literal_bytes = literal_builder.build(&literal_pattern_data)?;
@@ -486,6 +2084,28 @@ impl MmdbBuilder {
             (false, Vec::new())
         };
 
+        // Build suffix trie section for domain suffix entries
+        let has_suffixes = !self.suffixes.is_empty();
+        let suffix_section_bytes = if has_suffixes {
+            self.suffixes.build()?
+        } else {
+            Vec::new()
+        };
+
+        // Build numeric index section for integer-keyed entries
+        let has_numerics = !self.numerics.is_empty();
+        let numeric_section_bytes = if has_numerics {
+            self.numerics.build()?
+        } else {
+            Vec::new()
+        };
+
+        let group_section_bytes = if has_groups {
+            groups.build()?
+        } else {
+            Vec::new()
+        };
+
         // Assemble final database - always use MMDB format
         let mut database = Vec::new();
 
@@ -519,12 +2139,12 @@ impl MmdbBuilder {
             );
             metadata.insert(
                 "build_epoch".to_string(),
-                DataValue::Uint64(
+                DataValue::Uint64(self.build_epoch.unwrap_or_else(|| {
                     std::time::SystemTime::now()
                         .duration_since(std::time::UNIX_EPOCH)
                         .unwrap()
-                        .as_secs(),
-                ),
+                        .as_secs()
+                })),
             );
             // Database type - use custom if provided, otherwise auto-generate
             let db_type = self.database_type.clone().unwrap_or_else(|| {
@@ -556,7 +2176,32 @@ impl MmdbBuilder {
                     .map(|(k, v)| (k.clone(), DataValue::String(v.clone())))
                     .collect()
             };
-            metadata.insert("description".to_string(), DataValue::Map(description_map));
+            metadata.insert(
+                "description".to_string(),
+                DataValue::from_hash_map(description_map),
+            );
+
+            // Optional provenance fields (only written when set)
+            if let Some(tag) = &self.version_tag {
+                metadata.insert("version_tag".to_string(), DataValue::String(tag.clone()));
+            }
+            if let Some(comment) = &self.comment {
+                metadata.insert("comment".to_string(), DataValue::String(comment.clone()));
+            }
+            if let Some(data) = &self.default_data {
+                metadata.insert("default_data".to_string(), DataValue::Map(data.clone()));
+            }
+            if !self.pattern_groups.is_empty() {
+                metadata.insert(
+                    "pattern_groups".to_string(),
+                    DataValue::Array(
+                        self.pattern_groups
+                            .iter()
+                            .map(|name| DataValue::String(name.clone()))
+                            .collect(),
+                    ),
+                );
+            }
             metadata.insert(
                 "languages".to_string(),
                 DataValue::Array(vec![DataValue::String("en".to_string())]),
@@ -622,7 +2267,7 @@ impl MmdbBuilder {
             };
             metadata.insert(
                 "pattern_section_offset".to_string(),
-                DataValue::Uint32(pattern_offset as u32),
+                DataValue::Uint32(offset_u32(pattern_offset, "pattern section offset")?),
             );
 
             // Literal section offset (after pattern section if present)
@@ -643,36 +2288,497 @@ impl MmdbBuilder {
             };
             metadata.insert(
                 "literal_section_offset".to_string(),
-                DataValue::Uint32(literal_offset as u32),
+                DataValue::Uint32(offset_u32(literal_offset, "literal section offset")?),
             );
 
+            // Suffix section offset (after literal section if present)
+            // Only written when suffix entries exist
+            if has_suffixes {
+                let mut suffix_offset =
+                    tree_and_separator_size + data_section_size + padding_before_paraglob;
+                if has_globs {
+                    suffix_offset += 16 + glob_section_bytes.len();
+                }
+                if has_literals {
+                    suffix_offset += 16 + literal_section_bytes.len();
+                }
+                suffix_offset += 16; // +16 for "MMDB_SUFFIX" separator
+                metadata.insert(
+                    "suffix_section_offset".to_string(),
+                    DataValue::Uint32(offset_u32(suffix_offset, "suffix section offset")?),
+                );
+                metadata.insert(
+                    "suffix_entry_count".to_string(),
+                    DataValue::Uint32(self.suffixes.len() as u32),
+                );
+            }
+
+            // Numeric section offset (after suffix section if present)
+            // Only written when numeric entries exist
+            if has_numerics {
+                let mut numeric_offset =
+                    tree_and_separator_size + data_section_size + padding_before_paraglob;
+                if has_globs {
+                    numeric_offset += 16 + glob_section_bytes.len();
+                }
+                if has_literals {
+                    numeric_offset += 16 + literal_section_bytes.len();
+                }
+                if has_suffixes {
+                    numeric_offset += 16 + suffix_section_bytes.len();
+                }
+                numeric_offset += 16; // +16 for "MMDB_NUMERIC" separator
+                metadata.insert(
+                    "numeric_section_offset".to_string(),
+                    DataValue::Uint32(offset_u32(numeric_offset, "numeric section offset")?),
+                );
+                metadata.insert(
+                    "numeric_entry_count".to_string(),
+                    DataValue::Uint32(self.numerics.len() as u32),
+                );
+            }
+
+            // Group section offset (after numeric section if present)
+            // Only written when some literal or glob is grouped
+            if has_groups {
+                let mut group_offset =
+                    tree_and_separator_size + data_section_size + padding_before_paraglob;
+                if has_globs {
+                    group_offset += 16 + glob_section_bytes.len();
+                }
+                if has_literals {
+                    group_offset += 16 + literal_section_bytes.len();
+                }
+                if has_suffixes {
+                    group_offset += 16 + suffix_section_bytes.len();
+                }
+                if has_numerics {
+                    group_offset += 16 + numeric_section_bytes.len();
+                }
+                group_offset += 16; // +16 for "MMDB_GROUPS" separator
+                metadata.insert(
+                    "group_section_offset".to_string(),
+                    DataValue::Uint32(offset_u32(group_offset, "group section offset")?),
+                );
+            }
+
             // Encode metadata
             let mut meta_encoder = DataEncoder::new();
-            let metadata_value = DataValue::Map(metadata);
+            let metadata_value = DataValue::from_hash_map(metadata);
             meta_encoder.encode(&metadata_value);
             let metadata_bytes = meta_encoder.into_bytes();
 
-            // Save metadata for end of file (will be added after pattern section)
-            // This ensures it's in the last 128KB for the metadata marker search
+            // Save metadata for end of file (will be added after pattern section)
+            // This ensures it's in the last 128KB for the metadata marker search
+
+            // Add MMDB_PATTERN separator before globs (if any)
+            if has_globs {
+                database.extend_from_slice(b"MMDB_PATTERN\x00\x00\x00\x00");
+                database.extend_from_slice(&glob_section_bytes);
+            }
+
+            // Add MMDB_LITERAL separator before literals (if any)
+            if has_literals {
+                database.extend_from_slice(b"MMDB_LITERAL\x00\x00\x00\x00");
+                database.extend_from_slice(&literal_section_bytes);
+            }
+
+            // Add MMDB_SUFFIX separator before suffix trie (if any)
+            if has_suffixes {
+                database.extend_from_slice(b"MMDB_SUFFIX\x00\x00\x00\x00\x00");
+                database.extend_from_slice(&suffix_section_bytes);
+            }
+
+            // Add MMDB_NUMERIC separator before numeric index (if any)
+            if has_numerics {
+                database.extend_from_slice(b"MMDB_NUMERIC\x00\x00\x00\x00");
+                database.extend_from_slice(&numeric_section_bytes);
+            }
+
+            // Add MMDB_GROUPS separator before the group table (if any)
+            if has_groups {
+                database.extend_from_slice(b"MMDB_GROUPS\x00\x00\x00\x00\x00");
+                database.extend_from_slice(&group_section_bytes);
+            }
+
+            // Add metadata at the END of the file so it's within the 128KB search window
+            database.extend_from_slice(b"\xAB\xCD\xEFMaxMind.com");
+            database.extend_from_slice(&metadata_bytes);
+        }
+
+        Ok(database)
+    }
+
+    /// Choose the tree record size for a given number of IP entries
+    ///
+    /// For /32 IPs, worst case is ~ip_count nodes:
+    /// - 24-bit: max 16,777,216 nodes (16M IPs)
+    /// - 28-bit: max 268,435,456 nodes (268M IPs)
+    /// - 32-bit: max 4,294,967,296 nodes (4.2B IPs)
+    fn record_size_for(ip_count: usize) -> RecordSize {
+        if ip_count > 200_000_000 {
+            // Over 200M IPs - use 32-bit for safety
+            RecordSize::Bits32
+        } else if ip_count > 15_000_000 {
+            // Over 15M IPs - use 28-bit
+            RecordSize::Bits28
+        } else {
+            // Under 15M IPs - use 24-bit (most common)
+            RecordSize::Bits24
+        }
+    }
+
+    /// Estimate the size of the database without building it
+    ///
+    /// Computed from the entries added so far, without constructing the IP
+    /// tree, automaton, or hash table. Useful for choosing whether to split a
+    /// large feed before paying for a full [`build`](Self::build).
+    ///
+    /// - The data section size is exact (data is encoded and deduplicated as
+    ///   entries are added).
+    /// - The tree size counts the distinct prefix nodes the entries would create.
+    /// - Pattern section sizes are approximations; the glob automaton estimate
+    ///   assumes no shared prefixes and tends to run high.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_entry("10.0.0.0/8", HashMap::new())?;
+    /// builder.add_entry("*.evil.com", HashMap::new())?;
+    ///
+    /// let estimate = builder.estimate_size();
+    /// println!("~{} bytes", estimate.total_size);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn estimate_size(&self) -> SizeEstimate {
+        let mut ip_prefixes = Vec::new();
+        let mut needs_v6 = false;
+        let mut literal_count = 0;
+        let mut literal_bytes = 0;
+        let mut glob_count = 0;
+        let mut glob_bytes = 0;
+
+        for entry in &self.entries {
+            match &entry.entry_type {
+                EntryType::IpAddress { addr, prefix_len } => {
+                    needs_v6 |= addr.is_ipv6();
+                    ip_prefixes.push((*addr, *prefix_len));
+                }
+                EntryType::Literal(pattern) => {
+                    literal_count += 1;
+                    literal_bytes += pattern.len();
+                }
+                EntryType::Glob(pattern) => {
+                    glob_count += 1;
+                    glob_bytes += pattern.len();
+                }
+            }
+        }
+
+        let record_size = Self::record_size_for(ip_prefixes.len());
+        let needs_v6 = self.ip_version.uses_v6_tree(needs_v6);
+        let tree_nodes = Self::count_tree_nodes(&ip_prefixes, needs_v6).max(1);
+        let tree_size = tree_nodes * record_size.node_bytes();
+
+        let (data_section_size, dictionary_strings, dictionary_size) = if self.defers_encoding() {
+            let (encoder, _, strings, size) = self.encode_pending(self.pending_data.clone());
+            (encoder.size(), strings, size)
+        } else {
+            (self.data_encoder.size(), 0, 0)
+        };
+
+        // [total_size][paraglob_size][paraglob][pattern_count][data offsets]
+        let glob_section_size = if glob_count > 0 {
+            let paraglob_size = mem::size_of::<ParaglobHeader>()
+                + glob_count * (mem::size_of::<PatternEntry>() + 4)
+                + (glob_bytes + glob_count)
+                + (glob_bytes + 1) * mem::size_of::<ACNodeHot>();
+            8 + paraglob_size + 4 + glob_count * 4
+        } else {
+            0
+        };
+
+        // Header, shard table, hash table at 0.6 load, string pool, mappings
+        let literal_section_size = if literal_count > 0 {
+            let num_shards = if literal_count < 10_000 {
+                16
+            } else if literal_count < 100_000 {
+                32
+            } else {
+                64
+            };
+            let per_shard = ((literal_count as f64 / 0.6) / num_shards as f64).ceil() as usize;
+            let table_entries = per_shard.next_power_of_two().max(16) * num_shards;
+            32 + (num_shards + 1) * 4
+                + table_entries * 16
+                + (literal_bytes + literal_count * 3)
+                + 4
+                + literal_count * 8
+        } else {
+            0
+        };
+
+        // Exact: the suffix trie is built incrementally
+        let suffix_section_size = if self.suffixes.is_empty() {
+            0
+        } else {
+            self.suffixes.serialized_size()
+        };
+
+        // Exact: one fixed-size record per key
+        let numeric_section_size = if self.numerics.is_empty() {
+            0
+        } else {
+            self.numerics.serialized_size()
+        };
+
+        let total_size = tree_size
+            + data_section_size
+            + glob_section_size
+            + literal_section_size
+            + suffix_section_size
+            + numeric_section_size
+            + METADATA_SIZE_ESTIMATE;
+
+        SizeEstimate {
+            record_size,
+            tree_size,
+            data_section_size,
+            dictionary_strings,
+            dictionary_size,
+            glob_section_size,
+            literal_section_size,
+            suffix_section_size,
+            numeric_section_size,
+            total_size,
+        }
+    }
+
+    /// Count the distinct search tree nodes a set of prefixes would create
+    ///
+    /// Prefixes are left-aligned into 128-bit keys (IPv4 is placed under ::/96
+    /// in an IPv6 tree). After sorting, each prefix only adds the nodes it does
+    /// not share with its predecessor.
+    fn count_tree_nodes(prefixes: &[(IpAddr, u8)], needs_v6: bool) -> usize {
+        let mut keys: Vec<(u128, u8)> = prefixes
+            .iter()
+            .map(|&(addr, prefix_len)| {
+                let (bits, depth) = match addr {
+                    IpAddr::V4(v4) if needs_v6 => (u32::from(v4) as u128, prefix_len + 96),
+                    IpAddr::V4(v4) => ((u32::from(v4) as u128) << 96, prefix_len),
+                    IpAddr::V6(v6) => (u128::from(v6), prefix_len),
+                };
+                let mask = u128::MAX.checked_shl(128 - depth as u32).unwrap_or(0);
+                (bits & mask, depth)
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut nodes = 0;
+        let mut prev: Option<(u128, u8)> = None;
+        for &(bits, depth) in &keys {
+            let shared = match prev {
+                Some((prev_bits, prev_depth)) => {
+                    let common = (prev_bits ^ bits).leading_zeros() as u8;
+                    (common as usize + 1)
+                        .min(depth as usize)
+                        .min(prev_depth as usize)
+                }
+                None => 0,
+            };
+            nodes += depth as usize - shared;
+            prev = Some((bits, depth));
+        }
+        nodes
+    }
+
+    /// List glob patterns that have no usable Aho-Corasick anchor
+    ///
+    /// Globs are found by searching the input for their literal parts, but
+    /// literals shorter than 3 bytes are too common to search for. A glob
+    /// whose literals are all that short (e.g. `*`, `a*b`, `*.i?`) is a
+    /// "pure wildcard": it is verified against every query and slows down all
+    /// lookups. Call this before [`build`](Self::build) to find such patterns
+    /// and rewrite them with a longer literal.
+    ///
+    /// Patterns are returned once each, in insertion order.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_glob("*.evil.com", HashMap::new())?;
+    /// builder.add_glob("a*b", HashMap::new())?;
+    ///
+    /// assert_eq!(builder.pure_wildcard_patterns(), vec!["a*b"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn pure_wildcard_patterns(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.entry_type {
+                EntryType::Glob(pattern) => Some(pattern.as_str()),
+                _ => None,
+            })
+            .filter(|pattern| is_pure_wildcard(pattern) && seen.insert(*pattern))
+            .collect()
+    }
+
+    /// Find globs that can never be the highest-precedence match
+    ///
+    /// Lookups report literal matches first and then globs in insertion
+    /// order, and single-result consumers such as
+    /// [`QueryResult::first_data`](crate::QueryResult::first_data) take the
+    /// first. A glob is dead for those consumers when:
+    /// - an earlier glob matches everything it matches, e.g. `*` or
+    ///   `*.com` added before `*.evil.com`
+    ///   ([`DeadReason::ShadowedByGlob`]), or
+    /// - it can only match one string that is also a literal entry, e.g.
+    ///   `evil.co[m]` next to `evil.com` ([`DeadReason::ShadowedByLiteral`]).
+    ///
+    /// Dead globs still appear in the full match list, so this is a lint
+    /// for rule-set hygiene, not an error. The check is conservative: it only
+    /// recognizes containment for `*`, `*text`, `text*`, and `*text*`
+    /// shapes, so a glob it does not report may still be shadowed.
+    ///
+    /// Returns `(glob_id, pattern, reason)` in glob ID order. Glob IDs number
+    /// the distinct glob patterns in insertion order, as in the built
+    /// database.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{DatabaseBuilder, DeadReason, MatchMode};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut builder = DatabaseBuilder::new(MatchMode::CaseSensitive);
+    /// builder.add_glob("*.com", HashMap::new())?;
+    /// builder.add_glob("*.evil.com", HashMap::new())?;
+    ///
+    /// let dead = builder.find_dead_patterns();
+    /// assert_eq!(dead, vec![(1, "*.evil.com".to_string(), DeadReason::ShadowedByGlob(0))]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn find_dead_patterns(&self) -> Vec<(u32, String, DeadReason)> {
+        let fold = |s: &str| match self.match_mode {
+            MatchMode::CaseSensitive => s.to_string(),
+            MatchMode::CaseInsensitive => s.to_lowercase(),
+        };
 
-            // Add MMDB_PATTERN separator before globs (if any)
-            if has_globs {
-                database.extend_from_slice(b"MMDB_PATTERN\x00\x00\x00\x00");
-                database.extend_from_slice(&glob_section_bytes);
+        let mut seen = HashSet::new();
+        let mut globs = Vec::new();
+        let mut literals = HashSet::new();
+        for entry in &self.entries {
+            match &entry.entry_type {
+                EntryType::Glob(pattern) if seen.insert(pattern.as_str()) => {
+                    globs.push(pattern.as_str())
+                }
+                EntryType::Literal(literal) => {
+                    literals.insert(fold(literal));
+                }
+                _ => {}
             }
+        }
 
-            // Add MMDB_LITERAL separator before literals (if any)
-            if has_literals {
-                database.extend_from_slice(b"MMDB_LITERAL\x00\x00\x00\x00");
-                database.extend_from_slice(&literal_section_bytes);
+        let mut dead = Vec::new();
+        // Earlier globs that can contain others: (id, folded segments)
+        let mut coverers: Vec<(u32, Vec<GlobSegment>)> = Vec::new();
+        for (id, pattern) in globs.into_iter().enumerate() {
+            let id = id as u32;
+            let Ok(glob) = GlobPattern::new(pattern, self.match_mode) else {
+                continue;
+            };
+            // Brace alternations are neither reported nor treated as covering
+            if glob.alternatives().len() > 1 {
+                continue;
+            }
+            let segments: Vec<GlobSegment> = glob
+                .segments()
+                .iter()
+                .map(|segment| match segment {
+                    GlobSegment::Literal(text) => GlobSegment::Literal(fold(text)),
+                    other => other.clone(),
+                })
+                .collect();
+
+            let reason = coverers
+                .iter()
+                .find(|(_, cover)| Self::glob_covers(cover, &segments))
+                .map(|(cover_id, _)| DeadReason::ShadowedByGlob(*cover_id))
+                .or_else(|| {
+                    Self::single_match(&segments)
+                        .filter(|text| literals.contains(&fold(text)))
+                        .map(DeadReason::ShadowedByLiteral)
+                });
+            match reason {
+                Some(reason) => dead.push((id, pattern.to_string(), reason)),
+                None => {
+                    if Self::can_cover(&segments) {
+                        coverers.push((id, segments));
+                    }
+                }
             }
+        }
+        dead
+    }
 
-            // Add metadata at the END of the file so it's within the 128KB search window
-            database.extend_from_slice(b"\xAB\xCD\xEFMaxMind.com");
-            database.extend_from_slice(&metadata_bytes);
+    /// Whether a glob has a shape [`glob_covers`](Self::glob_covers) understands
+    fn can_cover(segments: &[GlobSegment]) -> bool {
+        use GlobSegment::{Literal, Star};
+        matches!(
+            segments,
+            [Star] | [Star, Literal(_)] | [Literal(_), Star] | [Star, Literal(_), Star]
+        )
+    }
+
+    /// Whether every input matched by `inner` is also matched by `outer`
+    ///
+    /// Only exact for the shapes accepted by `can_cover`; otherwise `false`.
+    fn glob_covers(outer: &[GlobSegment], inner: &[GlobSegment]) -> bool {
+        use GlobSegment::{Literal, Star};
+        let literal = |segment: Option<&GlobSegment>| match segment {
+            Some(Literal(text)) => Some(text.clone()),
+            _ => None,
+        };
+        match outer {
+            [Star] => true,
+            [Star, Literal(suffix)] => {
+                literal(inner.last()).is_some_and(|tail| tail.ends_with(suffix.as_str()))
+            }
+            [Literal(prefix), Star] => {
+                literal(inner.first()).is_some_and(|head| head.starts_with(prefix.as_str()))
+            }
+            [Star, Literal(needle), Star] => inner
+                .iter()
+                .any(|segment| matches!(segment, Literal(text) if text.contains(needle.as_str()))),
+            _ => false,
         }
+    }
 
-        Ok(database)
+    /// The only string a glob can match, if it has no wildcards or choices
+    fn single_match(segments: &[GlobSegment]) -> Option<String> {
+        use crate::glob::CharClassItem;
+        let mut text = String::new();
+        for segment in segments {
+            match segment {
+                GlobSegment::Literal(literal) => text.push_str(literal),
+                GlobSegment::CharClass {
+                    chars,
+                    negated: false,
+                } => match chars.as_slice() {
+                    [CharClassItem::Char(c)] => text.push(*c),
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+        Some(text)
     }
 
     /// Get statistics about the builder
@@ -690,14 +2796,42 @@ impl MmdbBuilder {
         }
 
         BuilderStats {
-            total_entries: self.entries.len(),
+            total_entries: self.entries.len() + self.suffixes.len() + self.numerics.len(),
             ip_entries: ip_count,
             literal_entries: literal_count,
             glob_entries: glob_count,
+            suffix_entries: self.suffixes.len(),
+            numeric_entries: self.numerics.len(),
+            duplicate_entries: self.duplicate_count,
         }
     }
 }
 
+/// Projected database size from [`MmdbBuilder::estimate_size`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeEstimate {
+    /// Record size the build would choose for the IP tree
+    pub record_size: RecordSize,
+    /// IP search tree size in bytes
+    pub tree_size: usize,
+    /// Data section size in bytes (exact, after deduplication)
+    pub data_section_size: usize,
+    /// Strings in the shared string dictionary (0 without string interning)
+    pub dictionary_strings: usize,
+    /// Size of the string dictionary in bytes, included in `data_section_size`
+    pub dictionary_size: usize,
+    /// Glob pattern section size in bytes (0 if there are no globs)
+    pub glob_section_size: usize,
+    /// Literal hash section size in bytes (0 if there are no literals)
+    pub literal_section_size: usize,
+    /// Domain suffix section size in bytes (0 if there are no suffixes)
+    pub suffix_section_size: usize,
+    /// Numeric index section size in bytes (0 if there are no numeric entries)
+    pub numeric_section_size: usize,
+    /// Total file size in bytes, including metadata and separators
+    pub total_size: usize,
+}
+
 /// Builder statistics
 #[derive(Debug, Clone)]
 pub struct BuilderStats {
@@ -709,6 +2843,12 @@ pub struct BuilderStats {
     pub literal_entries: usize,
     /// Number of glob pattern entries (wildcard match)
     pub glob_entries: usize,
+    /// Number of domain suffix entries
+    pub suffix_entries: usize,
+    /// Number of distinct numeric entries
+    pub numeric_entries: usize,
+    /// Number of repeated keys skipped, replaced, or rejected by the duplicate policy
+    pub duplicate_entries: usize,
 }
 
 #[cfg(test)]
@@ -932,6 +3072,925 @@ mod tests {
         assert_eq!(stats.literal_entries, 0);
     }
 
+    #[test]
+    fn test_add_entries_lenient_skips_bad_rows() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let rows = vec![
+            ("10.0.0.0/8", HashMap::new()),
+            ("ip:not-an-ip", HashMap::new()),
+            ("evil.com", HashMap::new()),
+            ("glob:[unclosed", HashMap::new()),
+        ];
+
+        let failures = builder.add_entries_lenient(rows);
+        let failed_indices: Vec<usize> = failures.iter().map(|(i, _)| *i).collect();
+        assert_eq!(failed_indices, vec![1, 3]);
+
+        let stats = builder.stats();
+        assert_eq!(stats.ip_entries, 1);
+        assert_eq!(stats.literal_entries, 1);
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_add_entries_stops_at_first_error() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let rows = vec![
+            ("1.2.3.4", HashMap::new()),
+            ("ip:bogus", HashMap::new()),
+            ("evil.com", HashMap::new()),
+        ];
+
+        assert!(builder.add_entries(rows).is_err());
+        assert_eq!(builder.stats().total_entries, 1);
+    }
+
+    #[test]
+    fn test_estimate_size() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let empty = builder.estimate_size();
+        assert_eq!(empty.data_section_size, 0);
+        assert_eq!(empty.glob_section_size, 0);
+        assert_eq!(empty.literal_section_size, 0);
+
+        for i in 0..200u32 {
+            let mut data = HashMap::new();
+            data.insert("id".to_string(), DataValue::Uint32(i % 10));
+            builder
+                .add_entry(&format!("10.0.{}.0/24", i), data.clone())
+                .unwrap();
+            builder
+                .add_entry(&format!("host{}.example.com", i), data.clone())
+                .unwrap();
+            builder
+                .add_entry(&format!("*.evil{}.net", i), data)
+                .unwrap();
+        }
+
+        let estimate = builder.estimate_size();
+        assert_eq!(estimate.record_size, RecordSize::Bits24);
+        assert!(estimate.tree_size > 0);
+        assert!(estimate.glob_section_size > 0);
+        assert!(estimate.literal_section_size > 0);
+
+        // Within a small factor of the real build
+        let total = estimate.total_size;
+        let actual = builder.build().unwrap();
+        assert!(estimate.data_section_size > 0);
+        assert!(actual.len() <= total * 2);
+        assert!(total <= actual.len() * 4);
+    }
+
+    #[test]
+    fn test_estimate_tree_nodes() {
+        let p = |s: &str, len: u8| (s.parse::<IpAddr>().unwrap(), len);
+
+        // A single /24 needs one node per bit
+        assert_eq!(
+            MmdbBuilder::count_tree_nodes(&[p("10.0.0.0", 24)], false),
+            24
+        );
+        // Sibling /24s hang off the same /23 node
+        assert_eq!(
+            MmdbBuilder::count_tree_nodes(&[p("10.0.0.0", 24), p("10.0.1.0", 24)], false),
+            24
+        );
+        // Cousins diverge one level higher and need one extra node
+        assert_eq!(
+            MmdbBuilder::count_tree_nodes(&[p("10.0.0.0", 24), p("10.0.2.0", 24)], false),
+            25
+        );
+        // IPv4 in an IPv6 tree sits under ::/96
+        assert_eq!(
+            MmdbBuilder::count_tree_nodes(&[p("10.0.0.0", 8)], true),
+            104
+        );
+    }
+
+    #[test]
+    fn test_ip_only_build() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("2001:db8::/32", HashMap::new()).unwrap();
+        let bytes = builder.build().unwrap();
+
+        // No pattern scaffolding, and the offsets say so
+        assert!(!bytes.windows(8).any(|w| w == b"PARAGLOB"));
+        assert!(!bytes.windows(12).any(|w| w == b"MMDB_PATTERN"));
+        assert!(!bytes.windows(12).any(|w| w == b"MMDB_LITERAL"));
+
+        let db = crate::Database::from_bytes(bytes).unwrap();
+        let Some(DataValue::Map(metadata)) = db.metadata() else {
+            panic!("expected metadata map");
+        };
+        for key in ["pattern_section_offset", "literal_section_offset"] {
+            assert_eq!(metadata.get(key), Some(&DataValue::Uint32(0)), "{key}");
+        }
+        assert_eq!(db.format(), "IP database");
+        assert!(!db.has_string_data());
+        assert_eq!(db.literal_count(), 0);
+        assert_eq!(db.glob_count(), 0);
+        assert!(matches!(
+            db.lookup("10.1.2.3").unwrap(),
+            Some(crate::QueryResult::Ip { .. })
+        ));
+        assert!(db.lookup("example.com").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_literal_only_build() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseInsensitive);
+        for domain in ["evil.example.com", "bad.example.org", "malware.test"] {
+            builder.add_entry(domain, HashMap::new()).unwrap();
+        }
+        let bytes = builder.build().unwrap();
+
+        // Just the literal hash; no automaton or glob section
+        assert!(!bytes.windows(8).any(|w| w == b"PARAGLOB"));
+        assert!(!bytes.windows(12).any(|w| w == b"MMDB_PATTERN"));
+        assert!(bytes.windows(12).any(|w| w == b"MMDB_LITERAL"));
+
+        let db = crate::Database::from_bytes(bytes).unwrap();
+        assert!(db.has_literal_data());
+        assert_eq!(db.literal_count(), 3);
+        assert_eq!(db.glob_count(), 0);
+        assert!(matches!(
+            db.lookup("BAD.example.org").unwrap(),
+            Some(crate::QueryResult::Pattern { .. })
+        ));
+        assert!(matches!(
+            db.lookup("good.example.org").unwrap(),
+            Some(crate::QueryResult::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_add_entry_as() {
+        use crate::QueryResult;
+
+        let mut data = HashMap::new();
+        data.insert("feed".to_string(), DataValue::String("strings".to_string()));
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder
+            .add_entry_as("192.168.1.1", data, ForcedKind::Pattern)
+            .unwrap();
+        builder
+            .add_entry_as("10.0.0.0/8", HashMap::new(), ForcedKind::Ip)
+            .unwrap();
+        builder
+            .add_entry_as("1.2.3.*", HashMap::new(), ForcedKind::Pattern)
+            .unwrap();
+        assert!(builder
+            .add_entry_as("evil.com", HashMap::new(), ForcedKind::Ip)
+            .is_err());
+        let db = crate::Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        // The forced literal is not in the IP tree, so lookup() misses it
+        assert_eq!(db.literal_count(), 1);
+        assert_eq!(db.glob_count(), 1);
+        assert!(matches!(
+            db.lookup("192.168.1.1").unwrap(),
+            Some(QueryResult::NotFound) | None
+        ));
+        assert!(matches!(
+            db.lookup_pattern("192.168.1.1").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        assert!(matches!(
+            db.lookup("10.0.0.1").unwrap(),
+            Some(QueryResult::Ip { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ip_version_mode() {
+        use crate::{Database, QueryResult};
+
+        let build = |builder: MmdbBuilder| Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        let mut v4 =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_ip_version(IpVersionMode::V4Only);
+        v4.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        let err = v4.add_entry("2001:db8::/32", HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("IPv4-only"), "{}", err);
+        assert_eq!(build(v4).ip_version(), Some(4));
+
+        let mut v6 =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_ip_version(IpVersionMode::V6Only);
+        assert!(v6.add_entry("10.0.0.0/8", HashMap::new()).is_err());
+        v6.add_entry("2001:db8::/32", HashMap::new()).unwrap();
+        assert_eq!(build(v6).ip_version(), Some(6));
+
+        // Dual stack uses an IPv6 tree even when every network is IPv4
+        let mut dual =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_ip_version(IpVersionMode::DualStack);
+        dual.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        let db = build(dual);
+        assert_eq!(db.ip_version(), Some(6));
+        assert!(matches!(
+            db.lookup("10.1.2.3").unwrap(),
+            Some(QueryResult::Ip { .. })
+        ));
+
+        let mut lenient = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_ip_version(IpVersionMode::V4Only)
+            .with_lenient_ip_version();
+        lenient.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        lenient.add_entry("2001:db8::/32", HashMap::new()).unwrap();
+        assert_eq!(lenient.stats().ip_entries, 1);
+        let (_, warnings) = lenient.build_with_report().unwrap();
+        assert_eq!(
+            warnings,
+            vec![BuildWarning::IpFamilySkipped {
+                network: "2001:db8::/32".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_jsonl_reports_bad_lines() {
+        let feed = concat!(
+            "{\"key\": \"evil.example.com\", \"score\": 90, \"tags\": [\"c2\"], \"note\": null}\n",
+            "\n",
+            "{\"key\": \"192.168.0.0/16\", \"source\": \"internal\"}\n",
+            "{\"score\": 10}\n",
+            "{\"key\": 42}\n",
+            "{broken\n",
+            "{\"key\": \"*.bad.net\"}\n",
+        );
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let failures = builder.import_jsonl(feed.as_bytes(), "key").unwrap();
+        let lines: Vec<usize> = failures.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![4, 5, 6]);
+
+        let stats = builder.stats();
+        assert_eq!(stats.ip_entries, 1);
+        assert_eq!(stats.literal_entries, 1);
+        assert_eq!(stats.glob_entries, 1);
+
+        let db = crate::Database::from_bytes(builder.build().unwrap()).unwrap();
+        let result = db.lookup("evil.example.com").unwrap().unwrap();
+        let Some(DataValue::Map(data)) = result.first_data() else {
+            panic!("expected map data");
+        };
+        assert_eq!(data.get("score"), Some(&DataValue::Uint16(90)));
+        assert!(!data.contains_key("note"));
+        assert!(!data.contains_key("key"));
+    }
+
+    #[test]
+    fn test_literal_hash_seed() {
+        use crate::QueryResult;
+
+        let mut builder =
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_literal_hash_seed(0xdead_beef);
+        builder
+            .add_literal("evil.example.com", HashMap::new())
+            .unwrap();
+        let bytes = builder.build().unwrap();
+
+        let db = crate::Database::from_bytes(bytes).unwrap();
+        assert!(matches!(
+            db.lookup("evil.example.com").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+        assert!(matches!(
+            db.lookup("good.example.com").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_pure_wildcard_patterns() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_glob("*.evil.com", HashMap::new()).unwrap();
+        builder.add_glob("*", HashMap::new()).unwrap();
+        builder.add_glob("a*b", HashMap::new()).unwrap();
+        builder.add_glob("*", HashMap::new()).unwrap();
+        builder.add_literal("a*", HashMap::new()).unwrap();
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+
+        assert_eq!(builder.pure_wildcard_patterns(), vec!["*", "a*b"]);
+    }
+
+    #[test]
+    fn test_wildcard_and_range_ip_entries() {
+        let cidr = |key: &str| match MmdbBuilder::detect_entry_type(key).unwrap() {
+            EntryType::IpAddress { addr, prefix_len } => format!("{}/{}", addr, prefix_len),
+            other => panic!("{} detected as {:?}", key, other),
+        };
+        assert_eq!(cidr("10.0.*.*"), "10.0.0.0/16");
+        assert_eq!(cidr("10.*.*.*"), "10.0.0.0/8");
+        assert_eq!(cidr("*.*.*.*"), "0.0.0.0/0");
+        assert_eq!(cidr("10.0.0.0-10.0.255.255"), "10.0.0.0/16");
+        assert_eq!(cidr("1.2.3.4 - 1.2.3.4"), "1.2.3.4/32");
+        assert_eq!(cidr("2001:db8::-2001:db8::ffff"), "2001:db8::/112");
+
+        // Non-trailing wildcards stay string globs
+        assert!(matches!(
+            MmdbBuilder::detect_entry_type("10.*.0.*"),
+            Ok(EntryType::Glob(_))
+        ));
+        // Ranges spanning several blocks have no single type; add_entry splits them
+        assert!(MmdbBuilder::detect_entry_type("10.0.0.1-10.0.0.2").is_err());
+        // Reversed or mixed-family ranges are errors
+        assert!(MmdbBuilder::detect_entry_type("10.0.0.255-10.0.0.0").is_err());
+        assert!(MmdbBuilder::detect_entry_type("10.0.0.0-::1").is_err());
+        // Hyphenated names are unaffected
+        assert!(matches!(
+            MmdbBuilder::detect_entry_type("my-host.example.com"),
+            Ok(EntryType::Literal(_))
+        ));
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("10.0.*.*", HashMap::new()).unwrap();
+        let db = crate::Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert!(matches!(
+            db.lookup("10.0.200.1").unwrap(),
+            Some(crate::QueryResult::Ip { .. })
+        ));
+        assert!(!matches!(
+            db.lookup("10.1.0.1").unwrap(),
+            Some(crate::QueryResult::Ip { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unaligned_ip_range_entry() {
+        let mut data = HashMap::new();
+        data.insert("asn".to_string(), DataValue::Uint32(64512));
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("1.2.3.5-1.2.3.20", data.clone()).unwrap();
+        // Staged as its five blocks
+        assert_eq!(builder.stats().ip_entries, 5);
+        let db = crate::Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        for inside in ["1.2.3.5", "1.2.3.12", "1.2.3.20"] {
+            let result = db.lookup(inside).unwrap();
+            assert!(
+                matches!(result, Some(crate::QueryResult::Ip { .. })),
+                "{} should match",
+                inside
+            );
+        }
+        for outside in ["1.2.3.4", "1.2.3.21"] {
+            assert!(!matches!(
+                db.lookup(outside).unwrap(),
+                Some(crate::QueryResult::Ip { .. })
+            ));
+        }
+
+        // Each of the five blocks carries the data
+        let canonical = db.canonical_text().unwrap();
+        assert_eq!(canonical.matches("64512").count(), 5);
+
+        // Removing the range removes every block
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_ip("1.2.3.5-1.2.3.20", data).unwrap();
+        builder.add_ip("1.2.3.0/24", HashMap::new()).unwrap();
+        assert!(builder.remove_entry("1.2.3.5-1.2.3.20"));
+        assert_eq!(builder.stats().ip_entries, 1);
+    }
+
+    #[test]
+    fn test_record_size_grows_with_data_section() {
+        // A data section past 16MB can't be addressed by 24-bit records even
+        // with only a handful of IP entries
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for i in 0..20u32 {
+            let mut data = HashMap::new();
+            data.insert(
+                "blob".to_string(),
+                DataValue::Bytes(vec![i as u8; 1024 * 1024]),
+            );
+            builder.add_ip(&format!("10.0.0.{}", i), data).unwrap();
+        }
+        let db = crate::Database::from_bytes(builder.build().unwrap()).unwrap();
+        assert_eq!(
+            db.lookup("10.0.0.19").unwrap().unwrap().first_data(),
+            Some(&DataValue::Map(crate::data_section::DataMap::from([(
+                "blob".to_string(),
+                DataValue::Bytes(vec![19; 1024 * 1024])
+            )])))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        use crate::{Database, QueryResult};
+
+        let tagged = |tag: &str| {
+            let mut data = HashMap::new();
+            data.insert("tag".to_string(), DataValue::String(tag.to_string()));
+            data
+        };
+        let tag_of = |bytes: Vec<u8>, query: &str| {
+            let db = Database::from_bytes(bytes).unwrap();
+            let result: QueryResult = db.lookup(query).unwrap().unwrap();
+            match result.first_data() {
+                Some(DataValue::Map(m)) => m.get("tag").cloned(),
+                _ => None,
+            }
+        };
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_duplicate_policy(DuplicatePolicy::KeepFirst);
+        builder.add_entry("evil.com", tagged("first")).unwrap();
+        builder.add_literal("evil.com", tagged("second")).unwrap();
+        builder.add_ip("10.0.0.1", tagged("first")).unwrap();
+        builder.add_entry("10.0.0.1/32", tagged("second")).unwrap();
+        let stats = builder.stats();
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.duplicate_entries, 2);
+        let bytes = builder.build().unwrap();
+        assert_eq!(
+            tag_of(bytes.clone(), "evil.com"),
+            Some(DataValue::String("first".to_string()))
+        );
+        assert_eq!(
+            tag_of(bytes, "10.0.0.1"),
+            Some(DataValue::String("first".to_string()))
+        );
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_duplicate_policy(DuplicatePolicy::KeepLast);
+        builder.add_entry("evil.com", tagged("first")).unwrap();
+        builder.add_entry("evil.com", tagged("second")).unwrap();
+        // A glob with the same text is a different key
+        builder.add_glob("evil.com", tagged("glob")).unwrap();
+        assert_eq!(builder.stats().total_entries, 2);
+        assert_eq!(
+            tag_of(builder.build().unwrap(), "evil.com"),
+            Some(DataValue::String("second".to_string()))
+        );
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_duplicate_policy(DuplicatePolicy::Error);
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        assert!(builder.add_entry("evil.com", HashMap::new()).is_err());
+        // A duplicate aborts a JSONL import instead of being skipped as malformed
+        let feed = "{\"key\": \"other.com\"}\n{\"key\": \"evil.com\"}\n";
+        assert!(builder.import_jsonl(feed.as_bytes(), "key").is_err());
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        assert_eq!(builder.stats().total_entries, 2);
+        assert_eq!(builder.stats().duplicate_entries, 0);
+    }
+
+    #[test]
+    fn test_string_interning() {
+        use crate::Database;
+
+        // Unique categories up front put the shared ones past the first 2KB,
+        // where the dictionary's 2-byte pointers beat inline interning
+        let categories = ["malware", "phishing", "botnet"];
+        let category = |i: u32| match i {
+            0..150 => format!("unknown-{}", i),
+            _ => categories[i as usize % 3].to_string(),
+        };
+        let fill = |mut builder: MmdbBuilder| {
+            for i in 0..300u32 {
+                let mut data = HashMap::new();
+                data.insert("category".to_string(), DataValue::String(category(i)));
+                data.insert("id".to_string(), DataValue::String(format!("ioc-{}", i)));
+                builder
+                    .add_literal(&format!("host{}.example.com", i), data)
+                    .unwrap();
+            }
+            builder
+                .add_suffix("evil.net", {
+                    let mut data = HashMap::new();
+                    data.insert(
+                        "category".to_string(),
+                        DataValue::String("malware".to_string()),
+                    );
+                    data
+                })
+                .unwrap();
+            builder
+        };
+
+        let plain = fill(MmdbBuilder::new(MatchMode::CaseSensitive));
+        let interned = fill(MmdbBuilder::new(MatchMode::CaseSensitive).with_string_interning());
+        assert_eq!(plain.estimate_size().dictionary_strings, 0);
+
+        // "category", "id", and the three categories; ids are unique
+        let estimate = interned.estimate_size();
+        assert_eq!(estimate.dictionary_strings, 5);
+        assert!(estimate.dictionary_size > 0);
+        assert!(estimate.data_section_size <= plain.estimate_size().data_section_size);
+
+        // Shared strings all within the first 2KB: the dictionary can't win
+        let mut small = MmdbBuilder::new(MatchMode::CaseSensitive).with_string_interning();
+        for i in 0..3u32 {
+            let mut data = HashMap::new();
+            data.insert(
+                "category".to_string(),
+                DataValue::String(categories[0].to_string()),
+            );
+            data.insert("id".to_string(), DataValue::Uint32(i));
+            small
+                .add_literal(&format!("host{}.example.com", i), data)
+                .unwrap();
+        }
+        assert_eq!(small.estimate_size().dictionary_strings, 0);
+
+        let db = Database::from_bytes(interned.build().unwrap()).unwrap();
+        for i in [0u32, 151, 299] {
+            let result = db
+                .lookup(&format!("host{}.example.com", i))
+                .unwrap()
+                .unwrap();
+            let Some(DataValue::Map(m)) = result.first_data() else {
+                panic!("expected map data");
+            };
+            assert_eq!(m.get("category"), Some(&DataValue::String(category(i))));
+            assert_eq!(m.get("id"), Some(&DataValue::String(format!("ioc-{}", i))));
+        }
+        let suffix = db.lookup_suffix("www.evil.net").unwrap().unwrap();
+        let DataValue::Map(m) = suffix.data else {
+            panic!("expected map data");
+        };
+        assert_eq!(
+            m.get("category"),
+            Some(&DataValue::String("malware".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_add_entry_with_provenance() {
+        use crate::Database;
+
+        let mut data = HashMap::new();
+        data.insert("asn".to_string(), DataValue::Uint32(64512));
+        data.insert("category".to_string(), DataValue::String("c2".to_string()));
+        data.insert("note".to_string(), DataValue::String("manual".to_string()));
+        let mut provenance = HashMap::new();
+        provenance.insert("asn".to_string(), "feed-a".to_string());
+        provenance.insert("category".to_string(), "feed-b".to_string());
+
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder
+            .add_entry_with_provenance("evil.com", data.clone(), provenance)
+            .unwrap();
+        // No sources: no provenance map
+        builder
+            .add_entry_with_provenance("plain.com", data.clone(), HashMap::new())
+            .unwrap();
+
+        let mut unknown = HashMap::new();
+        unknown.insert("missing".to_string(), "feed-a".to_string());
+        assert!(builder
+            .add_entry_with_provenance("bad.com", data, unknown)
+            .is_err());
+
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+        let result = db.lookup("evil.com").unwrap().unwrap();
+        assert_eq!(result.field_source("asn"), Some("feed-a"));
+        assert_eq!(result.field_source("category"), Some("feed-b"));
+        assert_eq!(result.field_source("note"), None);
+
+        let result = db.lookup("plain.com").unwrap().unwrap();
+        assert_eq!(result.field_source("asn"), None);
+        let Some(DataValue::Map(m)) = result.first_data() else {
+            panic!("expected map data");
+        };
+        assert!(!m.contains_key(PROVENANCE_FIELD));
+        assert!(matches!(
+            db.lookup("bad.com").unwrap(),
+            Some(crate::QueryResult::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_find_dead_patterns() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseInsensitive);
+        for glob in [
+            "*.evil.com",      // 0: live
+            "*.cdn.evil.com",  // 1: covered by 0
+            "login.*",         // 2: live
+            "login.bank.*",    // 3: covered by 2
+            "*tracker*",       // 4: live
+            "ad.TRACKER.net*", // 5: covered by 4 (case-insensitive)
+            "exact.co[m]",     // 6: only matches the literal below
+            "other.co[mn]",    // 7: live (two choices)
+            "*.evil.com",      // duplicate of 0, same ID
+        ] {
+            builder.add_glob(glob, HashMap::new()).unwrap();
+        }
+        builder.add_literal("Exact.com", HashMap::new()).unwrap();
+
+        assert_eq!(
+            builder.find_dead_patterns(),
+            vec![
+                (
+                    1,
+                    "*.cdn.evil.com".to_string(),
+                    DeadReason::ShadowedByGlob(0)
+                ),
+                (3, "login.bank.*".to_string(), DeadReason::ShadowedByGlob(2)),
+                (
+                    5,
+                    "ad.TRACKER.net*".to_string(),
+                    DeadReason::ShadowedByGlob(4)
+                ),
+                (
+                    6,
+                    "exact.co[m]".to_string(),
+                    DeadReason::ShadowedByLiteral("exact.com".to_string())
+                ),
+            ]
+        );
+
+        // A catch-all shadows everything added after it, but not before
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        builder.add_glob("a*b", HashMap::new()).unwrap();
+        builder.add_glob("*", HashMap::new()).unwrap();
+        builder.add_glob("*.net", HashMap::new()).unwrap();
+        assert_eq!(
+            builder.find_dead_patterns(),
+            vec![(2, "*.net".to_string(), DeadReason::ShadowedByGlob(1))]
+        );
+    }
+
+    #[test]
+    fn test_compression_friendly_layout() {
+        use crate::Database;
+
+        let categories = ["malware", "phishing", "botnet"];
+        let fill = |mut builder: MmdbBuilder| {
+            for i in 0..90u32 {
+                let mut data = HashMap::new();
+                data.insert(
+                    "category".to_string(),
+                    DataValue::String(categories[i as usize % 3].to_string()),
+                );
+                data.insert("score".to_string(), DataValue::Uint32(i));
+                builder
+                    .add_literal(&format!("host{}.example.com", i), data)
+                    .unwrap();
+            }
+            builder
+        };
+
+        for builder in [
+            MmdbBuilder::new(MatchMode::CaseSensitive).with_compression_friendly_layout(),
+            MmdbBuilder::new(MatchMode::CaseSensitive)
+                .with_compression_friendly_layout()
+                .with_string_interning(),
+        ] {
+            let builder = fill(builder);
+            let estimate = builder.estimate_size().data_section_size;
+            let bytes = builder.build().unwrap();
+            assert_ne!(
+                bytes,
+                fill(MmdbBuilder::new(MatchMode::CaseSensitive))
+                    .build()
+                    .unwrap()
+            );
+            assert!(estimate > 0);
+
+            // Entries still point at their own records after reordering
+            let db = Database::from_bytes(bytes).unwrap();
+            for i in [0u32, 1, 2, 44, 89] {
+                let result = db
+                    .lookup(&format!("host{}.example.com", i))
+                    .unwrap()
+                    .unwrap();
+                let Some(DataValue::Map(m)) = result.first_data() else {
+                    panic!("expected map data");
+                };
+                assert_eq!(
+                    m.get("category"),
+                    Some(&DataValue::String(categories[i as usize % 3].to_string()))
+                );
+                assert_eq!(m.get("score"), Some(&DataValue::Uint32(i)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_options_after_entries() {
+        use crate::Database;
+
+        let mut original = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for i in 0..20u32 {
+            let mut data = HashMap::new();
+            data.insert(
+                "category".to_string(),
+                DataValue::String(["malware", "phishing"][i as usize % 2].to_string()),
+            );
+            data.insert("score".to_string(), DataValue::Uint64(i as u64));
+            original
+                .add_entry(&format!("host{}.example.com", i), data.clone())
+                .unwrap();
+            original
+                .add_entry(&format!("10.0.{}.0/24", i), data.clone())
+                .unwrap();
+            original.add_numeric_entry(i as u64, data).unwrap();
+        }
+        original.add_suffix("example.org", HashMap::new()).unwrap();
+        let original = Database::from_bytes(original.build().unwrap()).unwrap();
+        let expected = original.canonical_text().unwrap();
+
+        // Switching a layout on after entries were encoded keeps their data
+        let relayouts: [fn(MmdbBuilder) -> MmdbBuilder; 2] = [
+            |b| b.with_string_interning(),
+            |b| b.with_compression_friendly_layout(),
+        ];
+        for relayout in relayouts {
+            let mut builder = relayout(original.to_builder().unwrap());
+            builder
+                .add_entry("late.example.com", HashMap::new())
+                .unwrap();
+            let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+            assert!(db.lookup("late.example.com").unwrap().is_some());
+            let text = db.canonical_text().unwrap();
+            assert_eq!(
+                text.replace("literal\t\"late.example.com\"\t{}\n", ""),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_with_report() {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let mut data = HashMap::new();
+        data.insert("threat".to_string(), DataValue::String("high".to_string()));
+        builder.add_entry("10.1.2.3/8", data.clone()).unwrap();
+        builder.add_entry("192.168.0.0/16", data.clone()).unwrap();
+        builder.add_entry("2001:db8::1/32", HashMap::new()).unwrap();
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        builder.add_entry("a*b", HashMap::new()).unwrap();
+
+        let (bytes, warnings) = builder.build_with_report().unwrap();
+        assert!(crate::Database::from_bytes(bytes).is_ok());
+        assert_eq!(
+            warnings,
+            vec![
+                BuildWarning::HostBitsMasked {
+                    cidr: "10.1.2.3/8".to_string()
+                },
+                BuildWarning::HostBitsMasked {
+                    cidr: "2001:db8::1/32".to_string()
+                },
+                BuildWarning::DuplicateKey {
+                    key: "evil.com".to_string()
+                },
+                BuildWarning::PureWildcard {
+                    pattern: "a*b".to_string()
+                },
+                BuildWarning::DataDeduplicated { count: 5 },
+            ]
+        );
+        assert_eq!(warnings[2].to_string(), "duplicate key: evil.com");
+
+        // Checking policies report the repeats they collapsed
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+            .with_duplicate_policy(DuplicatePolicy::KeepFirst);
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        builder.add_entry("evil.com", HashMap::new()).unwrap();
+        let (_, warnings) = builder.build_with_report().unwrap();
+        assert_eq!(
+            warnings,
+            vec![BuildWarning::DuplicateKey {
+                key: "evil.com".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remove_and_retain() {
+        let score = |n: u32| HashMap::from([("score".to_string(), DataValue::Uint32(n))]);
+
+        for interning in [false, true] {
+            let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive)
+                .with_duplicate_policy(DuplicatePolicy::Error);
+            if interning {
+                builder = builder.with_string_interning();
+            }
+            builder.add_entry("10.1.2.3/8", score(90)).unwrap();
+            builder.add_entry("evil.com", score(90)).unwrap();
+            builder.add_entry("*.cdn.example", score(10)).unwrap();
+            builder
+                .add_entry("literal:*.cdn.example", score(90))
+                .unwrap();
+            builder.add_entry("low.example", score(20)).unwrap();
+
+            assert!(builder.remove_entry("10.0.0.0/8"));
+            assert!(!builder.remove_entry("10.0.0.0/8"));
+            assert!(builder.remove_entry("literal:*.cdn.example"));
+            assert!(!builder.remove_entry("absent.example"));
+
+            let mut seen = Vec::new();
+            builder.retain(|key, data| {
+                seen.push(key.to_string());
+                data.get("score") != Some(&DataValue::Uint32(20))
+            });
+            assert_eq!(seen, vec!["evil.com", "*.cdn.example", "low.example"]);
+
+            // The duplicate index still tracks the remaining entries
+            assert!(builder.add_entry("evil.com", score(1)).is_err());
+            builder.add_entry("low.example", score(30)).unwrap();
+
+            let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+            assert!(matches!(
+                db.lookup("10.1.2.3").unwrap(),
+                Some(crate::QueryResult::NotFound)
+            ));
+            assert!(db
+                .lookup("a.cdn.example")
+                .unwrap()
+                .unwrap()
+                .first_data()
+                .is_some());
+            assert_eq!(db.lookup_all("*.cdn.example").unwrap().len(), 1);
+            assert_eq!(
+                db.lookup("low.example").unwrap().unwrap().first_data(),
+                Some(&DataValue::from_hash_map(score(30)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let tag = |v: &str| HashMap::from([("src".to_string(), DataValue::String(v.into()))]);
+
+        let mut base = MmdbBuilder::new(MatchMode::CaseSensitive);
+        base.add_entry("10.0.0.0/8", tag("base")).unwrap();
+        base.add_entry("192.168.0.0/16", tag("base")).unwrap();
+        base.add_entry("evil.com", tag("base")).unwrap();
+        base.add_entry("*.evil.com", tag("base")).unwrap();
+        base.add_suffix("ads.example", tag("base")).unwrap();
+        base.add_numeric_entry(15169, tag("base")).unwrap();
+        let base = Database::from_bytes(base.build().unwrap()).unwrap();
+
+        let mut overlay = MmdbBuilder::new(MatchMode::CaseSensitive);
+        // Host bits differ from the base key but name the same network
+        overlay.add_entry("10.9.9.9/8", tag("overlay")).unwrap();
+        overlay.add_entry("*.evil.com", tag("overlay")).unwrap();
+        overlay.add_numeric_entry(15169, tag("overlay")).unwrap();
+        overlay.merge_from(&base).unwrap();
+        let db = Database::from_bytes(overlay.build().unwrap()).unwrap();
+
+        let overlay_data = DataValue::from_hash_map(tag("overlay"));
+        let base_data = DataValue::from_hash_map(tag("base"));
+        let first = |query: &str| db.lookup(query).unwrap().unwrap().first_data().cloned();
+        assert_eq!(first("10.1.2.3"), Some(overlay_data.clone()));
+        assert_eq!(first("192.168.1.1"), Some(base_data.clone()));
+        assert_eq!(first("evil.com"), Some(base_data.clone()));
+        assert_eq!(first("www.evil.com"), Some(overlay_data.clone()));
+        assert_eq!(
+            db.lookup_suffix("x.ads.example").unwrap().unwrap().data,
+            base_data
+        );
+        assert_eq!(db.lookup_numeric(15169).unwrap(), Some(overlay_data));
+
+        // One glob per key, not one from each side
+        assert_eq!(db.lookup_all("www.evil.com").unwrap().len(), 1);
+
+        let mut insensitive = MmdbBuilder::new(MatchMode::CaseInsensitive);
+        assert!(insensitive.merge_from(&base).is_err());
+    }
+
+    #[test]
+    fn test_merge_from_renumbers_groups() {
+        let mut base = MmdbBuilder::new(MatchMode::CaseSensitive);
+        base.add_entry_in_group("*.phish.com", HashMap::new(), "phishing")
+            .unwrap();
+        base.add_entry_in_group("c2.net", HashMap::new(), "c2")
+            .unwrap();
+        let base = Database::from_bytes(base.build().unwrap()).unwrap();
+
+        // "c2" is group 0 here but group 1 in the base
+        let mut overlay = MmdbBuilder::new(MatchMode::CaseSensitive);
+        overlay
+            .add_entry_in_group("*.c2.org", HashMap::new(), "c2")
+            .unwrap();
+        overlay.merge_from(&base).unwrap();
+        let db = Database::from_bytes(overlay.build().unwrap()).unwrap();
+
+        assert_eq!(db.pattern_groups(), ["c2", "phishing"]);
+        let found = |key: &str, group: &str| {
+            matches!(
+                db.lookup_in_group(key, group).unwrap(),
+                Some(crate::QueryResult::Pattern { .. })
+            )
+        };
+        assert!(found("c2.net", "c2"));
+        assert!(found("x.c2.org", "c2"));
+        assert!(found("x.phish.com", "phishing"));
+        assert!(!found("c2.net", "phishing"));
+        assert!(!found("x.phish.com", "c2"));
+    }
+
     #[test]
     fn test_empty_prefix_value() {
         // Edge case: what if someone uses "literal:" with nothing after?
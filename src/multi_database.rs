@@ -0,0 +1,214 @@
+//! Query several databases through a single handle
+//!
+//! Useful when feeds are maintained separately (e.g. independent IPv4 and
+//! IPv6 databases, or a GeoIP file plus a threat-intel file) and should not
+//! be rebuilt into one combined file.
+//!
+//! # Precedence
+//!
+//! Databases are consulted in the order they were supplied:
+//! - IP queries only go to databases whose IP tree covers the address family
+//!   (IPv4 queries go to every database with IP data, IPv6 queries only to
+//!   IPv6 databases).
+//! - String queries fan out to every database with literal or glob data.
+//! - [`MultiDatabase::lookup`] returns the match from the first database that
+//!   has one; [`MultiDatabase::lookup_all`] returns matches from all of them.
+
+use crate::database::{Database, DatabaseError, QueryResult};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// A read-only view over several databases queried in order
+///
+/// # Examples
+///
+/// ```no_run
+/// use matchy::Database;
+///
+/// let multi = Database::open_multi(&["geo-v4.mxy", "geo-v6.mxy"])?;
+///
+/// // First matching database wins
+/// if let Some(result) = multi.lookup("2001:db8::1")? {
+///     println!("Found: {:?}", result);
+/// }
+///
+/// // Every database that matches, tagged with its index
+/// for (index, result) in multi.lookup_all("evil.example.com")? {
+///     println!("db #{}: {:?}", index, result);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct MultiDatabase {
+    databases: Vec<Database>,
+}
+
+impl MultiDatabase {
+    /// Open each path with default options, in precedence order
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, DatabaseError> {
+        let databases = paths
+            .iter()
+            .map(|p| Database::from(p.as_ref()).open())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { databases })
+    }
+
+    /// Wrap already-opened databases, in precedence order
+    pub fn from_databases(databases: Vec<Database>) -> Self {
+        Self { databases }
+    }
+
+    /// Underlying databases in precedence order
+    pub fn databases(&self) -> &[Database] {
+        &self.databases
+    }
+
+    /// Number of underlying databases
+    pub fn len(&self) -> usize {
+        self.databases.len()
+    }
+
+    /// Check whether there are no underlying databases
+    pub fn is_empty(&self) -> bool {
+        self.databases.is_empty()
+    }
+
+    /// Look up a query, returning the match from the first database that has one
+    ///
+    /// Returns `Ok(Some(QueryResult::NotFound))` if at least one database could
+    /// answer the query but none matched, and `Ok(None)` if no database holds
+    /// data of the queried kind.
+    pub fn lookup(&self, query: &str) -> Result<Option<QueryResult>, DatabaseError> {
+        let mut answered = false;
+        for db in self.candidates(query) {
+            match db.lookup(query)? {
+                Some(QueryResult::NotFound) => answered = true,
+                Some(result) => return Ok(Some(result)),
+                None => {}
+            }
+        }
+        Ok(answered.then_some(QueryResult::NotFound))
+    }
+
+    /// Look up an already-parsed IP address, like [`lookup`](Self::lookup)
+    pub fn lookup_ip(&self, addr: IpAddr) -> Result<Option<QueryResult>, DatabaseError> {
+        let mut answered = false;
+        for db in self
+            .databases
+            .iter()
+            .filter(|db| Self::applies(db, Some(&addr)))
+        {
+            match db.lookup_ip(addr)? {
+                Some(QueryResult::NotFound) => answered = true,
+                Some(result) => return Ok(Some(result)),
+                None => {}
+            }
+        }
+        Ok(answered.then_some(QueryResult::NotFound))
+    }
+
+    /// Look up a query in every applicable database
+    ///
+    /// Returns `(database_index, result)` for each database that matched, in
+    /// precedence order. Databases without a match are omitted.
+    pub fn lookup_all(&self, query: &str) -> Result<Vec<(usize, QueryResult)>, DatabaseError> {
+        let ip = query.parse::<IpAddr>().ok();
+        let mut results = Vec::new();
+        for (index, db) in self.databases.iter().enumerate() {
+            if !Self::applies(db, ip.as_ref()) {
+                continue;
+            }
+            match db.lookup(query)? {
+                Some(QueryResult::NotFound) | None => {}
+                Some(result) => results.push((index, result)),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Databases that should see this query, in precedence order
+    fn candidates<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a Database> + 'a {
+        let ip = query.parse::<IpAddr>().ok();
+        self.databases
+            .iter()
+            .filter(move |db| Self::applies(db, ip.as_ref()))
+    }
+
+    /// Check whether a database can answer an IP query (Some) or string query (None)
+    fn applies(db: &Database, ip: Option<&IpAddr>) -> bool {
+        match ip {
+            Some(IpAddr::V4(_)) => db.ip_version().is_some(),
+            Some(IpAddr::V6(_)) => db.ip_version() == Some(6),
+            None => db.has_string_data(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_section::DataValue;
+    use crate::glob::MatchMode;
+    use crate::mmdb_builder::MmdbBuilder;
+    use std::collections::HashMap;
+
+    fn db_with(entries: &[(&str, &str)]) -> Database {
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        for (key, source) in entries {
+            let mut data = HashMap::new();
+            data.insert("source".to_string(), DataValue::String(source.to_string()));
+            builder.add_entry(key, data).unwrap();
+        }
+        Database::from_bytes(builder.build().unwrap()).unwrap()
+    }
+
+    fn source_of(result: &QueryResult) -> String {
+        let data = match result {
+            QueryResult::Ip { data, .. } => data.clone(),
+            QueryResult::Pattern { data, .. } => data[0].clone().unwrap(),
+            QueryResult::NotFound => panic!("expected a match"),
+        };
+        match data {
+            DataValue::Map(map) => match map.get("source") {
+                Some(DataValue::String(s)) => s.clone(),
+                _ => panic!("missing source"),
+            },
+            _ => panic!("expected map"),
+        }
+    }
+
+    #[test]
+    fn test_routes_ip_queries_by_family() {
+        let v4 = db_with(&[("10.0.0.0/8", "v4")]);
+        let v6 = db_with(&[("2001:db8::/32", "v6")]);
+        let multi = MultiDatabase::from_databases(vec![v4, v6]);
+
+        let r = multi.lookup("10.1.2.3").unwrap().unwrap();
+        assert_eq!(source_of(&r), "v4");
+
+        let r = multi.lookup("2001:db8::1").unwrap().unwrap();
+        assert_eq!(source_of(&r), "v6");
+
+        assert!(matches!(
+            multi.lookup("192.0.2.1").unwrap(),
+            Some(QueryResult::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_first_match_wins_and_lookup_all() {
+        let first = db_with(&[("*.example.com", "first")]);
+        let second = db_with(&[("evil.example.com", "second")]);
+        let multi = MultiDatabase::from_databases(vec![first, second]);
+
+        let r = multi.lookup("evil.example.com").unwrap().unwrap();
+        assert_eq!(source_of(&r), "first");
+
+        let all = multi.lookup_all("evil.example.com").unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0, 0);
+        assert_eq!(all[1].0, 1);
+        assert_eq!(source_of(&all[1].1), "second");
+
+        assert!(multi.lookup_all("benign.org").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+//! Sorted Integer Index for Numeric Keys
+//!
+//! Maps integer identifiers (ASNs, port numbers, numeric country codes) to
+//! data without formatting them as strings for the literal hash. Keys are
+//! stored once, in ascending order, and found by binary search.
+//!
+//! # Format
+//!
+//! ```text
+//! [Header]
+//!   magic: [u8; 4]           // "NUMX"
+//!   version: u32              // 1
+//!   entry_count: u32          // Number of keys
+//!   reserved: u32             // 0
+//!
+//! [Entries] (starting at offset 16)
+//!   entries: [(key: u64, data_offset: u32); entry_count]
+//!   Sorted by key, no duplicates
+//! ```
+
+use crate::error::ParaglobError;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Magic bytes for numeric index section
+pub const NUMERIC_INDEX_MAGIC: &[u8; 4] = b"NUMX";
+
+/// Current numeric index format version
+pub const NUMERIC_INDEX_VERSION: u32 = 1;
+
+/// Header size: 4 byte magic + 3 u32 fields
+const HEADER_SIZE: usize = 16;
+
+/// Size of a serialized entry (u64 key + u32 data offset)
+const ENTRY_SIZE: usize = 12;
+
+/// Builder for the numeric index section
+#[derive(Default)]
+pub struct NumericIndexBuilder {
+    entries: BTreeMap<u64, u32>,
+}
+
+impl NumericIndexBuilder {
+    /// Create a new builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a key
+    ///
+    /// If the same key is added twice, the first data offset is kept.
+    pub fn add(&mut self, key: u64, data_offset: u32) {
+        self.entries.entry(key).or_insert(data_offset);
+    }
+
+    /// Rewrite every stored data offset with `f`
+    ///
+    /// Used when data is encoded after the keys were added.
+    pub fn remap_data_offsets(&mut self, f: impl Fn(u32) -> u32) {
+        for offset in self.entries.values_mut() {
+            *offset = f(*offset);
+        }
+    }
+
+    /// Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether no keys have been added
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Exact size of the serialized section in bytes
+    pub fn serialized_size(&self) -> usize {
+        HEADER_SIZE + self.entries.len() * ENTRY_SIZE
+    }
+
+    /// Serialize the index
+    pub fn build(&self) -> Result<Vec<u8>, ParaglobError> {
+        let total_size = self.serialized_size();
+        if total_size > u32::MAX as usize {
+            return Err(ParaglobError::ResourceLimitExceeded(
+                "Numeric index exceeds 4GB".to_string(),
+            ));
+        }
+
+        let mut buffer = Vec::with_capacity(total_size);
+        buffer.extend_from_slice(NUMERIC_INDEX_MAGIC);
+        for field in [NUMERIC_INDEX_VERSION, self.entries.len() as u32, 0] {
+            buffer.extend_from_slice(&field.to_le_bytes());
+        }
+        for (&key, &data_offset) in &self.entries {
+            buffer.extend_from_slice(&key.to_le_bytes());
+            buffer.extend_from_slice(&data_offset.to_le_bytes());
+        }
+        Ok(buffer)
+    }
+}
+
+/// Memory-mapped numeric index for lookups
+pub struct NumericIndex<'a> {
+    buffer: &'a [u8],
+    entry_count: u32,
+}
+
+impl<'a> NumericIndex<'a> {
+    /// Load from memory-mapped buffer
+    ///
+    /// The buffer may extend past the end of the section.
+    pub fn from_buffer(buffer: &'a [u8]) -> Result<Self, ParaglobError> {
+        if buffer.len() < HEADER_SIZE {
+            return Err(ParaglobError::Format(
+                "Buffer too small for numeric index header".to_string(),
+            ));
+        }
+        if &buffer[0..4] != NUMERIC_INDEX_MAGIC {
+            return Err(ParaglobError::Format(format!(
+                "Invalid numeric index magic: expected {:?}, got {:?}",
+                NUMERIC_INDEX_MAGIC,
+                &buffer[0..4]
+            )));
+        }
+
+        let field = |i: usize| read_u32(buffer, 4 + i * 4).unwrap_or(0);
+        let version = field(0);
+        if version != NUMERIC_INDEX_VERSION {
+            return Err(ParaglobError::UnsupportedVersion(format!(
+                "Unsupported numeric index version: {}",
+                version
+            )));
+        }
+        let entry_count = field(1);
+        if HEADER_SIZE + entry_count as usize * ENTRY_SIZE > buffer.len() {
+            return Err(ParaglobError::OutOfBounds(
+                "Numeric index entries out of bounds".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            buffer,
+            entry_count,
+        })
+    }
+
+    /// Number of keys
+    pub fn entry_count(&self) -> u32 {
+        self.entry_count
+    }
+
+    /// Find the data offset stored for `key`
+    pub fn get(&self, key: u64) -> Option<u32> {
+        let (mut lo, mut hi) = (0, self.entry_count as usize);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let (stored, data_offset) = self.entry(mid)?;
+            match stored.cmp(&key) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Some(data_offset),
+            }
+        }
+        None
+    }
+
+    /// List all keys as `(key, data_offset)`, in ascending key order
+    pub fn entries(&self) -> Vec<(u64, u32)> {
+        (0..self.entry_count as usize)
+            .map_while(|i| self.entry(i))
+            .collect()
+    }
+
+    /// Read the entry at `index`
+    fn entry(&self, index: usize) -> Option<(u64, u32)> {
+        let pos = HEADER_SIZE + index * ENTRY_SIZE;
+        let key = self.buffer.get(pos..pos + 8)?;
+        Some((
+            u64::from_le_bytes(key.try_into().ok()?),
+            read_u32(self.buffer, pos + 8)?,
+        ))
+    }
+}
+
+/// Read a little-endian u32 at `pos`
+fn read_u32(buffer: &[u8], pos: usize) -> Option<u32> {
+    let bytes = buffer.get(pos..pos + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(keys: &[(u64, u32)]) -> Vec<u8> {
+        let mut builder = NumericIndexBuilder::new();
+        for &(key, offset) in keys {
+            builder.add(key, offset);
+        }
+        let bytes = builder.build().unwrap();
+        assert_eq!(bytes.len(), builder.serialized_size());
+        bytes
+    }
+
+    #[test]
+    fn test_lookup() {
+        let bytes = build(&[(15169, 1), (13335, 2), (0, 3), (u64::MAX, 4)]);
+        let index = NumericIndex::from_buffer(&bytes).unwrap();
+        assert_eq!(index.entry_count(), 4);
+
+        assert_eq!(index.get(15169), Some(1));
+        assert_eq!(index.get(13335), Some(2));
+        assert_eq!(index.get(0), Some(3));
+        assert_eq!(index.get(u64::MAX), Some(4));
+        assert_eq!(index.get(15170), None);
+        assert_eq!(
+            index.entries(),
+            vec![(0, 3), (13335, 2), (15169, 1), (u64::MAX, 4)]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keeps_first() {
+        let bytes = build(&[(443, 1), (443, 2)]);
+        let index = NumericIndex::from_buffer(&bytes).unwrap();
+        assert_eq!(index.entry_count(), 1);
+        assert_eq!(index.get(443), Some(1));
+    }
+
+    #[test]
+    fn test_corrupt_header_rejected() {
+        let mut bytes = build(&[(1, 1)]);
+        assert!(NumericIndex::from_buffer(&bytes[..20]).is_err());
+        bytes[0] = b'X';
+        assert!(NumericIndex::from_buffer(&bytes).is_err());
+    }
+}
@@ -33,6 +33,7 @@
 //! 3. **Zero-copy**: Can read directly from mmap without parsing
 //! 4. **Portability**: Little-endian u32/u8 only (standard on x86/ARM)
 
+use crate::error::ParaglobError;
 use std::mem;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
@@ -333,7 +334,8 @@ pub struct ACEdge {
 /// Dense lookup table for states with many transitions (1024 bytes, 64-byte aligned)
 ///
 /// Used by DENSE state encoding for O(1) transition lookup.
-/// Each entry is a target node offset (0 = no transition).
+/// Each entry is a target node offset (0 = no transition). Offset 0 holds the
+/// root node, which no trie edge ever points to, so 0 is unambiguous.
 ///
 /// **Cache-line alignment**: The 64-byte alignment ensures this structure starts on a
 /// cache line boundary, preventing cache line splits and improving memory access performance
@@ -356,7 +358,7 @@ pub struct PatternEntry {
     /// Pattern ID (matches IDs used in AC automaton)
     pub pattern_id: u32,
 
-    /// Pattern type: 0=Literal, 1=Glob
+    /// Pattern type: 0=Literal, 1=Glob, 2=Suffix glob (`*text`), 3=Prefix glob (`text*`)
     pub pattern_type: u8,
 
     /// Reserved for alignment
@@ -640,6 +642,20 @@ impl PatternEntry {
     }
 }
 
+/// Convert a size, offset, or count to the `u32` stored in the file
+///
+/// Section offsets, section sizes, and data pointers are all `u32`, so a
+/// database past 4GB cannot be represented. Returns an error instead of
+/// letting an `as u32` cast silently wrap into a corrupt file.
+pub(crate) fn offset_u32(value: usize, what: &str) -> Result<u32, ParaglobError> {
+    u32::try_from(value).map_err(|_| {
+        ParaglobError::SerializationError(format!(
+            "database exceeds 4GB format limit: {} is {}",
+            what, value
+        ))
+    })
+}
+
 /// Helper to safely read a struct from a byte buffer at an offset
 ///
 /// # Safety
@@ -872,4 +888,16 @@ mod tests {
             assert_eq!(s3, "");
         }
     }
+    #[test]
+    fn test_offset_u32_overflow() {
+        assert_eq!(offset_u32(1234, "offset").unwrap(), 1234);
+        assert_eq!(offset_u32(u32::MAX as usize, "offset").unwrap(), u32::MAX);
+        #[cfg(target_pointer_width = "64")]
+        {
+            let err = offset_u32(u32::MAX as usize + 1, "literal section offset").unwrap_err();
+            assert!(matches!(err, ParaglobError::SerializationError(_)));
+            assert!(err.to_string().contains("4GB"));
+            assert!(err.to_string().contains("literal section offset"));
+        }
+    }
 }
@@ -18,16 +18,25 @@
 use crate::ac_offset::{ACAutomaton, MatchMode as ACMatchMode};
 use crate::data_section::{DataEncoder, DataValue};
 use crate::error::ParaglobError;
-use crate::glob::{GlobPattern, MatchMode as GlobMatchMode};
+use crate::glob::{expand_braces, GlobPattern, MatchMode as GlobMatchMode};
 use crate::offset_format::{
-    read_cstring, read_str_checked, ACEdge, ParaglobHeader, PatternDataMapping, PatternEntry,
-    SingleWildcard,
+    offset_u32, read_cstring, read_str_checked, ACEdge, ACNodeHot, ParaglobHeader,
+    PatternDataMapping, PatternEntry, SingleWildcard, StateKind, MAGIC, VERSION,
 };
+use lru::LruCache;
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use zerocopy::Ref;
 
+/// Shortest glob literal used as an Aho-Corasick anchor
+///
+/// Short literals like "-", "." or ".a" occur in almost every input and would
+/// make nearly every glob a candidate, so they are not added to the automaton.
+const MIN_ANCHOR_LEN: usize = 3;
+
 /// Pattern classification for optimization
 #[derive(Debug, Clone)]
 enum PatternType {
@@ -37,14 +46,14 @@ enum PatternType {
         id: u32,
         data: Option<DataValue>,
     },
-    /// Glob pattern with extracted literals
+    /// Glob pattern with extracted anchor literals (at least [`MIN_ANCHOR_LEN`] bytes)
     Glob {
         pattern: String,
         literals: Vec<String>,
         id: u32,
         data: Option<DataValue>,
     },
-    /// Pure wildcard pattern (no literals to extract)
+    /// Glob with no usable anchor literal, checked against every query
     PureWildcard {
         pattern: String,
         id: u32,
@@ -63,7 +72,8 @@ impl PatternType {
         }
 
         if Self::is_glob(pattern) {
-            let literals = Self::extract_literals(pattern);
+            expand_braces(pattern)?;
+            let literals = Self::anchor_literals(pattern);
 
             if literals.is_empty() {
                 Ok(Self::PureWildcard {
@@ -88,10 +98,23 @@ impl PatternType {
         }
     }
 
+    /// Whether the pattern needs the glob matcher
+    ///
+    /// An escaped wildcard (`\*`, `\?`, `\[`, `\{`) makes a pattern a glob even
+    /// without a live wildcard: only the glob matcher unescapes it, while a
+    /// literal would be confirmed by the automaton with the backslash still
+    /// in place and never match. So does a brace alternation, or a brace
+    /// group too large to expand (rejected by `new_with_data`).
     fn is_glob(pattern: &str) -> bool {
+        if expand_braces(pattern).map_or(true, |alternatives| alternatives.len() > 1) {
+            return true;
+        }
         let mut escaped = false;
         for ch in pattern.chars() {
             if escaped {
+                if matches!(ch, '*' | '?' | '[' | '{') {
+                    return true;
+                }
                 escaped = false;
                 continue;
             }
@@ -104,6 +127,31 @@ impl PatternType {
         false
     }
 
+    /// Literals long enough to anchor the pattern in the automaton
+    ///
+    /// Each brace alternative contributes its own literals. If any
+    /// alternative has no anchor, the pattern has none: it must be checked
+    /// against every query to find inputs matching that alternative.
+    fn anchor_literals(pattern: &str) -> Vec<String> {
+        let Ok(alternatives) = expand_braces(pattern) else {
+            return Vec::new();
+        };
+        let mut anchors = Vec::new();
+        for alternative in &alternatives {
+            let mut literals = Self::extract_literals(alternative);
+            literals.retain(|lit| lit.len() >= MIN_ANCHOR_LEN);
+            if literals.is_empty() {
+                return Vec::new();
+            }
+            for literal in literals {
+                if !anchors.contains(&literal) {
+                    anchors.push(literal);
+                }
+            }
+        }
+        anchors
+    }
+
     fn extract_literals(pattern: &str) -> Vec<String> {
         let mut literals = Vec::new();
         let mut current = String::new();
@@ -181,6 +229,48 @@ impl PatternType {
     }
 }
 
+/// Check whether a pattern is a glob with no usable Aho-Corasick anchor
+///
+/// Such patterns are verified against every query, so many of them slow down
+/// all lookups. Plain literals return `false`.
+pub(crate) fn is_pure_wildcard(pattern: &str) -> bool {
+    PatternType::is_glob(pattern) && PatternType::anchor_literals(pattern).is_empty()
+}
+
+/// On-disk pattern type for a glob
+///
+/// A single leading or trailing `*` around plain text is stored as a suffix
+/// (2) or prefix (3) glob, which is verified with `ends_with`/`starts_with`
+/// instead of the glob matcher. Other globs are type 1. Readers that predate
+/// types 2 and 3 match any non-zero type as a glob, but their validator
+/// (`matchy validate`) rejects the new types as invalid, so files with these
+/// patterns only validate with this version or later. Text with braces is
+/// never plain, since it may hold alternations.
+fn glob_pattern_type(pattern: &str) -> u8 {
+    let is_plain = |s: &str| !s.is_empty() && !s.contains(['*', '?', '[', '\\', '{']);
+    if pattern.strip_prefix('*').is_some_and(is_plain) {
+        2
+    } else if pattern.strip_suffix('*').is_some_and(is_plain) {
+        3
+    } else {
+        1
+    }
+}
+
+/// Check a header's magic and version before reading the rest of the buffer
+///
+/// A valid magic with another version is an unsupported version rather than
+/// a malformed buffer.
+fn check_header(header: &ParaglobHeader) -> Result<(), ParaglobError> {
+    header.validate().map_err(|e| {
+        if &header.magic == MAGIC && header.version != VERSION {
+            ParaglobError::UnsupportedVersion(e.to_string())
+        } else {
+            ParaglobError::SerializationError(e.to_string())
+        }
+    })
+}
+
 /// Database statistics
 #[derive(Debug, Clone)]
 pub struct Stats {
@@ -196,6 +286,24 @@ pub struct Stats {
     pub mapping_count: usize,
 }
 
+/// Read-only view of one Aho-Corasick automaton node
+///
+/// Yielded by [`Paraglob::ac_nodes`]. Node IDs are indices in serialization
+/// order; node 0 is the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ACNodeInfo {
+    /// Node index (0 = root)
+    pub id: u32,
+    /// Transition encoding used for this node
+    pub state_kind: StateKind,
+    /// Outgoing transitions as (input byte, target node ID), sorted by byte
+    pub edges: Vec<(u8, u32)>,
+    /// Failure link target node ID (0 = root)
+    pub failure: u32,
+    /// Pattern IDs whose literals are recognized on reaching this node
+    pub pattern_ids: Vec<u32>,
+}
+
 /// Incremental builder for constructing Paraglob pattern matchers
 ///
 /// This builder allows you to add patterns one at a time before
@@ -216,7 +324,7 @@ pub struct Stats {
 /// // Add pattern with associated data
 /// let mut threat_data = HashMap::new();
 /// threat_data.insert("level".to_string(), DataValue::String("high".to_string()));
-/// builder.add_pattern_with_data("*.evil.com", Some(DataValue::Map(threat_data))).unwrap();
+/// builder.add_pattern_with_data("*.evil.com", Some(DataValue::from_hash_map(threat_data))).unwrap();
 ///
 /// // Build the final matcher
 /// let mut pg = builder.build().unwrap();
@@ -284,7 +392,7 @@ impl ParaglobBuilder {
     ///
     /// let pattern_id = builder.add_pattern_with_data(
     ///     "*.malware.com",
-    ///     Some(DataValue::Map(threat_info))
+    ///     Some(DataValue::from_hash_map(threat_info))
     /// ).unwrap();
     /// ```
     pub fn add_pattern_with_data(
@@ -346,7 +454,7 @@ impl ParaglobBuilder {
         let ac_literal_hash = if header.has_ac_literal_mapping() {
             let hash_offset = header.ac_literal_map_offset as usize;
             if hash_offset >= buffer.len() {
-                return Err(ParaglobError::Validation(format!(
+                return Err(ParaglobError::OutOfBounds(format!(
                     "AC literal map offset {} out of bounds (buffer size: {})",
                     hash_offset,
                     buffer.len()
@@ -375,13 +483,14 @@ impl ParaglobBuilder {
         Ok(Paraglob {
             buffer: BufferStorage::Owned(buffer),
             mode,
-            glob_cache: RefCell::new(HashMap::new()),
+            glob_cache: Mutex::new(LruCache::unbounded()),
+            max_input_len: None,
+            literal_policy: LiteralMatchPolicy::All,
+            anchoring: Anchoring::Substring,
+            disabled_patterns: Vec::new(),
             ac_literal_hash,
             pattern_data_map,
-            candidate_buffer: RefCell::new(HashSet::new()),
-            ac_literal_buffer: RefCell::new(HashSet::new()),
-            result_buffer: RefCell::new(Vec::new()),
-            normalized_text_buffer: RefCell::new(Vec::new()),
+            scratch: QueryScratch::new(),
         })
     }
 
@@ -411,13 +520,8 @@ impl ParaglobBuilder {
                         .push(*id);
                 }
                 PatternType::Glob { literals, id, .. } => {
+                    // Short literals were already dropped during classification
                     for lit in literals {
-                        // Filter out very short literals (< 3 chars) to reduce false positives
-                        // Short literals like "-", ".", ".com" match too many patterns
-                        if lit.len() < 3 {
-                            continue;
-                        }
-
                         // O(1) check with HashSet, only clone once for Vec if needed
                         let is_new = ac_literals_set.insert(lit.as_str());
                         if is_new {
@@ -551,6 +655,10 @@ impl ParaglobBuilder {
             + data_padding  // Alignment padding before mapping table
             + mappings_size
             + ac_literal_map_size;
+
+        // Every offset and size below is bounded by total_size, so checking it
+        // first avoids allocating a buffer the u32 header can't describe
+        let total_size_u32 = offset_u32(total_size, "pattern section size")?;
         let mut buffer = vec![0u8; total_size];
 
         // Write header (v2 if we have data, v1 otherwise)
@@ -559,29 +667,31 @@ impl ParaglobBuilder {
             ACMatchMode::CaseSensitive => 0,
             ACMatchMode::CaseInsensitive => 1,
         };
-        header.ac_node_count = ac_automaton.buffer().len() as u32; // Approximation
-        header.ac_nodes_offset = header_size as u32;
-        header.ac_edges_size = ac_size as u32;
-        header.pattern_count = self.patterns.len() as u32;
-        header.patterns_offset = patterns_start as u32;
-        header.pattern_strings_offset = pattern_strings_start as u32;
-        header.pattern_strings_size = pattern_strings_size as u32;
-        header.wildcard_count = pure_wildcards.len() as u32;
-        header.total_buffer_size = total_size as u32;
+        header.ac_node_count = offset_u32(ac_automaton.buffer().len(), "ac node count")?; // Approximation
+        header.ac_nodes_offset = offset_u32(header_size, "ac nodes offset")?;
+        header.ac_edges_size = offset_u32(ac_size, "ac edges size")?;
+        header.pattern_count = offset_u32(self.patterns.len(), "pattern count")?;
+        header.patterns_offset = offset_u32(patterns_start, "patterns offset")?;
+        header.pattern_strings_offset =
+            offset_u32(pattern_strings_start, "pattern strings offset")?;
+        header.pattern_strings_size = offset_u32(pattern_strings_size, "pattern strings size")?;
+        header.wildcard_count = offset_u32(pure_wildcards.len(), "wildcard count")?;
+        header.total_buffer_size = total_size_u32;
         // header.reserved is already initialized to [0; 3] in new()
 
         // v2 fields (if we have data)
         if data_section_size > 0 {
-            header.data_section_offset = data_section_start as u32;
-            header.data_section_size = data_section_size as u32;
-            header.mapping_table_offset = mappings_start as u32;
-            header.mapping_count = pattern_data_mappings.len() as u32;
+            header.data_section_offset = offset_u32(data_section_start, "data section offset")?;
+            header.data_section_size = offset_u32(data_section_size, "data section size")?;
+            header.mapping_table_offset = offset_u32(mappings_start, "mapping table offset")?;
+            header.mapping_count = offset_u32(pattern_data_mappings.len(), "mapping count")?;
             header.data_flags = 0x1; // Inline data flag
         }
 
         // v3 fields (AC literal mapping - always present)
-        header.ac_literal_map_offset = ac_literal_map_start as u32;
-        header.ac_literal_map_count = ac_literal_to_patterns.len() as u32;
+        header.ac_literal_map_offset = offset_u32(ac_literal_map_start, "ac literal map offset")?;
+        header.ac_literal_map_count =
+            offset_u32(ac_literal_to_patterns.len(), "ac literal map count")?;
 
         unsafe {
             let ptr = buffer.as_mut_ptr() as *mut ParaglobHeader;
@@ -596,16 +706,21 @@ impl ParaglobBuilder {
         // Write pattern entries
         for (i, pat) in self.patterns.iter().enumerate() {
             let entry_offset = patterns_start + i * pattern_entry_size;
-            let string_offset = (pattern_strings_start + pattern_string_offsets[i]) as u32;
+            let string_offset = offset_u32(
+                pattern_strings_start + pattern_string_offsets[i],
+                "pattern string offset",
+            )?;
 
             let pattern_type = match pat {
                 PatternType::Literal { .. } => 0u8,
-                PatternType::Glob { .. } | PatternType::PureWildcard { .. } => 1u8,
+                PatternType::Glob { pattern, .. } | PatternType::PureWildcard { pattern, .. } => {
+                    glob_pattern_type(pattern)
+                }
             };
 
             let mut entry = PatternEntry::new(pat.id(), pattern_type);
             entry.pattern_string_offset = string_offset;
-            entry.pattern_string_length = pat.pattern().len() as u32;
+            entry.pattern_string_length = offset_u32(pat.pattern().len(), "pattern string length")?;
 
             unsafe {
                 let ptr = buffer.as_mut_ptr().add(entry_offset) as *mut PatternEntry;
@@ -626,7 +741,7 @@ impl ParaglobBuilder {
 
             let wildcard = SingleWildcard {
                 pattern_id: pat.id(),
-                pattern_string_offset: string_offset as u32,
+                pattern_string_offset: offset_u32(string_offset, "pattern string offset")?,
             };
 
             unsafe {
@@ -683,6 +798,39 @@ struct PatternDataMetadata {
     count: u32,
 }
 
+/// Which literal patterns to report when their matches overlap
+///
+/// Literal patterns match anywhere in the input, so a query containing
+/// `mail.example.com` matches both the `example.com` and `mail.example.com`
+/// literals. Glob patterns are never filtered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LiteralMatchPolicy {
+    /// Report every literal that occurs in the input
+    #[default]
+    All,
+    /// Among literal matches whose spans overlap, report only the longest
+    ///
+    /// Ties go to the leftmost match, then the lowest pattern ID. A literal
+    /// is reported if any of its occurrences survives.
+    LongestOnly,
+}
+
+/// Where a literal pattern may match within the input
+///
+/// Glob patterns always match the whole input: `*.evil.com` does not match
+/// `www.evil.com.example`. Literal patterns (no wildcards) are found by the
+/// Aho-Corasick scan and by default match anywhere in the input, which suits
+/// log-line scanning. [`Anchoring::Full`] makes them whole-input matches too,
+/// which suits categorizing a single domain or URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchoring {
+    /// A literal matches wherever it occurs in the input
+    #[default]
+    Substring,
+    /// A literal matches only when it is the entire input
+    Full,
+}
+
 /// Offset-based Paraglob pattern matcher
 ///
 /// All data stored in a single byte buffer for zero-copy operation.
@@ -700,23 +848,156 @@ pub struct Paraglob {
     buffer: BufferStorage,
     /// Matching mode (public for Database::mode() access)
     pub(crate) mode: GlobMatchMode,
-    /// Compiled glob patterns (cached on first use)
-    /// Uses RefCell for interior mutability - allows &self methods while caching patterns
-    glob_cache: RefCell<HashMap<u32, GlobPattern>>,
+    /// Compiled glob patterns (cached on first use, unbounded unless a capacity is set)
+    /// Behind a Mutex so `&self` methods can cache patterns while `Paraglob` stays `Sync`
+    glob_cache: Mutex<LruCache<u32, GlobPattern>>,
+    /// Inputs longer than this (in bytes) match nothing (None = unbounded)
+    max_input_len: Option<usize>,
+    /// Whether overlapping literal matches are all reported
+    literal_policy: LiteralMatchPolicy,
+    /// Whether literal patterns must span the whole input
+    anchoring: Anchoring,
+    /// Bitset of pattern IDs muted at runtime (empty = all enabled)
+    disabled_patterns: Vec<u64>,
     /// Memory-mapped hash table for AC literal ID to pattern IDs mapping (O(1) lookup)
     ac_literal_hash: Option<crate::ac_literal_hash::ACLiteralHash<'static>>,
     /// Pattern ID to data mapping (lazy-loaded from buffer)
     pattern_data_map: Option<PatternDataMetadata>,
-    /// Reusable buffer for candidate patterns (avoids allocation on every query)
-    /// Uses RefCell for interior mutability - allows &self methods while mutating buffers
-    candidate_buffer: RefCell<HashSet<u32>>,
-    /// Reusable buffer for AC literal IDs (avoids allocation on every query)
-    /// Uses RefCell for interior mutability - allows &self methods while mutating buffers
-    ac_literal_buffer: RefCell<HashSet<u32>>,
-    /// Reusable buffer for final match results (avoids allocation on every query)
-    result_buffer: RefCell<Vec<u32>>,
-    /// Reusable buffer for normalized text (case-insensitive matching)
-    normalized_text_buffer: RefCell<Vec<u8>>,
+    /// Buffers for [`find_all_ref`](Self::find_all_ref), whose result borrows from them
+    ///
+    /// Other `&self` methods that don't take a [`QueryScratch`] use a
+    /// per-thread one instead (see [`with_thread_scratch`]).
+    scratch: QueryScratch,
+}
+
+thread_local! {
+    /// Query buffers shared by every `Paraglob` on this thread
+    static THREAD_SCRATCH: RefCell<QueryScratch> = RefCell::new(QueryScratch::new());
+}
+
+/// Run `f` with this thread's query buffers
+///
+/// Used by the `&self` methods that don't take a [`QueryScratch`], so they
+/// keep reusing buffers without interior mutability in `Paraglob` itself. A
+/// fresh scratch is used if the thread's one is already borrowed.
+fn with_thread_scratch<R>(f: impl FnOnce(&mut QueryScratch) -> R) -> R {
+    THREAD_SCRATCH.with(|cell| match cell.try_borrow_mut() {
+        Ok(mut scratch) => f(&mut scratch),
+        Err(_) => f(&mut QueryScratch::new()),
+    })
+}
+
+/// Reusable buffers for pattern matching queries
+///
+/// Every glob query gathers candidate pattern IDs, the AC literals that
+/// produced them, the verified results, and (in case-insensitive mode) a
+/// lowercased copy of the input. A `QueryScratch` holds those buffers so they
+/// keep their capacity from one query to the next. Once they have grown to
+/// fit the largest input seen, queries stop allocating for them. Other
+/// allocations (result data, query cache entries) are not covered.
+///
+/// Build one per worker thread and pass it to
+/// [`Database::lookup_into`](crate::Database::lookup_into),
+/// [`Database::scan_into`](crate::Database::scan_into), or
+/// [`Paraglob::find_all_with`]. A scratch is not tied to any database, so one
+/// thread can reuse the same scratch across several databases.
+///
+/// # Example
+/// ```
+/// use matchy::{Paraglob, QueryScratch, glob::MatchMode};
+///
+/// let pg = Paraglob::build_from_patterns(&["*.txt", "test_*"], MatchMode::CaseSensitive)?;
+/// let mut scratch = QueryScratch::new();
+///
+/// assert_eq!(pg.find_all_with("test_file.txt", &mut scratch), &[0, 1]);
+/// assert_eq!(pg.find_all_with("notes.txt", &mut scratch), &[0]);
+/// # Ok::<(), matchy::ParaglobError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct QueryScratch {
+    /// Candidate pattern IDs, sorted and deduplicated before verification so
+    /// candidates are always checked in ascending pattern ID order,
+    /// independent of hash iteration order
+    candidates: Vec<u32>,
+    /// AC literal IDs found in the input
+    literals: HashSet<u32>,
+    /// Verified matches for the current query
+    results: Vec<u32>,
+    /// Lowercased input for case-insensitive matching
+    normalized: Vec<u8>,
+}
+
+impl QueryScratch {
+    /// Create an empty scratch; buffers grow on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Upper bound on the matching work a single query may do
+///
+/// Most of the cost of a string query is verifying glob candidates and
+/// following Aho-Corasick failure links. Input crafted to hit thousands of
+/// candidates can make one query far slower than the rest. A budget caps both
+/// counts; once either runs out, matching stops and the query reports the
+/// matches found so far as truncated.
+///
+/// Pass one to [`Database::lookup_budgeted`](crate::Database::lookup_budgeted)
+/// or [`Paraglob::find_all_budgeted`]. Literal patterns confirmed by the
+/// automaton are free; only glob verifications count.
+///
+/// # Example
+/// ```
+/// use matchy::{Paraglob, QueryBudget, glob::MatchMode};
+///
+/// let pg = Paraglob::build_from_patterns(&["*.txt", "*file*"], MatchMode::CaseSensitive)?;
+///
+/// let (ids, truncated) = pg.find_all_budgeted("file.txt", QueryBudget::new(1, 1000));
+/// assert_eq!(ids.len(), 1);
+/// assert!(truncated);
+///
+/// let (ids, truncated) = pg.find_all_budgeted("file.txt", QueryBudget::UNLIMITED);
+/// assert_eq!(ids, vec![0, 1]);
+/// assert!(!truncated);
+/// # Ok::<(), matchy::ParaglobError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryBudget {
+    /// Maximum number of glob verifications (pure wildcards and glob candidates)
+    pub max_verifications: usize,
+    /// Maximum number of Aho-Corasick failure-link traversals
+    pub max_failure_links: usize,
+}
+
+impl QueryBudget {
+    /// A budget that never runs out
+    pub const UNLIMITED: Self = Self {
+        max_verifications: usize::MAX,
+        max_failure_links: usize::MAX,
+    };
+
+    /// Create a budget with the given limits
+    pub fn new(max_verifications: usize, max_failure_links: usize) -> Self {
+        Self {
+            max_verifications,
+            max_failure_links,
+        }
+    }
+
+    /// Spend one glob verification, returning false if none are left
+    fn take_verification(&mut self) -> bool {
+        if self.max_verifications == 0 {
+            return false;
+        }
+        self.max_verifications -= 1;
+        true
+    }
+}
+
+impl Default for QueryBudget {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
 }
 
 impl Paraglob {
@@ -730,13 +1011,14 @@ impl Paraglob {
         Self {
             buffer: BufferStorage::Owned(Vec::new()),
             mode,
-            glob_cache: RefCell::new(HashMap::new()),
+            glob_cache: Mutex::new(LruCache::unbounded()),
+            max_input_len: None,
+            literal_policy: LiteralMatchPolicy::All,
+            anchoring: Anchoring::Substring,
+            disabled_patterns: Vec::new(),
             ac_literal_hash: None,
             pattern_data_map: None,
-            candidate_buffer: RefCell::new(HashSet::new()),
-            ac_literal_buffer: RefCell::new(HashSet::new()),
-            result_buffer: RefCell::new(Vec::new()),
-            normalized_text_buffer: RefCell::new(Vec::new()),
+            scratch: QueryScratch::new(),
         }
     }
 
@@ -766,8 +1048,8 @@ impl Paraglob {
     /// threat_data.insert("threat_level".to_string(), DataValue::String("high".to_string()));
     ///
     /// let data_values = vec![
-    ///     Some(DataValue::Map(threat_data.clone())),
-    ///     Some(DataValue::Map(threat_data)),
+    ///     Some(DataValue::from_hash_map(threat_data.clone())),
+    ///     Some(DataValue::from_hash_map(threat_data)),
     /// ];
     ///
     /// let pg = Paraglob::build_from_patterns_with_data(
@@ -795,6 +1077,11 @@ impl Paraglob {
     ///
     /// Returns (end_position, pattern_id) for each match.
     /// The end_position is the byte offset immediately after the match.
+    ///
+    /// These are raw automaton hits for every literal occurrence, so they
+    /// ignore [`Anchoring`]: with [`Anchoring::Full`] a hit in the middle of
+    /// the input is still reported here even though
+    /// [`find_all`](Self::find_all) would not return its pattern.
     pub fn find_matches_with_positions(&self, text: &str) -> Vec<(usize, u32)> {
         self.find_matches_with_positions_bytes(text.as_bytes())
     }
@@ -879,67 +1166,79 @@ impl Paraglob {
         }
 
         let ac_buffer = &buffer[ac_start..ac_start + ac_size];
-        Self::run_ac_matching_with_positions_with_buffer(
-            ac_buffer,
-            text,
-            self.mode,
-            output,
-            &self.normalized_text_buffer,
-        );
+        with_thread_scratch(|scratch| {
+            Self::run_ac_matching_with_positions_with_buffer(
+                ac_buffer,
+                text,
+                self.mode,
+                output,
+                &mut scratch.normalized,
+            )
+        });
     }
 
     /// Find all matching pattern IDs
+    ///
+    /// Returns no matches if `text` is longer than the
+    /// [maximum input length](Self::set_max_input_len).
     pub fn find_all(&self, text: &str) -> Vec<u32> {
+        // Clone the result (caller owns it)
+        // Use find_all_with and a caller-owned QueryScratch to avoid this copy
+        with_thread_scratch(|scratch| self.find_all_with(text, scratch).to_vec())
+    }
+
+    /// Find all matching pattern IDs using caller-owned buffers
+    ///
+    /// Same results as [`find_all`](Self::find_all), but every intermediate
+    /// buffer lives in `scratch` and the returned slice borrows from it, so
+    /// repeated queries with the same scratch don't allocate once the buffers
+    /// have grown. See [`QueryScratch`].
+    pub fn find_all_with<'s>(&self, text: &str, scratch: &'s mut QueryScratch) -> &'s [u32] {
+        self.find_all_budgeted_with(text, scratch, QueryBudget::UNLIMITED)
+            .0
+    }
+
+    /// Find matching pattern IDs, stopping once `budget` is spent
+    ///
+    /// Returns the matches found so far and whether the budget ran out. A
+    /// truncated result is a subset of what [`find_all`](Self::find_all)
+    /// returns. See [`QueryBudget`].
+    pub fn find_all_budgeted(&self, text: &str, budget: QueryBudget) -> (Vec<u32>, bool) {
+        with_thread_scratch(|scratch| {
+            let (ids, truncated) = self.find_all_budgeted_with(text, scratch, budget);
+            (ids.to_vec(), truncated)
+        })
+    }
+
+    /// Budgeted variant of [`find_all_with`](Self::find_all_with)
+    pub fn find_all_budgeted_with<'s>(
+        &self,
+        text: &str,
+        scratch: &'s mut QueryScratch,
+        mut budget: QueryBudget,
+    ) -> (&'s [u32], bool) {
+        scratch.results.clear();
+
         let buffer = self.buffer.as_slice();
-        if buffer.is_empty() {
-            return Vec::new();
+        if buffer.is_empty() || self.input_too_long(text) {
+            return (&scratch.results, false);
         }
 
-        // SAFETY: Fast path - header is at offset 0, always aligned
-        let header = unsafe {
-            if buffer.len() < mem::size_of::<ParaglobHeader>() {
-                return Vec::new();
-            }
-            let ptr = buffer.as_ptr() as *const ParaglobHeader;
-            ptr.read()
+        let header = match Ref::<_, ParaglobHeader>::from_prefix(buffer) {
+            Ok((r, _)) => *r,
+            Err(_) => return (&scratch.results, false), // Invalid header, return empty
         };
 
         // Phase 1: Use AC automaton to find literal matches and candidate patterns
-        let ac_start = header.ac_nodes_offset as usize;
-        let ac_size = header.ac_edges_size as usize;
-
-        // Reuse buffers (clear from previous query)
-        self.candidate_buffer.borrow_mut().clear();
-        self.ac_literal_buffer.borrow_mut().clear();
-
-        if ac_size > 0 {
-            // Extract AC buffer and run AC matching on it
-            let ac_buffer = &buffer[ac_start..ac_start + ac_size];
-
-            // Run AC automaton matching directly on text bytes (AC handles case-insensitivity)
-            let text_bytes = text.as_bytes();
-            let mode = self.mode;
-            Self::run_ac_matching_into_static(
-                ac_buffer,
-                text_bytes,
-                mode,
-                &mut self.ac_literal_buffer.borrow_mut(),
-            );
-
-            // Map AC literal IDs to pattern IDs using hash table lookup (O(1))
-            if !self.ac_literal_buffer.borrow().is_empty() {
-                if let Some(ref ac_hash) = self.ac_literal_hash {
-                    for &literal_id in self.ac_literal_buffer.borrow().iter() {
-                        let pattern_ids = ac_hash.lookup_slice(literal_id);
-                        self.candidate_buffer.borrow_mut().extend(pattern_ids);
-                    }
-                }
-            }
-        }
+        let mut truncated = self.collect_candidates(
+            buffer,
+            &header,
+            text,
+            scratch,
+            &mut budget.max_failure_links,
+        );
 
         // Phase 2: Verify candidates (or all patterns if no AC)
-        // Reuse result buffer to avoid allocation
-        self.result_buffer.borrow_mut().clear();
 
         // CRITICAL: Always check pure wildcards first (patterns with no literals)
         // These must be checked on every query regardless of AC results
@@ -990,28 +1289,21 @@ impl Paraglob {
                     Err(_) => continue, // Skip corrupted pattern
                 };
 
-                // Check glob pattern - ensure it's cached first
-                if !self.glob_cache.borrow().contains_key(&wildcard.pattern_id) {
-                    self.glob_cache.borrow_mut().insert(
-                        wildcard.pattern_id,
-                        GlobPattern::new(pattern_str, self.mode).expect("Invalid wildcard pattern"),
-                    );
+                if self.is_disabled(wildcard.pattern_id) {
+                    continue;
                 }
-
-                if self
-                    .glob_cache
-                    .borrow()
-                    .get(&wildcard.pattern_id)
-                    .unwrap()
-                    .matches(text)
-                {
-                    self.result_buffer.borrow_mut().push(wildcard.pattern_id);
+                if !budget.take_verification() {
+                    truncated = true;
+                    break;
+                }
+                if self.cached_glob_matches(wildcard.pattern_id, pattern_str, text) {
+                    scratch.results.push(wildcard.pattern_id);
                 }
             }
         }
 
         // Check AC candidates (patterns that have literals that were found)
-        for &pattern_id in self.candidate_buffer.borrow().iter() {
+        for &pattern_id in scratch.candidates.iter() {
             let patterns_offset = header.patterns_offset as usize;
             let entry_offset =
                 patterns_offset + (pattern_id as usize) * mem::size_of::<PatternEntry>();
@@ -1027,9 +1319,11 @@ impl Paraglob {
 
             // Check if pattern matches
             if entry.pattern_type == 0 {
-                // Literal pattern - AC automaton already confirmed this matches!
-                // No need to read string or verify, just add to results.
-                self.result_buffer.borrow_mut().push(entry.pattern_id);
+                // Literal pattern - AC automaton already confirmed it occurs,
+                // so only the anchoring needs checking
+                if self.literal_anchored(&entry, text) {
+                    scratch.results.push(entry.pattern_id);
+                }
             } else {
                 // Glob pattern - need to read pattern string and do glob matching
                 // Validate UTF-8 on every string read
@@ -1044,33 +1338,256 @@ impl Paraglob {
                     Err(_) => continue, // Skip corrupted pattern
                 };
 
-                // Ensure pattern is cached first
-                if !self.glob_cache.borrow().contains_key(&entry.pattern_id) {
-                    self.glob_cache.borrow_mut().insert(
-                        entry.pattern_id,
-                        GlobPattern::new(pattern_str, self.mode)
-                            .expect("Invalid cached glob pattern"),
-                    );
+                // Out of budget: keep going so free literal candidates still count
+                if !budget.take_verification() {
+                    truncated = true;
+                    continue;
                 }
+                if self.entry_matches(&entry, pattern_str, text) {
+                    scratch.results.push(entry.pattern_id);
+                }
+            }
+        }
 
-                if self
-                    .glob_cache
-                    .borrow()
-                    .get(&entry.pattern_id)
-                    .unwrap()
-                    .matches(text)
-                {
-                    self.result_buffer.borrow_mut().push(entry.pattern_id);
+        scratch.results.sort_unstable();
+        scratch.results.dedup();
+        if self.literal_policy == LiteralMatchPolicy::LongestOnly {
+            self.retain_longest_literals(buffer, &header, text, &mut scratch.results);
+        }
+        (&scratch.results, truncated)
+    }
+
+    /// Drop literal matches from `results` that lose to a longer overlap
+    ///
+    /// Spans come from the AC automaton: a literal pattern is its own AC
+    /// literal, so each occurrence ends at the reported position and is as
+    /// long as the pattern string. Occurrences are taken longest first and
+    /// kept unless they overlap one already kept.
+    fn retain_longest_literals(
+        &self,
+        buffer: &[u8],
+        header: &ParaglobHeader,
+        text: &str,
+        results: &mut Vec<u32>,
+    ) {
+        let ac_hash = match self.ac_literal_hash {
+            Some(ref h) => h,
+            None => return,
+        };
+
+        // Byte length of each matched literal pattern
+        let mut literal_len: HashMap<u32, usize> = HashMap::new();
+        for &pattern_id in results.iter() {
+            let entry_offset = header.patterns_offset as usize
+                + (pattern_id as usize) * mem::size_of::<PatternEntry>();
+            if let Some((entry, _)) = buffer
+                .get(entry_offset..)
+                .and_then(|s| Ref::<_, PatternEntry>::from_prefix(s).ok())
+            {
+                if entry.pattern_type == 0 {
+                    literal_len.insert(pattern_id, entry.pattern_string_length as usize);
                 }
             }
         }
+        if literal_len.len() < 2 {
+            return;
+        }
 
-        self.result_buffer.borrow_mut().sort_unstable();
-        self.result_buffer.borrow_mut().dedup();
-        // Clone the result (caller owns it)
-        // Note: This still allocates once per query, but it's unavoidable
-        // without changing the API to return &[u32] or using arena allocation
-        self.result_buffer.borrow().clone()
+        let mut spans: Vec<(usize, usize, u32)> = Vec::new();
+        for (end, literal_id) in self.find_matches_with_positions(text) {
+            for pattern_id in ac_hash.lookup_slice(literal_id) {
+                if let Some(&len) = literal_len.get(&pattern_id) {
+                    if len <= end {
+                        spans.push((end - len, end, pattern_id));
+                    }
+                }
+            }
+        }
+        spans.sort_unstable_by(|a, b| {
+            (b.1 - b.0)
+                .cmp(&(a.1 - a.0))
+                .then(a.0.cmp(&b.0))
+                .then(a.2.cmp(&b.2))
+        });
+
+        let mut kept: Vec<(usize, usize)> = Vec::new();
+        let mut kept_ids: HashSet<u32> = HashSet::new();
+        for (start, end, pattern_id) in spans {
+            if kept.iter().all(|&(s, e)| end <= s || start >= e) {
+                kept.push((start, end));
+                kept_ids.insert(pattern_id);
+            }
+        }
+
+        results.retain(|id| !literal_len.contains_key(id) || kept_ids.contains(id));
+    }
+
+    /// Run the AC automaton over `text` and fill `scratch.candidates`
+    ///
+    /// Candidates are the sorted, deduplicated IDs of patterns whose literals
+    /// occur in `text`. Pure wildcards are never candidates. Returns true if
+    /// the scan stopped early because `failure_links` ran out.
+    fn collect_candidates(
+        &self,
+        buffer: &[u8],
+        header: &ParaglobHeader,
+        text: &str,
+        scratch: &mut QueryScratch,
+        failure_links: &mut usize,
+    ) -> bool {
+        let ac_start = header.ac_nodes_offset as usize;
+        let ac_size = header.ac_edges_size as usize;
+
+        // Reuse buffers (clear from previous query)
+        scratch.candidates.clear();
+        scratch.literals.clear();
+
+        if ac_size == 0 {
+            return false;
+        }
+
+        // Extract AC buffer and run AC matching on it
+        let ac_buffer = &buffer[ac_start..ac_start + ac_size];
+
+        // Run AC automaton matching directly on text bytes (AC handles case-insensitivity)
+        let truncated = Self::run_ac_matching_into_static(
+            ac_buffer,
+            text.as_bytes(),
+            self.mode,
+            &mut scratch.literals,
+            &mut scratch.normalized,
+            failure_links,
+        );
+
+        // Map AC literal IDs to pattern IDs using hash table lookup (O(1))
+        // Use zero-copy lookup_into to avoid allocations
+        if !scratch.literals.is_empty() {
+            if let Some(ref ac_hash) = self.ac_literal_hash {
+                for &literal_id in scratch.literals.iter() {
+                    ac_hash.lookup_into(literal_id, &mut scratch.candidates);
+                }
+            }
+        }
+        Self::sort_candidates(&mut scratch.candidates);
+        self.retain_enabled_candidates(&mut scratch.candidates);
+        truncated
+    }
+
+    /// Verify a single pure wildcard or AC candidate against `text`
+    ///
+    /// Literal patterns are confirmed by the AC automaton and match subject
+    /// to [`Anchoring`]. Corrupted entries never match.
+    fn verify_pattern(
+        &self,
+        buffer: &[u8],
+        header: &ParaglobHeader,
+        pattern_id: u32,
+        text: &str,
+    ) -> bool {
+        let entry_offset = header.patterns_offset as usize
+            + (pattern_id as usize) * mem::size_of::<PatternEntry>();
+        let entry = match buffer
+            .get(entry_offset..)
+            .and_then(|s| Ref::<_, PatternEntry>::from_prefix(s).ok())
+        {
+            Some((r, _)) => *r,
+            None => return false,
+        };
+
+        if entry.pattern_type == 0 {
+            return self.literal_anchored(&entry, text);
+        }
+
+        // Validate UTF-8 on every string read
+        match unsafe {
+            read_str_checked(
+                buffer,
+                entry.pattern_string_offset as usize,
+                entry.pattern_string_length as usize,
+            )
+        } {
+            Ok(pattern_str) => self.entry_matches(&entry, pattern_str, text),
+            Err(_) => false,
+        }
+    }
+
+    /// Count matching patterns without collecting their IDs
+    ///
+    /// Equivalent to `find_all(text).len()`, but skips building, sorting, and
+    /// copying the result vector.
+    pub fn count_matches(&self, text: &str) -> usize {
+        self.count_matches_up_to(text, usize::MAX)
+    }
+
+    /// Count matching patterns, stopping as soon as `limit` is reached
+    ///
+    /// Returns `min(find_all(text).len(), limit)`. Verification stops at the
+    /// limit, which bounds the work spent on inputs crafted to match many
+    /// patterns. With [`LiteralMatchPolicy::LongestOnly`] every match is
+    /// collected first so overlapping literals can be filtered.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::{Paraglob, glob::MatchMode};
+    ///
+    /// let patterns = vec!["*.txt", "test_*", "*file*"];
+    /// let pg = Paraglob::build_from_patterns(&patterns, MatchMode::CaseSensitive).unwrap();
+    ///
+    /// assert_eq!(pg.count_matches("test_file.txt"), 3);
+    /// assert_eq!(pg.count_matches_up_to("test_file.txt", 2), 2);
+    /// ```
+    pub fn count_matches_up_to(&self, text: &str, limit: usize) -> usize {
+        let buffer = self.buffer.as_slice();
+        let header = match Ref::<_, ParaglobHeader>::from_prefix(buffer) {
+            Ok((r, _)) => *r,
+            Err(_) => return 0,
+        };
+        if limit == 0 || self.input_too_long(text) {
+            return 0;
+        }
+        if self.literal_policy == LiteralMatchPolicy::LongestOnly {
+            return self.find_all(text).len().min(limit);
+        }
+
+        let mut count = 0;
+
+        // Pure wildcards are checked on every query (same layout as find_all)
+        let unaligned_offset =
+            (header.pattern_strings_offset + header.pattern_strings_size) as usize;
+        let wildcards_offset = unaligned_offset + (8 - (unaligned_offset % 8)) % 8;
+        for i in 0..header.wildcard_count as usize {
+            let offset = wildcards_offset + i * mem::size_of::<SingleWildcard>();
+            let wildcard = match buffer
+                .get(offset..)
+                .and_then(|s| Ref::<_, SingleWildcard>::from_prefix(s).ok())
+            {
+                Some((r, _)) => *r,
+                None => continue, // Skip corrupted wildcard
+            };
+            if !self.is_disabled(wildcard.pattern_id)
+                && self.verify_pattern(buffer, &header, wildcard.pattern_id, text)
+            {
+                count += 1;
+                if count >= limit {
+                    return count;
+                }
+            }
+        }
+
+        // Wildcards have no literals, so they never reappear as candidates
+        with_thread_scratch(|scratch| {
+            let mut unlimited = usize::MAX;
+            self.collect_candidates(buffer, &header, text, scratch, &mut unlimited);
+            for &pattern_id in scratch.candidates.iter() {
+                if self.verify_pattern(buffer, &header, pattern_id, text) {
+                    count += 1;
+                    if count >= limit {
+                        break;
+                    }
+                }
+            }
+            count
+        })
     }
 
     /// Find all matching pattern IDs (zero-allocation variant)
@@ -1091,193 +1608,12 @@ impl Paraglob {
     /// # Ok::<(), matchy::ParaglobError>(())
     /// ```
     pub fn find_all_ref(&mut self, text: &str) -> &[u32] {
-        let buffer = self.buffer.as_slice();
-        if buffer.is_empty() {
-            return &[];
-        }
-
-        let (header_ref, _) = match Ref::<_, ParaglobHeader>::from_prefix(buffer) {
-            Ok(r) => r,
-            Err(_) => return &[], // Invalid header, return empty
-        };
-        let header = *header_ref;
-
-        // Phase 1: Use AC automaton to find literal matches and candidate patterns
-        let ac_start = header.ac_nodes_offset as usize;
-        let ac_size = header.ac_edges_size as usize;
-
-        // Reuse buffers (clear from previous query)
-        self.candidate_buffer.borrow_mut().clear();
-        self.ac_literal_buffer.borrow_mut().clear();
-
-        if ac_size > 0 {
-            // Extract AC buffer and run AC matching on it
-            let ac_buffer = &buffer[ac_start..ac_start + ac_size];
-
-            // Run AC automaton matching directly on text bytes (AC handles case-insensitivity)
-            let text_bytes = text.as_bytes();
-            let mode = self.mode;
-            Self::run_ac_matching_into_static(
-                ac_buffer,
-                text_bytes,
-                mode,
-                &mut self.ac_literal_buffer.borrow_mut(),
-            );
-
-            // Map AC literal IDs to pattern IDs using hash table lookup (O(1))
-            // Use zero-copy lookup_into to avoid allocations
-            if !self.ac_literal_buffer.borrow().is_empty() {
-                if let Some(ref ac_hash) = self.ac_literal_hash {
-                    // Collect literal IDs first to avoid multiple borrows
-                    let literal_ids: Vec<u32> =
-                        self.ac_literal_buffer.borrow().iter().copied().collect();
-                    let mut candidates = self.candidate_buffer.borrow_mut();
-                    for literal_id in literal_ids {
-                        ac_hash.lookup_into(literal_id, &mut *candidates);
-                    }
-                }
-            }
-        }
-
-        // Phase 2: Verify candidates (or all patterns if no AC)
-        // Reuse result buffer to avoid allocation
-        self.result_buffer.borrow_mut().clear();
-
-        // CRITICAL: Always check pure wildcards first (patterns with no literals)
-        // These must be checked on every query regardless of AC results
-        // Wildcards are stored after pattern strings with 8-byte alignment padding
-        let unaligned_offset =
-            (header.pattern_strings_offset + header.pattern_strings_size) as usize;
-        let alignment = 8;
-        let padding = (alignment - (unaligned_offset % alignment)) % alignment;
-        let wildcards_offset = unaligned_offset + padding;
-        let wildcard_count = header.wildcard_count as usize;
-
-        if wildcard_count > 0 {
-            for i in 0..wildcard_count {
-                let wildcard_offset_val = wildcards_offset + i * mem::size_of::<SingleWildcard>();
-                let buffer_slice = match buffer.get(wildcard_offset_val..) {
-                    Some(s) => s,
-                    None => continue, // Skip corrupted wildcard
-                };
-                let (wildcard_ref, _) = match Ref::<_, SingleWildcard>::from_prefix(buffer_slice) {
-                    Ok(r) => r,
-                    Err(_) => continue, // Skip corrupted wildcard
-                };
-                let wildcard = *wildcard_ref;
-
-                // Look up PatternEntry to get the string length
-                let patterns_offset = header.patterns_offset as usize;
-                let entry_offset = patterns_offset
-                    + (wildcard.pattern_id as usize) * mem::size_of::<PatternEntry>();
-                let entry_slice = match buffer.get(entry_offset..) {
-                    Some(s) => s,
-                    None => continue, // Skip corrupted entry
-                };
-                let (entry_ref, _) = match Ref::<_, PatternEntry>::from_prefix(entry_slice) {
-                    Ok(r) => r,
-                    Err(_) => continue, // Skip corrupted entry
-                };
-                let entry = *entry_ref;
-
-                // Validate UTF-8 on every string read
-                let pattern_str = match unsafe {
-                    read_str_checked(
-                        buffer,
-                        entry.pattern_string_offset as usize,
-                        entry.pattern_string_length as usize,
-                    )
-                } {
-                    Ok(s) => s,
-                    Err(_) => continue, // Skip corrupted pattern
-                };
-
-                // Check glob pattern - ensure it's cached first
-                if !self.glob_cache.borrow().contains_key(&wildcard.pattern_id) {
-                    self.glob_cache.borrow_mut().insert(
-                        wildcard.pattern_id,
-                        GlobPattern::new(pattern_str, self.mode).expect("Invalid wildcard pattern"),
-                    );
-                }
-
-                if self
-                    .glob_cache
-                    .borrow()
-                    .get(&wildcard.pattern_id)
-                    .unwrap()
-                    .matches(text)
-                {
-                    self.result_buffer.borrow_mut().push(wildcard.pattern_id);
-                }
-            }
-        }
-
-        // Check AC candidates (patterns that have literals that were found)
-        for &pattern_id in self.candidate_buffer.borrow().iter() {
-            let patterns_offset = header.patterns_offset as usize;
-            let entry_offset =
-                patterns_offset + (pattern_id as usize) * mem::size_of::<PatternEntry>();
-            let entry_slice = match buffer.get(entry_offset..) {
-                Some(s) => s,
-                None => continue, // Skip corrupted pattern
-            };
-            let entry_ref = match Ref::<_, PatternEntry>::from_prefix(entry_slice) {
-                Ok((r, _)) => r,
-                Err(_) => continue, // Skip corrupted pattern
-            };
-            let entry = *entry_ref;
-
-            // Check if pattern matches
-            if entry.pattern_type == 0 {
-                // Literal pattern - AC automaton already confirmed this matches!
-                // No need to read string or verify, just add to results.
-                self.result_buffer.borrow_mut().push(entry.pattern_id);
-            } else {
-                // Glob pattern - need to read pattern string and do glob matching
-                // Validate UTF-8 on every string read
-                let pattern_str = match unsafe {
-                    read_str_checked(
-                        buffer,
-                        entry.pattern_string_offset as usize,
-                        entry.pattern_string_length as usize,
-                    )
-                } {
-                    Ok(s) => s,
-                    Err(_) => continue, // Skip corrupted pattern
-                };
-
-                // Ensure pattern is cached first
-                if !self.glob_cache.borrow().contains_key(&entry.pattern_id) {
-                    self.glob_cache.borrow_mut().insert(
-                        entry.pattern_id,
-                        GlobPattern::new(pattern_str, self.mode)
-                            .expect("Invalid cached glob pattern"),
-                    );
-                }
-
-                if self
-                    .glob_cache
-                    .borrow()
-                    .get(&entry.pattern_id)
-                    .unwrap()
-                    .matches(text)
-                {
-                    self.result_buffer.borrow_mut().push(entry.pattern_id);
-                }
-            }
-        }
-
-        self.result_buffer.borrow_mut().sort_unstable();
-        self.result_buffer.borrow_mut().dedup();
-        // Return slice (zero allocation!)
-        // SAFETY: This is safe because the function signature guarantees &mut self,
-        // so no other borrows can exist during this call
-        unsafe {
-            let ptr = self.result_buffer.as_ptr();
-            let borrowed = &*ptr;
-            std::slice::from_raw_parts(borrowed.as_ptr(), borrowed.len())
-        }
-    }
+        // Taking the scratch out leaves empty buffers that don't allocate
+        let mut scratch = mem::take(&mut self.scratch);
+        self.find_all_with(text, &mut scratch);
+        self.scratch = scratch;
+        &self.scratch.results
+    }
 
     /// Find all matching pattern IDs and write into caller's buffer (zero-allocation variant)
     ///
@@ -1307,6 +1643,82 @@ impl Paraglob {
         output.extend_from_slice(results);
     }
 
+    /// Whether a literal the AC automaton found in `text` satisfies the anchoring
+    ///
+    /// A whole-input match is an occurrence as long as the input itself.
+    #[inline]
+    fn literal_anchored(&self, entry: &PatternEntry, text: &str) -> bool {
+        self.anchoring == Anchoring::Substring || entry.pattern_string_length as usize == text.len()
+    }
+
+    /// Put candidates in ascending pattern ID order with duplicates removed
+    ///
+    /// Literal IDs come out of a `HashSet`, so the order candidates are gathered
+    /// in varies between runs. Sorting makes verification order deterministic.
+    #[inline]
+    fn sort_candidates(candidates: &mut Vec<u32>) {
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+
+    /// Match text against a glob pattern entry
+    ///
+    /// Suffix (`*literal`) and prefix (`literal*`) globs are checked with a
+    /// plain byte comparison; everything else goes through the glob matcher.
+    fn entry_matches(&self, entry: &PatternEntry, pattern_str: &str, text: &str) -> bool {
+        let text_bytes = text.as_bytes();
+        let affix = match entry.pattern_type {
+            2 => pattern_str.strip_prefix('*').map(|literal| {
+                let literal = literal.as_bytes();
+                let start = text_bytes.len().checked_sub(literal.len());
+                (start.map(|start| &text_bytes[start..]), literal)
+            }),
+            3 => pattern_str.strip_suffix('*').map(|literal| {
+                let literal = literal.as_bytes();
+                (text_bytes.get(..literal.len()), literal)
+            }),
+            _ => None,
+        };
+
+        match affix {
+            Some((Some(candidate), literal)) => match self.mode {
+                GlobMatchMode::CaseSensitive => candidate == literal,
+                GlobMatchMode::CaseInsensitive => candidate.eq_ignore_ascii_case(literal),
+            },
+            Some((None, _)) => false,
+            None => self.cached_glob_matches(entry.pattern_id, pattern_str, text),
+        }
+    }
+
+    /// Lock the compiled glob cache
+    ///
+    /// A panic while holding the lock can at worst leave a pattern uncached,
+    /// so a poisoned lock is used as is.
+    fn glob_cache(&self) -> MutexGuard<'_, LruCache<u32, GlobPattern>> {
+        self.glob_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Match text against a glob pattern, compiling and caching it on first use
+    ///
+    /// When the cache is bounded, inserting evicts the least recently used pattern.
+    fn cached_glob_matches(&self, pattern_id: u32, pattern_str: &str, text: &str) -> bool {
+        let mut cache = self.glob_cache();
+        if let Some(glob) = cache.get(&pattern_id) {
+            return glob.matches(text);
+        }
+
+        // A stored pattern can fail to parse, e.g. one from an older database
+        // whose brace text now expands past MAX_BRACE_EXPANSION
+        let Ok(glob) = GlobPattern::new(pattern_str, self.mode) else {
+            return false;
+        };
+        let matched = glob.matches(text);
+        cache.put(pattern_id, glob);
+        matched
+    }
+
     /// Run AC automaton matching with position tracking (allocates normalized buffer)
     fn run_ac_matching_with_positions(
         ac_buffer: &[u8],
@@ -1314,14 +1726,14 @@ impl Paraglob {
         mode: GlobMatchMode,
         matches: &mut Vec<(usize, u32)>,
     ) {
-        // Stack-allocated buffer for one-off calls
-        let normalized_buf = RefCell::new(Vec::new());
+        // Fresh buffer for one-off calls
+        let mut normalized_buf = Vec::new();
         Self::run_ac_matching_with_positions_with_buffer(
             ac_buffer,
             text,
             mode,
             matches,
-            &normalized_buf,
+            &mut normalized_buf,
         );
     }
 
@@ -1331,10 +1743,8 @@ impl Paraglob {
         text: &[u8],
         mode: GlobMatchMode,
         matches: &mut Vec<(usize, u32)>,
-        normalized_text_buffer: &RefCell<Vec<u8>>,
+        normalized_text_buffer: &mut Vec<u8>,
     ) {
-        use crate::offset_format::ACNodeHot;
-
         if ac_buffer.is_empty() || text.is_empty() {
             return;
         }
@@ -1343,19 +1753,18 @@ impl Paraglob {
         // Reuse buffer to avoid allocation
         let search_text = match mode {
             GlobMatchMode::CaseInsensitive => {
-                let mut buf = normalized_text_buffer.borrow_mut();
-                crate::simd_utils::ascii_lowercase(text, &mut buf);
-                // SAFETY: We copy the data out immediately and don't hold the borrow
-                // This is safe because search_text lifetime doesn't escape this function
-                unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) }
+                crate::simd_utils::ascii_lowercase(text, normalized_text_buffer);
+                normalized_text_buffer.as_slice()
             }
             GlobMatchMode::CaseSensitive => text,
         };
 
         let mut current_offset = 0usize;
+        let failure_cap = Self::failure_chain_cap(ac_buffer);
 
         for (pos, &search_ch) in search_text.iter().enumerate() {
             // Traverse to next state
+            let mut followed = 0;
             loop {
                 if let Some(next_offset) =
                     Self::find_ac_transition(ac_buffer, current_offset, search_ch)
@@ -1367,6 +1776,12 @@ impl Paraglob {
                 if current_offset == 0 {
                     break;
                 }
+                if followed == failure_cap {
+                    // Failure cycle in a corrupt automaton: no match here
+                    current_offset = 0;
+                    break;
+                }
+                followed += 1;
 
                 // Fast path: aligned pointer read for failure link
                 // SAFETY: ACNodeHot is 4-byte aligned (written at 16-byte intervals)
@@ -1415,27 +1830,29 @@ impl Paraglob {
         text: &[u8],
         mode: GlobMatchMode,
         matches: &mut HashSet<u32>,
-    ) {
-        use crate::offset_format::ACNodeHot;
-
+        normalized_text_buffer: &mut Vec<u8>,
+        failure_links: &mut usize,
+    ) -> bool {
         if ac_buffer.is_empty() || text.is_empty() {
-            return;
+            return false;
         }
 
         // Pre-lowercase text once for case-insensitive mode using SIMD (4-8x faster)
-        let mut normalized_text_buf: Vec<u8> = Vec::new();
+        // Reuse buffer to avoid allocation
         let search_text = match mode {
             GlobMatchMode::CaseInsensitive => {
-                crate::simd_utils::ascii_lowercase(text, &mut normalized_text_buf);
-                normalized_text_buf.as_slice()
+                crate::simd_utils::ascii_lowercase(text, normalized_text_buffer);
+                normalized_text_buffer.as_slice()
             }
             GlobMatchMode::CaseSensitive => text,
         };
 
         let mut current_offset = 0usize; // Start at root node
+        let failure_cap = Self::failure_chain_cap(ac_buffer);
 
         for &search_ch in search_text.iter() {
             // Traverse to next state
+            let mut followed = 0;
             loop {
                 // Try to find transition
                 if let Some(next_offset) =
@@ -1449,6 +1866,16 @@ impl Paraglob {
                 if current_offset == 0 {
                     break; // At root, stay there
                 }
+                if followed == failure_cap {
+                    // Failure cycle in a corrupt automaton: no match here
+                    current_offset = 0;
+                    break;
+                }
+                followed += 1;
+                if *failure_links == 0 {
+                    return true; // Budget spent, keep what was found so far
+                }
+                *failure_links -= 1;
 
                 // SAFETY: Fast path with aligned pointer read
                 let node = unsafe {
@@ -1490,14 +1917,25 @@ impl Paraglob {
                 }
             }
         }
+        false
+    }
+
+    /// Most failure links one text position can follow in a valid automaton
+    ///
+    /// Each failure link leads to a strictly shallower node, so a chain is
+    /// shorter than the node count, which is at most one node per
+    /// `ACNodeHot` in the buffer. Databases opened in trusted mode skip the
+    /// validator's cycle check, so the matching loops stop at this cap
+    /// instead of spinning forever on a corrupt, cyclic failure chain.
+    #[inline]
+    fn failure_chain_cap(ac_buffer: &[u8]) -> usize {
+        ac_buffer.len() / mem::size_of::<ACNodeHot>()
     }
 
     /// Find a transition from a node for a character in AC automaton
     /// Uses state-specific encoding for optimal performance
     #[inline(always)]
     fn find_ac_transition(ac_buffer: &[u8], node_offset: usize, ch: u8) -> Option<usize> {
-        use crate::offset_format::{ACNodeHot, StateKind};
-
         // Fast path: aligned pointer read (no validation overhead)
         // SAFETY: We validate the offset bounds before casting.
         // ACNodeHot is 16 bytes and always written at 16-byte intervals (offset 0, 16, 32, ...)
@@ -1570,6 +2008,7 @@ impl Paraglob {
                     ac_buffer[target_offset_offset + 3],
                 ]);
 
+                // 0 is the root, which is never a transition target
                 if target != 0 {
                     Some(target as usize)
                 } else {
@@ -1600,16 +2039,14 @@ impl Paraglob {
         let (header_ref, _) = Ref::<_, ParaglobHeader>::from_prefix(buffer.as_slice())
             .map_err(|_| ParaglobError::SerializationError("Invalid header".to_string()))?;
         let header = *header_ref;
-        header
-            .validate()
-            .map_err(|e| ParaglobError::SerializationError(e.to_string()))?;
+        check_header(&header)?;
 
         // Create AC literal hash table from the buffer
         // This is O(1) - just validates header and stores slice reference
         let ac_literal_hash = if header.has_ac_literal_mapping() {
             let hash_offset = header.ac_literal_map_offset as usize;
             if hash_offset >= buffer.len() {
-                return Err(ParaglobError::Validation(format!(
+                return Err(ParaglobError::OutOfBounds(format!(
                     "AC literal map offset {} out of bounds (buffer size: {})",
                     hash_offset,
                     buffer.len()
@@ -1639,13 +2076,14 @@ impl Paraglob {
         Ok(Self {
             buffer: BufferStorage::Owned(buffer),
             mode,
-            glob_cache: RefCell::new(HashMap::new()),
+            glob_cache: Mutex::new(LruCache::unbounded()),
+            max_input_len: None,
+            literal_policy: LiteralMatchPolicy::All,
+            anchoring: Anchoring::Substring,
+            disabled_patterns: Vec::new(),
             ac_literal_hash,
             pattern_data_map,
-            candidate_buffer: RefCell::new(HashSet::new()),
-            ac_literal_buffer: RefCell::new(HashSet::new()),
-            result_buffer: RefCell::new(Vec::new()),
-            normalized_text_buffer: RefCell::new(Vec::new()),
+            scratch: QueryScratch::new(),
         })
     }
 
@@ -1673,16 +2111,14 @@ impl Paraglob {
         let (header_ref, _) = Ref::<_, ParaglobHeader>::from_prefix(slice)
             .map_err(|_| ParaglobError::SerializationError("Invalid header".to_string()))?;
         let header = *header_ref;
-        header
-            .validate()
-            .map_err(|e| ParaglobError::SerializationError(e.to_string()))?;
+        check_header(&header)?;
 
         // O(1): Load AC literal hash table from mmap'd buffer
         // This just validates header and stores offsets - no data copying!
         let ac_literal_hash = if header.has_ac_literal_mapping() {
             let hash_offset = header.ac_literal_map_offset as usize;
             if hash_offset >= slice.len() {
-                return Err(ParaglobError::Validation(format!(
+                return Err(ParaglobError::OutOfBounds(format!(
                     "AC literal map offset {} out of bounds (slice size: {})",
                     hash_offset,
                     slice.len()
@@ -1709,13 +2145,14 @@ impl Paraglob {
         Ok(Self {
             buffer: BufferStorage::Borrowed(slice),
             mode,
-            glob_cache: RefCell::new(HashMap::new()),
+            glob_cache: Mutex::new(LruCache::unbounded()),
+            max_input_len: None,
+            literal_policy: LiteralMatchPolicy::All,
+            anchoring: Anchoring::Substring,
+            disabled_patterns: Vec::new(),
             ac_literal_hash,
             pattern_data_map,
-            candidate_buffer: RefCell::new(HashSet::new()),
-            ac_literal_buffer: RefCell::new(HashSet::new()),
-            result_buffer: RefCell::new(Vec::new()),
-            normalized_text_buffer: RefCell::new(Vec::new()),
+            scratch: QueryScratch::new(),
         })
     }
 
@@ -1734,6 +2171,382 @@ impl Paraglob {
         header.pattern_count as usize
     }
 
+    /// Iterate over the nodes of the Aho-Corasick automaton
+    ///
+    /// Walks the serialized automaton in node order, decoding each node's
+    /// transitions, failure link, and attached pattern IDs. Intended for
+    /// debugging and visualization (e.g. emitting Graphviz DOT); it is not
+    /// used on the matching path. Nodes that fail to decode are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let pg = Paraglob::build_from_patterns(&["he", "she"], MatchMode::CaseSensitive)?;
+    ///
+    /// println!("digraph ac {{");
+    /// for node in pg.ac_nodes() {
+    ///     for (ch, target) in &node.edges {
+    ///         println!("  {} -> {} [label=\"{}\"];", node.id, target, *ch as char);
+    ///     }
+    ///     println!("  {} -> {} [style=dashed];", node.id, node.failure);
+    /// }
+    /// println!("}}");
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn ac_nodes(&self) -> impl Iterator<Item = ACNodeInfo> {
+        let buffer = self.buffer.as_slice();
+        let ac_buffer = match Ref::<_, ParaglobHeader>::from_prefix(buffer) {
+            Ok((header, _)) => {
+                let start = header.ac_nodes_offset as usize;
+                let end = start + header.ac_edges_size as usize;
+                buffer.get(start..end).unwrap_or(&[])
+            }
+            Err(_) => &[],
+        };
+
+        // The header's node count is approximate, so discover nodes by walking from the root
+        let mut nodes = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        if !ac_buffer.is_empty() {
+            queue.push_back(0u32);
+        }
+        while let Some(id) = queue.pop_front() {
+            if nodes.contains_key(&id) {
+                continue;
+            }
+            if let Some(node) = self.read_ac_node(ac_buffer, id) {
+                queue.extend(node.edges.iter().map(|&(_, target)| target));
+                nodes.insert(id, node);
+            }
+        }
+
+        nodes.into_values()
+    }
+
+    /// Longest chain of failure links from any AC node back to the root
+    ///
+    /// On a mismatch the automaton follows failure links until a node with a
+    /// matching transition is found, so this bounds the work done for a single
+    /// input byte. It grows with deep shared prefixes and repetitive literals
+    /// (e.g. `aaaa...`); a high value means worst-case queries are slower than
+    /// the pattern count alone suggests. Returns 0 when there is no automaton.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let pg = Paraglob::build_from_patterns(&["aaaa"], MatchMode::CaseSensitive)?;
+    /// // "aaaa" -> "aaa" -> "aa" -> "a" -> root
+    /// assert_eq!(pg.max_failure_chain(), 4);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn max_failure_chain(&self) -> usize {
+        let failures: BTreeMap<u32, u32> = self.ac_nodes().map(|n| (n.id, n.failure)).collect();
+        let mut chain: HashMap<u32, usize> = HashMap::with_capacity(failures.len());
+        let mut longest = 0;
+
+        for &id in failures.keys() {
+            // Walk up until the root or a node whose chain length is known
+            let mut path = Vec::new();
+            let mut current = id;
+            let base = loop {
+                if current == 0 {
+                    break 0;
+                }
+                if let Some(&len) = chain.get(&current) {
+                    break len;
+                }
+                if path.len() > failures.len() {
+                    break 0; // Corrupt automaton with a failure cycle
+                }
+                path.push(current);
+                current = match failures.get(&current) {
+                    Some(&failure) => failure,
+                    None => break 0,
+                };
+            };
+            for (i, &node) in path.iter().rev().enumerate() {
+                chain.insert(node, base + i + 1);
+            }
+            longest = longest.max(chain.get(&id).copied().unwrap_or(0));
+        }
+
+        longest
+    }
+
+    /// Decode a single AC node by index (None if out of bounds or corrupt)
+    fn read_ac_node(&self, ac_buffer: &[u8], id: u32) -> Option<ACNodeInfo> {
+        let node_size = mem::size_of::<ACNodeHot>();
+        let node_slice = ac_buffer.get(id as usize * node_size..)?;
+        let (node_ref, _) = Ref::<_, ACNodeHot>::from_prefix(node_slice).ok()?;
+        let node = *node_ref;
+        let state_kind = StateKind::from_u8(node.state_kind)?;
+
+        // Nodes are laid out contiguously, so offsets map directly to IDs
+        let to_id = |offset: u32| offset / node_size as u32;
+
+        let edges = match state_kind {
+            StateKind::Empty => Vec::new(),
+            StateKind::One => vec![(node.one_char, to_id(node.edges_offset))],
+            StateKind::Sparse => {
+                let start = node.edges_offset as usize;
+                let edge_size = mem::size_of::<ACEdge>();
+                (0..node.edge_count as usize)
+                    .map(|i| {
+                        let slice = ac_buffer.get(start + i * edge_size..)?;
+                        let (edge, _) = Ref::<_, ACEdge>::from_prefix(slice).ok()?;
+                        Some((edge.character, to_id(edge.target_offset)))
+                    })
+                    .collect::<Option<Vec<_>>>()?
+            }
+            StateKind::Dense => {
+                let start = node.edges_offset as usize;
+                let table = ac_buffer.get(start..start + 256 * 4)?;
+                table
+                    .chunks_exact(4)
+                    .enumerate()
+                    .filter_map(|(ch, bytes)| {
+                        let target = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                        (target != 0).then(|| (ch as u8, to_id(target)))
+                    })
+                    .collect()
+            }
+        };
+
+        // Nodes store AC literal IDs; resolve them to pattern IDs
+        let ids_start = node.patterns_offset as usize;
+        let ids = ac_buffer.get(ids_start..ids_start + node.pattern_count as usize * 4)?;
+        let mut pattern_ids = Vec::new();
+        for bytes in ids.chunks_exact(4) {
+            let literal_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            match self.ac_literal_hash {
+                Some(ref ac_hash) => ac_hash.lookup_into(literal_id, &mut pattern_ids),
+                None => pattern_ids.push(literal_id),
+            }
+        }
+        pattern_ids.sort_unstable();
+        pattern_ids.dedup();
+
+        Some(ACNodeInfo {
+            id,
+            state_kind,
+            edges,
+            failure: to_id(node.failure_offset),
+            pattern_ids,
+        })
+    }
+
+    /// Limit the number of compiled glob patterns kept in memory
+    ///
+    /// Globs are compiled on first use and cached. By default the cache is
+    /// unbounded, which is fastest but grows with every distinct glob a query
+    /// touches. With a capacity set, the least recently used patterns are
+    /// evicted and recompiled if needed again. `None` restores the unbounded default.
+    /// Plain suffix (`*.txt`) and prefix (`log_*`) globs are matched without
+    /// compiling and never enter the cache.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let mut pg = Paraglob::build_from_patterns(&["*.t?t", "*.l?g"], MatchMode::CaseSensitive)?;
+    /// pg.set_glob_cache_capacity(NonZeroUsize::new(1));
+    ///
+    /// pg.find_all("a.txt");
+    /// pg.find_all("b.log");
+    /// assert_eq!(pg.glob_cache_len(), 1);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn set_glob_cache_capacity(&mut self, capacity: Option<NonZeroUsize>) {
+        let capacity = capacity.unwrap_or(NonZeroUsize::MAX);
+        self.glob_cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .resize(capacity);
+    }
+
+    /// Limit the length of inputs the matcher will process
+    ///
+    /// Inputs longer than `max_len` bytes match nothing in
+    /// [`find_all`](Self::find_all), [`find_all_ref`](Self::find_all_ref),
+    /// [`find_all_into`](Self::find_all_into), and the `count_matches`
+    /// methods, without running the automaton or verifying globs. This keeps
+    /// a single huge input (e.g. a malformed multi-megabyte log line) from
+    /// stalling the matcher. `None` removes the limit.
+    ///
+    /// Default: unbounded
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let mut pg = Paraglob::build_from_patterns(&["*.txt"], MatchMode::CaseSensitive)?;
+    /// pg.set_max_input_len(Some(8));
+    ///
+    /// assert_eq!(pg.find_all("a.txt").len(), 1);
+    /// assert!(pg.find_all("longer-name.txt").is_empty());
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn set_max_input_len(&mut self, max_len: Option<usize>) {
+        self.max_input_len = max_len;
+    }
+
+    /// Maximum input length in bytes, if one is set
+    pub fn max_input_len(&self) -> Option<usize> {
+        self.max_input_len
+    }
+
+    /// Choose how overlapping literal matches are reported
+    ///
+    /// With the default [`LiteralMatchPolicy::All`], every literal pattern
+    /// that occurs in the input is returned. With
+    /// [`LiteralMatchPolicy::LongestOnly`], literals whose matches overlap a
+    /// longer literal match are dropped. Applies to
+    /// [`find_all`](Self::find_all), [`find_all_ref`](Self::find_all_ref),
+    /// [`find_all_into`](Self::find_all_into), and the `count_matches`
+    /// methods.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    /// use matchy::paraglob_offset::LiteralMatchPolicy;
+    ///
+    /// let patterns = ["example.com", "mail.example.com"];
+    /// let mut pg = Paraglob::build_from_patterns(&patterns, MatchMode::CaseSensitive)?;
+    /// assert_eq!(pg.find_all("mail.example.com").len(), 2);
+    ///
+    /// pg.set_literal_match_policy(LiteralMatchPolicy::LongestOnly);
+    /// assert_eq!(pg.find_all("mail.example.com"), vec![1]);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn set_literal_match_policy(&mut self, policy: LiteralMatchPolicy) {
+        self.literal_policy = policy;
+    }
+
+    /// How overlapping literal matches are reported
+    pub fn literal_match_policy(&self) -> LiteralMatchPolicy {
+        self.literal_policy
+    }
+
+    /// Choose whether literal patterns match anywhere or only the whole input
+    ///
+    /// With the default [`Anchoring::Substring`], a literal pattern matches
+    /// any input containing it. With [`Anchoring::Full`], it matches only an
+    /// input equal to it (ignoring case in case-insensitive mode), the same
+    /// answer an exact lookup would give. Glob patterns always match the whole
+    /// input and are unaffected. Applies to [`find_all`](Self::find_all),
+    /// [`find_all_ref`](Self::find_all_ref), [`find_all_into`](Self::find_all_into),
+    /// and the `count_matches` methods, but not to
+    /// [`find_matches_with_positions`](Self::find_matches_with_positions).
+    ///
+    /// A [`Database`](crate::Database) keeps its literals in a separate hash
+    /// table that is always an exact lookup, so this only matters for
+    /// literals stored as patterns.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    /// use matchy::paraglob_offset::Anchoring;
+    ///
+    /// let patterns = ["evil.com", "*.example.org"];
+    /// let mut pg = Paraglob::build_from_patterns(&patterns, MatchMode::CaseSensitive)?;
+    /// assert_eq!(pg.find_all("GET http://evil.com/x"), vec![0]);
+    ///
+    /// pg.set_anchoring(Anchoring::Full);
+    /// assert!(pg.find_all("GET http://evil.com/x").is_empty());
+    /// assert_eq!(pg.find_all("evil.com"), vec![0]);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn set_anchoring(&mut self, anchoring: Anchoring) {
+        self.anchoring = anchoring;
+    }
+
+    /// Whether literal patterns match anywhere or only the whole input
+    pub fn anchoring(&self) -> Anchoring {
+        self.anchoring
+    }
+
+    /// Enable or disable a pattern without rebuilding
+    ///
+    /// Disabled patterns are skipped before verification, so they never
+    /// appear in [`find_all`](Self::find_all), [`find_all_ref`](Self::find_all_ref),
+    /// [`find_all_into`](Self::find_all_into), or the `count_matches`
+    /// methods. The state lives only in this instance; the buffer (and any
+    /// file it was loaded from) is unchanged. Unknown pattern IDs are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use matchy::Paraglob;
+    /// use matchy::glob::MatchMode;
+    ///
+    /// let mut pg = Paraglob::build_from_patterns(&["*.txt", "test_*"], MatchMode::CaseSensitive)?;
+    /// pg.set_pattern_enabled(0, false);
+    /// assert_eq!(pg.find_all("test_file.txt"), vec![1]);
+    ///
+    /// pg.set_pattern_enabled(0, true);
+    /// assert_eq!(pg.find_all("test_file.txt"), vec![0, 1]);
+    /// # Ok::<(), matchy::ParaglobError>(())
+    /// ```
+    pub fn set_pattern_enabled(&mut self, pattern_id: u32, enabled: bool) {
+        if pattern_id as usize >= self.pattern_count() {
+            return;
+        }
+        let (word, bit) = (pattern_id as usize / 64, pattern_id % 64);
+        if enabled {
+            if let Some(bits) = self.disabled_patterns.get_mut(word) {
+                *bits &= !(1 << bit);
+            }
+            // Keep the common "nothing disabled" case a single emptiness check
+            if self.disabled_patterns.iter().all(|&bits| bits == 0) {
+                self.disabled_patterns.clear();
+            }
+        } else {
+            if self.disabled_patterns.len() <= word {
+                self.disabled_patterns.resize(word + 1, 0);
+            }
+            self.disabled_patterns[word] |= 1 << bit;
+        }
+    }
+
+    /// Whether a pattern is enabled (see [`set_pattern_enabled`](Self::set_pattern_enabled))
+    pub fn is_pattern_enabled(&self, pattern_id: u32) -> bool {
+        !self.is_disabled(pattern_id)
+    }
+
+    #[inline]
+    fn is_disabled(&self, pattern_id: u32) -> bool {
+        self.disabled_patterns
+            .get(pattern_id as usize / 64)
+            .is_some_and(|bits| bits & (1 << (pattern_id % 64)) != 0)
+    }
+
+    /// Drop disabled patterns from `candidates`
+    #[inline]
+    fn retain_enabled_candidates(&self, candidates: &mut Vec<u32>) {
+        if !self.disabled_patterns.is_empty() {
+            candidates.retain(|&pattern_id| !self.is_disabled(pattern_id));
+        }
+    }
+
+    /// Check whether an input exceeds the configured maximum length
+    #[inline]
+    fn input_too_long(&self, text: &str) -> bool {
+        self.max_input_len.is_some_and(|max| text.len() > max)
+    }
+
+    /// Number of compiled glob patterns currently cached
+    pub fn glob_cache_len(&self) -> usize {
+        self.glob_cache().len()
+    }
+
     /// Get data associated with a pattern (v2 feature)
     ///
     /// Returns `None` if the pattern has no associated data or if the file is v1.
@@ -1928,6 +2741,321 @@ mod tests {
         assert_eq!(matches.len(), 2);
     }
 
+    #[test]
+    fn test_bounded_glob_cache_evicts() {
+        // Affix globs (`*.txt`, `test_*`) never compile a matcher, so use `?`
+        let patterns = vec!["*.t?t", "*.l?g", "test_?*"];
+        let mut pg =
+            Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        pg.set_glob_cache_capacity(NonZeroUsize::new(2));
+
+        // Each query still gets correct results while patterns are evicted and recompiled
+        for _ in 0..3 {
+            assert_eq!(pg.find_all("test_file.txt"), vec![0, 2]);
+            assert_eq!(pg.find_all("app.log"), vec![1]);
+            assert!(pg.glob_cache_len() <= 2);
+        }
+
+        pg.set_glob_cache_capacity(None);
+        pg.find_all("test_file.txt");
+        pg.find_all("app.log");
+        assert_eq!(pg.glob_cache_len(), 3);
+    }
+
+    #[test]
+    fn test_ac_nodes() {
+        let patterns = vec!["he", "she", "*.his"];
+        let pg = Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        let nodes: Vec<ACNodeInfo> = pg.ac_nodes().collect();
+
+        // Node IDs are dense and every edge and failure link points at a real node
+        assert!(!nodes.is_empty());
+        for (index, node) in nodes.iter().enumerate() {
+            assert_eq!(node.id as usize, index);
+            assert!((node.failure as usize) < nodes.len());
+            for &(_, target) in &node.edges {
+                assert!((target as usize) < nodes.len());
+            }
+        }
+
+        // Follow "she" from the root: the final node recognizes both "she" and "he"
+        let mut current = 0u32;
+        for ch in b"she" {
+            let node = &nodes[current as usize];
+            current = node.edges.iter().find(|(c, _)| c == ch).unwrap().1;
+        }
+        assert_eq!(nodes[current as usize].pattern_ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_first_match_is_stable() {
+        // Overlapping globs share the "log" literal, so candidates arrive in hash order
+        let patterns: Vec<String> = (0..50).map(|i| format!("*log*{}*", i)).collect();
+        let refs: Vec<&str> = patterns.iter().map(|s| s.as_str()).collect();
+
+        for _ in 0..20 {
+            // Fresh instance each time so hash sets get new random seeds
+            let mut pg =
+                Paraglob::build_from_patterns(&refs, GlobMatchMode::CaseSensitive).unwrap();
+            assert_eq!(pg.find_all("syslog-31.txt"), vec![1, 3, 31]);
+
+            let candidates = with_thread_scratch(|scratch| scratch.candidates.clone());
+            assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+
+            assert_eq!(pg.find_all_ref("syslog-31.txt").first(), Some(&1));
+        }
+    }
+
+    #[test]
+    fn test_unparsable_stored_glob_is_a_miss() {
+        let pg = Paraglob::build_from_patterns(&["*.txt"], GlobMatchMode::CaseSensitive).unwrap();
+        let too_many_alternatives = "{a,b}".repeat(11);
+        assert!(GlobPattern::new(&too_many_alternatives, GlobMatchMode::CaseSensitive).is_err());
+
+        assert!(!pg.cached_glob_matches(7, &too_many_alternatives, "aaaaaaaaaaa"));
+        assert!(pg.glob_cache().get(&7).is_none());
+        assert!(pg.cached_glob_matches(0, "*.txt", "a.txt"));
+    }
+
+    #[test]
+    fn test_short_literal_globs_are_pure_wildcards() {
+        assert!(is_pure_wildcard("*"));
+        assert!(is_pure_wildcard("a*b"));
+        // Literals under MIN_ANCHOR_LEN bytes don't anchor; ".io" is long enough
+        assert!(is_pure_wildcard("*.i?"));
+        assert!(!is_pure_wildcard("*.io?"));
+        assert!(!is_pure_wildcard("*.com"));
+        assert!(!is_pure_wildcard("ab*evil*"));
+        assert!(!is_pure_wildcard("plain"));
+
+        // Globs with only short literals still match (they are not anchored)
+        let patterns = vec!["a*b", "*.evil.com"];
+        let pg = Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.find_all("axxb"), vec![0]);
+        assert_eq!(pg.find_all("x.evil.com"), vec![1]);
+    }
+
+    #[test]
+    fn test_brace_alternation() {
+        assert_eq!(
+            PatternType::anchor_literals("*.{com,net}.{evil,bad}"),
+            vec![".com.evil", ".com.bad", ".net.evil", ".net.bad"]
+        );
+        // One unanchored alternative makes the whole pattern unanchored
+        assert!(is_pure_wildcard("{a*,*.example.com}"));
+        assert!(!is_pure_wildcard("{abc,def}"));
+        assert!(matches!(
+            PatternType::new_with_data(&"{a,b,c,d}".repeat(6), 0, None),
+            Err(ParaglobError::InvalidPattern(_))
+        ));
+
+        let patterns = vec!["*.{com,net}", "log-{2023,2024}-*.txt", "plain{x}"];
+        for mode in [GlobMatchMode::CaseSensitive, GlobMatchMode::CaseInsensitive] {
+            let pg = Paraglob::build_from_patterns(&patterns, mode).unwrap();
+            assert_eq!(pg.find_all("evil.com"), vec![0]);
+            assert_eq!(pg.find_all("evil.net"), vec![0]);
+            assert!(pg.find_all("evil.org").is_empty());
+            assert!(pg.find_all("evil.{com,net}").is_empty());
+            assert_eq!(pg.find_all("log-2024-app.txt"), vec![1]);
+            assert!(pg.find_all("log-2025-app.txt").is_empty());
+            assert_eq!(pg.find_all("plain{x}"), vec![2]);
+        }
+    }
+
+    #[test]
+    fn test_escaped_wildcards_round_trip() {
+        // Each escaped pattern matches its unescaped text literally
+        let patterns = vec![r"file\*.txt", r"what\?", r"\[log\]*", r"C:\\temp\\*", r"\*"];
+        assert!(!is_pure_wildcard(r"file\*.txt"));
+        assert_eq!(
+            PatternType::extract_literals(r"file\*.txt"),
+            vec!["file*.txt"]
+        );
+        assert_eq!(
+            PatternType::extract_literals(r"C:\\temp\\*"),
+            vec![r"C:\temp\"]
+        );
+
+        for mode in [GlobMatchMode::CaseSensitive, GlobMatchMode::CaseInsensitive] {
+            let pg = Paraglob::build_from_patterns(&patterns, mode).unwrap();
+            assert_eq!(pg.find_all("file*.txt"), vec![0]);
+            assert!(pg.find_all("fileX.txt").is_empty());
+            assert!(pg.find_all(r"file\*.txt").is_empty());
+
+            assert_eq!(pg.find_all("what?"), vec![1]);
+            assert!(pg.find_all("whatx").is_empty());
+
+            assert_eq!(pg.find_all("[log] started"), vec![2]);
+            assert!(pg.find_all("l started").is_empty());
+
+            assert_eq!(pg.find_all(r"C:\temp\notes"), vec![3]);
+            assert!(pg.find_all("C:tempnotes").is_empty());
+
+            assert_eq!(pg.find_all("*"), vec![4]);
+            assert!(pg.find_all("x").is_empty());
+        }
+    }
+
+    #[test]
+    fn test_count_matches() {
+        let patterns = vec!["*", "hello", "*.txt", "test_*", "*file*"];
+        let pg = Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+
+        for text in ["test_file.txt", "hello", "nothing", ""] {
+            assert_eq!(pg.count_matches(text), pg.find_all(text).len(), "{}", text);
+        }
+        assert_eq!(pg.count_matches("test_file.txt"), 4);
+        assert_eq!(pg.count_matches_up_to("test_file.txt", 2), 2);
+        assert_eq!(pg.count_matches_up_to("test_file.txt", 10), 4);
+        assert_eq!(pg.count_matches_up_to("test_file.txt", 0), 0);
+    }
+
+    #[test]
+    fn test_max_input_len() {
+        let patterns = vec!["*", "*.txt"];
+        let mut pg =
+            Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.max_input_len(), None);
+
+        let long = format!("{}.txt", "a".repeat(100));
+        assert_eq!(pg.find_all(&long).len(), 2);
+
+        pg.set_max_input_len(Some(100));
+        assert!(pg.find_all(&long).is_empty());
+        assert!(pg.find_all_ref(&long).is_empty());
+        assert_eq!(pg.count_matches(&long), 0);
+        assert_eq!(pg.find_all("a.txt").len(), 2);
+
+        pg.set_max_input_len(None);
+        assert_eq!(pg.find_all(&long).len(), 2);
+    }
+
+    #[test]
+    fn test_literal_match_policy() {
+        let patterns = ["example.com", "mail.example.com", "*.com", "mail"];
+        let mut pg =
+            Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.literal_match_policy(), LiteralMatchPolicy::All);
+        assert_eq!(pg.find_all("mail.example.com"), vec![0, 1, 2, 3]);
+
+        pg.set_literal_match_policy(LiteralMatchPolicy::LongestOnly);
+        // Both shorter literals overlap the longest one; the glob is kept
+        assert_eq!(pg.find_all("mail.example.com"), vec![1, 2]);
+        assert_eq!(pg.find_all_ref("mail.example.com"), &[1, 2]);
+        assert_eq!(pg.count_matches("mail.example.com"), 2);
+        // Literals that don't overlap are all kept
+        assert_eq!(pg.find_all("mail/example.com"), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_anchoring() {
+        let patterns = ["example.com", "mail", "*.com"];
+        for mode in [GlobMatchMode::CaseSensitive, GlobMatchMode::CaseInsensitive] {
+            let mut pg = Paraglob::build_from_patterns(&patterns, mode).unwrap();
+            assert_eq!(pg.anchoring(), Anchoring::Substring);
+            assert_eq!(pg.find_all("mail.example.com"), vec![0, 1, 2]);
+
+            pg.set_anchoring(Anchoring::Full);
+            // Only the glob still matches; literals must be the whole input
+            assert_eq!(pg.find_all("mail.example.com"), vec![2]);
+            assert_eq!(pg.count_matches("mail.example.com"), 1);
+            assert_eq!(pg.find_all("example.com"), vec![0, 2]);
+            assert_eq!(pg.find_all_ref("mail"), &[1]);
+            // Raw automaton hits are unaffected
+            assert!(!pg
+                .find_matches_with_positions("mail.example.com")
+                .is_empty());
+        }
+
+        let mut pg =
+            Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseInsensitive).unwrap();
+        pg.set_anchoring(Anchoring::Full);
+        assert_eq!(pg.find_all("EXAMPLE.com"), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_max_failure_chain() {
+        let pg = Paraglob::build_from_patterns(&[], GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.max_failure_chain(), 0);
+
+        // No shared structure: every failure link goes straight to the root
+        let pg =
+            Paraglob::build_from_patterns(&["abc", "xyz"], GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.max_failure_chain(), 1);
+
+        // "abab" -> "ab" -> "b" -> root
+        let pg =
+            Paraglob::build_from_patterns(&["abab", "b"], GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.max_failure_chain(), 3);
+    }
+
+    #[test]
+    fn test_cyclic_failure_links_terminate() {
+        use zerocopy::IntoBytes;
+
+        let node = |state_kind: StateKind, one_char: u8, edges_offset, failure_offset| ACNodeHot {
+            state_kind: state_kind as u8,
+            one_char,
+            edge_count: 0,
+            pattern_count: 0,
+            edges_offset,
+            failure_offset,
+            patterns_offset: 0,
+        };
+        // Root --a--> node 16, whose failure link starts the cycle 16 -> 32 -> 16
+        let nodes = [
+            node(StateKind::One, b'a', 16, 0),
+            node(StateKind::Empty, 0, 0, 32),
+            node(StateKind::Empty, 0, 0, 16),
+        ];
+        let ac_buffer = nodes.as_bytes();
+
+        let mut ids = HashSet::new();
+        let mut failure_links = usize::MAX;
+        let stopped = Paraglob::run_ac_matching_into_static(
+            ac_buffer,
+            b"abab",
+            GlobMatchMode::CaseSensitive,
+            &mut ids,
+            &mut Vec::new(),
+            &mut failure_links,
+        );
+        assert!(!stopped);
+        assert!(ids.is_empty());
+
+        let mut positions = Vec::new();
+        Paraglob::run_ac_matching_with_positions_with_buffer(
+            ac_buffer,
+            b"abab",
+            GlobMatchMode::CaseSensitive,
+            &mut positions,
+            &mut Vec::new(),
+        );
+        assert!(positions.is_empty());
+    }
+
+    #[test]
+    fn test_affix_globs() {
+        assert_eq!(glob_pattern_type("*.evil.com"), 2);
+        assert_eq!(glob_pattern_type("evil.*"), 3);
+        assert_eq!(glob_pattern_type("*.evil.*"), 1);
+        assert_eq!(glob_pattern_type("*.evil?.com"), 1);
+        assert_eq!(glob_pattern_type("*"), 1);
+
+        let patterns = ["*.evil.com", "evil.*", "*.évil.net"];
+        let pg = Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseSensitive).unwrap();
+        assert_eq!(pg.find_all("www.evil.com"), vec![0]);
+        assert!(pg.find_all("evil.com").contains(&1));
+        assert!(!pg.find_all("evil.com").contains(&0));
+        assert!(pg.find_all("WWW.EVIL.COM").is_empty());
+        assert_eq!(pg.find_all("a.évil.net"), vec![2]);
+        assert!(pg.find_all("évil.net").is_empty());
+
+        let pg = Paraglob::build_from_patterns(&patterns, GlobMatchMode::CaseInsensitive).unwrap();
+        assert_eq!(pg.find_all("WWW.EVIL.COM"), vec![0]);
+        assert_eq!(pg.find_all("Evil.Org"), vec![1]);
+    }
+
     #[test]
     fn test_pure_wildcard() {
         let patterns = vec!["*", "??"];
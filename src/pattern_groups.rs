@@ -0,0 +1,211 @@
+//! Pattern Group Table
+//!
+//! Records which named group, if any, each literal and glob belongs to, so
+//! group-filtered lookups never consult entry data. Group names live in the
+//! `pattern_groups` metadata field; this section only holds their IDs,
+//! indexed by pattern ID.
+//!
+//! # Format
+//!
+//! ```text
+//! [Header]
+//!   magic: [u8; 4]           // "GRPS"
+//!   version: u32              // 1
+//!   literal_count: u32        // Number of literal pattern IDs
+//!   glob_count: u32           // Number of glob pattern IDs
+//!
+//! [Literal groups] (starting at offset 16)
+//!   groups: [u16; literal_count]   // Group ID by literal pattern ID
+//!
+//! [Glob groups]
+//!   groups: [u16; glob_count]      // Group ID by glob pattern ID
+//! ```
+//!
+//! A group ID of `0xFFFF` marks an ungrouped pattern.
+
+use crate::error::ParaglobError;
+
+/// Magic bytes for the pattern group section
+pub const PATTERN_GROUPS_MAGIC: &[u8; 4] = b"GRPS";
+
+/// Current pattern group section format version
+const PATTERN_GROUPS_VERSION: u32 = 1;
+
+/// Header size: 4 byte magic + 3 u32 fields
+const HEADER_SIZE: usize = 16;
+
+/// Stored group ID of a pattern that belongs to no group
+const NO_GROUP: u16 = u16::MAX;
+
+/// Largest number of distinct groups a table can refer to
+pub(crate) const MAX_PATTERN_GROUPS: usize = NO_GROUP as usize;
+
+/// Builder for the pattern group section
+#[derive(Default)]
+pub struct PatternGroupsBuilder {
+    literals: Vec<u16>,
+    globs: Vec<u16>,
+}
+
+impl PatternGroupsBuilder {
+    /// Create a builder for `literal_count` literals and `glob_count` globs,
+    /// all ungrouped
+    pub fn new(literal_count: usize, glob_count: usize) -> Self {
+        Self {
+            literals: vec![NO_GROUP; literal_count],
+            globs: vec![NO_GROUP; glob_count],
+        }
+    }
+
+    /// Assign a literal pattern ID to a group
+    pub fn set_literal(&mut self, pattern_id: u32, group: u16) {
+        if let Some(slot) = self.literals.get_mut(pattern_id as usize) {
+            *slot = group;
+        }
+    }
+
+    /// Assign a glob pattern ID to a group
+    pub fn set_glob(&mut self, pattern_id: u32, group: u16) {
+        if let Some(slot) = self.globs.get_mut(pattern_id as usize) {
+            *slot = group;
+        }
+    }
+
+    /// Exact size of the serialized section in bytes
+    pub fn serialized_size(&self) -> usize {
+        HEADER_SIZE + (self.literals.len() + self.globs.len()) * 2
+    }
+
+    /// Serialize the table
+    pub fn build(&self) -> Result<Vec<u8>, ParaglobError> {
+        let total_size = self.serialized_size();
+        if total_size > u32::MAX as usize {
+            return Err(ParaglobError::ResourceLimitExceeded(
+                "Pattern group table exceeds 4GB".to_string(),
+            ));
+        }
+
+        let mut buffer = Vec::with_capacity(total_size);
+        buffer.extend_from_slice(PATTERN_GROUPS_MAGIC);
+        for field in [
+            PATTERN_GROUPS_VERSION,
+            self.literals.len() as u32,
+            self.globs.len() as u32,
+        ] {
+            buffer.extend_from_slice(&field.to_le_bytes());
+        }
+        for group in self.literals.iter().chain(&self.globs) {
+            buffer.extend_from_slice(&group.to_le_bytes());
+        }
+        Ok(buffer)
+    }
+}
+
+/// Memory-mapped pattern group table for lookups
+pub struct PatternGroups<'a> {
+    buffer: &'a [u8],
+    literal_count: u32,
+    glob_count: u32,
+}
+
+impl<'a> PatternGroups<'a> {
+    /// Load from memory-mapped buffer
+    ///
+    /// The buffer may extend past the end of the section.
+    pub fn from_buffer(buffer: &'a [u8]) -> Result<Self, ParaglobError> {
+        if buffer.len() < HEADER_SIZE {
+            return Err(ParaglobError::Format(
+                "Buffer too small for pattern group header".to_string(),
+            ));
+        }
+        if &buffer[0..4] != PATTERN_GROUPS_MAGIC {
+            return Err(ParaglobError::Format(format!(
+                "Invalid pattern group magic: expected {:?}, got {:?}",
+                PATTERN_GROUPS_MAGIC,
+                &buffer[0..4]
+            )));
+        }
+
+        let field = |i: usize| u32::from_le_bytes(buffer[4 + i * 4..8 + i * 4].try_into().unwrap());
+        let version = field(0);
+        if version != PATTERN_GROUPS_VERSION {
+            return Err(ParaglobError::UnsupportedVersion(format!(
+                "Unsupported pattern group version: {}",
+                version
+            )));
+        }
+        let literal_count = field(1);
+        let glob_count = field(2);
+        if HEADER_SIZE + (literal_count as usize + glob_count as usize) * 2 > buffer.len() {
+            return Err(ParaglobError::OutOfBounds(
+                "Pattern group entries out of bounds".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            buffer,
+            literal_count,
+            glob_count,
+        })
+    }
+
+    /// Group ID of a literal pattern, or `None` if it is ungrouped
+    pub fn literal_group(&self, pattern_id: u32) -> Option<u16> {
+        if pattern_id >= self.literal_count {
+            return None;
+        }
+        self.group_at(pattern_id as usize)
+    }
+
+    /// Group ID of a glob pattern, or `None` if it is ungrouped
+    pub fn glob_group(&self, pattern_id: u32) -> Option<u16> {
+        if pattern_id >= self.glob_count {
+            return None;
+        }
+        self.group_at(self.literal_count as usize + pattern_id as usize)
+    }
+
+    /// Read the group ID in slot `index`
+    fn group_at(&self, index: usize) -> Option<u16> {
+        let pos = HEADER_SIZE + index * 2;
+        let bytes = self.buffer.get(pos..pos + 2)?;
+        match u16::from_le_bytes([bytes[0], bytes[1]]) {
+            NO_GROUP => None,
+            group => Some(group),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let mut builder = PatternGroupsBuilder::new(2, 3);
+        builder.set_literal(1, 0);
+        builder.set_glob(0, 1);
+        builder.set_glob(2, 0);
+        // Out-of-range IDs are ignored
+        builder.set_glob(3, 0);
+        let bytes = builder.build().unwrap();
+        assert_eq!(bytes.len(), builder.serialized_size());
+
+        let groups = PatternGroups::from_buffer(&bytes).unwrap();
+        assert_eq!(groups.literal_group(0), None);
+        assert_eq!(groups.literal_group(1), Some(0));
+        assert_eq!(groups.literal_group(2), None);
+        assert_eq!(groups.glob_group(0), Some(1));
+        assert_eq!(groups.glob_group(1), None);
+        assert_eq!(groups.glob_group(2), Some(0));
+        assert_eq!(groups.glob_group(3), None);
+    }
+
+    #[test]
+    fn test_corrupt_header_rejected() {
+        let mut bytes = PatternGroupsBuilder::new(1, 1).build().unwrap();
+        assert!(PatternGroups::from_buffer(&bytes[..18]).is_err());
+        bytes[0] = b'X';
+        assert!(PatternGroups::from_buffer(&bytes).is_err());
+    }
+}
@@ -97,6 +97,7 @@ pub struct LineBatch {
 
 /// Statistics from parallel line processing
 #[derive(Default, Clone, Debug)]
+#[non_exhaustive]
 pub struct WorkerStats {
     /// Total lines processed
     pub lines_processed: usize,
@@ -140,6 +141,8 @@ pub struct WorkerStats {
     pub ethereum_count: usize,
     /// Monero addresses found
     pub monero_count: usize,
+    /// URLs found
+    pub url_count: usize,
 }
 
 /// Core match result without file/line context
@@ -147,6 +150,7 @@ pub struct WorkerStats {
 /// General-purpose match result suitable for any processing context.
 /// Use [`LineMatch`] when you have file/line information.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct MatchResult {
     /// Matched text
     pub matched_text: String,
@@ -158,6 +162,10 @@ pub struct MatchResult {
     pub database_id: String,
     /// Byte offset in the input data (0-indexed)
     pub byte_offset: usize,
+    /// End of the match in the input data (exclusive)
+    ///
+    /// Together with `byte_offset` this is the extractor's `Match::span`.
+    pub byte_end: usize,
 }
 
 /// Match with file/line context
@@ -443,6 +451,7 @@ impl Worker {
                 ExtractedItem::Bitcoin(_) => self.stats.bitcoin_count += 1,
                 ExtractedItem::Ethereum(_) => self.stats.ethereum_count += 1,
                 ExtractedItem::Monero(_) => self.stats.monero_count += 1,
+                ExtractedItem::Url(_) => self.stats.url_count += 1,
             }
 
             // Sample lookup timing every 100 lookups
@@ -475,7 +484,8 @@ impl Worker {
                     | ExtractedItem::Hash(_, s)
                     | ExtractedItem::Bitcoin(s)
                     | ExtractedItem::Ethereum(s)
-                    | ExtractedItem::Monero(s) => {
+                    | ExtractedItem::Monero(s)
+                    | ExtractedItem::Url(s) => {
                         let result = database.lookup(s).map_err(|e| e.to_string())?;
                         (result, s.to_string())
                     }
@@ -500,6 +510,7 @@ impl Worker {
                         result: query_result,
                         database_id: database_id.clone(),
                         byte_offset: item.span.0,
+                        byte_end: item.span.1,
                     });
                 }
             }
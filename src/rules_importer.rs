@@ -0,0 +1,214 @@
+//! TOML Rule File Importer
+//!
+//! Reads hand-written rule files into an [`MmdbBuilder`], so a database can be
+//! authored without writing a loader. Each rule names a key (IP address, CIDR,
+//! range, literal, glob, or domain suffix) and the data to store with it.
+//!
+//! Requires the `toml` feature (enabled by `cli`).
+//!
+//! # Format
+//!
+//! ```toml
+//! [[rule]]
+//! match = "*.evil.com"
+//! data = { category = "malware", score = 90 }
+//!
+//! [[rule]]
+//! match = "10.0.0.0/8"
+//! source = "netops"
+//! data = { zone = "internal", owners = ["alice", "bob"], contact = { team = "netops" } }
+//!
+//! [[rule]]
+//! match = "file[1].txt"
+//! type = "literal"
+//! ```
+//!
+//! Rule fields:
+//! - `match` (required): the key
+//! - `type`: `auto` (default, same detection as
+//!   [`MmdbBuilder::add_entry`]), `ip`, `literal`, `glob`, or `suffix`
+//! - `data`: a table of fields; values may be strings, numbers, booleans,
+//!   arrays, or nested tables
+//! - `source`: tags every data field with this source, readable with
+//!   [`QueryResult::field_source`](crate::QueryResult::field_source)
+//!
+//! Unknown fields are rejected. Errors name the line of the rule at fault.
+//!
+//! # Example
+//!
+//! ```
+//! use matchy::mmdb_builder::MmdbBuilder;
+//! use matchy::glob::MatchMode;
+//! use matchy::rules_importer::import_rules;
+//!
+//! let rules = r#"
+//! [[rule]]
+//! match = "*.evil.com"
+//! data = { category = "malware" }
+//! "#;
+//!
+//! let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+//! assert_eq!(import_rules(&mut builder, rules)?, 1);
+//! # Ok::<(), matchy::ParaglobError>(())
+//! ```
+
+use crate::data_section::DataValue;
+use crate::error::ParaglobError;
+use crate::mmdb_builder::{MmdbBuilder, PROVENANCE_FIELD};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use toml::Spanned;
+
+/// Top level of a rule file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    rule: Vec<Spanned<Rule>>,
+}
+
+/// A single `[[rule]]` table
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    #[serde(rename = "match")]
+    key: String,
+    #[serde(default, rename = "type")]
+    kind: RuleKind,
+    #[serde(default)]
+    data: HashMap<String, DataValue>,
+    source: Option<String>,
+}
+
+/// How a rule's key is added to the builder
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum RuleKind {
+    #[default]
+    Auto,
+    Ip,
+    Literal,
+    Glob,
+    Suffix,
+}
+
+/// Add every rule in `text` to `builder`
+///
+/// Returns the number of rules added. Stops at the first bad rule; the error
+/// message starts with its line number.
+pub fn import_rules(builder: &mut MmdbBuilder, text: &str) -> Result<usize, ParaglobError> {
+    let file: RuleFile = toml::from_str(text)
+        .map_err(|e| ParaglobError::Format(format!("Invalid rule file: {}", e)))?;
+
+    for rule in &file.rule {
+        let line = text[..rule.span().start].matches('\n').count() + 1;
+        add_rule(builder, rule.get_ref())
+            .map_err(|e| ParaglobError::Format(format!("line {}: {}", line, e)))?;
+    }
+    Ok(file.rule.len())
+}
+
+/// Read a rule file from disk and add its rules to `builder`
+pub fn import_rules_file<P: AsRef<Path>>(
+    builder: &mut MmdbBuilder,
+    path: P,
+) -> Result<usize, ParaglobError> {
+    let text = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+        ParaglobError::Io(format!(
+            "Failed to read rule file {}: {}",
+            path.as_ref().display(),
+            e
+        ))
+    })?;
+    import_rules(builder, &text)
+}
+
+/// Add one rule
+fn add_rule(builder: &mut MmdbBuilder, rule: &Rule) -> Result<(), ParaglobError> {
+    let mut data = rule.data.clone();
+    if let Some(source) = &rule.source {
+        let sources = data
+            .keys()
+            .map(|field| (field.clone(), DataValue::String(source.clone())))
+            .collect();
+        data.insert(
+            PROVENANCE_FIELD.to_string(),
+            DataValue::from_hash_map(sources),
+        );
+    }
+
+    let key = rule.key.as_str();
+    match rule.kind {
+        RuleKind::Auto => builder.add_entry(key, data),
+        RuleKind::Ip => builder.add_ip(key, data),
+        RuleKind::Literal => builder.add_literal(key, data),
+        RuleKind::Glob => builder.add_glob(key, data),
+        RuleKind::Suffix => builder.add_suffix(key, data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glob::MatchMode;
+    use crate::{Database, QueryResult};
+
+    #[test]
+    fn test_import_rules() {
+        let rules = r#"
+# Blocklist maintained by the SOC
+[[rule]]
+match = "*.evil.com"
+data = { category = "malware", score = 90, tags = ["c2", "phishing"] }
+
+[[rule]]
+match = "10.0.0.0/8"
+source = "netops"
+data = { zone = "internal", contact = { team = "netops" } }
+
+[[rule]]
+match = "file[1].txt"
+type = "literal"
+"#;
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        assert_eq!(import_rules(&mut builder, rules).unwrap(), 3);
+        let db = Database::from_bytes(builder.build().unwrap()).unwrap();
+
+        let result = db.lookup("www.evil.com").unwrap().unwrap();
+        let QueryResult::Pattern { data, .. } = &result else {
+            panic!("expected pattern match, got {:?}", result);
+        };
+        let DataValue::Map(map) = data[0].as_ref().unwrap() else {
+            panic!("expected map data");
+        };
+        assert_eq!(map.get("score"), Some(&DataValue::Uint16(90)));
+
+        let result = db.lookup("10.1.2.3").unwrap().unwrap();
+        assert_eq!(result.field_source("zone"), Some("netops"));
+        assert!(matches!(
+            db.lookup("file[1].txt").unwrap(),
+            Some(QueryResult::Pattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_import_rules_reports_line() {
+        let rules = r#"
+[[rule]]
+match = "good.example.com"
+
+[[rule]]
+match = "10.0.0.0/99"
+type = "ip"
+"#;
+        let mut builder = MmdbBuilder::new(MatchMode::CaseSensitive);
+        let err = import_rules(&mut builder, rules).unwrap_err().to_string();
+        assert!(err.contains("line 5"), "{}", err);
+
+        let err = import_rules(&mut builder, "[[rule]]\nmatch = \"a\"\ncolour = 1\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("colour"), "{}", err);
+    }
+}
@@ -0,0 +1,515 @@
+//! Reversed-Label Suffix Trie for Domain Matching
+//!
+//! Stores domains meaning "this domain and all of its subdomains" and finds the
+//! longest registered suffix of a hostname in O(labels). This is the common
+//! shape of domain categorization feeds, and is much smaller and faster than
+//! storing a `*.example.com` glob per domain.
+//!
+//! Labels are stored in reverse order (`com` → `example` → `www`), so a lookup
+//! walks the hostname from its last label towards its first, remembering the
+//! deepest node that carries data. Matching is ASCII case-insensitive and
+//! always on whole labels: `notexample.com` does not match `example.com`.
+//!
+//! # Format
+//!
+//! ```text
+//! [Header]
+//!   magic: [u8; 4]           // "SFXT"
+//!   version: u32              // 1
+//!   entry_count: u32          // Number of registered domains
+//!   node_count: u32           // Number of trie nodes (node 0 is the root)
+//!   edge_count: u32           // Number of edges
+//!   edges_offset: u32         // Offset to edge array
+//!   labels_offset: u32        // Offset to label pool
+//!   labels_size: u32          // Size of label pool
+//!
+//! [Nodes] (starting at offset 32)
+//!   nodes: [(edges_start: u32, edge_count: u32, data_offset: u32); node_count]
+//!   data_offset is 0xFFFFFFFF for nodes without data
+//!
+//! [Edges]
+//!   edges: [(label_offset: u32, label_len: u32, target: u32); edge_count]
+//!   Each node's edges are contiguous and sorted by label bytes
+//!
+//! [Label Pool]
+//!   Deduplicated lowercase labels, concatenated
+//! ```
+
+use crate::error::ParaglobError;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Magic bytes for suffix trie section
+pub const SUFFIX_TRIE_MAGIC: &[u8; 4] = b"SFXT";
+
+/// Current suffix trie format version
+pub const SUFFIX_TRIE_VERSION: u32 = 1;
+
+/// Header size: 4 byte magic + 7 u32 fields
+const HEADER_SIZE: usize = 32;
+
+/// Size of a serialized node or edge (three u32 fields)
+const RECORD_SIZE: usize = 12;
+
+/// Marker for nodes that carry no data
+const NO_DATA: u32 = 0xFFFFFFFF;
+
+/// Validate a domain suffix and return its normalized form
+///
+/// Leading and trailing dots are stripped and labels are lowercased. Empty
+/// domains and domains with empty labels (`a..b`) are rejected.
+pub fn normalize_domain(domain: &str) -> Result<String, ParaglobError> {
+    let normalized = domain.trim_matches('.').to_ascii_lowercase();
+    if normalized.is_empty() || normalized.split('.').any(|label| label.is_empty()) {
+        return Err(ParaglobError::InvalidPattern(format!(
+            "Invalid domain suffix: '{}'",
+            domain
+        )));
+    }
+    Ok(normalized)
+}
+
+/// In-memory trie node used while building
+#[derive(Default)]
+struct BuildNode {
+    children: BTreeMap<String, usize>,
+    data_offset: Option<u32>,
+}
+
+/// Builder for the suffix trie section
+pub struct SuffixTrieBuilder {
+    nodes: Vec<BuildNode>,
+    entry_count: usize,
+}
+
+impl SuffixTrieBuilder {
+    /// Create a new builder
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![BuildNode::default()],
+            entry_count: 0,
+        }
+    }
+
+    /// Register a domain and all of its subdomains
+    ///
+    /// Leading and trailing dots are ignored and labels are lowercased. If the
+    /// same domain is added twice, the first data offset is kept.
+    pub fn add_domain(&mut self, domain: &str, data_offset: u32) -> Result<(), ParaglobError> {
+        let normalized = normalize_domain(domain)?;
+
+        let mut node = 0;
+        for label in normalized.rsplit('.') {
+            node = match self.nodes[node].children.get(label) {
+                Some(&child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(BuildNode::default());
+                    self.nodes[node].children.insert(label.to_string(), child);
+                    child
+                }
+            };
+        }
+
+        if self.nodes[node].data_offset.is_none() {
+            self.nodes[node].data_offset = Some(data_offset);
+            self.entry_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Rewrite every stored data offset with `f`
+    ///
+    /// Used when data is encoded after the domains were added.
+    pub fn remap_data_offsets(&mut self, f: impl Fn(u32) -> u32) {
+        for node in &mut self.nodes {
+            if let Some(offset) = node.data_offset {
+                node.data_offset = Some(f(offset));
+            }
+        }
+    }
+
+    /// Number of distinct registered domains
+    pub fn len(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Check whether no domains have been added
+    pub fn is_empty(&self) -> bool {
+        self.entry_count == 0
+    }
+
+    /// Exact size of the serialized section in bytes
+    pub fn serialized_size(&self) -> usize {
+        let edge_count = self.nodes.len() - 1;
+        let mut labels: Vec<&str> = self
+            .nodes
+            .iter()
+            .flat_map(|n| n.children.keys().map(|s| s.as_str()))
+            .collect();
+        labels.sort_unstable();
+        labels.dedup();
+        let labels_size: usize = labels.iter().map(|l| l.len()).sum();
+        HEADER_SIZE + (self.nodes.len() + edge_count) * RECORD_SIZE + labels_size
+    }
+
+    /// Serialize the trie
+    ///
+    /// Nodes are numbered in breadth-first order so every node's children
+    /// occupy a contiguous, sorted run of the edge array.
+    pub fn build(&self) -> Result<Vec<u8>, ParaglobError> {
+        // Assign breadth-first IDs
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut new_id = vec![0u32; self.nodes.len()];
+        let mut queue = VecDeque::from([0usize]);
+        while let Some(node) = queue.pop_front() {
+            new_id[node] = order.len() as u32;
+            order.push(node);
+            queue.extend(self.nodes[node].children.values().copied());
+        }
+
+        let node_count = order.len();
+        let edge_count = node_count - 1;
+        let edges_offset = HEADER_SIZE + node_count * RECORD_SIZE;
+        let labels_offset = edges_offset + edge_count * RECORD_SIZE;
+
+        let mut nodes = Vec::with_capacity(node_count * RECORD_SIZE);
+        let mut edges = Vec::with_capacity(edge_count * RECORD_SIZE);
+        let mut labels = Vec::new();
+        let mut label_offsets: HashMap<&str, u32> = HashMap::new();
+        let mut edges_start = 0u32;
+
+        for &node in &order {
+            let build_node = &self.nodes[node];
+            let count = build_node.children.len() as u32;
+            nodes.extend_from_slice(&edges_start.to_le_bytes());
+            nodes.extend_from_slice(&count.to_le_bytes());
+            nodes.extend_from_slice(&build_node.data_offset.unwrap_or(NO_DATA).to_le_bytes());
+            edges_start += count;
+
+            for (label, &child) in &build_node.children {
+                let label_offset = *label_offsets.entry(label.as_str()).or_insert_with(|| {
+                    let offset = labels.len() as u32;
+                    labels.extend_from_slice(label.as_bytes());
+                    offset
+                });
+                edges.extend_from_slice(&label_offset.to_le_bytes());
+                edges.extend_from_slice(&(label.len() as u32).to_le_bytes());
+                edges.extend_from_slice(&new_id[child].to_le_bytes());
+            }
+        }
+
+        let total_size = labels_offset + labels.len();
+        if total_size > u32::MAX as usize {
+            return Err(ParaglobError::ResourceLimitExceeded(
+                "Suffix trie exceeds 4GB".to_string(),
+            ));
+        }
+
+        let mut buffer = Vec::with_capacity(total_size);
+        buffer.extend_from_slice(SUFFIX_TRIE_MAGIC);
+        for field in [
+            SUFFIX_TRIE_VERSION,
+            self.entry_count as u32,
+            node_count as u32,
+            edge_count as u32,
+            edges_offset as u32,
+            labels_offset as u32,
+            labels.len() as u32,
+        ] {
+            buffer.extend_from_slice(&field.to_le_bytes());
+        }
+        buffer.extend_from_slice(&nodes);
+        buffer.extend_from_slice(&edges);
+        buffer.extend_from_slice(&labels);
+        Ok(buffer)
+    }
+}
+
+impl Default for SuffixTrieBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Memory-mapped suffix trie for lookups
+pub struct SuffixTrie<'a> {
+    buffer: &'a [u8],
+    entry_count: u32,
+    node_count: u32,
+    edges_offset: usize,
+    labels_offset: usize,
+}
+
+impl<'a> SuffixTrie<'a> {
+    /// Load from memory-mapped buffer
+    ///
+    /// The buffer may extend past the end of the section.
+    pub fn from_buffer(buffer: &'a [u8]) -> Result<Self, ParaglobError> {
+        if buffer.len() < HEADER_SIZE {
+            return Err(ParaglobError::Format(
+                "Buffer too small for suffix trie header".to_string(),
+            ));
+        }
+        if &buffer[0..4] != SUFFIX_TRIE_MAGIC {
+            return Err(ParaglobError::Format(format!(
+                "Invalid suffix trie magic: expected {:?}, got {:?}",
+                SUFFIX_TRIE_MAGIC,
+                &buffer[0..4]
+            )));
+        }
+
+        let field = |i: usize| read_u32(buffer, 4 + i * 4).unwrap_or(0);
+        let version = field(0);
+        if version != SUFFIX_TRIE_VERSION {
+            return Err(ParaglobError::UnsupportedVersion(format!(
+                "Unsupported suffix trie version: {}",
+                version
+            )));
+        }
+        let entry_count = field(1);
+        let node_count = field(2);
+        let edge_count = field(3) as usize;
+        let edges_offset = field(4) as usize;
+        let labels_offset = field(5) as usize;
+        let labels_size = field(6) as usize;
+
+        let nodes_end = HEADER_SIZE + node_count as usize * RECORD_SIZE;
+        let edges_end = edges_offset + edge_count * RECORD_SIZE;
+        if node_count == 0
+            || edges_offset < nodes_end
+            || labels_offset < edges_end
+            || labels_offset + labels_size > buffer.len()
+        {
+            return Err(ParaglobError::OutOfBounds(
+                "Suffix trie sections out of bounds".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            buffer,
+            entry_count,
+            node_count,
+            edges_offset,
+            labels_offset,
+        })
+    }
+
+    /// Number of registered domains
+    pub fn entry_count(&self) -> u32 {
+        self.entry_count
+    }
+
+    /// Find the longest registered suffix of `host`
+    ///
+    /// Returns `(start, data_offset)`, where `host[start..]` (ignoring a
+    /// trailing dot) is the matched domain, or `None` if no registered domain
+    /// is a suffix of `host`.
+    pub fn longest_match(&self, host: &str) -> Option<(usize, u32)> {
+        let host = host.strip_suffix('.').unwrap_or(host);
+        let bytes = host.as_bytes();
+        let mut best = None;
+        let mut node = 0u32;
+        let mut end = bytes.len();
+
+        loop {
+            let start = bytes[..end]
+                .iter()
+                .rposition(|&b| b == b'.')
+                .map_or(0, |dot| dot + 1);
+            node = match self.find_child(node, &bytes[start..end]) {
+                Some(child) => child,
+                // No deeper label registered; keep the longest suffix so far
+                None => break,
+            };
+
+            let Some(data_offset) = read_u32(self.buffer, self.node_pos(node) + 8) else {
+                break;
+            };
+            if data_offset != NO_DATA {
+                best = Some((start, data_offset));
+            }
+
+            if start == 0 {
+                break;
+            }
+            end = start - 1;
+        }
+        best
+    }
+
+    /// List all registered domains as `(domain, data_offset)`, sorted by reversed labels
+    pub fn entries(&self) -> Vec<(String, u32)> {
+        let mut entries = Vec::with_capacity(self.entry_count as usize);
+        let mut labels = Vec::new();
+        self.collect_entries(0, &mut labels, &mut entries);
+        entries
+    }
+
+    /// Depth-first walk appending every node with data to `entries`
+    fn collect_entries<'b>(
+        &'b self,
+        node: u32,
+        labels: &mut Vec<&'b str>,
+        entries: &mut Vec<(String, u32)>,
+    ) {
+        if node >= self.node_count || labels.len() > self.node_count as usize {
+            return; // Corrupt trie
+        }
+        let pos = self.node_pos(node);
+        let (Some(edges_start), Some(edge_count), Some(data_offset)) = (
+            read_u32(self.buffer, pos),
+            read_u32(self.buffer, pos + 4),
+            read_u32(self.buffer, pos + 8),
+        ) else {
+            return;
+        };
+
+        if data_offset != NO_DATA {
+            let domain: Vec<&str> = labels.iter().rev().copied().collect();
+            entries.push((domain.join("."), data_offset));
+        }
+
+        for i in 0..edge_count as usize {
+            let edge = self.edges_offset + (edges_start as usize + i) * RECORD_SIZE;
+            let (Some(label_offset), Some(label_len), Some(target)) = (
+                read_u32(self.buffer, edge),
+                read_u32(self.buffer, edge + 4),
+                read_u32(self.buffer, edge + 8),
+            ) else {
+                return;
+            };
+            let start = self.labels_offset + label_offset as usize;
+            let Some(label) = self
+                .buffer
+                .get(start..start + label_len as usize)
+                .and_then(|b| std::str::from_utf8(b).ok())
+            else {
+                continue;
+            };
+            labels.push(label);
+            self.collect_entries(target, labels, entries);
+            labels.pop();
+        }
+    }
+
+    /// Byte position of a node record
+    fn node_pos(&self, node: u32) -> usize {
+        HEADER_SIZE + node as usize * RECORD_SIZE
+    }
+
+    /// Binary search a node's edges for a label (ASCII case-insensitive)
+    ///
+    /// Returns `None` both for a missing label and a corrupt node, which ends
+    /// the walk; any match found so far is kept.
+    fn find_child(&self, node: u32, label: &[u8]) -> Option<u32> {
+        if node >= self.node_count {
+            return None;
+        }
+        let pos = self.node_pos(node);
+        let edges_start = read_u32(self.buffer, pos)? as usize;
+        let edge_count = read_u32(self.buffer, pos + 4)? as usize;
+
+        let (mut lo, mut hi) = (0, edge_count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let edge = self.edges_offset + (edges_start + mid) * RECORD_SIZE;
+            let label_offset = read_u32(self.buffer, edge)? as usize;
+            let label_len = read_u32(self.buffer, edge + 4)? as usize;
+            let start = self.labels_offset + label_offset;
+            let stored = self.buffer.get(start..start + label_len)?;
+
+            match cmp_label(stored, label) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return read_u32(self.buffer, edge + 8),
+            }
+        }
+        None
+    }
+}
+
+/// Compare a stored (lowercase) label with a query label, lowercasing the query
+fn cmp_label(stored: &[u8], query: &[u8]) -> Ordering {
+    stored
+        .iter()
+        .copied()
+        .cmp(query.iter().map(|b| b.to_ascii_lowercase()))
+}
+
+/// Read a little-endian u32 at `pos`
+fn read_u32(buffer: &[u8], pos: usize) -> Option<u32> {
+    let bytes = buffer.get(pos..pos + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(domains: &[(&str, u32)]) -> Vec<u8> {
+        let mut builder = SuffixTrieBuilder::new();
+        for &(domain, offset) in domains {
+            builder.add_domain(domain, offset).unwrap();
+        }
+        let bytes = builder.build().unwrap();
+        assert_eq!(bytes.len(), builder.serialized_size());
+        bytes
+    }
+
+    #[test]
+    fn test_longest_suffix_wins() {
+        let bytes = build(&[("example.com", 1), ("ads.example.com", 2), ("org", 3)]);
+        let trie = SuffixTrie::from_buffer(&bytes).unwrap();
+        assert_eq!(trie.entry_count(), 3);
+
+        assert_eq!(trie.longest_match("example.com"), Some((0, 1)));
+        assert_eq!(trie.longest_match("www.example.com"), Some((4, 1)));
+        assert_eq!(trie.longest_match("x.ads.example.com"), Some((2, 2)));
+        assert_eq!(trie.longest_match("wikipedia.org"), Some((10, 3)));
+        assert_eq!(trie.longest_match("example.net"), None);
+        assert_eq!(trie.longest_match("com"), None);
+    }
+
+    #[test]
+    fn test_whole_labels_and_case() {
+        let bytes = build(&[("Example.COM.", 7)]);
+        let trie = SuffixTrie::from_buffer(&bytes).unwrap();
+
+        assert_eq!(trie.longest_match("WWW.EXAMPLE.com"), Some((4, 7)));
+        assert_eq!(trie.longest_match("www.example.com."), Some((4, 7)));
+        assert_eq!(trie.longest_match("notexample.com"), None);
+        assert_eq!(trie.longest_match(""), None);
+    }
+
+    #[test]
+    fn test_invalid_domains_rejected() {
+        let mut builder = SuffixTrieBuilder::new();
+        assert!(builder.add_domain("", 0).is_err());
+        assert!(builder.add_domain("...", 0).is_err());
+        assert!(builder.add_domain("a..b", 0).is_err());
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn test_entries() {
+        let bytes = build(&[("b.example.com", 2), ("example.com", 1), ("org", 3)]);
+        let trie = SuffixTrie::from_buffer(&bytes).unwrap();
+        assert_eq!(
+            trie.entries(),
+            vec![
+                ("example.com".to_string(), 1),
+                ("b.example.com".to_string(), 2),
+                ("org".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keeps_first() {
+        let bytes = build(&[("example.com", 1), ("EXAMPLE.com", 2)]);
+        let trie = SuffixTrie::from_buffer(&bytes).unwrap();
+        assert_eq!(trie.entry_count(), 1);
+        assert_eq!(trie.longest_match("a.example.com"), Some((2, 1)));
+    }
+}
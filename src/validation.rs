@@ -36,17 +36,23 @@
 //! ```
 
 use crate::error::{ParaglobError, Result};
+use crate::glob::MatchMode as GlobMatchMode;
+use crate::literal_hash::LiteralHash;
 use crate::offset_format::{
     ACEdge, ACNodeHot, MetaWordMapping, ParaglobHeader, PatternDataMapping, PatternEntry,
     StateKind, MAGIC, VERSION, VERSION_V1, VERSION_V2, VERSION_V3,
 };
+use crate::paraglob_offset::Paraglob;
+use serde_json::json;
 use std::collections::HashSet;
 use std::fs::File;
 use std::mem;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 use zerocopy::FromBytes;
 
 /// Validation strictness level
+#[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ValidationLevel {
     /// Standard checks: all offsets, UTF-8, basic structure
@@ -56,6 +62,17 @@ pub enum ValidationLevel {
     /// Audit mode: Track all unsafe code paths and trust assumptions
     /// Reports where --trusted mode would bypass validation
     Audit,
+    /// Exhaustive checks: everything in Strict, but UTF-8 and data pointer
+    /// checks cover every data record (tree, glob, and literal) instead of a
+    /// sample. Slow on large databases; use before enabling --trusted mode
+    Exhaustive,
+}
+
+impl ValidationLevel {
+    /// Whether this level runs the Strict checks
+    fn includes_strict(self) -> bool {
+        self != ValidationLevel::Standard
+    }
 }
 
 /// Validation report with detailed findings
@@ -96,6 +113,12 @@ pub struct DatabaseStats {
     pub has_ac_literal_mapping: bool,
     /// Number of state encoding types used
     pub state_encoding_distribution: [u32; 4], // Empty, One, Sparse, Dense
+    /// Longest AC failure-link chain, the worst-case hops per input byte (Audit mode only)
+    pub max_failure_chain: u32,
+    /// IP networks split into more-specific entries that repeat the same data (Strict mode)
+    pub redundant_networks: u32,
+    /// More-specific IP networks whose data differs from the network covering them (Strict mode)
+    pub conflicting_overlaps: u32,
     /// Locations where unsafe code is used (Audit mode only)
     pub unsafe_code_locations: Vec<UnsafeCodeLocation>,
     /// Trust assumptions that would bypass validation
@@ -167,6 +190,90 @@ impl ValidationReport {
     fn info(&mut self, msg: impl Into<String>) {
         self.info.push(msg.into());
     }
+
+    /// Machine-readable form of the report
+    ///
+    /// Field names are stable. Every [`DatabaseStats`] field is present; the
+    /// Audit-only lists are empty at other levels.
+    ///
+    /// ```rust,no_run
+    /// use matchy::validation::{validate_database, ValidationLevel};
+    /// use std::path::Path;
+    ///
+    /// let report = validate_database(Path::new("database.mxy"), ValidationLevel::Audit)?;
+    /// let json = report.to_json();
+    /// assert_eq!(json["is_valid"], report.is_valid());
+    /// println!("{}", json["stats"]["state_encoding_distribution"]["dense"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        let stats = &self.stats;
+        let [empty, one, sparse, dense] = stats.state_encoding_distribution;
+        let unsafe_code_locations: Vec<_> = stats
+            .unsafe_code_locations
+            .iter()
+            .map(|loc| {
+                json!({
+                    "location": loc.location,
+                    "operation": loc.operation.name(),
+                    "justification": loc.justification,
+                })
+            })
+            .collect();
+        let trust_assumptions: Vec<_> = stats
+            .trust_assumptions
+            .iter()
+            .map(|trust| {
+                json!({
+                    "context": trust.context,
+                    "bypassed_check": trust.bypassed_check,
+                    "risk": trust.risk,
+                })
+            })
+            .collect();
+
+        json!({
+            "is_valid": self.is_valid(),
+            "errors": self.errors,
+            "warnings": self.warnings,
+            "info": self.info,
+            "stats": {
+                "file_size": stats.file_size,
+                "version": stats.version,
+                "ac_node_count": stats.ac_node_count,
+                "pattern_count": stats.pattern_count,
+                "ip_entry_count": stats.ip_entry_count,
+                "literal_count": stats.literal_count,
+                "glob_count": stats.glob_count,
+                "string_data_size": stats.string_data_size,
+                "has_data_section": stats.has_data_section,
+                "has_ac_literal_mapping": stats.has_ac_literal_mapping,
+                "state_encoding_distribution": {
+                    "empty": empty,
+                    "one": one,
+                    "sparse": sparse,
+                    "dense": dense,
+                },
+                "max_failure_chain": stats.max_failure_chain,
+                "redundant_networks": stats.redundant_networks,
+                "conflicting_overlaps": stats.conflicting_overlaps,
+                "unsafe_code_locations": unsafe_code_locations,
+                "trust_assumptions": trust_assumptions,
+            }
+        })
+    }
+}
+
+impl UnsafeOperation {
+    /// Stable snake_case name, as used in [`ValidationReport::to_json`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnsafeOperation::UncheckedStringRead => "unchecked_string_read",
+            UnsafeOperation::PointerDereference => "pointer_dereference",
+            UnsafeOperation::MmapLifetimeExtension => "mmap_lifetime_extension",
+            UnsafeOperation::Transmute => "transmute",
+        }
+    }
 }
 
 impl DatabaseStats {
@@ -402,18 +509,35 @@ fn validate_mmdb_database(
                 // Always validate data section structure and UTF-8 (critical for safety)
                 validate_mmdb_data_section(buffer, tree_size, report)?;
 
+                // Data records to check: a sample of tree records, or all of them
+                let data_offsets = if level == ValidationLevel::Exhaustive {
+                    let section = |key: &str| match map.get(key) {
+                        Some(crate::DataValue::Uint32(offset)) if *offset > 0 => {
+                            Some(*offset as usize).filter(|&o| o < buffer.len())
+                        }
+                        _ => None,
+                    };
+                    all_data_offsets(
+                        buffer,
+                        tree_size,
+                        node_count,
+                        node_bytes,
+                        section("pattern_section_offset"),
+                        section("literal_section_offset"),
+                    )
+                } else {
+                    let max_samples = if level.includes_strict() { 100 } else { 20 };
+                    sampled_data_offsets(buffer, tree_size, node_count, node_bytes, max_samples)
+                };
+
                 // Validate UTF-8 in data section (critical for safety)
-                validate_data_section_utf8(
-                    buffer, tree_size, node_count, node_bytes, report, level,
-                )?;
+                validate_data_section_utf8(buffer, tree_size, &data_offsets, report)?;
 
                 // Validate data section pointers (critical for safety)
-                validate_data_section_pointers(
-                    buffer, tree_size, node_count, node_bytes, report, level,
-                )?;
+                validate_data_section_pointers(buffer, tree_size, &data_offsets, report)?;
 
-                // Strict/Audit mode: deep validation
-                if level == ValidationLevel::Strict || level == ValidationLevel::Audit {
+                // Strict/Audit/Exhaustive mode: deep validation
+                if level.includes_strict() {
                     // Check for size bombs
                     validate_size_limits(buffer.len(), node_count, tree_size, report)?;
 
@@ -517,7 +641,7 @@ fn validate_literal_hash_section(
             ));
 
             // Basic sanity checks
-            if version != 1 {
+            if version != 1 && version != 2 {
                 report.warning(format!("Unexpected literal hash version: {}", version));
             }
 
@@ -713,118 +837,181 @@ fn validate_data_pointers(
 }
 
 /// Validate UTF-8 in data section strings (CRITICAL for safety)
+///
+/// Checks the records at `data_offsets`, relative to the data section.
 fn validate_data_section_utf8(
     buffer: &[u8],
     tree_size: usize,
-    node_count: u32,
-    node_bytes: usize,
+    data_offsets: &[usize],
     report: &mut ValidationReport,
-    level: ValidationLevel,
 ) -> Result<()> {
     let data_section_start = tree_size + 16; // Tree + separator
 
-    if data_section_start >= buffer.len() {
+    if data_section_start >= buffer.len() || data_offsets.is_empty() {
         return Ok(()); // No data section
     }
 
     let data_section = &buffer[data_section_start..];
 
-    // Strategy: Sample data records by following pointers from tree nodes
-    // This validates the strings that are actually reachable
-
-    let sample_count = if level == ValidationLevel::Strict {
-        node_count.min(100) // Sample up to 100 in strict mode
-    } else {
-        node_count.min(20) // Sample 20 in standard mode
-    };
+    let mut strings_checked = 0;
+    let mut invalid_utf8_found = false;
 
-    if node_count == 0 || sample_count == 0 {
-        return Ok(());
+    for &data_offset in data_offsets {
+        if data_offset < data_section.len() {
+            // Try to decode this data value and check strings
+            match check_data_value_utf8(data_section, data_offset) {
+                Ok(count) => {
+                    strings_checked += count;
+                }
+                Err(e) => {
+                    report.error(format!(
+                        "Invalid UTF-8 found in data section at offset {}: {}",
+                        data_section_start + data_offset,
+                        e
+                    ));
+                    invalid_utf8_found = true;
+                    break;
+                }
+            }
+        }
     }
 
-    let step = if node_count > sample_count {
-        (node_count / sample_count).max(1)
+    if invalid_utf8_found {
+        report
+            .error("Database contains invalid UTF-8 - DO NOT use with --trusted mode!".to_string());
+    } else if strings_checked > 0 {
+        report.info(format!(
+            "UTF-8 validated: {} string(s) checked in {} data record(s) (all valid)",
+            strings_checked,
+            data_offsets.len()
+        ));
     } else {
-        1
-    };
+        report.info("UTF-8 validation: no strings found in checked data records");
+    }
 
-    let mut strings_checked = 0;
-    let mut invalid_utf8_found = false;
+    Ok(())
+}
+
+/// Data offsets from a sample of tree nodes
+///
+/// Takes the left record of up to `max_samples` evenly spaced nodes and keeps
+/// those that point into the data section.
+fn sampled_data_offsets(
+    buffer: &[u8],
+    tree_size: usize,
+    node_count: u32,
+    node_bytes: usize,
+    max_samples: u32,
+) -> Vec<usize> {
+    let sample_count = node_count.min(max_samples);
+    if sample_count == 0 {
+        return Vec::new();
+    }
+    let step = (node_count / sample_count).max(1);
 
-    for i in (0..node_count)
+    (0..node_count)
         .step_by(step as usize)
         .take(sample_count as usize)
-    {
-        let node_offset = (i as usize) * node_bytes;
+        .filter_map(|i| read_node_records(buffer, tree_size, i, node_bytes))
+        .filter_map(|(left, _)| record_data_offset(left, node_count))
+        .collect()
+}
 
-        if node_offset + node_bytes > tree_size {
-            continue;
+/// Every data offset the database can hand to the decoder, in ascending order
+///
+/// Reads both records of every tree node, plus the data offset of every glob
+/// (from the pattern section mappings) and every literal (from the literal
+/// hash mappings).
+fn all_data_offsets(
+    buffer: &[u8],
+    tree_size: usize,
+    node_count: u32,
+    node_bytes: usize,
+    pattern_section: Option<usize>,
+    literal_section: Option<usize>,
+) -> Vec<usize> {
+    let mut offsets = std::collections::BTreeSet::new();
+
+    for i in 0..node_count {
+        if let Some((left, right)) = read_node_records(buffer, tree_size, i, node_bytes) {
+            offsets.extend(record_data_offset(left, node_count));
+            offsets.extend(record_data_offset(right, node_count));
         }
+    }
 
-        // Read record value (simplified - just check left record)
-        let record_val = match node_bytes {
-            6 => {
-                // 24-bit
-                let b0 = buffer[node_offset] as u32;
-                let b1 = buffer[node_offset + 1] as u32;
-                let b2 = buffer[node_offset + 2] as u32;
-                (b0 << 16) | (b1 << 8) | b2
-            }
-            7 => {
-                // 28-bit - complex, skip for now
-                continue;
-            }
-            8 => {
-                // 32-bit
-                u32::from_be_bytes([
-                    buffer[node_offset],
-                    buffer[node_offset + 1],
-                    buffer[node_offset + 2],
-                    buffer[node_offset + 3],
-                ])
-            }
-            _ => continue,
+    // Pattern section: [total_size][paraglob_size][PARAGLOB][count][offsets...]
+    if let Some(offset) = pattern_section {
+        let read = |pos: usize| {
+            buffer
+                .get(pos..pos + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
         };
-
-        // If record points to data (> node_count), decode it
-        if record_val > node_count {
-            let data_offset = (record_val - node_count - 16) as usize;
-
-            if data_offset < data_section.len() {
-                // Try to decode this data value and check strings
-                match check_data_value_utf8(data_section, data_offset) {
-                    Ok(count) => {
-                        strings_checked += count;
-                    }
-                    Err(e) => {
-                        report.error(format!(
-                            "Invalid UTF-8 found in data section at offset {}: {}",
-                            data_section_start + data_offset,
-                            e
-                        ));
-                        invalid_utf8_found = true;
-                        break;
+        if let Some(paraglob_size) = read(offset + 4) {
+            let mappings = offset + 8 + paraglob_size;
+            let count = read(mappings).unwrap_or(0);
+            for i in 0..count {
+                match read(mappings + 4 + i * 4) {
+                    Some(data_offset) => {
+                        offsets.insert(data_offset);
                     }
+                    None => break,
                 }
             }
         }
     }
 
-    if strings_checked > 0 {
-        report.info(format!(
-            "UTF-8 validated: {} string(s) checked in data section (all valid)",
-            strings_checked
-        ));
-    } else if sample_count > 0 {
-        report.info("UTF-8 validation: no data records found to sample");
+    if let Some(offset) = literal_section {
+        if let Ok(hash) = LiteralHash::from_buffer(&buffer[offset..], GlobMatchMode::CaseSensitive)
+        {
+            offsets.extend(
+                hash.data_offsets()
+                    .into_iter()
+                    .map(|(_, data_offset)| data_offset as usize),
+            );
+        }
     }
 
-    if invalid_utf8_found {
-        report
-            .error("Database contains invalid UTF-8 - DO NOT use with --trusted mode!".to_string());
+    offsets.into_iter().collect()
+}
+
+/// Convert a tree record to a data section offset, if it points at data
+fn record_data_offset(record: u32, node_count: u32) -> Option<usize> {
+    if record > node_count {
+        record
+            .checked_sub(node_count + 16)
+            .map(|offset| offset as usize)
+    } else {
+        None
     }
+}
 
-    Ok(())
+/// Read the left and right records of tree node `index`
+fn read_node_records(
+    buffer: &[u8],
+    tree_size: usize,
+    index: u32,
+    node_bytes: usize,
+) -> Option<(u32, u32)> {
+    let node_offset = (index as usize) * node_bytes;
+    if node_offset + node_bytes > tree_size {
+        return None;
+    }
+    let b = buffer.get(node_offset..node_offset + node_bytes)?;
+    let be24 = |b: &[u8]| u32::from_be_bytes([0, b[0], b[1], b[2]]);
+
+    match node_bytes {
+        6 => Some((be24(&b[0..3]), be24(&b[3..6]))),
+        // 28-bit: the middle byte holds the high nibble of each record
+        7 => Some((
+            (((b[3] as u32) >> 4) << 24) | be24(&b[0..3]),
+            (((b[3] as u32) & 0x0F) << 24) | be24(&b[4..7]),
+        )),
+        8 => Some((
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+            u32::from_be_bytes([b[4], b[5], b[6], b[7]]),
+        )),
+        _ => None,
+    }
 }
 
 /// Check UTF-8 validity of all strings in a data value
@@ -836,6 +1023,8 @@ fn check_data_value_utf8(data_section: &[u8], offset: usize) -> std::result::Res
 
     match decoder.decode(offset as u32) {
         Ok(value) => check_value_strings_utf8(&value),
+        // The decoder rejects bad strings itself, so that error is the finding
+        Err(e @ "Invalid UTF-8") => Err(e.to_string()),
         Err(_) => Ok(0), // Can't decode, skip
     }
 }
@@ -996,8 +1185,8 @@ fn validate_paraglob_section(
         return Ok(());
     }
 
-    // PARAGLOB consistency checks in strict/audit modes
-    if level == ValidationLevel::Strict || level == ValidationLevel::Audit {
+    // PARAGLOB consistency checks in strict/audit/exhaustive modes
+    if level.includes_strict() {
         validate_paraglob_consistency(paraglob_data, &header, report, level)?;
     }
 
@@ -1389,7 +1578,7 @@ fn validate_ac_structure(
                         "AC node {} edge array out of bounds: offset={}, count={}",
                         i, edges_offset, edge_count
                     ));
-                } else if level == ValidationLevel::Strict || level == ValidationLevel::Audit {
+                } else if level.includes_strict() {
                     // Validate each edge
                     for j in 0..edge_count {
                         let edge_offset = edges_offset + j * mem::size_of::<ACEdge>();
@@ -1427,8 +1616,8 @@ fn validate_ac_structure(
                     ));
                 }
 
-                // Optionally validate all targets in strict/audit mode
-                if level == ValidationLevel::Strict || level == ValidationLevel::Audit {
+                // Optionally validate all targets in strict/audit/exhaustive mode
+                if level.includes_strict() {
                     for j in 0..256 {
                         let target_offset_pos = lookup_offset + j * 4;
                         if target_offset_pos + 4 <= buffer.len() {
@@ -1541,8 +1730,8 @@ fn validate_patterns(
 
         // Validate pattern type
         match entry.pattern_type {
-            0 => literal_count += 1, // Literal
-            1 => glob_count += 1,    // Glob
+            0 => literal_count += 1,  // Literal
+            1..=3 => glob_count += 1, // Glob (general, suffix, prefix)
             t => report.error(format!("Pattern {} has invalid type: {}", i, t)),
         }
 
@@ -1607,7 +1796,7 @@ fn validate_paraglob_consistency(
 
     // 6. Audit mode: track potential performance issues
     if level == ValidationLevel::Audit {
-        audit_paraglob_performance(header, report)?;
+        audit_paraglob_performance(buffer, header, report)?;
     }
 
     report.info("✓ PARAGLOB consistency checks complete");
@@ -2112,6 +2301,7 @@ fn validate_meta_word_consistency(
 
 /// Audit PARAGLOB performance characteristics
 fn audit_paraglob_performance(
+    buffer: &[u8],
     header: &ParaglobHeader,
     report: &mut ValidationReport,
 ) -> Result<()> {
@@ -2167,9 +2357,27 @@ fn audit_paraglob_performance(
         ));
     }
 
+    // Failure-link depth bounds the automaton work per input byte
+    if let Ok(pg) = Paraglob::from_buffer(buffer.to_vec(), GlobMatchMode::CaseSensitive) {
+        let chain = pg.max_failure_chain();
+        report.stats.max_failure_chain = chain.min(u32::MAX as usize) as u32;
+        report.info(format!("Longest AC failure chain: {} links", chain));
+
+        if chain > MAX_FAILURE_CHAIN_WARNING {
+            report.warning(format!(
+                "Deep AC failure chain ({} links) - patterns with long shared prefixes or \
+                 repeated characters slow worst-case matching",
+                chain
+            ));
+        }
+    }
+
     Ok(())
 }
 
+/// Failure chains longer than this are reported as a performance warning
+const MAX_FAILURE_CHAIN_WARNING: usize = 32;
+
 /// Validate IP tree structure with full traversal
 /// Checks for cycles, invalid pointers, orphaned nodes, and structural integrity
 fn validate_ip_tree_structure(
@@ -2203,10 +2411,13 @@ fn validate_ip_tree_structure(
     };
 
     // Traverse tree starting from root (node 0)
+    let mut overlaps = OverlapScan::new(ip_version);
     let result = traverse_ip_tree_node(
         buffer,
         0, // Start at root
         0, // Depth 0
+        0, // Empty prefix
+        None,
         tree_depth,
         node_count,
         node_bytes,
@@ -2214,6 +2425,7 @@ fn validate_ip_tree_structure(
         &mut visited,
         &mut cycle_detected,
         &mut invalid_pointers,
+        &mut overlaps,
     );
 
     if let Err(e) = result {
@@ -2256,15 +2468,190 @@ fn validate_ip_tree_structure(
         report.error(format!("Tree traversal found {} errors", traversal_errors));
     }
 
+    overlaps.report(report);
+
     Ok(())
 }
 
+/// Examples listed per overlap warning
+const OVERLAP_EXAMPLES: usize = 5;
+
+/// A less-specific network seen while walking down the IP tree
+///
+/// The builder stores a network with more-specific entries inside it by
+/// pointing every sibling along the path to each inner entry at the outer
+/// network's data, so the outer network shows up as a run of sibling leaves
+/// sharing one data record.
+#[derive(Clone, Copy)]
+struct CoverRun {
+    /// Data record of the covering network
+    data: u32,
+    /// Sibling leaves seen so far that carry `data`
+    siblings: u32,
+    /// Prefix bits of the covering network (MSB-aligned)
+    bits: u128,
+    /// Prefix length of the covering network
+    depth: usize,
+}
+
+/// Redundant and conflicting network overlaps found during the IP tree walk
+///
+/// Prefix lengths are not stored in the tree, so this is a heuristic: a
+/// covering network is only recognized once at least two sibling leaves share
+/// its data, since a single sibling is indistinguishable from two adjacent
+/// networks, and any empty record ends it. A range split into CIDRs can still
+/// look like a covering network.
+struct OverlapScan {
+    ip_version: u16,
+    redundant: u32,
+    conflicting: u32,
+    redundant_examples: Vec<String>,
+    conflicting_examples: Vec<String>,
+}
+
+impl OverlapScan {
+    fn new(ip_version: u16) -> Self {
+        Self {
+            ip_version,
+            redundant: 0,
+            conflicting: 0,
+            redundant_examples: Vec::new(),
+            conflicting_examples: Vec::new(),
+        }
+    }
+
+    /// Check one node's records and return the cover each child inherits
+    fn visit(
+        &mut self,
+        bits: u128,
+        depth: usize,
+        left_record: u32,
+        right_record: u32,
+        node_count: u32,
+        cover: Option<CoverRun>,
+    ) -> (Option<CoverRun>, Option<CoverRun>) {
+        let data = |record: u32| (record > node_count).then_some(record);
+        let right_bits = bits | (1u128 << (127 - depth));
+
+        match (data(left_record), data(right_record)) {
+            // Both halves carry the same data: the node could be one network
+            (Some(left), Some(right)) if left == right => {
+                self.redundant += 1;
+                if self.redundant_examples.len() < OVERLAP_EXAMPLES {
+                    self.redundant_examples
+                        .push(self.format_prefix(bits, depth));
+                }
+                (None, None)
+            }
+            (Some(left), Some(right)) => {
+                if let Some(cover) = cover {
+                    // Whichever half differs from the cover overrides it
+                    let (inner_bits, inner_depth, siblings) = if left == cover.data {
+                        (right_bits, depth + 1, cover.siblings + 1)
+                    } else if right == cover.data {
+                        (bits, depth + 1, cover.siblings + 1)
+                    } else {
+                        (bits, depth, cover.siblings)
+                    };
+                    if siblings >= 2 {
+                        self.conflict(cover, inner_bits, inner_depth);
+                    }
+                }
+                (None, None)
+            }
+            // One leaf beside a subtree: the leaf starts or extends a cover
+            (Some(leaf), None) | (None, Some(leaf)) => {
+                let subtree_is_left = data(left_record).is_none();
+                let subtree = if subtree_is_left {
+                    left_record
+                } else {
+                    right_record
+                };
+                if subtree == node_count {
+                    return (None, None);
+                }
+                let inherited = match cover {
+                    Some(cover) if cover.data == leaf => CoverRun {
+                        siblings: cover.siblings + 1,
+                        ..cover
+                    },
+                    _ => {
+                        if let Some(cover) = cover.filter(|c| c.siblings >= 2) {
+                            self.conflict(cover, bits, depth);
+                        }
+                        CoverRun {
+                            data: leaf,
+                            siblings: 1,
+                            bits,
+                            depth,
+                        }
+                    }
+                };
+                if subtree_is_left {
+                    (Some(inherited), None)
+                } else {
+                    (None, Some(inherited))
+                }
+            }
+            // A covering network leaves no empty records beneath it
+            (None, None) if left_record == node_count || right_record == node_count => (None, None),
+            // Two subtrees share the cover
+            (None, None) => (cover, cover),
+        }
+    }
+
+    fn conflict(&mut self, cover: CoverRun, bits: u128, depth: usize) {
+        self.conflicting += 1;
+        if self.conflicting_examples.len() < OVERLAP_EXAMPLES {
+            self.conflicting_examples.push(format!(
+                "{} inside {}",
+                self.format_prefix(bits, depth),
+                self.format_prefix(cover.bits, cover.depth)
+            ));
+        }
+    }
+
+    /// Render MSB-aligned prefix bits as a CIDR
+    fn format_prefix(&self, bits: u128, depth: usize) -> String {
+        if self.ip_version == 4 {
+            format!("{}/{}", Ipv4Addr::from((bits >> 96) as u32), depth)
+        } else if depth >= 96 && bits >> 32 == 0 {
+            // IPv4 networks live under ::/96 in IPv6 trees
+            format!("{}/{}", Ipv4Addr::from(bits as u32), depth - 96)
+        } else {
+            format!("{}/{}", Ipv6Addr::from(bits), depth)
+        }
+    }
+
+    fn report(&self, report: &mut ValidationReport) {
+        report.stats.redundant_networks = self.redundant;
+        report.stats.conflicting_overlaps = self.conflicting;
+
+        if self.redundant > 0 {
+            report.warning(format!(
+                "{} IP network(s) split into more-specific entries with identical data (redundant), e.g. {}",
+                self.redundant,
+                self.redundant_examples.join(", ")
+            ));
+        }
+        if self.conflicting > 0 {
+            report.warning(format!(
+                "{} more-specific IP network(s) override a covering network with different data, e.g. {}",
+                self.conflicting,
+                self.conflicting_examples.join(", ")
+            ));
+        }
+    }
+}
+
 /// Recursively traverse IP tree node and validate structure
 #[allow(clippy::too_many_arguments)]
 fn traverse_ip_tree_node(
     buffer: &[u8],
     node_index: u32,
     depth: usize,
+    bits: u128,
+    cover: Option<CoverRun>,
     max_depth: usize,
     node_count: u32,
     node_bytes: usize,
@@ -2272,6 +2659,7 @@ fn traverse_ip_tree_node(
     visited: &mut HashSet<u32>,
     cycle_detected: &mut bool,
     invalid_pointers: &mut usize,
+    overlaps: &mut OverlapScan,
 ) -> std::result::Result<(), String> {
     // Check for cycles
     if visited.contains(&node_index) {
@@ -2312,51 +2700,8 @@ fn traverse_ip_tree_node(
     }
 
     // Read both records (left and right)
-    let (left_record, right_record) = match node_bytes {
-        6 => {
-            // 24-bit records
-            let left = (buffer[node_offset] as u32) << 16
-                | (buffer[node_offset + 1] as u32) << 8
-                | (buffer[node_offset + 2] as u32);
-            let right = (buffer[node_offset + 3] as u32) << 16
-                | (buffer[node_offset + 4] as u32) << 8
-                | (buffer[node_offset + 5] as u32);
-            (left, right)
-        }
-        7 => {
-            // 28-bit records (more complex bit packing)
-            // First 3.5 bytes: left record
-            // Last 3.5 bytes: right record
-            let left = (buffer[node_offset] as u32) << 20
-                | (buffer[node_offset + 1] as u32) << 12
-                | (buffer[node_offset + 2] as u32) << 4
-                | ((buffer[node_offset + 3] as u32) >> 4);
-            let right = ((buffer[node_offset + 3] as u32) & 0x0F) << 24
-                | (buffer[node_offset + 4] as u32) << 16
-                | (buffer[node_offset + 5] as u32) << 8
-                | (buffer[node_offset + 6] as u32);
-            (left, right)
-        }
-        8 => {
-            // 32-bit records
-            let left = u32::from_be_bytes([
-                buffer[node_offset],
-                buffer[node_offset + 1],
-                buffer[node_offset + 2],
-                buffer[node_offset + 3],
-            ]);
-            let right = u32::from_be_bytes([
-                buffer[node_offset + 4],
-                buffer[node_offset + 5],
-                buffer[node_offset + 6],
-                buffer[node_offset + 7],
-            ]);
-            (left, right)
-        }
-        _ => {
-            return Err(format!("Invalid node_bytes: {}", node_bytes));
-        }
-    };
+    let (left_record, right_record) = read_node_records(buffer, tree_size, node_index, node_bytes)
+        .ok_or_else(|| format!("Invalid node_bytes: {}", node_bytes))?;
 
     // Validate and recurse into child nodes
     // Records can be:
@@ -2366,6 +2711,10 @@ fn traverse_ip_tree_node(
 
     // Only recurse if we haven't reached maximum depth
     if depth < max_depth {
+        // Track networks that cover more-specific entries
+        let (left_cover, right_cover) =
+            overlaps.visit(bits, depth, left_record, right_record, node_count, cover);
+
         // Validate left record
         if left_record < node_count {
             // It's a node pointer - recurse
@@ -2373,6 +2722,8 @@ fn traverse_ip_tree_node(
                 buffer,
                 left_record,
                 depth + 1,
+                bits,
+                left_cover,
                 max_depth,
                 node_count,
                 node_bytes,
@@ -2380,6 +2731,7 @@ fn traverse_ip_tree_node(
                 visited,
                 cycle_detected,
                 invalid_pointers,
+                overlaps,
             )?;
         } else if left_record > node_count {
             // It's a data pointer - validate it points to reasonable location
@@ -2394,6 +2746,8 @@ fn traverse_ip_tree_node(
                 buffer,
                 right_record,
                 depth + 1,
+                bits | (1u128 << (127 - depth)),
+                right_cover,
                 max_depth,
                 node_count,
                 node_bytes,
@@ -2401,6 +2755,7 @@ fn traverse_ip_tree_node(
                 visited,
                 cycle_detected,
                 invalid_pointers,
+                overlaps,
             )?;
         } else if right_record > node_count {
             // It's a data pointer - validate it points to reasonable location
@@ -2412,119 +2767,58 @@ fn traverse_ip_tree_node(
 }
 
 /// Validate data section pointers for safety issues
-/// Checks for cycles, depth limits, bounds, and type validity
+/// Checks for cycles, depth limits, bounds, and type validity in the records
+/// at `data_offsets`, relative to the data section
 fn validate_data_section_pointers(
     buffer: &[u8],
     tree_size: usize,
-    node_count: u32,
-    node_bytes: usize,
+    data_offsets: &[usize],
     report: &mut ValidationReport,
-    level: ValidationLevel,
 ) -> Result<()> {
     let data_section_start = tree_size + 16; // Tree + separator
 
-    if data_section_start >= buffer.len() {
+    if data_section_start >= buffer.len() || data_offsets.is_empty() {
         return Ok(()); // No data section
     }
 
     let data_section = &buffer[data_section_start..];
 
-    // Sample data values and validate their pointer chains
-    let sample_count = if level == ValidationLevel::Strict || level == ValidationLevel::Audit {
-        node_count.min(100) // More thorough sampling
-    } else {
-        node_count.min(20) // Basic sampling
-    };
-
-    if node_count == 0 || sample_count == 0 {
-        return Ok(());
-    }
-
-    let step = if node_count > sample_count {
-        (node_count / sample_count).max(1)
-    } else {
-        1
-    };
-
     let mut pointers_checked = 0;
     let mut cycles_detected = 0;
     let mut max_depth_found = 0;
     let mut invalid_pointers = 0;
 
-    // Check data values reachable from tree nodes
-    for i in (0..node_count)
-        .step_by(step as usize)
-        .take(sample_count as usize)
-    {
-        let node_offset = (i as usize) * node_bytes;
-
-        if node_offset + node_bytes > tree_size {
-            continue;
-        }
-
-        // Read record value (simplified - just check left record)
-        let record_val = match node_bytes {
-            6 => {
-                // 24-bit
-                let b0 = buffer[node_offset] as u32;
-                let b1 = buffer[node_offset + 1] as u32;
-                let b2 = buffer[node_offset + 2] as u32;
-                (b0 << 16) | (b1 << 8) | b2
-            }
-            7 => continue, // 28-bit - complex, skip
-            8 => {
-                // 32-bit
-                u32::from_be_bytes([
-                    buffer[node_offset],
-                    buffer[node_offset + 1],
-                    buffer[node_offset + 2],
-                    buffer[node_offset + 3],
-                ])
-            }
-            _ => continue,
-        };
-
-        // If record points to data (> node_count), validate it
-        if record_val > node_count {
-            let data_offset = (record_val - node_count - 16) as usize;
-
-            if data_offset < data_section.len() {
-                // Validate this data value and all its pointer chains
-                let mut visited = HashSet::new();
-                match validate_data_value_pointers(
-                    data_section,
-                    data_offset,
-                    &mut visited,
-                    0,
-                    report,
-                ) {
-                    Ok(depth) => {
-                        pointers_checked += visited.len();
-                        max_depth_found = max_depth_found.max(depth);
-                    }
-                    Err(ValidationError::Cycle { offset }) => {
-                        cycles_detected += 1;
-                        report.error(format!(
-                            "Pointer cycle detected in data section at offset {}",
-                            offset
-                        ));
-                    }
-                    Err(ValidationError::DepthExceeded { depth }) => {
-                        report.error(format!(
-                            "Pointer chain depth {} exceeds safe limit (max: {})",
-                            depth, MAX_POINTER_DEPTH
-                        ));
-                    }
-                    Err(ValidationError::InvalidOffset { offset, reason }) => {
-                        invalid_pointers += 1;
-                        report.error(format!("Invalid pointer at offset {}: {}", offset, reason));
-                    }
-                    Err(ValidationError::InvalidType { offset, type_id }) => {
-                        report.error(format!(
-                            "Invalid data type {} at offset {}",
-                            type_id, offset
-                        ));
-                    }
+    for &data_offset in data_offsets {
+        if data_offset < data_section.len() {
+            // Validate this data value and all its pointer chains
+            let mut visited = HashSet::new();
+            match validate_data_value_pointers(data_section, data_offset, &mut visited, 0, report) {
+                Ok(depth) => {
+                    pointers_checked += visited.len();
+                    max_depth_found = max_depth_found.max(depth);
+                }
+                Err(ValidationError::Cycle { offset }) => {
+                    cycles_detected += 1;
+                    report.error(format!(
+                        "Pointer cycle detected in data section at offset {}",
+                        offset
+                    ));
+                }
+                Err(ValidationError::DepthExceeded { depth }) => {
+                    report.error(format!(
+                        "Pointer chain depth {} exceeds safe limit (max: {})",
+                        depth, MAX_POINTER_DEPTH
+                    ));
+                }
+                Err(ValidationError::InvalidOffset { offset, reason }) => {
+                    invalid_pointers += 1;
+                    report.error(format!("Invalid pointer at offset {}: {}", offset, reason));
+                }
+                Err(ValidationError::InvalidType { offset, type_id }) => {
+                    report.error(format!(
+                        "Invalid data type {} at offset {}",
+                        type_id, offset
+                    ));
                 }
             }
         }
@@ -3068,10 +3362,14 @@ mod tests {
         let standard = ValidationLevel::Standard;
         let strict = ValidationLevel::Strict;
         let audit = ValidationLevel::Audit;
+        let exhaustive = ValidationLevel::Exhaustive;
 
         assert_ne!(standard, strict);
         assert_ne!(strict, audit);
         assert_ne!(standard, audit);
+        assert_ne!(strict, exhaustive);
+        assert!(!standard.includes_strict());
+        assert!(exhaustive.includes_strict());
     }
 
     #[test]
@@ -3158,6 +3456,45 @@ mod tests {
         assert!(!result_strict.unwrap().is_valid());
     }
 
+    #[test]
+    fn test_exhaustive_mode_checks_every_record() {
+        use crate::mmdb_builder::MmdbBuilder;
+        use crate::DataValue;
+        use std::collections::HashMap;
+
+        let mut builder = MmdbBuilder::new(GlobMatchMode::CaseSensitive);
+        for i in 0..200u32 {
+            let mut data = HashMap::new();
+            data.insert("id".to_string(), DataValue::Uint32(i));
+            builder
+                .add_entry(&format!("10.0.{}.{}/32", i / 256, i % 256), data)
+                .unwrap();
+        }
+        // Only reachable through the literal table, never through the tree
+        let mut data = HashMap::new();
+        data.insert(
+            "note".to_string(),
+            DataValue::String("zzmarkerzz".to_string()),
+        );
+        builder.add_entry("evil.example.com", data).unwrap();
+        let mut bytes = builder.build().unwrap();
+
+        let temp = NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), &bytes).unwrap();
+        let report = validate_database(temp.path(), ValidationLevel::Exhaustive).unwrap();
+        assert!(report.is_valid(), "{:?}", report.errors);
+
+        // Corrupt the literal's string; sampling never visits it
+        let pos = bytes.windows(10).position(|w| w == b"zzmarkerzz").unwrap();
+        bytes[pos + 2] = 0xFF;
+        std::fs::write(temp.path(), &bytes).unwrap();
+
+        let strict = validate_database(temp.path(), ValidationLevel::Strict).unwrap();
+        assert!(!strict.errors.iter().any(|e| e.contains("UTF-8")));
+        let exhaustive = validate_database(temp.path(), ValidationLevel::Exhaustive).unwrap();
+        assert!(exhaustive.errors.iter().any(|e| e.contains("UTF-8")));
+    }
+
     #[test]
     fn test_validation_error_accumulation() {
         let mut report = ValidationReport::new();
@@ -3186,6 +3523,95 @@ mod tests {
         assert_ne!(op3, op4);
     }
 
+    #[test]
+    fn test_ip_overlap_detection() {
+        use crate::mmdb_builder::MmdbBuilder;
+        use crate::DataValue;
+        use std::collections::HashMap;
+
+        let threat = |level: &str| {
+            let mut data = HashMap::new();
+            data.insert(
+                "threat_level".to_string(),
+                DataValue::String(level.to_string()),
+            );
+            data
+        };
+        let mut builder = MmdbBuilder::new(GlobMatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", threat("low")).unwrap();
+        builder.add_entry("10.1.2.0/24", threat("high")).unwrap();
+        builder
+            .add_entry("192.168.0.0/16", threat("medium"))
+            .unwrap();
+        builder
+            .add_entry("192.168.5.0/24", threat("medium"))
+            .unwrap();
+        builder.add_entry("172.16.0.0/24", threat("low")).unwrap();
+        builder.add_entry("172.16.1.0/24", threat("high")).unwrap();
+        let temp = NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), builder.build().unwrap()).unwrap();
+
+        let report = validate_database(temp.path(), ValidationLevel::Strict).unwrap();
+        assert!(report.is_valid(), "{:?}", report.errors);
+        assert_eq!(report.stats.conflicting_overlaps, 1);
+        assert_eq!(report.stats.redundant_networks, 1);
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| w.contains("10.1.2.0/24 inside 10.0.0.0/8")));
+
+        // Standard mode skips the tree walk
+        let report = validate_database(temp.path(), ValidationLevel::Standard).unwrap();
+        assert_eq!(report.stats.conflicting_overlaps, 0);
+    }
+
+    #[test]
+    fn test_report_to_json() {
+        use crate::mmdb_builder::MmdbBuilder;
+        use std::collections::HashMap;
+
+        let mut builder = MmdbBuilder::new(GlobMatchMode::CaseSensitive);
+        builder.add_entry("10.0.0.0/8", HashMap::new()).unwrap();
+        builder.add_entry("*.evil.com", HashMap::new()).unwrap();
+        let temp = NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), builder.build().unwrap()).unwrap();
+
+        let report = validate_database(temp.path(), ValidationLevel::Audit).unwrap();
+        let json = report.to_json();
+        assert_eq!(json["is_valid"], report.is_valid());
+        assert_eq!(
+            json["errors"].as_array().unwrap().len(),
+            report.errors.len()
+        );
+        assert_eq!(json["info"].as_array().unwrap().len(), report.info.len());
+
+        let stats = &json["stats"];
+        assert_eq!(stats["glob_count"], report.stats.glob_count);
+        let distribution = &stats["state_encoding_distribution"];
+        let total: u64 = ["empty", "one", "sparse", "dense"]
+            .iter()
+            .map(|kind| distribution[kind].as_u64().unwrap())
+            .sum();
+        assert_eq!(
+            total,
+            report.stats.state_encoding_distribution.iter().sum::<u32>() as u64
+        );
+
+        let locations = stats["unsafe_code_locations"].as_array().unwrap();
+        assert_eq!(locations.len(), report.stats.unsafe_code_locations.len());
+        assert!(!locations.is_empty());
+        assert!(locations[0]["operation"].is_string());
+        let trust = stats["trust_assumptions"].as_array().unwrap();
+        assert_eq!(trust.len(), report.stats.trust_assumptions.len());
+        assert!(trust.iter().all(|t| t["bypassed_check"].is_string()));
+
+        // Non-audit levels keep the same shape
+        let report = validate_database(temp.path(), ValidationLevel::Standard).unwrap();
+        let json = report.to_json();
+        assert_eq!(json["stats"]["unsafe_code_locations"], json!([]));
+        assert_eq!(json["stats"]["trust_assumptions"], json!([]));
+    }
+
     #[test]
     fn test_database_stats_summary() {
         let stats = DatabaseStats {
@@ -367,7 +367,7 @@ fn test_validate_levels() {
         .success();
 
     // Test different validation levels - they should at least run
-    for level in &["standard", "strict", "audit"] {
+    for level in &["standard", "strict", "audit", "exhaustive"] {
         matchy_cmd()
             .arg("validate")
             .arg(&output_file)
@@ -579,6 +579,139 @@ fn test_combined_database() {
         .success();
 }
 
+#[test]
+fn test_build_mixed_inputs() {
+    let temp_dir = TempDir::new().unwrap();
+    let csv_file = temp_dir.path().join("feed.csv");
+    let jsonl_file = temp_dir.path().join("feed.jsonl");
+    let list_file = temp_dir.path().join("feed.list");
+    let output_file = temp_dir.path().join("test.mxy");
+
+    fs::write(
+        &csv_file,
+        "entry,source\nevil.com,csv-feed\n10.0.0.0/8,csv-feed\n",
+    )
+    .unwrap();
+    fs::write(
+        &jsonl_file,
+        "{\"key\": \"evil.com\", \"source\": \"jsonl-feed\"}\n{\"key\": \"*.bad.net\"}\n",
+    )
+    .unwrap();
+    // Comma-separated, but forced to CSV with a format prefix
+    fs::write(&list_file, "key,source\nother.org,list-feed\n").unwrap();
+
+    matchy_cmd()
+        .arg("build")
+        .arg(&csv_file)
+        .arg("--input")
+        .arg(&jsonl_file)
+        .arg(format!("csv:{}", list_file.display()))
+        .arg("-o")
+        .arg(&output_file)
+        .arg("--on-duplicate")
+        .arg("keep-first")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total entries:   4"))
+        .stdout(predicate::str::contains("Duplicate keys:  1"));
+
+    matchy_cmd()
+        .arg("query")
+        .arg(&output_file)
+        .arg("evil.com")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("csv-feed"));
+
+    matchy_cmd()
+        .arg("query")
+        .arg(&output_file)
+        .arg("other.org")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("list-feed"));
+
+    // The same inputs are rejected when duplicates are an error
+    matchy_cmd()
+        .arg("build")
+        .arg(&csv_file)
+        .arg(&jsonl_file)
+        .arg("-o")
+        .arg(temp_dir.path().join("strict.mxy"))
+        .arg("--on-duplicate")
+        .arg("error")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Duplicate entry: evil.com"));
+}
+
+#[test]
+fn test_diff_build_and_apply_patch() {
+    let temp_dir = TempDir::new().unwrap();
+    let old_input = temp_dir.path().join("old.txt");
+    let new_input = temp_dir.path().join("new.txt");
+    let old_db = temp_dir.path().join("old.mxy");
+    let new_db = temp_dir.path().join("new.mxy");
+    let patch = temp_dir.path().join("patch.mxd");
+    let result_db = temp_dir.path().join("result.mxy");
+
+    fs::write(&old_input, "evil.com\n10.0.0.0/8\n*.bad.net\n").unwrap();
+    fs::write(
+        &new_input,
+        "evil.com\n192.168.0.0/16\n*.bad.net\n*.worse.org\n",
+    )
+    .unwrap();
+    for (input, output) in [(&old_input, &old_db), (&new_input, &new_db)] {
+        matchy_cmd()
+            .arg("build")
+            .arg(input)
+            .arg("-o")
+            .arg(output)
+            .assert()
+            .success();
+    }
+
+    matchy_cmd()
+        .arg("diff-build")
+        .arg(&old_db)
+        .arg(&new_db)
+        .arg("-o")
+        .arg(&patch)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 added, 0 changed, 1 removed"));
+
+    matchy_cmd()
+        .arg("apply-patch")
+        .arg(&old_db)
+        .arg(&patch)
+        .arg("-o")
+        .arg(&result_db)
+        .assert()
+        .success();
+
+    let canonical = |path: &std::path::Path| {
+        matchy_cmd()
+            .arg("canonicalize")
+            .arg(path)
+            .output()
+            .unwrap()
+            .stdout
+    };
+    assert_eq!(canonical(&result_db), canonical(&new_db));
+
+    // A patch only applies to the database it was made against
+    matchy_cmd()
+        .arg("apply-patch")
+        .arg(&new_db)
+        .arg(&patch)
+        .arg("-o")
+        .arg(temp_dir.path().join("wrong.mxy"))
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_bench_ip() {
     matchy_cmd()
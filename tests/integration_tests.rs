@@ -286,7 +286,10 @@ fn test_v2_simple_pattern_with_data() {
         DataValue::String("malware".to_string()),
     );
 
-    let data_values = vec![Some(DataValue::Map(threat1)), Some(DataValue::Map(threat2))];
+    let data_values = vec![
+        Some(DataValue::from_hash_map(threat1)),
+        Some(DataValue::from_hash_map(threat2)),
+    ];
 
     let pg = Paraglob::build_from_patterns_with_data(
         &patterns,
@@ -391,8 +394,8 @@ fn test_v2_roundtrip_serialization() {
     threat_data.insert("active".to_string(), DataValue::Bool(true));
 
     let data_values = vec![
-        Some(DataValue::Map(threat_data.clone())),
-        Some(DataValue::Map(threat_data)),
+        Some(DataValue::from_hash_map(threat_data.clone())),
+        Some(DataValue::from_hash_map(threat_data)),
         Some(DataValue::String("test_data".to_string())),
     ];
 
@@ -479,7 +482,10 @@ fn test_v2_complex_nested_data() {
     threat_data.insert("level".to_string(), DataValue::String("high".to_string()));
     threat_data.insert("confidence".to_string(), DataValue::Float(0.95));
     threat_data.insert("first_seen".to_string(), DataValue::Uint64(1704067200));
-    threat_data.insert("indicators".to_string(), DataValue::Map(indicators));
+    threat_data.insert(
+        "indicators".to_string(),
+        DataValue::from_hash_map(indicators),
+    );
     threat_data.insert(
         "tags".to_string(),
         DataValue::Array(vec![
@@ -489,7 +495,7 @@ fn test_v2_complex_nested_data() {
     );
     threat_data.insert("active".to_string(), DataValue::Bool(true));
 
-    let data_values = vec![Some(DataValue::Map(threat_data))];
+    let data_values = vec![Some(DataValue::from_hash_map(threat_data))];
 
     let pg = Paraglob::build_from_patterns_with_data(
         &patterns,
@@ -544,9 +550,9 @@ fn test_v2_matching_with_data_retrieval() {
     data3.insert("id".to_string(), DataValue::String("TEST-001".to_string()));
 
     let data_values = vec![
-        Some(DataValue::Map(data1)),
-        Some(DataValue::Map(data2)),
-        Some(DataValue::Map(data3)),
+        Some(DataValue::from_hash_map(data1)),
+        Some(DataValue::from_hash_map(data2)),
+        Some(DataValue::from_hash_map(data3)),
     ];
 
     let pg = Paraglob::build_from_patterns_with_data(
@@ -610,7 +616,7 @@ fn test_v2_all_mmdb_data_types() {
         ]),
     );
 
-    let data_values = vec![Some(DataValue::Map(data))];
+    let data_values = vec![Some(DataValue::from_hash_map(data))];
 
     let pg = Paraglob::build_from_patterns_with_data(
         &patterns,
@@ -660,7 +666,7 @@ fn test_v2_incremental_builder() {
     threat_data.insert("score".to_string(), DataValue::Uint32(95));
 
     let id3 = builder
-        .add_pattern_with_data("*.evil.com", Some(DataValue::Map(threat_data)))
+        .add_pattern_with_data("*.evil.com", Some(DataValue::from_hash_map(threat_data)))
         .unwrap();
 
     // Check builder state
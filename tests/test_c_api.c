@@ -71,6 +71,20 @@ int main() {
     // Free the result
     matchy_free_result(&result);
     
+    // Test batch queries
+    const char* keys[] = {"test_file.txt", "readme.md", NULL, "server.log"};
+    matchy_result_t results[4];
+    size_t found = matchy_query_batch(db, keys, 4, results);
+    if (found != 2 || !results[0].found || results[1].found || results[2].found
+        || !results[3].found) {
+        fprintf(stderr, "Batch query: expected 2 matches, got %zu\n", found);
+        return 1;
+    }
+    for (size_t i = 0; i < 4; i++) {
+        matchy_free_result(&results[i]);
+    }
+    printf("✓ Batch query found %zu of 4 keys\n", found);
+    
     matchy_close(db);
     
     // Test new open_with_options API
@@ -7,6 +7,8 @@
 #include <stdlib.h>
 #include <string.h>
 #include <assert.h>
+#include <arpa/inet.h>
+#include <netinet/in.h>
 
 #define TEST_DB_PATH "/tmp/matchy_extensions_test.db"
 #define PASSED_COLOR "\033[32m"
@@ -475,6 +477,181 @@ void test_null_parameters(matchy_t *db) {
     END_TEST();
 }
 
+void test_get_metadata(matchy_t *db) {
+    TEST("matchy_get_metadata");
+    
+    matchy_metadata_t meta;
+    int status = matchy_get_metadata(db, &meta);
+    ASSERT(status == MATCHY_SUCCESS, "Should read metadata");
+    
+    if (status == MATCHY_SUCCESS) {
+        ASSERT(meta.node_count > 0, "Should have tree nodes");
+        ASSERT(meta.ip_version == 4 || meta.ip_version == 6, "IP version should be 4 or 6");
+        ASSERT(meta.record_size == 24 || meta.record_size == 28 || meta.record_size == 32,
+               "Record size should be 24, 28, or 32");
+        ASSERT(meta.build_epoch > 0, "Should have a build time");
+        ASSERT(meta.database_type != NULL, "Database type should not be NULL");
+        ASSERT(meta.languages != NULL && meta.languages[meta.language_count] == NULL,
+               "Languages should be NULL-terminated");
+    }
+    
+    ASSERT(matchy_get_metadata(db, NULL) == MATCHY_ERROR_INVALID_PARAM,
+           "Should return error for NULL output");
+    
+    END_TEST();
+}
+
+void test_lookup_sockaddr(matchy_t *db) {
+    TEST("matchy_lookup_sockaddr");
+    
+    struct sockaddr_in v4 = {0};
+    v4.sin_family = AF_INET;
+    v4.sin_addr.s_addr = inet_addr("8.8.8.8");
+    
+    int error = -1;
+    matchy_result_t result = matchy_lookup_sockaddr(db, (struct sockaddr *)&v4, &error);
+    ASSERT(error == MATCHY_SUCCESS, "Should succeed for AF_INET");
+    ASSERT(result.found, "Should find 8.8.8.8");
+    matchy_free_result(&result);
+    
+    v4.sin_addr.s_addr = inet_addr("11.11.11.11");
+    result = matchy_lookup_sockaddr(db, (struct sockaddr *)&v4, &error);
+    ASSERT(error == MATCHY_SUCCESS && !result.found, "Should not find 11.11.11.11");
+    matchy_free_result(&result);
+    
+    struct sockaddr_in6 v6 = {0};
+    v6.sin6_family = AF_INET6;
+    inet_pton(AF_INET6, "::ffff:8.8.8.8", &v6.sin6_addr);
+    result = matchy_lookup_sockaddr(db, (struct sockaddr *)&v6, &error);
+    ASSERT(error == MATCHY_SUCCESS, "Should succeed for AF_INET6");
+    matchy_free_result(&result);
+    
+    struct sockaddr other = {0};
+    other.sa_family = AF_UNIX;
+    result = matchy_lookup_sockaddr(db, &other, &error);
+    ASSERT(error == MATCHY_ERROR_INVALID_PARAM && !result.found,
+           "Should reject other address families");
+    
+    END_TEST();
+}
+
+struct iterate_state {
+    matchy_t *db;
+    int count;
+    int stop_after;
+    int first_is_1111;
+    int requery_ok;
+    int found_us;
+};
+
+int collect_network(const char *network, uint8_t prefix_len,
+                    const matchy_entry_s *entry, void *user_data) {
+    struct iterate_state *state = user_data;
+    if (state->count == 0) {
+        state->first_is_1111 = strcmp(network, "1.1.1.1") == 0 && prefix_len == 32;
+    }
+    state->count++;
+    
+    // Re-entering the database from the callback is allowed
+    matchy_result_t result = matchy_query(state->db, network);
+    if (!result.found) {
+        state->requery_ok = 0;
+    }
+    matchy_free_result(&result);
+    
+    if (strcmp(network, "8.8.8.8") == 0) {
+        matchy_entry_data_t data;
+        const char *path[] = {"country", "iso_code", NULL};
+        if (matchy_aget_value(entry, &data, path) == MATCHY_SUCCESS
+            && strcmp(data.value.utf8_string, "US") == 0) {
+            state->found_us = 1;
+        }
+    }
+    return state->count == state->stop_after;
+}
+
+void test_iterate(matchy_t *db) {
+    TEST("matchy_iterate");
+    
+    struct iterate_state state = {db, 0, 0, 0, 1, 0};
+    ASSERT(matchy_iterate(db, collect_network, &state) == MATCHY_SUCCESS,
+           "Should iterate the database");
+    ASSERT(state.count == 5, "Should visit all 5 networks");
+    ASSERT(state.first_is_1111, "Should start at 1.1.1.1/32");
+    ASSERT(state.requery_ok, "Should allow queries from the callback");
+    ASSERT(state.found_us, "Entry should expose 8.8.8.8's data");
+    
+    struct iterate_state early = {db, 0, 2, 0, 1, 0};
+    ASSERT(matchy_iterate(db, collect_network, &early) == MATCHY_SUCCESS
+           && early.count == 2, "Nonzero return should stop the iteration");
+    
+    ASSERT(matchy_iterate(db, NULL, NULL) == MATCHY_ERROR_INVALID_PARAM,
+           "Should reject NULL callback");
+    ASSERT(matchy_iterate(NULL, collect_network, &state) == MATCHY_ERROR_INVALID_PARAM,
+           "Should reject NULL database");
+    
+    END_TEST();
+}
+
+void test_strerror(void) {
+    TEST("matchy_strerror");
+    
+    ASSERT(strcmp(matchy_strerror(MATCHY_SUCCESS), "Success") == 0,
+           "Success should have a description");
+    ASSERT(strcmp(matchy_strerror(MATCHY_ERROR_NO_DATA), "No data") == 0,
+           "MATCHY_ERROR_NO_DATA should have a description");
+    ASSERT(strcmp(matchy_strerror(-1000), "Unknown error") == 0,
+           "Unknown codes should get a generic description");
+    
+    END_TEST();
+}
+
+void test_open_multi(void) {
+    TEST("matchy_open_multi");
+    
+    // A second feed that overrides 1.1.1.1 and adds a pattern
+    const char *overlay_path = "/tmp/matchy_extensions_overlay.db";
+    matchy_builder_t *builder = matchy_builder_new();
+    matchy_builder_add(builder, "1.1.1.1", "{\"value\":\"overlay\"}");
+    matchy_builder_add(builder, "*.evil.com", "{\"category\":\"malware\"}");
+    int status = matchy_builder_save(builder, overlay_path);
+    matchy_builder_free(builder);
+    ASSERT(status == MATCHY_SUCCESS, "Should save overlay database");
+    
+    const char *paths[] = {overlay_path, TEST_DB_PATH};
+    matchy_t *multi = matchy_open_multi(paths, 2);
+    ASSERT(multi != NULL, "Should open both databases");
+    
+    if (multi != NULL) {
+        // First database wins
+        matchy_result_t result = matchy_query(multi, "1.1.1.1");
+        char *json = matchy_result_to_json(&result);
+        ASSERT(json != NULL && strstr(json, "overlay") != NULL,
+               "1.1.1.1 should come from the first database");
+        matchy_free_string(json);
+        matchy_free_result(&result);
+        
+        // Falls through to the second database
+        result = matchy_query(multi, "8.8.8.8");
+        ASSERT(result.found, "8.8.8.8 should come from the second database");
+        matchy_free_result(&result);
+        
+        result = matchy_query(multi, "www.evil.com");
+        ASSERT(result.found, "Pattern should match in the first database");
+        matchy_free_result(&result);
+        
+        ASSERT(matchy_has_glob_data(multi), "Composite handle should report glob data");
+        matchy_close(multi);
+    }
+    
+    const char *missing[] = {TEST_DB_PATH, "/nonexistent/matchy.db"};
+    ASSERT(matchy_open_multi(missing, 2) == NULL, "Should fail if any database fails to open");
+    ASSERT(matchy_open_multi(paths, 0) == NULL, "Should reject an empty path list");
+    
+    remove(overlay_path);
+    END_TEST();
+}
+
 int main() {
     printf("========================================\n");
     printf("Matchy C API Extensions Test Suite\n");
@@ -510,6 +687,11 @@ int main() {
     test_get_entry_data_list_complex(db);
     test_numeric_types(db);
     test_null_parameters(db);
+    test_get_metadata(db);
+    test_lookup_sockaddr(db);
+    test_iterate(db);
+    test_strerror();
+    test_open_multi();
     
     // Cleanup
     matchy_close(db);